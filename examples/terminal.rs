@@ -0,0 +1,33 @@
+//! Draws a glider gun in the terminal.
+//!
+//! Run with `cargo run --release --example terminal`.
+
+use gridsim::{rules, SquareGrid, TermRenderer};
+use ndarray::Array2;
+use std::io;
+
+fn main() -> io::Result<()> {
+    let gun = gridsim::parse_rle(
+        "x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!",
+    )
+    .expect("the pattern is valid");
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((30, 70), false));
+    grid.stamp(gun.cells.view(), (1, 1));
+    let mut renderer = TermRenderer::new();
+    renderer.set_max_fps(Some(20.0));
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for _ in 0..300 {
+        renderer.render_colored(&mut stdout, &grid, |&alive| {
+            if alive {
+                ('#', Some([80, 220, 120]))
+            } else {
+                ('.', Some([60, 60, 60]))
+            }
+        })?;
+        grid.step();
+    }
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! Compares stepping a large grid all at once against stepping it in tiles, with SIMD counting,
+//! and as a bit-packed grid.
+//!
+//! Run with `cargo run --release --example throughput`, and add `--features simd` to count the
+//! neighbors with SSE2 on x86_64.
+
+use gridsim::{bench::Bench, LifeLikeRule};
+
+fn main() {
+    let rule = LifeLikeRule::parse("B3/S23").unwrap();
+    let measurements = Bench::new(rule, |rng| rng.gen_bool(0.4))
+        .sizes(vec![(4096, 4096)])
+        .tile_sizes(vec![(64, 64), (128, 256), (256, 512)])
+        .steps(20)
+        .run_with_bits();
+    for measurement in &measurements {
+        println!("{}", measurement);
+    }
+}
@@ -0,0 +1,348 @@
+//! Derive macros for [gridsim](https://docs.rs/gridsim/).
+//!
+//! These are re-exported from gridsim when its `derive` feature is enabled.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericParam, Ident, LitInt, Result,
+    Token,
+};
+
+/// Derive a square grid neighborhood from a struct with one `&'a T` field per neighbor.
+///
+/// Each field is annotated with `#[delta(y, x)]`, the offset of its neighbor from the center.
+/// The negation of every offset must also be a field so that flows can be sent back.
+///
+/// If the struct is named `FooNeighbors`, this generates the neighborhood `Foo` and the
+/// direction enum `FooDirection`. Otherwise, they are named with `Neighborhood` and
+/// `Direction` appended to the name of the struct. Directions are named after the fields
+/// and edges are in the same order as the fields.
+///
+/// ```ignore
+/// #[derive(Neighborhood)]
+/// pub struct CrossNeighbors<'a, T> {
+///     #[delta(0, 1)]
+///     pub east: &'a T,
+///     #[delta(-1, 0)]
+///     pub north: &'a T,
+///     #[delta(0, -1)]
+///     pub west: &'a T,
+///     #[delta(1, 0)]
+///     pub south: &'a T,
+/// }
+/// ```
+#[proc_macro_derive(Neighborhood, attributes(delta))]
+pub fn derive_neighborhood(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    neighborhood(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The `(y, x)` offset in a `#[delta(y, x)]` attribute.
+struct Delta(isize, isize);
+
+impl Parse for Delta {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        fn component(input: ParseStream<'_>) -> Result<isize> {
+            let negative = input.parse::<Option<Token![-]>>()?.is_some();
+            let value: isize = input.parse::<LitInt>()?.base10_parse()?;
+            Ok(if negative { -value } else { value })
+        }
+        let y = component(input)?;
+        input.parse::<Token![,]>()?;
+        let x = component(input)?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Delta(y, x))
+    }
+}
+
+fn neighborhood(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let generics = || {
+        Error::new_spanned(
+            &input.generics,
+            "neighbors must have exactly one lifetime and one type parameter, like `Foo<'a, T>`",
+        )
+    };
+    let mut lifetime = None;
+    let mut ty = None;
+    for param in &input.generics.params {
+        match param {
+            GenericParam::Lifetime(param) if lifetime.is_none() => {
+                lifetime = Some(param.lifetime.clone())
+            }
+            GenericParam::Type(param) if ty.is_none() => ty = Some(param.ident.clone()),
+            _ => return Err(generics()),
+        }
+    }
+    let (lifetime, ty) = (lifetime.ok_or_else(generics)?, ty.ok_or_else(generics)?);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(name, "neighbors must have named fields")),
+        },
+        _ => return Err(Error::new_spanned(name, "neighbors must be a struct")),
+    };
+    let mut idents = vec![];
+    let mut deltas = vec![];
+    for field in fields {
+        let ident = field.ident.clone().unwrap();
+        let attr = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("delta"))
+            .ok_or_else(|| Error::new_spanned(&ident, "missing `#[delta(y, x)]` attribute"))?;
+        let Delta(y, x) = attr.parse_args()?;
+        if (y, x) == (0, 0) || deltas.contains(&(y, x)) {
+            return Err(Error::new_spanned(
+                attr,
+                "deltas must be unique and not `(0, 0)`",
+            ));
+        }
+        idents.push(ident);
+        deltas.push((y, x));
+    }
+    let mut opposites = vec![];
+    for (ident, &(y, x)) in idents.iter().zip(&deltas) {
+        let opposite = deltas
+            .iter()
+            .position(|&delta| delta == (-y, -x))
+            .ok_or_else(|| Error::new_spanned(ident, "no field has the negation of this delta"))?;
+        opposites.push(opposite);
+    }
+
+    let base = name.to_string();
+    let (neighborhood, direction) = match base.strip_suffix("Neighbors") {
+        Some(base) if !base.is_empty() => (
+            Ident::new(base, name.span()),
+            format_ident!("{}Direction", base),
+        ),
+        _ => (
+            format_ident!("{}Neighborhood", name),
+            format_ident!("{}Direction", name),
+        ),
+    };
+    let variants: Vec<Ident> = idents
+        .iter()
+        .map(|ident| Ident::new(&camel_case(&ident.to_string()), Span::call_site()))
+        .collect();
+    let count = idents.len();
+    let radius = deltas
+        .iter()
+        .map(|&(y, x)| y.unsigned_abs().max(x.unsigned_abs()))
+        .max()
+        .unwrap_or(0);
+    let ys = deltas.iter().map(|&(y, _)| y);
+    let xs = deltas.iter().map(|&(_, x)| x);
+    let rows = deltas.iter().map(|&(y, _)| (radius as isize + y) as usize);
+    let columns = deltas.iter().map(|&(_, x)| (radius as isize + x) as usize);
+    let opposite_variants = opposites.iter().map(|&i| &variants[i]);
+    let calls = idents.iter().map(|_| quote!(f()));
+    let direction_doc = format!("A direction in the [`{}`] neighborhood.", neighborhood);
+    let neighborhood_doc = format!(
+        "The neighborhood made up of the fields of [`{}`], which is derived.",
+        name
+    );
+    let gridsim = quote!(::gridsim);
+    let ndarray = quote!(::gridsim::__private::ndarray);
+
+    Ok(quote! {
+        #[doc = #direction_doc]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #vis enum #direction {
+            #(#variants,)*
+        }
+
+        impl #direction {
+            /// All directions in edge order.
+            pub const ALL: [#direction; #count] = [#(#direction::#variants,)*];
+
+            /// The `(y, x)` offset of the neighbor in this direction.
+            pub fn delta(self) -> (isize, isize) {
+                match self {
+                    #(#direction::#variants => (#ys, #xs),)*
+                }
+            }
+
+            /// The direction pointing the opposite way.
+            pub fn opposite(self) -> Self {
+                match self {
+                    #(#direction::#variants => #direction::#opposite_variants,)*
+                }
+            }
+        }
+
+        impl<#lifetime, #ty> #name<#lifetime, #ty> {
+            /// Picks the neighbors out of a square window centered on the cell.
+            pub fn new(window: #ndarray::ArrayView2<#lifetime, #ty>) -> Self {
+                let width = 2 * #radius + 1;
+                assert_eq!(window.dim(), (width, width), "window is the wrong size for the neighborhood");
+                Self {
+                    #(#idents: #ndarray::IndexLonger::index(&window, (#rows, #columns)),)*
+                }
+            }
+
+            /// The neighbor in the given direction.
+            pub fn get(&self, direction: #direction) -> &#lifetime #ty {
+                match direction {
+                    #(#direction::#variants => self.#idents,)*
+                }
+            }
+
+            /// Iterate over the neighbors in edge order.
+            pub fn iter(&self) -> impl Iterator<Item = &#lifetime #ty> {
+                ::core::iter::IntoIterator::into_iter([#(self.#idents,)*])
+            }
+
+            /// Iterate over the directions and neighbors in edge order.
+            pub fn dir_iter(&self) -> impl Iterator<Item = (#direction, &#lifetime #ty)> {
+                ::core::iter::IntoIterator::into_iter(#direction::ALL).zip(self.iter())
+            }
+        }
+
+        impl<#lifetime, #ty> #gridsim::NeighborsExt for #name<#lifetime, #ty> {
+            type Item = &#lifetime #ty;
+
+            fn iter_neighbors(&self) -> impl Iterator<Item = &#lifetime #ty> + '_ {
+                self.iter()
+            }
+        }
+
+        impl<#lifetime, #ty> ::core::ops::Index<#direction> for #name<#lifetime, #ty> {
+            type Output = #ty;
+
+            fn index(&self, direction: #direction) -> &#ty {
+                self.get(direction)
+            }
+        }
+
+        #[doc = #neighborhood_doc]
+        #vis enum #neighborhood {}
+
+        impl #gridsim::Neighborhood for #neighborhood {
+            type Neighbors<'a, T: 'a> = #name<'a, T>;
+            type Edges<T> = [T; #count];
+        }
+
+        impl #gridsim::SquareNeighborhood for #neighborhood {
+            const RADIUS: usize = #radius;
+            const EDGES: usize = #count;
+
+            fn delta(edge: usize) -> (isize, isize) {
+                #direction::ALL[edge].delta()
+            }
+
+            fn opposite(edge: usize) -> usize {
+                #direction::ALL[edge].opposite() as usize
+            }
+
+            fn neighbors<T>(window: #ndarray::ArrayView2<'_, T>) -> #name<'_, T> {
+                #name::new(window)
+            }
+
+            fn edges<T>(mut f: impl FnMut() -> T) -> [T; #count] {
+                [#(#calls,)*]
+            }
+
+            fn edges_mut<T>(edges: &mut [T; #count]) -> &mut [T] {
+                edges
+            }
+        }
+    })
+}
+
+/// Derive `SoaCell` for a struct whose fields all have the same type, numbered in the order
+/// they are declared.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, SoaCell)]
+/// pub struct Fluid {
+///     pub density: f32,
+///     pub velocity_x: f32,
+///     pub velocity_y: f32,
+/// }
+/// ```
+#[proc_macro_derive(SoaCell)]
+pub fn derive_soa_cell(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    soa_cell(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn soa_cell(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(name, "soa cells must be a struct")),
+    };
+    let scalar = match fields.iter().next() {
+        Some(field) => &field.ty,
+        None => return Err(Error::new_spanned(name, "soa cells must have a field")),
+    };
+    let tokens = |ty| quote!(#ty).to_string();
+    if let Some(field) = fields
+        .iter()
+        .find(|field| tokens(&field.ty) != tokens(scalar))
+    {
+        return Err(Error::new_spanned(
+            &field.ty,
+            "every field of a soa cell must have the same type",
+        ));
+    }
+    let members: Vec<TokenStream2> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            }
+        })
+        .collect();
+    let count = members.len();
+    let numbers = 0..count;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let gridsim = quote!(::gridsim);
+
+    Ok(quote! {
+        impl #impl_generics #gridsim::SoaCell for #name #ty_generics #where_clause {
+            type Scalar = #scalar;
+            const FIELDS: usize = #count;
+
+            fn field(&self, field: usize) -> #scalar {
+                match field {
+                    #(#numbers => self.#members,)*
+                    _ => panic!("field {} is out of range", field),
+                }
+            }
+
+            fn from_fields(mut field: impl FnMut(usize) -> #scalar) -> Self {
+                let mut next = 0..;
+                Self {
+                    #(#members: field(next.next().unwrap()),)*
+                }
+            }
+        }
+    })
+}
+
+/// Convert a `snake_case` field name to a `CamelCase` variant name.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
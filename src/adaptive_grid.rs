@@ -0,0 +1,393 @@
+use crate::{moore::MOORE_DELTAS, Moore, Sim};
+use itertools::Itertools;
+use ndarray::{s, Array2};
+
+/// How far the cells which a step of a cell depends on can be from it: the diffs of its
+/// neighbors depend on their neighbors, and the flows it receives are sent by its neighbors
+/// using the diffs of their neighbors.
+const REACH: usize = 3;
+
+/// A node of the quadtree of an [`AdaptiveGrid`].
+#[derive(Clone, Debug)]
+enum Node<C> {
+    /// A square where every cell is the same, stored as one cell.
+    Uniform(C),
+    /// A tile where every cell is stored.
+    Tile(Array2<C>),
+    /// A square split into its north west, north east, south west, and south east quarters.
+    Split(Box<[Node<C>; 4]>),
+}
+
+/// A square grid stored as a quadtree of tiles, where squares of identical cells are stored
+/// as a single cell and only the tiles near cells which differ from them are simulated.
+///
+/// The grid is `tile_size << levels` cells on each side. A tile is stepped if any of its
+/// cells are stored, if it is in a square of cells which a step would change, or if it is
+/// close enough to a different cell or the edge of the grid that its cells could change.
+/// Tiles are refined when a step or [`AdaptiveGrid::set`] changes a square of identical
+/// cells, and tiles and squares which become identical are coarsened again after each step,
+/// so a mostly still landscape with small active fronts only costs as much as the fronts.
+///
+/// Cells beyond the edges of the grid are padding cells which don't step, like a
+/// [`SquareGrid`](crate::SquareGrid) with the default boundary. Cells are compared with
+/// `PartialEq` to tell if squares are identical.
+#[derive(Clone, Debug)]
+pub struct AdaptiveGrid<S>
+where
+    S: Sim<Moore>,
+{
+    sim: S,
+    root: Node<S::Cell>,
+    levels: u32,
+    tile_size: usize,
+    generation: u64,
+    /// The number of tiles stepped in the last step.
+    stepped: usize,
+}
+
+impl<S> AdaptiveGrid<S>
+where
+    S: Sim<Moore> + Sync,
+    S::Cell: Clone + PartialEq + Send + Sync,
+{
+    /// Make a grid of `tile_size << levels` cells on each side where every cell is `cell`.
+    ///
+    /// Panics if `tile_size` is less than `3`, which is how far the step of a cell reaches.
+    pub fn new(sim: S, levels: u32, tile_size: usize, cell: S::Cell) -> Self {
+        assert!(
+            tile_size >= REACH,
+            "tiles must have at least 3 cells on each side"
+        );
+        Self {
+            sim,
+            root: Node::Uniform(cell),
+            levels,
+            tile_size,
+            generation: 0,
+            stepped: 0,
+        }
+    }
+
+    /// Get the number of cells on each side of the grid.
+    pub fn size(&self) -> usize {
+        self.tile_size << self.levels
+    }
+
+    /// Get the number of cells on each side of a tile.
+    pub fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    /// Get the number of steps taken.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Get the sim.
+    pub fn sim(&self) -> &S {
+        &self.sim
+    }
+
+    /// Get the cell at a `(y, x)` index, or `None` if it is outside of the grid.
+    pub fn get(&self, (y, x): (usize, usize)) -> Option<&S::Cell> {
+        if y >= self.size() || x >= self.size() {
+            return None;
+        }
+        let mut node = &self.root;
+        let mut side = self.size();
+        let (mut y, mut x) = (y, x);
+        loop {
+            match node {
+                Node::Uniform(cell) => return Some(cell),
+                Node::Tile(cells) => return Some(&cells[(y, x)]),
+                Node::Split(quarters) => {
+                    side /= 2;
+                    node = &quarters[quarter(y / side, x / side)];
+                    y %= side;
+                    x %= side;
+                }
+            }
+        }
+    }
+
+    /// Set the cell at a `(y, x)` index, refining the tile it is in if needed.
+    ///
+    /// Panics if the index is outside of the grid.
+    pub fn set(&mut self, (y, x): (usize, usize), cell: S::Cell) {
+        assert!(
+            y < self.size() && x < self.size(),
+            "index is outside of the grid"
+        );
+        if self.get((y, x)) == Some(&cell) {
+            return;
+        }
+        let t = self.tile_size;
+        let tile = self.tile_mut((y / t, x / t));
+        tile[(y % t, x % t)] = cell;
+    }
+
+    /// Copy every cell into an array.
+    pub fn to_array(&self) -> Array2<S::Cell> {
+        let size = self.size();
+        let mut cells = Array2::from_elem((size, size), self.sim.cell_padding());
+        let t = self.tile_size;
+        visit(
+            &self.root,
+            (0, 0),
+            1 << self.levels,
+            &mut |(ty, tx), tiles, node| {
+                let region = s![ty * t..(ty + tiles) * t, tx * t..(tx + tiles) * t];
+                match node {
+                    Node::Uniform(cell) => cells.slice_mut(region).fill(cell.clone()),
+                    Node::Tile(tile) => cells.slice_mut(region).assign(tile),
+                    Node::Split(_) => unreachable!("only leaves are visited"),
+                }
+            },
+        );
+        cells
+    }
+
+    /// Get the number of tiles whose cells are stored one by one.
+    pub fn fine_tiles(&self) -> usize {
+        let mut count = 0;
+        visit(&self.root, (0, 0), 1 << self.levels, &mut |_, _, node| {
+            count += matches!(node, Node::Tile(_)) as usize;
+        });
+        count
+    }
+
+    /// Get the number of tiles which the last step simulated.
+    pub fn stepped_tiles(&self) -> usize {
+        self.stepped
+    }
+
+    /// Simulate one step of every tile that could change.
+    ///
+    /// The tiles are stepped in parallel when the `use-rayon` feature is enabled.
+    pub fn step(&mut self) {
+        let tiles = self.active_tiles();
+        let next = |&index: &(usize, usize)| (index, self.step_tile(index));
+        #[cfg(feature = "use-rayon")]
+        let stepped: Vec<_> = {
+            use rayon::prelude::*;
+            tiles.par_iter().map(next).collect()
+        };
+        #[cfg(not(feature = "use-rayon"))]
+        let stepped: Vec<_> = tiles.iter().map(next).collect();
+        self.stepped = stepped.len();
+        for (index, cells) in stepped {
+            *self.tile_mut(index) = cells;
+        }
+        coarsen(&mut self.root);
+        self.generation += 1;
+    }
+
+    /// Simulate `n` steps.
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Get the `(y, x)` tile indices of every tile which could change in the next step.
+    fn active_tiles(&self) -> Vec<(usize, usize)> {
+        let mut tiles = vec![];
+        visit(
+            &self.root,
+            (0, 0),
+            1 << self.levels,
+            &mut |(ty, tx), n, node| {
+                match node {
+                    Node::Tile(_) => tiles.push((ty, tx)),
+                    Node::Uniform(cell) if !self.quiescent(cell) => {
+                        tiles.extend((ty..ty + n).cartesian_product(tx..tx + n))
+                    }
+                    Node::Uniform(cell) => {
+                        // Tiles inside of the square only see the square, so only the tiles on
+                        // its border need to look at what is around it.
+                        let last = n - 1;
+                        let border = (0..n).flat_map(|y| {
+                            let xs = if y == 0 || y == last {
+                                (0..n).collect()
+                            } else {
+                                vec![0, last]
+                            };
+                            xs.into_iter().map(move |x| (ty + y, tx + x))
+                        });
+                        for tile in border {
+                            if !self.surrounded_by(tile, cell) {
+                                tiles.push(tile);
+                            }
+                        }
+                    }
+                    Node::Split(_) => unreachable!("only leaves are visited"),
+                }
+            },
+        );
+        tiles
+    }
+
+    /// Check if a square of cells which are all `cell` stays the same in a step.
+    fn quiescent(&self, cell: &S::Cell) -> bool {
+        let width = 2 * REACH + 1;
+        let window = Array2::from_elem((width, width), Some(cell.clone()));
+        step_window(&self.sim, window)[(0, 0)] == *cell
+    }
+
+    /// Check if every cell which a step of the tile could see is `cell` and on the grid.
+    fn surrounded_by(&self, (ty, tx): (usize, usize), cell: &S::Cell) -> bool {
+        let t = self.tile_size as isize;
+        let (y, x) = (ty as isize * t, tx as isize * t);
+        let reach = REACH as isize;
+        (y - reach..y + t + reach)
+            .cartesian_product(x - reach..x + t + reach)
+            .all(|position| self.get_isize(position) == Some(cell))
+    }
+
+    /// Get the cell at a `(y, x)` position, or `None` if it is outside of the grid.
+    fn get_isize(&self, (y, x): (isize, isize)) -> Option<&S::Cell> {
+        if y < 0 || x < 0 {
+            return None;
+        }
+        self.get((y as usize, x as usize))
+    }
+
+    /// Compute the next cells of a tile.
+    fn step_tile(&self, (ty, tx): (usize, usize)) -> Array2<S::Cell> {
+        let t = self.tile_size;
+        let width = t + 2 * REACH;
+        let (y, x) = ((ty * t) as isize, (tx * t) as isize);
+        let reach = REACH as isize;
+        let window = Array2::from_shape_fn((width, width), |(wy, wx)| {
+            self.get_isize((y + wy as isize - reach, x + wx as isize - reach))
+                .cloned()
+        });
+        step_window(&self.sim, window)
+    }
+
+    /// Get the tile at `(y, x)` tile indices, splitting squares of identical cells to reach
+    /// it and storing its cells if they were stored as one.
+    fn tile_mut(&mut self, (ty, tx): (usize, usize)) -> &mut Array2<S::Cell> {
+        let t = self.tile_size;
+        let mut node = &mut self.root;
+        let mut tiles = 1 << self.levels;
+        let (mut ty, mut tx) = (ty, tx);
+        loop {
+            if let Node::Uniform(cell) = node {
+                *node = if tiles == 1 {
+                    Node::Tile(Array2::from_elem((t, t), cell.clone()))
+                } else {
+                    let cell = cell.clone();
+                    Node::Split(Box::new([(); 4].map(|_| Node::Uniform(cell.clone()))))
+                };
+            }
+            match node {
+                Node::Tile(cells) => return cells,
+                Node::Split(quarters) => {
+                    tiles /= 2;
+                    node = &mut quarters[quarter(ty / tiles, tx / tiles)];
+                    ty %= tiles;
+                    tx %= tiles;
+                }
+                Node::Uniform(_) => unreachable!("uniform nodes were just split"),
+            }
+        }
+    }
+}
+
+/// Get the index of the quarter at `(y, x)`, which are each `0` or `1`.
+fn quarter(y: usize, x: usize) -> usize {
+    2 * y + x
+}
+
+/// Call `f` with the `(y, x)` tile indices, width in tiles, and node of every leaf.
+fn visit<C>(
+    node: &Node<C>,
+    (ty, tx): (usize, usize),
+    tiles: usize,
+    f: &mut impl FnMut((usize, usize), usize, &Node<C>),
+) {
+    match node {
+        Node::Split(quarters) => {
+            let half = tiles / 2;
+            for (i, quarter) in quarters.iter().enumerate() {
+                visit(quarter, (ty + i / 2 * half, tx + i % 2 * half), half, f);
+            }
+        }
+        _ => f((ty, tx), tiles, node),
+    }
+}
+
+/// Store tiles and squares whose cells are all the same as one cell.
+fn coarsen<C: Clone + PartialEq>(node: &mut Node<C>) {
+    let uniform = match node {
+        Node::Uniform(_) => None,
+        Node::Tile(cells) => {
+            let first = &cells[(0, 0)];
+            cells
+                .iter()
+                .all(|cell| cell == first)
+                .then(|| first.clone())
+        }
+        Node::Split(quarters) => {
+            quarters.iter_mut().for_each(coarsen);
+            match &quarters[0] {
+                Node::Uniform(first) => quarters
+                    .iter()
+                    .all(|quarter| matches!(quarter, Node::Uniform(cell) if cell == first))
+                    .then(|| first.clone()),
+                _ => None,
+            }
+        }
+    };
+    if let Some(cell) = uniform {
+        *node = Node::Uniform(cell);
+    }
+}
+
+/// Step the cells of a window, where `None` is beyond the edges of the grid, and get the
+/// cells at least [`REACH`] cells from its edges.
+fn step_window<S>(sim: &S, window: Array2<Option<S::Cell>>) -> Array2<S::Cell>
+where
+    S: Sim<Moore>,
+    S::Cell: Clone,
+{
+    let (h, w) = window.dim();
+    let padding = sim.cell_padding();
+    let cells = window.map(|cell| cell.clone().unwrap_or_else(|| padding.clone()));
+    let on_grid = |y: usize, x: usize| window[(y, x)].is_some();
+
+    // Diffs are needed up to two cells away from the result and flows up to one.
+    let diffs = Array2::from_shape_fn((h - 2, w - 2), |(y, x)| {
+        if on_grid(y + 1, x + 1) {
+            sim.compute(cells.slice(s![y..y + 3, x..x + 3]))
+        } else {
+            sim.diff_padding()
+        }
+    });
+    let mut egressed = Array2::from_shape_fn((h - 4, w - 4), |(y, x)| {
+        let mut cell = cells[(y + 2, x + 2)].clone();
+        let flows = if on_grid(y + 2, x + 2) {
+            sim.egress(&mut cell, diffs.slice(s![y..y + 3, x..x + 3]))
+        } else {
+            [(); 8].map(|_| sim.flow_padding())
+        };
+        (cell, flows.map(Some))
+    });
+    let (eh, ew) = egressed.dim();
+    let mut next = Vec::with_capacity((eh - 2) * (ew - 2));
+    for (y, x) in (1..eh - 1).cartesian_product(1..ew - 1) {
+        let mut flows = [(); 8].map(|_| sim.flow_padding());
+        for (edge, flow) in flows.iter_mut().enumerate() {
+            let (dy, dx) = MOORE_DELTAS[edge];
+            let neighbor = ((y as isize + dy) as usize, (x as isize + dx) as usize);
+            *flow = egressed[neighbor].1[(edge + 4) % 8]
+                .take()
+                .expect("flow was taken twice");
+        }
+        let mut cell = egressed[(y, x)].0.clone();
+        sim.ingress(&mut cell, flows);
+        next.push(cell);
+    }
+    Array2::from_shape_vec((eh - 2, ew - 2), next).unwrap()
+}
@@ -0,0 +1,248 @@
+use crate::CellRng;
+use ndarray::Array2;
+use std::collections::{BTreeMap, HashMap};
+
+/// The unique ID of an agent in an [`AgentLayer`], which is never reused.
+pub type AgentId = u64;
+
+/// A `(y, x)` index.
+type Index = (usize, usize);
+
+/// An agent as seen while its move is decided.
+#[derive(Debug)]
+pub struct AgentRef<'a, A> {
+    pub id: AgentId,
+    /// The `(y, x)` index of the agent.
+    pub position: (usize, usize),
+    pub state: &'a A,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Entry<A> {
+    position: (usize, usize),
+    state: A,
+}
+
+/// Agents with their own IDs and state which each occupy a cell of a grid and move around it.
+///
+/// Every step, each agent proposes a cell to move to, looking at the layer as it was at the
+/// start of the step. When several agents want the same cell, one of them is picked at random
+/// from the seed, generation, and cell, so the result doesn't depend on the order of the agents
+/// or how the work is split between threads. An agent can move into a cell which is being left
+/// in the same step, and agents can swap places.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgentLayer<A> {
+    cells: Array2<Option<AgentId>>,
+    agents: BTreeMap<AgentId, Entry<A>>,
+    next_id: AgentId,
+    generation: u64,
+    seed: u64,
+    wrap: bool,
+}
+
+impl<A> AgentLayer<A> {
+    /// Make an empty layer of `(height, width)` cells.
+    pub fn new(dims: (usize, usize)) -> Self {
+        assert!(
+            dims.0 >= 1 && dims.1 >= 1,
+            "grid is empty, which isnt allowed"
+        );
+        Self {
+            cells: Array2::from_elem(dims, None),
+            agents: BTreeMap::new(),
+            next_id: 0,
+            generation: 0,
+            seed: 0,
+            wrap: false,
+        }
+    }
+
+    /// The `(height, width)` of the layer.
+    pub fn dims(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    /// The number of steps the layer has taken.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The seed used to break ties between agents, which is `0` by default.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Set the seed used to break ties between agents.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Whether [`AgentLayer::offset`] wraps around the edges, which is `false` by default.
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
+    /// Set whether [`AgentLayer::offset`] wraps around the edges.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Get the index at a `(y, x)` offset from another, or `None` if it is beyond an edge and
+    /// the layer doesn't wrap.
+    pub fn offset(
+        &self,
+        (y, x): (usize, usize),
+        (dy, dx): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let (h, w) = self.dims();
+        let axis = |position: usize, delta: isize, len: usize| {
+            let moved = position as isize + delta;
+            if self.wrap {
+                Some(moved.rem_euclid(len as isize) as usize)
+            } else if (0..len as isize).contains(&moved) {
+                Some(moved as usize)
+            } else {
+                None
+            }
+        };
+        Some((axis(y, dy, h)?, axis(x, dx, w)?))
+    }
+
+    /// Add an agent at a `(y, x)` index, returning its ID, or `None` if the cell is taken.
+    pub fn spawn(&mut self, position: (usize, usize), state: A) -> Option<AgentId> {
+        let cell = self.cells.get_mut(position)?;
+        if cell.is_some() {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        *cell = Some(id);
+        self.agents.insert(id, Entry { position, state });
+        Some(id)
+    }
+
+    /// Remove an agent, returning its state.
+    pub fn remove(&mut self, id: AgentId) -> Option<A> {
+        let entry = self.agents.remove(&id)?;
+        self.cells[entry.position] = None;
+        Some(entry.state)
+    }
+
+    /// Get the state of an agent.
+    pub fn get(&self, id: AgentId) -> Option<&A> {
+        self.agents.get(&id).map(|entry| &entry.state)
+    }
+
+    /// Get the state of an agent mutably.
+    pub fn get_mut(&mut self, id: AgentId) -> Option<&mut A> {
+        self.agents.get_mut(&id).map(|entry| &mut entry.state)
+    }
+
+    /// Get the `(y, x)` index of an agent.
+    pub fn position(&self, id: AgentId) -> Option<(usize, usize)> {
+        self.agents.get(&id).map(|entry| entry.position)
+    }
+
+    /// Get the agent at a `(y, x)` index.
+    pub fn at(&self, position: (usize, usize)) -> Option<AgentId> {
+        self.cells.get(position).copied().flatten()
+    }
+
+    /// The number of agents.
+    pub fn len(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// Check if there are no agents.
+    pub fn is_empty(&self) -> bool {
+        self.agents.is_empty()
+    }
+
+    /// Iterate over the agents in order of their IDs.
+    pub fn iter(&self) -> impl Iterator<Item = AgentRef<'_, A>> {
+        self.agents.iter().map(|(&id, entry)| AgentRef {
+            id,
+            position: entry.position,
+            state: &entry.state,
+        })
+    }
+
+    /// Iterate over the IDs and states of the agents mutably in order of their IDs.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (AgentId, &mut A)> {
+        self.agents
+            .iter_mut()
+            .map(|(&id, entry)| (id, &mut entry.state))
+    }
+
+    /// Move every agent to the cell returned by `decide`, or keep it in place if `None` is
+    /// returned, resolving conflicts.
+    ///
+    /// `decide` is given the layer at the start of the step. It is called in parallel when the
+    /// `use-rayon` feature is enabled. Agents which lose a conflict, or want a cell whose agent
+    /// stays, don't move.
+    pub fn step(
+        &mut self,
+        decide: impl Fn(AgentRef<'_, A>, &Self) -> Option<(usize, usize)> + Sync + Send,
+    ) where
+        A: Sync,
+    {
+        let propose = |agent: AgentRef<'_, A>| {
+            let (id, from) = (agent.id, agent.position);
+            decide(agent, self)
+                .filter(|&to| to != from && self.cells.get(to).is_some())
+                .map(|to| (id, from, to))
+        };
+        #[cfg(feature = "use-rayon")]
+        let proposals: Vec<_> = {
+            use rayon::prelude::*;
+            let agents: Vec<_> = self.iter().collect();
+            agents.into_par_iter().filter_map(propose).collect()
+        };
+        #[cfg(not(feature = "use-rayon"))]
+        let proposals: Vec<_> = self.iter().filter_map(propose).collect();
+
+        // Pick one agent for every cell that is wanted.
+        let mut wanted: BTreeMap<Index, Vec<(AgentId, Index)>> = BTreeMap::new();
+        for (id, from, to) in proposals {
+            wanted.entry(to).or_default().push((id, from));
+        }
+        let mut moves: HashMap<AgentId, (Index, Index)> = wanted
+            .into_iter()
+            .map(|(to, contenders)| {
+                let mut rng = CellRng::new(self.seed, self.generation, to);
+                let (id, from) = contenders[rng.gen_range(contenders.len() as u64) as usize];
+                (id, (from, to))
+            })
+            .collect();
+
+        // Cancel moves into cells whose agents stay until only moves into cells being emptied
+        // are left, which keeps chains and cycles of agents moving together.
+        loop {
+            let blocked: Vec<AgentId> = moves
+                .iter()
+                .filter(|(_, &(_, to))| match self.cells[to] {
+                    Some(occupant) => !moves.contains_key(&occupant),
+                    None => false,
+                })
+                .map(|(&id, _)| id)
+                .collect();
+            if blocked.is_empty() {
+                break;
+            }
+            for id in blocked {
+                moves.remove(&id);
+            }
+        }
+
+        for &(from, _) in moves.values() {
+            self.cells[from] = None;
+        }
+        for (&id, &(_, to)) in &moves {
+            self.cells[to] = Some(id);
+            self.agents.get_mut(&id).expect("agent moved").position = to;
+        }
+        self.generation += 1;
+    }
+}
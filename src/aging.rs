@@ -0,0 +1,184 @@
+use crate::{project::project, render::render_rows, CellColor, Moore, Sim, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+/// A cell along with how long it has gone without changing, kept by [`Aging`].
+///
+/// Generations are counted by the cell from when it was made, so cells made at the start of a
+/// grid count the same generations as the grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgedCell<C> {
+    pub cell: C,
+    age: u64,
+    changes: u64,
+    last_change: Option<u64>,
+    generation: u64,
+    /// Whether egress changed the cell in the current step.
+    changing: bool,
+}
+
+impl<C> AgedCell<C> {
+    /// Wrap a cell which hasn't changed yet.
+    pub fn new(cell: C) -> Self {
+        Self {
+            cell,
+            age: 0,
+            changes: 0,
+            last_change: None,
+            generation: 0,
+            changing: false,
+        }
+    }
+
+    /// The number of steps since the cell last changed, or since it was made.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// The number of steps which changed the cell.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+
+    /// The generation the cell last changed in, which is the generation reached by the step
+    /// that changed it, or `None` if it never changed.
+    pub fn last_change(&self) -> Option<u64> {
+        self.last_change
+    }
+
+    /// Finish a step, counting it as a change if `changed`.
+    fn finish_step(&mut self, changed: bool) {
+        self.generation += 1;
+        self.changing = false;
+        if changed {
+            self.age = 0;
+            self.changes += 1;
+            self.last_change = Some(self.generation);
+        } else {
+            self.age += 1;
+        }
+    }
+}
+
+/// Aged cells look like the cells inside of them.
+impl<C> CellColor for AgedCell<C>
+where
+    C: CellColor,
+{
+    fn color(&self) -> [u8; 4] {
+        self.cell.color()
+    }
+}
+
+/// A sim which keeps the age and number of changes of every cell of the sim inside of it in
+/// an [`AgedCell`], without the sim or its cells knowing about it.
+///
+/// A cell has changed in a step if egress or ingress made it unequal to what it was. The inner
+/// sim is given a view of the cells in place when an aged cell is a whole number of inner
+/// cells long. Otherwise, each window is copied first, which costs a clone of nine cells for
+/// each cell on top of the inner sim.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aging<S>(pub S);
+
+impl<S> Sim<Moore> for Aging<S>
+where
+    S: Sim<Moore>,
+    S::Cell: Clone + PartialEq,
+{
+    type Cell = AgedCell<S::Cell>;
+    type Diff = S::Diff;
+    type Flow = S::Flow;
+
+    fn compute(&self, cells: ArrayView2<'_, AgedCell<S::Cell>>) -> S::Diff {
+        // The inner cell is a plain field of every aged cell.
+        if let Some(window) = unsafe { project(cells, |aged| &aged.cell) } {
+            return self.0.compute(window);
+        }
+        let mut window = cells.iter().map(|aged| aged.cell.clone());
+        let window = [(); 9].map(|_| window.next().unwrap());
+        let window = ArrayView2::from_shape((3, 3), &window[..]).unwrap();
+        self.0.compute(window)
+    }
+
+    fn egress(&self, cell: &mut AgedCell<S::Cell>, diffs: ArrayView2<'_, S::Diff>) -> [S::Flow; 8] {
+        let before = cell.cell.clone();
+        let flows = self.0.egress(&mut cell.cell, diffs);
+        cell.changing = cell.cell != before;
+        flows
+    }
+
+    fn ingress(&self, cell: &mut AgedCell<S::Cell>, flows: [S::Flow; 8]) {
+        let before = cell.cell.clone();
+        self.0.ingress(&mut cell.cell, flows);
+        let changed = cell.changing || cell.cell != before;
+        cell.finish_step(changed);
+    }
+
+    fn cell_padding(&self) -> AgedCell<S::Cell> {
+        AgedCell::new(self.0.cell_padding())
+    }
+
+    fn diff_padding(&self) -> S::Diff {
+        self.0.diff_padding()
+    }
+
+    fn flow_padding(&self) -> S::Flow {
+        self.0.flow_padding()
+    }
+}
+
+impl<S> SquareGrid<Aging<S>>
+where
+    S: Sim<Moore>,
+    S::Cell: Clone + PartialEq + Send,
+{
+    /// Make a new grid of aged cells which haven't changed yet.
+    pub fn aging(sim: S, cells: Array2<S::Cell>) -> Self {
+        Self::new(Aging(sim), cells.mapv(AgedCell::new))
+    }
+
+    /// Get the number of steps since each cell last changed.
+    pub fn ages(&self) -> Array2<u64> {
+        self.cells().map(AgedCell::age)
+    }
+
+    /// Get the number of steps which changed each cell.
+    pub fn change_counts(&self) -> Array2<u64> {
+        self.cells().map(AgedCell::changes)
+    }
+
+    /// Draw a heatmap of `value` into an RGBA framebuffer with one pixel per cell, going from
+    /// black through red and yellow to white as the value goes from `0` to `max`.
+    ///
+    /// For example, `|cell| cell.changes() as f64` shows where the grid is most active.
+    pub fn render_heatmap(
+        &self,
+        value: impl Fn(&AgedCell<S::Cell>) -> f64 + Send + Sync,
+        max: f64,
+    ) -> Vec<u8>
+    where
+        S::Cell: Sync,
+    {
+        let cells = self.cells();
+        let (h, w) = cells.dim();
+        let mut buffer = vec![0; 4 * h * w];
+        render_rows(&mut buffer, (h, w), |y, x| {
+            heat_color(value(&cells[(y, x)]) / max)
+        });
+        buffer
+    }
+}
+
+/// Get the color of a heat from `0` to `1`, going from black through red and yellow to white.
+///
+/// Heats beyond the range are clamped to it.
+pub fn heat_color(heat: f64) -> [u8; 4] {
+    let heat = if heat.is_nan() {
+        0.0
+    } else {
+        heat.clamp(0.0, 1.0)
+    };
+    let channel = |start: f64| ((3.0 * heat - start).clamp(0.0, 1.0) * 255.0).round() as u8;
+    [channel(0.0), channel(1.0), channel(2.0), 255]
+}
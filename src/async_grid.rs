@@ -0,0 +1,235 @@
+use crate::{Moore, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+use std::{
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex, MutexGuard,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+/// A handle to a grid which is stepped on other threads, so async code can await steps
+/// instead of blocking the executor on them.
+///
+/// Operations run one at a time in the order they started on a thread kept for the grid, and
+/// return an [`Offload`] which resolves when they finish. An operation keeps running if its
+/// [`Offload`] is dropped. Handles are cheap to clone and all of them refer to the same grid,
+/// whose thread stops once every handle is dropped.
+pub struct AsyncGrid<S, N = Moore>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    grid: Arc<Mutex<SquareGrid<S, N>>>,
+    jobs: Sender<Job>,
+}
+
+/// An operation waiting for the thread of an [`AsyncGrid`].
+type Job = Box<dyn FnOnce() + Send>;
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Move the grid behind an [`AsyncGrid`] to step it from async code.
+    pub fn into_async(self) -> AsyncGrid<S, N> {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        thread::spawn(move || {
+            for job in receiver {
+                job();
+            }
+        });
+        AsyncGrid {
+            grid: Arc::new(Mutex::new(self)),
+            jobs,
+        }
+    }
+}
+
+impl<S, N> AsyncGrid<S, N>
+where
+    S: Sim<N> + Send + Sync + 'static,
+    N: SquareNeighborhood + 'static,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Run `f` on the grid on another thread once the operations before it finish.
+    pub fn with<R>(&self, f: impl FnOnce(&mut SquareGrid<S, N>) -> R + Send + 'static) -> Offload<R>
+    where
+        R: Send + 'static,
+    {
+        let grid = self.grid.clone();
+        let (job, offload) = offload(move || f(&mut lock(&grid)));
+        // The thread only stops once every handle, including this one, is dropped.
+        self.jobs.send(job).unwrap();
+        offload
+    }
+
+    /// Simulate one step on another thread.
+    pub fn step(&self) -> Offload<()> {
+        self.with(SquareGrid::step)
+    }
+
+    /// Simulate `n` steps on another thread.
+    pub fn step_n(&self, n: u64) -> Offload<()> {
+        self.with(move |grid| grid.step_n(n))
+    }
+
+    /// Get a copy of the cells once the operations before it finish.
+    pub fn cells(&self) -> Offload<Array2<S::Cell>> {
+        self.with(|grid| grid.cells().to_owned())
+    }
+
+    /// Step the grid and get each generation it reaches, such as to stream frames to clients.
+    pub fn generations(&self) -> Generations<S, N> {
+        Generations { grid: self.clone() }
+    }
+
+    /// Lock the grid on the current thread, blocking until the operation which is running
+    /// finishes.
+    pub fn lock(&self) -> MutexGuard<'_, SquareGrid<S, N>> {
+        lock(&self.grid)
+    }
+
+    /// Take the grid back out, or get the handle back if there are other handles to it or
+    /// operations on it which haven't finished.
+    pub fn try_into_inner(self) -> Result<SquareGrid<S, N>, Self> {
+        let Self { grid, jobs } = self;
+        Arc::try_unwrap(grid)
+            .map(|grid| grid.into_inner().expect(POISONED))
+            .map_err(|grid| Self { grid, jobs })
+    }
+}
+
+impl<S, N> Clone for AsyncGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn clone(&self) -> Self {
+        Self {
+            grid: self.grid.clone(),
+            jobs: self.jobs.clone(),
+        }
+    }
+}
+
+impl<S, N> fmt::Debug for AsyncGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncGrid").finish_non_exhaustive()
+    }
+}
+
+/// The generations of an [`AsyncGrid`], made with [`AsyncGrid::generations`].
+///
+/// This works like a stream, and can be turned into one with something like
+/// `futures::stream::unfold`.
+pub struct Generations<S, N = Moore>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    grid: AsyncGrid<S, N>,
+}
+
+impl<S, N> fmt::Debug for Generations<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Generations").finish_non_exhaustive()
+    }
+}
+
+impl<S, N> Generations<S, N>
+where
+    S: Sim<N> + Send + Sync + 'static,
+    N: SquareNeighborhood + 'static,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Step the grid and get the generation it reached along with a copy of its cells.
+    pub fn next_generation(&mut self) -> Offload<(u64, Array2<S::Cell>)> {
+        self.grid.with(|grid| {
+            grid.step();
+            (grid.generation(), grid.cells().to_owned())
+        })
+    }
+}
+
+const POISONED: &str = "the grid panicked during an operation";
+
+fn lock<T>(grid: &Mutex<T>) -> MutexGuard<'_, T> {
+    grid.lock().expect(POISONED)
+}
+
+/// The state shared by an [`Offload`] and the thread running its work.
+struct Shared<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A future which resolves to the result of work running on another thread.
+///
+/// If the work panics, the panic is resumed when the future is polled.
+pub struct Offload<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// Make a job which runs `f` and the future which resolves to what it returns.
+fn offload<T>(f: impl FnOnce() -> T + Send + 'static) -> (Job, Offload<T>)
+where
+    T: Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+    let worker = shared.clone();
+    let job: Job = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let mut shared = worker.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+    (job, Offload { shared })
+}
+
+impl<T> Future for Offload<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Offload<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let done = self.shared.lock().unwrap().result.is_some();
+        f.debug_struct("Offload").field("done", &done).finish()
+    }
+}
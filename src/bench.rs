@@ -0,0 +1,251 @@
+//! Measuring how fast a sim steps on each of the ways gridsim can step a grid, so the fastest
+//! configuration for a sim and grid size can be picked from measurements.
+//!
+//! ```no_run
+//! use gridsim::{bench::Bench, rules};
+//!
+//! let measurements = Bench::new(rules::life(), |rng| rng.gen_bool(0.3))
+//!     .sizes(vec![(512, 512), (2048, 2048)])
+//!     .run_with_bits();
+//! for measurement in &measurements {
+//!     println!("{}", measurement);
+//! }
+//! ```
+
+use crate::{BitGrid, CellRng, CountRule, LifeLikeRule, Moore, Sim, SquareGrid};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// A way of stepping a grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// [`SquareGrid::step`] on a single thread.
+    Scalar,
+    /// [`SquareGrid::step`] on every thread when the `use-rayon` feature is enabled, which is
+    /// the same as [`Backend::Scalar`] otherwise.
+    Parallel,
+    /// [`SquareGrid::step`] with tiles of `(height, width)` cells.
+    Tiled((usize, usize)),
+    /// [`SquareGrid::step_simd`], which requires a [`CountRule`].
+    Simd,
+    /// A [`BitGrid`], which requires a [`LifeLikeRule`].
+    Bits,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Scalar => write!(f, "scalar"),
+            Backend::Parallel => write!(f, "parallel"),
+            Backend::Tiled((h, w)) => write!(f, "tiled {}x{}", h, w),
+            Backend::Simd => write!(f, "simd"),
+            Backend::Bits => write!(f, "bits"),
+        }
+    }
+}
+
+/// How long a backend took to step a grid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub backend: Backend,
+    /// The `(height, width)` of the grid.
+    pub dims: (usize, usize),
+    /// The number of steps which were timed.
+    pub steps: u64,
+    /// The time taken by all of the steps.
+    pub elapsed: Duration,
+}
+
+impl Measurement {
+    /// The average time taken by a step.
+    pub fn per_step(&self) -> Duration {
+        self.elapsed.div_f64(self.steps as f64)
+    }
+
+    /// The number of cells stepped per second.
+    pub fn cells_per_second(&self) -> f64 {
+        (self.dims.0 * self.dims.1) as f64 * self.steps as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} {}: {:?} per step, {:.3e} cells/s",
+            self.dims.0,
+            self.dims.1,
+            self.backend,
+            self.per_step(),
+            self.cells_per_second()
+        )
+    }
+}
+
+/// Measures a sim on random grids of several sizes with each backend it can run on.
+///
+/// Every grid is stepped once before it is timed, so buffers are allocated outside of the
+/// timed steps. Build with `--release` for meaningful numbers.
+pub struct Bench<S, F>
+where
+    S: Sim<Moore>,
+{
+    sim: S,
+    cell: F,
+    sizes: Vec<(usize, usize)>,
+    tile_sizes: Vec<(usize, usize)>,
+    steps: u64,
+    seed: u64,
+}
+
+impl<S, F> Bench<S, F>
+where
+    S: Sim<Moore> + Clone + Sync + Send,
+    S::Cell: Clone + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+    F: Fn(&mut CellRng) -> S::Cell + Sync + Send,
+{
+    /// Measure grids of cells made by `cell`, with 256x256 and 1024x1024 grids, tiles of
+    /// 64x64 and 256x256 cells, and 10 timed steps.
+    pub fn new(sim: S, cell: F) -> Self {
+        Self {
+            sim,
+            cell,
+            sizes: vec![(256, 256), (1024, 1024)],
+            tile_sizes: vec![(64, 64), (256, 256)],
+            steps: 10,
+            seed: 0,
+        }
+    }
+
+    /// Set the `(height, width)` of the grids.
+    pub fn sizes(self, sizes: Vec<(usize, usize)>) -> Self {
+        Self { sizes, ..self }
+    }
+
+    /// Set the `(height, width)` of the tiles measured by [`Backend::Tiled`].
+    pub fn tile_sizes(self, tile_sizes: Vec<(usize, usize)>) -> Self {
+        Self { tile_sizes, ..self }
+    }
+
+    /// Set the number of steps which are timed on each grid.
+    pub fn steps(self, steps: u64) -> Self {
+        assert!(steps >= 1, "at least one step must be timed");
+        Self { steps, ..self }
+    }
+
+    /// Set the seed the grids are made from.
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Measure the backends which can run any sim on every size.
+    pub fn run(&self) -> Vec<Measurement> {
+        let mut backends = vec![Backend::Scalar, Backend::Parallel];
+        backends.extend(self.tile_sizes.iter().map(|&tile| Backend::Tiled(tile)));
+        self.sizes
+            .iter()
+            .flat_map(|&dims| backends.iter().map(move |&backend| (backend, dims)))
+            .map(|(backend, dims)| self.measure(backend, dims, |grid| grid.step()))
+            .collect()
+    }
+
+    /// Make a grid and time `step` on it after stepping it once.
+    fn measure(
+        &self,
+        backend: Backend,
+        dims: (usize, usize),
+        step: impl Fn(&mut SquareGrid<S>) + Sync,
+    ) -> Measurement {
+        let mut grid = SquareGrid::new_random(self.sim.clone(), dims, self.seed, &self.cell);
+        if let Backend::Tiled(tile) = backend {
+            grid.set_tile_size(Some(tile));
+        }
+        let time = |grid: &mut SquareGrid<S>| {
+            step(grid);
+            let start = Instant::now();
+            for _ in 0..self.steps {
+                step(grid);
+            }
+            start.elapsed()
+        };
+        #[cfg(feature = "use-rayon")]
+        let elapsed = if backend == Backend::Scalar {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .expect("failed to make a thread pool");
+            pool.install(|| time(&mut grid))
+        } else {
+            time(&mut grid)
+        };
+        #[cfg(not(feature = "use-rayon"))]
+        let elapsed = time(&mut grid);
+        Measurement {
+            backend,
+            dims,
+            steps: self.steps,
+            elapsed,
+        }
+    }
+}
+
+impl<S, F> Bench<S, F>
+where
+    S: CountRule + Clone + Sync + Send,
+    S::Cell: Clone + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+    F: Fn(&mut CellRng) -> S::Cell + Sync + Send,
+{
+    /// Measure the backends which can run any sim and [`Backend::Simd`] on every size.
+    pub fn run_with_simd(&self) -> Vec<Measurement> {
+        let mut measurements = self.run();
+        for &dims in &self.sizes {
+            measurements.push(self.measure(Backend::Simd, dims, SquareGrid::step_simd));
+        }
+        measurements
+    }
+}
+
+impl<F> Bench<LifeLikeRule, F>
+where
+    F: Fn(&mut CellRng) -> bool + Sync + Send,
+{
+    /// Measure every backend on every size.
+    pub fn run_with_bits(&self) -> Vec<Measurement> {
+        let mut measurements = self.run_with_simd();
+        for &dims in &self.sizes {
+            let grid = SquareGrid::new_random(self.sim, dims, self.seed, &self.cell);
+            let mut bits = BitGrid::from_cells(self.sim, grid.cells());
+            bits.step();
+            let start = Instant::now();
+            bits.step_n(self.steps);
+            measurements.push(Measurement {
+                backend: Backend::Bits,
+                dims,
+                steps: self.steps,
+                elapsed: start.elapsed(),
+            });
+        }
+        measurements
+    }
+}
+
+impl<S, F> fmt::Debug for Bench<S, F>
+where
+    S: Sim<Moore> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bench")
+            .field("sim", &self.sim)
+            .field("sizes", &self.sizes)
+            .field("tile_sizes", &self.tile_sizes)
+            .field("steps", &self.steps)
+            .field("seed", &self.seed)
+            .finish_non_exhaustive()
+    }
+}
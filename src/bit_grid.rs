@@ -0,0 +1,178 @@
+use crate::LifeLikeRule;
+use ndarray::{Array2, ArrayView2};
+
+/// A grid of two-state cells stored 64 to a word, stepped with a life-like rule.
+///
+/// Every cell in a word is updated at once by counting neighbors with bitwise adders, which is
+/// much faster than a [`SquareGrid`](crate::SquareGrid) of `bool` for the same rule.
+/// Cells beyond the edges are always dead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitGrid {
+    rule: LifeLikeRule,
+    dim: (usize, usize),
+    /// The words of each row, with bit `x % 64` of word `x / 64` holding cell `x`.
+    bits: Vec<u64>,
+    generation: u64,
+}
+
+impl BitGrid {
+    /// Make a new grid of `(height, width)` dead cells.
+    pub fn new(rule: LifeLikeRule, dim: (usize, usize)) -> Self {
+        assert!(
+            dim.0 >= 1 && dim.1 >= 1,
+            "grid is empty, which isnt allowed"
+        );
+        Self {
+            rule,
+            dim,
+            bits: vec![0; dim.0 * words(dim.1)],
+            generation: 0,
+        }
+    }
+
+    /// Make a new grid with the given cells.
+    pub fn from_cells(rule: LifeLikeRule, cells: ArrayView2<'_, bool>) -> Self {
+        let mut grid = Self::new(rule, cells.dim());
+        for (index, _) in cells.indexed_iter().filter(|&(_, &alive)| alive) {
+            grid.set(index, true);
+        }
+        grid
+    }
+
+    /// Get the rule.
+    pub fn rule(&self) -> &LifeLikeRule {
+        &self.rule
+    }
+
+    /// The `(height, width)` of the grid.
+    pub fn dim(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    /// The number of steps the grid has taken.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of live cells.
+    pub fn population(&self) -> u64 {
+        self.bits.iter().map(|word| word.count_ones() as u64).sum()
+    }
+
+    /// Get the cell at a `(y, x)` index.
+    pub fn get(&self, (y, x): (usize, usize)) -> bool {
+        let (word, bit) = self.locate((y, x));
+        self.bits[word] >> bit & 1 == 1
+    }
+
+    /// Set the cell at a `(y, x)` index.
+    pub fn set(&mut self, (y, x): (usize, usize), alive: bool) {
+        let (word, bit) = self.locate((y, x));
+        if alive {
+            self.bits[word] |= 1 << bit;
+        } else {
+            self.bits[word] &= !(1 << bit);
+        }
+    }
+
+    /// Copy the cells out of the grid.
+    pub fn cells(&self) -> Array2<bool> {
+        Array2::from_shape_fn(self.dim, |index| self.get(index))
+    }
+
+    /// Simulate one step, updating the rows in parallel when the `use-rayon` feature is
+    /// enabled and on the current thread otherwise.
+    pub fn step(&mut self) {
+        let mut next = vec![0; self.bits.len()];
+        let row_words = words(self.dim.1);
+        let step_row = |(y, row): (usize, &mut [u64])| self.step_row(y, row);
+        #[cfg(feature = "use-rayon")]
+        {
+            use rayon::prelude::*;
+            next.par_chunks_mut(row_words)
+                .enumerate()
+                .for_each(step_row);
+        }
+        #[cfg(not(feature = "use-rayon"))]
+        next.chunks_mut(row_words).enumerate().for_each(step_row);
+        self.bits = next;
+        self.generation += 1;
+    }
+
+    /// Simulate `n` steps.
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Compute the next state of a row.
+    fn step_row(&self, y: usize, next: &mut [u64]) {
+        let row_words = words(self.dim.1);
+        let row = |y: Option<usize>| match y {
+            Some(y) if y < self.dim.0 => &self.bits[y * row_words..(y + 1) * row_words],
+            _ => &[][..],
+        };
+        let rows = [row(y.checked_sub(1)), row(Some(y)), row(Some(y + 1))];
+        let word = |row: &[u64], i: Option<usize>| i.and_then(|i| row.get(i)).copied().unwrap_or(0);
+        for (i, next) in next.iter_mut().enumerate() {
+            // Each plane holds one bit of the live neighbor count of every cell in the word.
+            let mut planes = [0u64; 4];
+            let mut add = |mut carry: u64| {
+                for plane in &mut planes {
+                    let overflow = *plane & carry;
+                    *plane ^= carry;
+                    carry = overflow;
+                }
+            };
+            for (r, row) in rows.iter().enumerate() {
+                let center = word(row, Some(i));
+                // Shifting towards the high bits moves each cell onto its neighbor to the east.
+                add(center << 1 | word(row, i.checked_sub(1)) >> 63);
+                add(center >> 1 | word(row, Some(i + 1)) << 63);
+                if r != 1 {
+                    add(center);
+                }
+            }
+            let alive = word(rows[1], Some(i));
+            let mut result = 0;
+            for n in 0..=8 {
+                let count = planes.iter().enumerate().fold(!0, |count, (b, &plane)| {
+                    count & if n >> b & 1 == 1 { plane } else { !plane }
+                });
+                if self.rule.is_birth(n) {
+                    result |= count & !alive;
+                }
+                if self.rule.is_survival(n) {
+                    result |= count & alive;
+                }
+            }
+            *next = result & mask(self.dim.1, i);
+        }
+    }
+
+    /// Get the word and bit of a cell, panicking if it is outside of the grid.
+    fn locate(&self, (y, x): (usize, usize)) -> (usize, u32) {
+        assert!(
+            y < self.dim.0 && x < self.dim.1,
+            "index is outside of the grid"
+        );
+        (y * words(self.dim.1) + x / 64, (x % 64) as u32)
+    }
+}
+
+/// The number of words in a row of `width` cells.
+fn words(width: usize) -> usize {
+    width.div_ceil(64)
+}
+
+/// The bits of word `i` of a row of `width` cells which are on the grid.
+fn mask(width: usize, i: usize) -> u64 {
+    let cells = width - i * 64;
+    if cells >= 64 {
+        !0
+    } else {
+        (1 << cells) - 1
+    }
+}
@@ -0,0 +1,114 @@
+/// How cells beyond the edges of a grid are treated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Boundary<C> {
+    /// Opposite edges are connected, so flows leaving one edge enter the opposite edge.
+    Wrap,
+    /// Opposite edges are connected with a twist, so crossing the edge mirrors the other axis.
+    ///
+    /// Twisting one axis makes a Möbius strip, or a Klein bottle if the other axis wraps. The
+    /// other axis can't be twisted or shifted, which would make a projective plane or leave the
+    /// seams misaligned.
+    Twist,
+    /// Opposite edges are connected with a shift, so crossing the edge moves the other axis by
+    /// the given number of cells, making a twisted torus if the other axis wraps.
+    ///
+    /// Shifting the seam breaks up the alignment of patterns which would otherwise meet
+    /// themselves after going around the grid. The other axis can't be shifted or twisted, since
+    /// the seams wouldn't fit together and cells would be left without exactly one neighbor in
+    /// each direction.
+    Shift(isize),
+    /// Cells beyond the edge copy the nearest cell on the edge.
+    Clamp,
+    /// Cells beyond the edge are a constant cell.
+    Constant(C),
+    /// Cells beyond the edge mirror the cells inside it, not including the edge itself.
+    Reflect,
+    /// Cells beyond the edge are ghost cells which are only changed by
+    /// [`SquareGrid::set_halo`](crate::SquareGrid::set_halo), such as to copy the edge of a
+    /// neighboring grid on another machine. Flows sent across the edge are dropped.
+    Halo,
+}
+
+impl<C> Boundary<C> {
+    /// Map a position on an axis which may be beyond the edges to the position on the axis
+    /// it copies, or `None` if it is constant or a ghost cell.
+    pub fn map(&self, position: isize, len: usize) -> Option<usize> {
+        let len = len as isize;
+        let position = match self {
+            Boundary::Wrap | Boundary::Twist | Boundary::Shift(_) => position.rem_euclid(len),
+            Boundary::Clamp => position.clamp(0, len - 1),
+            Boundary::Constant(_) | Boundary::Halo => {
+                if (0..len).contains(&position) {
+                    position
+                } else {
+                    return None;
+                }
+            }
+            Boundary::Reflect => {
+                let period = 2 * (len - 1);
+                let position = position.rem_euclid(period.max(1));
+                if position < len {
+                    position
+                } else {
+                    period - position
+                }
+            }
+        };
+        Some(position as usize)
+    }
+
+    /// Check if the other axis is mirrored at a position on this axis which may be beyond the edges.
+    pub fn twists(&self, position: isize, len: usize) -> bool {
+        matches!(self, Boundary::Twist) && position.div_euclid(len as isize) & 1 == 1
+    }
+
+    /// Get how far the other axis is moved at a position on this axis which may be beyond the edges.
+    pub fn shift(&self, position: isize, len: usize) -> isize {
+        match self {
+            Boundary::Shift(shift) => position.div_euclid(len as isize) * shift,
+            _ => 0,
+        }
+    }
+
+    /// Check if the boundaries of the `y` and `x` axes can be used together, panicking if they
+    /// can't.
+    pub(crate) fn check_pair(y: &Self, x: &Self) {
+        let seam = |boundary: &Self| matches!(boundary, Boundary::Shift(_) | Boundary::Twist);
+        assert!(
+            !seam(y) || !seam(x),
+            "only one axis can have a shifted or twisted boundary"
+        );
+    }
+
+    /// Check if opposite edges are connected, so that flows can cross them.
+    pub fn connects(&self) -> bool {
+        matches!(self, Boundary::Wrap | Boundary::Twist | Boundary::Shift(_))
+    }
+}
+
+/// A side of a grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Side {
+    /// The side with the lowest `y`.
+    North,
+    /// The side with the highest `y`.
+    South,
+    /// The side with the lowest `x`.
+    West,
+    /// The side with the highest `x`.
+    East,
+}
+
+impl Side {
+    /// The side on the other side of the grid.
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::North => Side::South,
+            Side::South => Side::North,
+            Side::West => Side::East,
+            Side::East => Side::West,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{Location, Moore, Neighborhood, SquareNeighborhood, WindowBounds};
+use ndarray::{ArrayView2, IndexLonger};
+use std::marker::PhantomData;
+
+/// A neighborhood which can tell cells on the grid apart from cells beyond its edges.
+///
+/// The edges are the same as the wrapped neighborhood, but neighbors beyond an edge of the
+/// grid that doesn't wrap are seen as `None`.
+pub struct Bounded<N = Moore>(PhantomData<N>);
+
+impl<N> Neighborhood for Bounded<N>
+where
+    N: SquareNeighborhood,
+{
+    type Neighbors<'a, T: 'a> = BoundedNeighbors<'a, T, N>;
+    type Edges<T> = N::Edges<T>;
+}
+
+impl<N> SquareNeighborhood for Bounded<N>
+where
+    N: SquareNeighborhood,
+{
+    const RADIUS: usize = N::RADIUS;
+    const EDGES: usize = N::EDGES;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        N::delta(edge)
+    }
+
+    fn opposite(edge: usize) -> usize {
+        N::opposite(edge)
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> BoundedNeighbors<'_, T, N> {
+        let (h, w) = window.dim();
+        BoundedNeighbors {
+            window,
+            bounds: WindowBounds { y: 0..h, x: 0..w },
+            _neighborhood: PhantomData,
+        }
+    }
+
+    fn located_neighbors<T>(
+        window: ArrayView2<'_, T>,
+        location: Location,
+    ) -> BoundedNeighbors<'_, T, N> {
+        BoundedNeighbors {
+            window,
+            bounds: location.bounds,
+            _neighborhood: PhantomData,
+        }
+    }
+
+    fn edges<T>(f: impl FnMut() -> T) -> N::Edges<T> {
+        N::edges(f)
+    }
+
+    fn edges_mut<T>(edges: &mut N::Edges<T>) -> &mut [T] {
+        N::edges_mut(edges)
+    }
+}
+
+/// The neighbors of a cell, where neighbors beyond the edges of the grid are `None`.
+pub struct BoundedNeighbors<'a, T, N = Moore> {
+    window: ArrayView2<'a, T>,
+    bounds: WindowBounds,
+    _neighborhood: PhantomData<N>,
+}
+
+impl<'a, T, N> BoundedNeighbors<'a, T, N>
+where
+    N: SquareNeighborhood,
+{
+    /// Get the cell in the center.
+    pub fn center(&self) -> &'a T {
+        let r = N::RADIUS;
+        IndexLonger::index(&self.window, (r, r))
+    }
+
+    /// Get the cell at a `(y, x)` offset from the center, or `None` if it is beyond the edges.
+    pub fn get(&self, (dy, dx): (isize, isize)) -> Option<&'a T> {
+        let r = N::RADIUS as isize;
+        let index = ((r + dy) as usize, (r + dx) as usize);
+        if self.bounds.contains(index) {
+            Some(IndexLonger::index(&self.window, index))
+        } else {
+            None
+        }
+    }
+
+    /// Get the neighbor at an edge, or `None` if it is beyond the edges.
+    pub fn edge(&self, edge: usize) -> Option<&'a T> {
+        self.get(N::delta(edge))
+    }
+
+    /// Iterate over the neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = Option<&'a T>> + '_ {
+        (0..N::EDGES).map(move |edge| self.edge(edge))
+    }
+
+    /// Check if any neighbor is beyond the edges of the grid.
+    pub fn at_edge(&self) -> bool {
+        let width = 2 * N::RADIUS + 1;
+        self.bounds.y != (0..width) || self.bounds.x != (0..width)
+    }
+
+    /// Get the whole window, including any padding beyond the edges.
+    pub fn window(&self) -> ArrayView2<'a, T> {
+        self.window
+    }
+
+    /// Get the part of the window which is on the grid.
+    pub fn bounds(&self) -> &WindowBounds {
+        &self.bounds
+    }
+}
+
+impl<'a, T, N> Clone for BoundedNeighbors<'a, T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window,
+            bounds: self.bounds.clone(),
+            _neighborhood: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{GenerationsRule, LifeLikeRule, Moore, Sim};
+use ndarray::ArrayView2;
+use std::{fmt, sync::Arc};
+
+/// The function of a [`BoxedRule`].
+type RuleFn<C> = Arc<dyn Fn(ArrayView2<'_, C>) -> C + Send + Sync>;
+
+/// A rule chosen at runtime, which gives the next state of a cell from the 3x3 window of
+/// cells centered on it.
+///
+/// Every rule with the same cell type is the same type, so a single
+/// [`SquareGrid`](crate::SquareGrid) type can switch rules between steps through
+/// [`SquareGrid::sim_mut`](crate::SquareGrid::sim_mut). This costs a dynamic call per cell,
+/// so rules known at compile time should implement [`Sim`] instead.
+pub struct BoxedRule<C> {
+    rule: RuleFn<C>,
+    padding: C,
+}
+
+impl<C> BoxedRule<C> {
+    /// Make a rule from a function of the window around a cell, using `padding` as the cell
+    /// beyond the edges of the grid.
+    pub fn new(padding: C, rule: impl Fn(ArrayView2<'_, C>) -> C + Send + Sync + 'static) -> Self {
+        Self {
+            rule: Arc::new(rule),
+            padding,
+        }
+    }
+
+    /// Get the next state of a cell from the window centered on it.
+    pub fn next(&self, window: ArrayView2<'_, C>) -> C {
+        (self.rule)(window)
+    }
+}
+
+/// Clones share the same function.
+impl<C> Clone for BoxedRule<C>
+where
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            rule: self.rule.clone(),
+            padding: self.padding.clone(),
+        }
+    }
+}
+
+impl<C> fmt::Debug for BoxedRule<C>
+where
+    C: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedRule")
+            .field("padding", &self.padding)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<LifeLikeRule> for BoxedRule<bool> {
+    fn from(rule: LifeLikeRule) -> Self {
+        Self::new(rule.cell_padding(), move |window| rule.compute(window))
+    }
+}
+
+impl From<GenerationsRule> for BoxedRule<u8> {
+    fn from(rule: GenerationsRule) -> Self {
+        Self::new(rule.cell_padding(), move |window| rule.compute(window))
+    }
+}
+
+impl<C> Sim<Moore> for BoxedRule<C>
+where
+    C: Clone + 'static,
+{
+    type Cell = C;
+    type Diff = C;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, C>) -> C {
+        self.next(cells)
+    }
+
+    fn egress(&self, cell: &mut C, diffs: ArrayView2<'_, C>) -> [(); 8] {
+        *cell = diffs[(1, 1)].clone();
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut C, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> C {
+        self.padding.clone()
+    }
+
+    fn diff_padding(&self) -> C {
+        self.padding.clone()
+    }
+
+    fn flow_padding(&self) {}
+}
@@ -0,0 +1,105 @@
+use crate::{Neighborhood, Sim, SquareGrid, SquareNeighborhood};
+use itertools::Itertools;
+use ndarray::Array2;
+
+/// A simulation where cells can send a message to every cell within a radius, rather than
+/// only to their neighbors, such as a signal which would otherwise take one generation per
+/// cell to relay.
+///
+/// Before each step, every cell may emit a message. It is scattered directly to the cells
+/// within [`BroadcastSim::radius`] that [`BroadcastSim::reaches`], crossing the edges the
+/// boundaries connect, and the messages arriving at a cell are combined into one. Each cell
+/// then receives what arrived for it, and the step runs as usual.
+pub trait BroadcastSim<N>: Sim<N>
+where
+    N: Neighborhood,
+{
+    /// A message sent by a cell.
+    type Message: Clone + Send;
+
+    /// The largest number of cells a message travels along each axis.
+    fn radius(&self) -> usize;
+
+    /// Check if a message reaches the cell at a `(y, x)` delta from its sender.
+    ///
+    /// This is the whole square of [`BroadcastSim::radius`], including the sender, by default.
+    fn reaches(&self, delta: (isize, isize)) -> bool {
+        let _ = delta;
+        true
+    }
+
+    /// Get the message sent by a cell, if any.
+    fn emit(&self, cell: &Self::Cell) -> Option<Self::Message>;
+
+    /// Combine two messages which reached the same cell.
+    fn combine(&self, a: Self::Message, b: Self::Message) -> Self::Message;
+
+    /// Receive the combination of the messages which reached a cell, or `None` if none did.
+    fn receive(&self, cell: &mut Self::Cell, message: Option<Self::Message>);
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: BroadcastSim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Deliver the messages every cell emits without stepping.
+    ///
+    /// A message reaches each cell at most once, even on a wrapped grid which is narrower than
+    /// the square it is sent to.
+    pub fn broadcast(&mut self) {
+        let sim = &self.sim;
+        let mut outbox = Array2::from_shape_simple_fn(self.cells().dim(), || None);
+        maybe_par_azip!((message in &mut outbox, cell in self.cells()) {
+            *message = sim.emit(cell);
+        });
+
+        let r = sim.radius() as isize;
+        let deltas: Vec<(isize, isize)> = (-r..=r)
+            .cartesian_product(-r..=r)
+            .filter(|&delta| sim.reaches(delta))
+            .collect();
+        let (h, w) = self.cells().dim();
+        let overlaps = h <= 2 * r as usize || w <= 2 * r as usize;
+        let mut inbox: Array2<Option<S::Message>> = Array2::from_shape_simple_fn((h, w), || None);
+        let mut targets = Vec::with_capacity(deltas.len());
+        for ((y, x), message) in outbox.indexed_iter_mut() {
+            let message = match message.take() {
+                Some(message) => message,
+                None => continue,
+            };
+            targets.clear();
+            targets.extend(
+                deltas
+                    .iter()
+                    .filter_map(|&(dy, dx)| self.wrap((y as isize + dy, x as isize + dx))),
+            );
+            if overlaps {
+                targets.sort_unstable();
+                targets.dedup();
+            }
+            for &target in &targets {
+                let arrived = &mut inbox[target];
+                *arrived = Some(match arrived.take() {
+                    Some(other) => sim.combine(other, message.clone()),
+                    None => message.clone(),
+                });
+            }
+        }
+
+        let sim = &self.sim;
+        maybe_par_azip!((cell in &mut self.cells.slice_mut(&Self::inside()), message in &mut inbox) {
+            sim.receive(cell, message.take());
+        });
+        self.modified();
+    }
+
+    /// Deliver the messages every cell emits, then step.
+    pub fn step_broadcast(&mut self) {
+        self.broadcast();
+        self.step();
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{GridError, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+
+/// Memory for the cells and diffs of a [`SquareGrid`], which can be given to
+/// [`SquareGrid::with_buffers`] and taken back with [`SquareGrid::into_buffers`].
+///
+/// This allows the memory of a grid to come from an arena or a pool owned by the caller, and
+/// to be reused by the next grid once the grid is done with it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GridBuffers<C, D> {
+    /// The memory for the cells, including the padding around them.
+    pub cells: Vec<C>,
+    /// The memory for the diffs of each step, including the padding around them.
+    pub diffs: Vec<D>,
+}
+
+impl<C, D> GridBuffers<C, D> {
+    /// Make empty buffers which don't allocate until they are used.
+    pub fn new() -> Self {
+        Self {
+            cells: vec![],
+            diffs: vec![],
+        }
+    }
+
+    /// Make empty buffers with enough capacity for a grid with the `(height, width)` given
+    /// and a neighborhood with the radius given.
+    pub fn with_capacity((h, w): (usize, usize), radius: usize) -> Self {
+        let len = (h + 2 * radius) * (w + 2 * radius);
+        Self {
+            cells: Vec::with_capacity(len),
+            diffs: Vec::with_capacity(len),
+        }
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid with the `(height, width)` given in the memory of `buffers`, where
+    /// each cell is made from its `(y, x)` index. The grid is surrounded by the padding cell
+    /// of the sim like with [`SquareGrid::new`].
+    ///
+    /// Nothing is allocated if the buffers have enough capacity for the grid and its padding,
+    /// which [`GridBuffers::with_capacity`] gives them. Returns [`GridError::Empty`] if
+    /// there would be no cells.
+    pub fn with_buffers(
+        sim: S,
+        (h, w): (usize, usize),
+        buffers: GridBuffers<S::Cell, S::Diff>,
+        mut cell: impl FnMut((usize, usize)) -> S::Cell,
+    ) -> Result<Self, GridError> {
+        if h == 0 || w == 0 {
+            return Err(GridError::Empty);
+        }
+        let r = N::RADIUS;
+        let padded = (h + 2 * r, w + 2 * r);
+        let GridBuffers {
+            mut cells,
+            mut diffs,
+        } = buffers;
+        cells.clear();
+        for y in 0..padded.0 {
+            for x in 0..padded.1 {
+                cells.push(if (r..r + h).contains(&y) && (r..r + w).contains(&x) {
+                    cell((y - r, x - r))
+                } else {
+                    sim.cell_padding()
+                });
+            }
+        }
+        diffs.clear();
+        diffs.extend((0..cells.len()).map(|_| sim.diff_padding()));
+        let cells = Array2::from_shape_vec(padded, cells).unwrap();
+        let diffs = Array2::from_shape_vec(padded, diffs).unwrap();
+        let mut grid = Self::from_padded(sim, cells);
+        grid.diffs = Some(diffs);
+        Ok(grid)
+    }
+
+    /// Take the memory of the cells and diffs out of the grid, emptied, so it can be reused
+    /// with [`SquareGrid::with_buffers`].
+    pub fn into_buffers(self) -> GridBuffers<S::Cell, S::Diff> {
+        let mut cells = self.cells.into_raw_vec();
+        let mut diffs = self.diffs.map(Array2::into_raw_vec).unwrap_or_default();
+        cells.clear();
+        diffs.clear();
+        GridBuffers { cells, diffs }
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{Boundary, CellRng, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+use std::fmt;
+
+/// A setting applied to the grid once it is made.
+type Setup<S, N> = Box<dyn FnOnce(&mut SquareGrid<S, N>)>;
+
+/// Makes a cell from its `(y, x)` index.
+type IndexCell<C> = Box<dyn Fn((usize, usize)) -> C + Sync + Send>;
+
+/// Makes a cell from a generator.
+type RandomCell<C> = Box<dyn Fn(&mut CellRng) -> C + Sync + Send>;
+
+/// The boundaries of the `y` and `x` axes.
+type Boundaries<C> = (Boundary<C>, Boundary<C>);
+
+/// How the cells of a built grid are made.
+enum Init<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    Padding,
+    Cells(Array2<S::Cell>),
+    Index(IndexCell<S::Cell>),
+    Random(u64, RandomCell<S::Cell>),
+}
+
+/// Builds a [`SquareGrid`] from a sim and any number of options.
+///
+/// Made with [`SquareGrid::builder`]. Options which aren't set are left the same as with
+/// [`SquareGrid::new`].
+pub struct SquareGridBuilder<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    sim: S,
+    dims: Option<(usize, usize)>,
+    init: Init<S, N>,
+    boundary: Option<Boundaries<S::Cell>>,
+    seed: u64,
+    tile_size: Option<(usize, usize)>,
+    setup: Vec<Setup<S, N>>,
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Start building a grid for a sim.
+    ///
+    /// Either the size or the cells must be given before the grid is built.
+    pub fn builder(sim: S) -> SquareGridBuilder<S, N> {
+        SquareGridBuilder {
+            sim,
+            dims: None,
+            init: Init::Padding,
+            boundary: None,
+            seed: 0,
+            tile_size: None,
+            setup: vec![],
+        }
+    }
+}
+
+impl<S, N> SquareGridBuilder<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Set the `(height, width)` of the grid.
+    ///
+    /// Cells are the padding cell of the sim unless they are made another way.
+    pub fn size(mut self, dims: (usize, usize)) -> Self {
+        self.dims = Some(dims);
+        self
+    }
+
+    /// Start the grid with the given cells, which also sets its size.
+    pub fn cells(mut self, cells: Array2<S::Cell>) -> Self {
+        self.dims = Some(cells.dim());
+        self.init = Init::Cells(cells);
+        self
+    }
+
+    /// Make each cell from its `(y, x)` index.
+    pub fn init(
+        mut self,
+        cell: impl Fn((usize, usize)) -> S::Cell + Sync + Send + 'static,
+    ) -> Self {
+        self.init = Init::Index(Box::new(cell));
+        self
+    }
+
+    /// Make each cell like [`SquareGrid::new_random`] does.
+    pub fn random(
+        mut self,
+        seed: u64,
+        cell: impl Fn(&mut CellRng) -> S::Cell + Sync + Send + 'static,
+    ) -> Self {
+        self.init = Init::Random(seed, Box::new(cell));
+        self
+    }
+
+    /// Use the same boundary on every edge.
+    pub fn boundary(self, boundary: Boundary<S::Cell>) -> Self
+    where
+        S::Cell: Clone,
+    {
+        self.boundaries(boundary.clone(), boundary)
+    }
+
+    /// Use a boundary for each axis, like [`SquareGrid::with_boundaries`].
+    pub fn boundaries(mut self, y: Boundary<S::Cell>, x: Boundary<S::Cell>) -> Self {
+        self.boundary = Some((y, x));
+        self
+    }
+
+    /// Set the seed used to make random numbers for each cell.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Step the grid in tiles, like [`SquareGrid::set_tile_size`].
+    pub fn tile_size(mut self, tile_size: (usize, usize)) -> Self {
+        self.tile_size = Some(tile_size);
+        self
+    }
+
+    /// Keep the changes of the last `n` steps, like [`SquareGrid::keep_last`].
+    pub fn history(mut self, n: usize) -> Self
+    where
+        S::Cell: Clone + PartialEq,
+    {
+        self.setup.push(Box::new(move |grid| grid.keep_last(n)));
+        self
+    }
+
+    /// Add a hook like [`SquareGrid::on_before_step`].
+    pub fn on_before_step(mut self, hook: impl FnMut(&SquareGrid<S, N>) + Send + 'static) -> Self {
+        self.setup
+            .push(Box::new(move |grid| grid.on_before_step(hook)));
+        self
+    }
+
+    /// Add a hook like [`SquareGrid::on_after_step`].
+    pub fn on_after_step(mut self, hook: impl FnMut(&SquareGrid<S, N>) + Send + 'static) -> Self {
+        self.setup
+            .push(Box::new(move |grid| grid.on_after_step(hook)));
+        self
+    }
+
+    /// Add a hook like [`SquareGrid::on_cell_changed`].
+    pub fn on_cell_changed(
+        mut self,
+        hook: impl FnMut((usize, usize), &S::Cell, &S::Cell) + Send + 'static,
+    ) -> Self
+    where
+        S::Cell: Clone + PartialEq,
+    {
+        self.setup
+            .push(Box::new(move |grid| grid.on_cell_changed(hook)));
+        self
+    }
+
+    /// Make the grid.
+    ///
+    /// Panics if neither the size nor the cells were given, or if the size doesn't match the
+    /// cells.
+    pub fn build(self) -> SquareGrid<S, N>
+    where
+        S::Cell: Clone,
+        S::Diff: Clone,
+    {
+        let dims = self.dims.expect("grid needs a size or cells to be built");
+        let cells = match self.init {
+            Init::Padding => Array2::from_elem(dims, self.sim.cell_padding()),
+            Init::Cells(cells) => {
+                assert_eq!(cells.dim(), dims, "size doesn't match the cells");
+                cells
+            }
+            Init::Index(cell) => {
+                let mut cells = Array2::from_elem(dims, self.sim.cell_padding());
+                maybe_par_azip!((index index, c in &mut cells) {
+                    *c = cell(index);
+                });
+                cells
+            }
+            Init::Random(seed, cell) => {
+                let mut cells = Array2::from_elem(dims, self.sim.cell_padding());
+                maybe_par_azip!((index index, c in &mut cells) {
+                    *c = cell(&mut CellRng::new(seed, crate::random::INITIAL, index));
+                });
+                cells
+            }
+        };
+        let mut grid = match self.boundary {
+            Some((y, x)) => SquareGrid::with_boundaries(self.sim, cells, y, x),
+            None => SquareGrid::new(self.sim, cells),
+        };
+        grid.set_seed(self.seed);
+        grid.set_tile_size(self.tile_size);
+        for setup in self.setup {
+            setup(&mut grid);
+        }
+        grid
+    }
+}
+
+impl<S, N> fmt::Debug for SquareGridBuilder<S, N>
+where
+    S: Sim<N> + fmt::Debug,
+    N: SquareNeighborhood,
+    S::Cell: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SquareGridBuilder")
+            .field("sim", &self.sim)
+            .field("dims", &self.dims)
+            .field("boundary", &self.boundary)
+            .field("seed", &self.seed)
+            .field("tile_size", &self.tile_size)
+            .field("setup", &self.setup.len())
+            .finish_non_exhaustive()
+    }
+}
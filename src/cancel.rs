@@ -0,0 +1,117 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use std::{
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A flag which can be set from any thread to stop a grid from stepping.
+///
+/// Clones share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Make a token which isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the steps checking this token as soon as they can.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clear the flag so the token can be used again.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error returned when a step is stopped by a [`CancelToken`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// The number of chunks of rows whose diffs are computed between checks of a token.
+const CHUNKS: usize = 16;
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Simulate one step unless `token` is cancelled first.
+    ///
+    /// The token is checked between chunks of rows while the diffs are computed, which is
+    /// before any cell changes. If it is cancelled, the grid is left as it was at the start of
+    /// the step, though hooks for the start of the step may have been called. Egress and
+    /// ingress aren't stopped once they start, and neither are update orders other than
+    /// [`UpdateOrder::Synchronous`](crate::UpdateOrder::Synchronous).
+    pub fn step_cancellable(&mut self, token: &CancelToken) -> Result<(), Cancelled> {
+        token.check()?;
+        let rows = (self.cells().nrows() / CHUNKS).max(1);
+        while !self.step_rows(rows) {
+            if token.is_cancelled() {
+                self.partial = None;
+                return Err(Cancelled);
+            }
+        }
+        Ok(())
+    }
+
+    /// Simulate `n` steps unless `token` is cancelled first, stopping at the last generation
+    /// which was finished.
+    pub fn step_n_cancellable(&mut self, n: u64, token: &CancelToken) -> Result<(), Cancelled> {
+        for _ in 0..n {
+            self.step_cancellable(token)?;
+        }
+        Ok(())
+    }
+
+    /// Like [`SquareGrid::run_until_stable`], but stopping at the last generation which was
+    /// finished if `token` is cancelled.
+    pub fn run_until_stable_cancellable(
+        &mut self,
+        max_generations: u64,
+        token: &CancelToken,
+    ) -> Result<Option<u64>, Cancelled>
+    where
+        S::Cell: PartialEq,
+    {
+        for _ in 0..max_generations {
+            let before = self.cells().to_owned();
+            self.step_cancellable(token)?;
+            if self.cells() == before {
+                return Ok(Some(self.generation - 1));
+            }
+        }
+        Ok(None)
+    }
+}
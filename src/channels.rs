@@ -0,0 +1,146 @@
+use crate::{Moore, Sim, SquareNeighborhood};
+use std::mem;
+
+/// The payloads of the channels of a [`ChannelSim`], which is a tuple with one type per
+/// channel, such as `(f32, Creature)` for a flow of water and a migration of creatures.
+///
+/// Implemented for tuples of up to four channels.
+pub trait Channels<N>: Sized
+where
+    N: SquareNeighborhood,
+{
+    /// The edges of every channel, as a tuple such as `(N::Edges<f32>, N::Edges<Creature>)`.
+    type Edges;
+
+    /// Move the edges of each channel into one flow per edge, filling it from `padding`.
+    fn zip(edges: Self::Edges, padding: impl FnMut() -> Self) -> N::Edges<Self>;
+
+    /// Move the flows of each edge back out into the edges of each channel, filling them from
+    /// `padding`.
+    fn unzip(flows: N::Edges<Self>, padding: impl FnMut() -> Self) -> Self::Edges;
+}
+
+macro_rules! impl_channels {
+    ($($channel:ident $i:tt),+) => {
+        impl<N, $($channel),+> Channels<N> for ($($channel,)+)
+        where
+            N: SquareNeighborhood,
+        {
+            type Edges = ($(N::Edges<$channel>,)+);
+
+            fn zip(mut edges: Self::Edges, mut padding: impl FnMut() -> Self) -> N::Edges<Self> {
+                let mut edge = 0;
+                N::edges(|| {
+                    let mut flow = padding();
+                    $(mem::swap(&mut flow.$i, &mut N::edges_mut(&mut edges.$i)[edge]);)+
+                    edge += 1;
+                    flow
+                })
+            }
+
+            fn unzip(mut flows: N::Edges<Self>, mut padding: impl FnMut() -> Self) -> Self::Edges {
+                let flows = N::edges_mut(&mut flows);
+                ($({
+                    let mut edge = 0;
+                    N::edges(|| {
+                        let mut payload = padding().$i;
+                        mem::swap(&mut payload, &mut flows[edge].$i);
+                        edge += 1;
+                        payload
+                    })
+                },)+)
+            }
+        }
+    };
+}
+
+impl_channels!(A 0);
+impl_channels!(A 0, B 1);
+impl_channels!(A 0, B 1, C 2);
+impl_channels!(A 0, B 1, C 2, D 3);
+
+/// A sim which moves several independent kinds of payload to its neighbors, each on its own
+/// channel with its own type, instead of putting all of them in one flow. Grids run it with
+/// [`Channeled`].
+///
+/// Each channel is sent to and received from every edge separately, so a sim which only
+/// moves one kind of payload to a neighbor fills the other channels of that edge with padding.
+pub trait ChannelSim<N = Moore>
+where
+    N: SquareNeighborhood,
+{
+    /// The cells of the grid.
+    type Cell: 'static;
+    /// Result of the neighbor-observing computation.
+    type Diff: 'static;
+    /// The payload of each channel, as a tuple such as `(f32, Creature)`.
+    type Channels: Channels<N>;
+
+    /// Compute the diff of the cell in the center of the neighbors, like [`Sim::compute`].
+    fn compute(&self, cells: N::Neighbors<'_, Self::Cell>) -> Self::Diff;
+
+    /// Change the cell based on the diffs and get the payloads sent to each edge on every
+    /// channel, such as `(water, creatures)`.
+    fn egress(
+        &self,
+        cell: &mut Self::Cell,
+        diffs: N::Neighbors<'_, Self::Diff>,
+    ) -> <Self::Channels as Channels<N>>::Edges;
+
+    /// Receive the payloads from each edge on every channel.
+    fn ingress(&self, cell: &mut Self::Cell, channels: <Self::Channels as Channels<N>>::Edges);
+
+    /// The cell used as padding.
+    fn cell_padding(&self) -> Self::Cell;
+
+    /// The diff used as padding.
+    fn diff_padding(&self) -> Self::Diff;
+
+    /// The payload of every channel used as padding.
+    fn channel_padding(&self) -> Self::Channels;
+}
+
+/// A sim which runs a [`ChannelSim`] by moving the payloads of all of its channels for an edge
+/// in one flow.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Channeled<S>(pub S);
+
+impl<S, N> Sim<N> for Channeled<S>
+where
+    S: ChannelSim<N>,
+    N: SquareNeighborhood,
+{
+    type Cell = S::Cell;
+    type Diff = S::Diff;
+    type Flow = S::Channels;
+
+    fn compute(&self, cells: N::Neighbors<'_, S::Cell>) -> S::Diff {
+        self.0.compute(cells)
+    }
+
+    fn egress(
+        &self,
+        cell: &mut S::Cell,
+        diffs: N::Neighbors<'_, S::Diff>,
+    ) -> N::Edges<S::Channels> {
+        S::Channels::zip(self.0.egress(cell, diffs), || self.0.channel_padding())
+    }
+
+    fn ingress(&self, cell: &mut S::Cell, flows: N::Edges<S::Channels>) {
+        let channels = S::Channels::unzip(flows, || self.0.channel_padding());
+        self.0.ingress(cell, channels);
+    }
+
+    fn cell_padding(&self) -> S::Cell {
+        self.0.cell_padding()
+    }
+
+    fn diff_padding(&self) -> S::Diff {
+        self.0.diff_padding()
+    }
+
+    fn flow_padding(&self) -> S::Channels {
+        self.0.channel_padding()
+    }
+}
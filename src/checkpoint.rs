@@ -0,0 +1,277 @@
+use crate::{GridSnapshot, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// The bytes which start every checkpoint.
+const MAGIC: &[u8; 8] = b"GRIDSIM\0";
+
+/// The version of the checkpoint format written by this version of gridsim.
+pub const CHECKPOINT_VERSION: u32 = 2;
+
+/// A cell which can be written to and read from a checkpoint.
+///
+/// Cells are written one after another without any framing, so they must read back exactly
+/// the bytes they wrote. With the `serde` feature, a [`GridSnapshot`] can also be saved in
+/// any serde format instead.
+pub trait CheckpointCell: Sized {
+    /// Write the cell.
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Read a cell written by [`CheckpointCell::write_to`].
+    fn read_from<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+pub(crate) fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+macro_rules! numeric_cells {
+    ($($t:ty),*) => {$(
+        impl CheckpointCell for $t {
+            fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; std::mem::size_of::<$t>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$t>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+numeric_cells!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// Cells which are written as their RLE state.
+macro_rules! state_cells {
+    ($($t:ty),*) => {$(
+        impl $crate::CheckpointCell for $t {
+            fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                writer.write_all(&[$crate::RleCell::to_state(self)])
+            }
+
+            fn read_from<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+                let state = <u8 as $crate::CheckpointCell>::read_from(reader)?;
+                <$t as $crate::RleCell>::from_state(state)
+                    .ok_or_else(|| $crate::checkpoint::invalid("unsupported cell state"))
+            }
+        }
+    )*};
+}
+
+pub(crate) use state_cells;
+
+state_cells!(bool);
+
+/// How the cells of a checkpoint are compressed.
+///
+/// Other compression, such as zstd, can be added on top by wrapping the writer given to
+/// [`SquareGrid::write_checkpoint_with`] and the reader given to
+/// [`SquareGrid::read_checkpoint`] in an encoder and decoder.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Every cell is written.
+    #[default]
+    None,
+    /// Runs of identical cells are written once along with their length, which makes
+    /// checkpoints of mostly uniform grids much smaller.
+    RunLength,
+}
+
+/// Write a number in as few bytes as possible, seven bits at a time.
+fn write_varint(writer: &mut impl Write, mut n: u64) -> io::Result<()> {
+    while n >= 0x80 {
+        writer.write_all(&[n as u8 | 0x80])?;
+        n >>= 7;
+    }
+    writer.write_all(&[n as u8])
+}
+
+/// Read a number written by [`write_varint`].
+fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = u8::read_from(reader)?;
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(invalid("run length is too long"))
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + CheckpointCell,
+{
+    /// Write the dimensions, generation, seed, and cells of the grid to a file so the
+    /// simulation can be resumed with [`SquareGrid::load_checkpoint`].
+    ///
+    /// The checkpoint is written next to the file and then moved over it, so the file is
+    /// never left half written if the process stops.
+    pub fn save_checkpoint(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        save(path.as_ref(), |writer| self.write_checkpoint(writer))
+    }
+
+    /// Like [`SquareGrid::save_checkpoint`], but compressing the cells.
+    pub fn save_checkpoint_with(
+        &self,
+        path: impl AsRef<Path>,
+        compression: Compression,
+    ) -> io::Result<()>
+    where
+        S::Cell: PartialEq,
+    {
+        save(path.as_ref(), |writer| {
+            self.write_checkpoint_with(writer, compression)
+        })
+    }
+
+    /// Set the cells, generation, and seed of the grid from a file written by
+    /// [`SquareGrid::save_checkpoint`], resizing the grid if needed.
+    ///
+    /// The sim and boundaries aren't in checkpoints, so they are kept.
+    pub fn load_checkpoint(&mut self, path: impl AsRef<Path>) -> io::Result<()>
+    where
+        S::Cell: Clone,
+    {
+        self.read_checkpoint(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Write a checkpoint of the grid without compression.
+    ///
+    /// A checkpoint is the magic bytes `GRIDSIM\0`, the format version as a little-endian
+    /// `u32`, a byte for the [`Compression`], the height, width, generation, and seed as
+    /// little-endian `u64`s, and then the cells in row-major order.
+    pub fn write_checkpoint(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.write_header(writer, Compression::None)?;
+        for cell in self.cells() {
+            cell.write_to(writer)?;
+        }
+        writer.flush()
+    }
+
+    /// Write a checkpoint of the grid, compressing the cells.
+    ///
+    /// With [`Compression::RunLength`], each run of identical cells is written as its length
+    /// in little-endian base 128 followed by the cell.
+    pub fn write_checkpoint_with(
+        &self,
+        writer: &mut impl Write,
+        compression: Compression,
+    ) -> io::Result<()>
+    where
+        S::Cell: PartialEq,
+    {
+        if compression == Compression::None {
+            return self.write_checkpoint(writer);
+        }
+        self.write_header(writer, compression)?;
+        let mut cells = self.cells().into_iter().peekable();
+        while let Some(cell) = cells.next() {
+            let mut run = 1;
+            while cells.next_if(|&next| next == cell).is_some() {
+                run += 1;
+            }
+            write_varint(writer, run)?;
+            cell.write_to(writer)?;
+        }
+        writer.flush()
+    }
+
+    fn write_header(&self, writer: &mut impl Write, compression: Compression) -> io::Result<()> {
+        let (h, w) = self.cells().dim();
+        writer.write_all(MAGIC)?;
+        CHECKPOINT_VERSION.write_to(writer)?;
+        (compression as u8).write_to(writer)?;
+        for &n in &[h as u64, w as u64, self.generation(), self.seed()] {
+            n.write_to(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Read a checkpoint into the grid, which is left unchanged if the checkpoint is invalid.
+    ///
+    /// Checkpoints written by every earlier version of the format can be read.
+    pub fn read_checkpoint(&mut self, reader: &mut impl Read) -> io::Result<()>
+    where
+        S::Cell: Clone,
+    {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a gridsim checkpoint"));
+        }
+        let compression = match u32::read_from(reader)? {
+            1 => Compression::None,
+            2 => match u8::read_from(reader)? {
+                0 => Compression::None,
+                1 => Compression::RunLength,
+                _ => return Err(invalid("unsupported checkpoint compression")),
+            },
+            _ => return Err(invalid("unsupported checkpoint version")),
+        };
+        let h = u64::read_from(reader)? as usize;
+        let w = u64::read_from(reader)? as usize;
+        let generation = u64::read_from(reader)?;
+        let seed = u64::read_from(reader)?;
+        if h == 0 || w == 0 {
+            return Err(invalid("checkpoint has no cells"));
+        }
+        let len = h
+            .checked_mul(w)
+            .ok_or_else(|| invalid("checkpoint is too large"))?;
+        let cells = match compression {
+            Compression::None => (0..len)
+                .map(|_| S::Cell::read_from(reader))
+                .collect::<io::Result<Vec<_>>>()?,
+            Compression::RunLength => {
+                // The cells are only allocated as runs are read, since the header can't be
+                // trusted.
+                let mut cells = Vec::new();
+                while cells.len() < len {
+                    let run = read_varint(reader)?;
+                    if run == 0 || run > (len - cells.len()) as u64 {
+                        return Err(invalid("run of cells is outside of the grid"));
+                    }
+                    cells
+                        .try_reserve(run as usize)
+                        .map_err(|_| invalid("checkpoint is too large"))?;
+                    let cell = S::Cell::read_from(reader)?;
+                    cells.extend(std::iter::repeat_n(cell, run as usize));
+                }
+                cells
+            }
+        };
+        let cells = Array2::from_shape_vec((h, w), cells).expect("cells fit the dimensions");
+        let snapshot = GridSnapshot::new(generation, cells).map_err(|e| invalid(&e.to_string()))?;
+        self.restore(&snapshot)
+            .map_err(|e| invalid(&e.to_string()))?;
+        self.set_seed(seed);
+        Ok(())
+    }
+}
+
+/// Write a checkpoint next to a file and then move it over the file.
+fn save(path: &Path, write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        write(&mut writer)?;
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&temporary, path)
+    })();
+    if result.is_err() {
+        // The file may not have been made, in which case there is nothing to remove.
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
@@ -0,0 +1,124 @@
+use crate::{
+    moore::MOORE_DELTAS, BoundedNeighbors, ExtendedNeighbors, HexNeighbors, Moore3dNeighbors,
+    NdNeighbors, Neighborhood, OffsetNeighbors, Offsets, RadiusNeighbors, RandomNeighbors,
+    SquareNeighborhood, TriNeighborhood, TriNeighbors, VonNeumann3dNeighbors, VonNeumannNeighbors,
+};
+use ndarray::{ArrayView1, ArrayView2, IndexLonger};
+
+/// Combinators over the neighbors of a cell in edge order, which never include the cell itself.
+///
+/// These let rules say what they do, such as counting live neighbors or zipping neighbors with
+/// weights, and compile down to the same loops as iterating by hand. They are implemented for
+/// the neighbors of every neighborhood, including derived ones.
+///
+/// The names end in `_neighbors` since the neighbors of [`Moore`](crate::Moore) are an
+/// [`ArrayView2`], whose own `map` and `fold` visit the whole window.
+pub trait NeighborsExt {
+    /// A neighbor, such as `&T`.
+    type Item: Copy;
+
+    /// Iterate over the neighbors in edge order.
+    fn iter_neighbors(&self) -> impl Iterator<Item = Self::Item> + '_;
+
+    /// Count the neighbors for which `f` is true.
+    fn count_neighbors(&self, mut f: impl FnMut(Self::Item) -> bool) -> usize {
+        self.iter_neighbors()
+            .filter(|&neighbor| f(neighbor))
+            .count()
+    }
+
+    /// Combine the neighbors in edge order, starting from `init`.
+    fn fold_neighbors<B>(&self, init: B, f: impl FnMut(B, Self::Item) -> B) -> B {
+        self.iter_neighbors().fold(init, f)
+    }
+
+    /// Iterate over `f` of each neighbor in edge order.
+    fn map_neighbors<'s, U>(
+        &'s self,
+        f: impl FnMut(Self::Item) -> U + 's,
+    ) -> impl Iterator<Item = U> + 's {
+        self.iter_neighbors().map(f)
+    }
+
+    /// Iterate over the neighbors in edge order along with the items of `other`, such as a
+    /// weight for each edge.
+    fn zip_neighbors<'s, I>(&'s self, other: I) -> impl Iterator<Item = (Self::Item, I::Item)> + 's
+    where
+        I: IntoIterator,
+        I::IntoIter: 's,
+    {
+        self.iter_neighbors().zip(other)
+    }
+}
+
+/// Implement [`NeighborsExt`] with the `iter` method of each type.
+macro_rules! neighbors_ext {
+    ($(impl[$($g:tt)*] $t:ty $([$($w:tt)*])?;)*) => {
+        $(
+            impl<'a, $($g)*> NeighborsExt for $t $(where $($w)*)? {
+                type Item = &'a T;
+
+                fn iter_neighbors(&self) -> impl Iterator<Item = &'a T> + '_ {
+                    self.iter()
+                }
+            }
+        )*
+    };
+}
+
+neighbors_ext! {
+    impl[T] HexNeighbors<'a, T>;
+    impl[T] VonNeumannNeighbors<'a, T>;
+    impl[T] ExtendedNeighbors<'a, T>;
+    impl[T] VonNeumann3dNeighbors<'a, T>;
+    impl[T] Moore3dNeighbors<'a, T>;
+    impl[T, const R: usize] RadiusNeighbors<'a, T, R>;
+    impl[T, const D: usize] NdNeighbors<'a, T, D>;
+    impl[T, O] OffsetNeighbors<'a, T, O> [O: Offsets + 'a];
+    impl[T, N] TriNeighbors<'a, T, N> [N: TriNeighborhood + 'a];
+}
+
+/// The eight cells around the center of a 3x3 window, as seen by [`Moore`](crate::Moore).
+impl<'a, T> NeighborsExt for ArrayView2<'a, T> {
+    type Item = &'a T;
+
+    fn iter_neighbors(&self) -> impl Iterator<Item = &'a T> + '_ {
+        MOORE_DELTAS
+            .iter()
+            .map(move |&(y, x)| IndexLonger::index(self, ((1 + y) as usize, (1 + x) as usize)))
+    }
+}
+
+/// The two cells on either side of the center of a window of three, as seen by
+/// [`Line`](crate::Line).
+impl<'a, T> NeighborsExt for ArrayView1<'a, T> {
+    type Item = &'a T;
+
+    fn iter_neighbors(&self) -> impl Iterator<Item = &'a T> + '_ {
+        [2, 0].iter().map(move |&i| IndexLonger::index(self, i))
+    }
+}
+
+/// Neighbors beyond the edges of the grid are `None`.
+impl<'a, T, N> NeighborsExt for BoundedNeighbors<'a, T, N>
+where
+    N: SquareNeighborhood,
+{
+    type Item = Option<&'a T>;
+
+    fn iter_neighbors(&self) -> impl Iterator<Item = Option<&'a T>> + '_ {
+        self.iter()
+    }
+}
+
+impl<'a, T, N> NeighborsExt for RandomNeighbors<'a, T, N>
+where
+    N: Neighborhood,
+    N::Neighbors<'a, T>: NeighborsExt,
+{
+    type Item = <N::Neighbors<'a, T> as NeighborsExt>::Item;
+
+    fn iter_neighbors(&self) -> impl Iterator<Item = Self::Item> + '_ {
+        (**self).iter_neighbors()
+    }
+}
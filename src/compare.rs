@@ -0,0 +1,87 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+
+/// A cell which differs between two grids, as its `(y, x)` index, the cell before, and the
+/// cell after.
+pub type CellChange<C> = ((usize, usize), C, C);
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + PartialEq,
+{
+    /// Get every cell which differs between this grid and `other` in row-major order, with its
+    /// cell on this grid as the cell before and its cell on `other` as the cell after.
+    ///
+    /// The rows are compared in parallel when the `use-rayon` feature is enabled. The changes
+    /// can be sent to a copy of this grid and applied with [`SquareGrid::apply_diff`] to
+    /// make it match `other`.
+    ///
+    /// Panics if the grids have different dimensions.
+    pub fn diff<T, M>(&self, other: &SquareGrid<T, M>) -> Vec<CellChange<S::Cell>>
+    where
+        T: Sim<M, Cell = S::Cell>,
+        M: SquareNeighborhood,
+        S::Cell: Clone,
+    {
+        let (before, after) = (self.cells(), other.cells());
+        assert_eq!(before.dim(), after.dim(), "grids have different dimensions");
+        let row = |y: usize| -> Vec<CellChange<S::Cell>> {
+            before
+                .row(y)
+                .iter()
+                .zip(after.row(y))
+                .enumerate()
+                .filter(|(_, (before, after))| before != after)
+                .map(|(x, (before, after))| ((y, x), before.clone(), after.clone()))
+                .collect()
+        };
+        #[cfg(feature = "use-rayon")]
+        let rows: Vec<Vec<CellChange<S::Cell>>> = {
+            use rayon::prelude::*;
+            (0..before.nrows()).into_par_iter().map(row).collect()
+        };
+        #[cfg(not(feature = "use-rayon"))]
+        let rows: Vec<Vec<CellChange<S::Cell>>> = (0..before.nrows()).map(row).collect();
+        rows.concat()
+    }
+
+    /// Count the cells which differ between this grid and `other`, in parallel when the
+    /// `use-rayon` feature is enabled.
+    ///
+    /// Panics if the grids have different dimensions.
+    pub fn diff_count<T, M>(&self, other: &SquareGrid<T, M>) -> usize
+    where
+        T: Sim<M, Cell = S::Cell>,
+        M: SquareNeighborhood,
+    {
+        let (before, after) = (self.cells(), other.cells());
+        assert_eq!(before.dim(), after.dim(), "grids have different dimensions");
+        let row = |y: usize| {
+            before
+                .row(y)
+                .iter()
+                .zip(after.row(y))
+                .filter(|(before, after)| before != after)
+                .count()
+        };
+        #[cfg(feature = "use-rayon")]
+        {
+            use rayon::prelude::*;
+            (0..before.nrows()).into_par_iter().map(row).sum()
+        }
+        #[cfg(not(feature = "use-rayon"))]
+        {
+            (0..before.nrows()).map(row).sum()
+        }
+    }
+
+    /// Set each cell changed by a diff from [`SquareGrid::diff`] to its cell after.
+    ///
+    /// Panics if a change is outside of the grid.
+    pub fn apply_diff(&mut self, diff: impl IntoIterator<Item = CellChange<S::Cell>>) {
+        for (index, _, after) in diff {
+            self[index] = after;
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{Neighborhood, Sim, SquareGrid, SquareNeighborhood};
+
+/// Which of the two sims of a composed sim is used.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Phase {
+    First,
+    Second,
+}
+
+/// Combinators for building one sim out of several.
+///
+/// The sims must have the same cells, diffs, and flows. Grids of composed sims are stepped with
+/// [`SquareGrid::step_layers`], since [`SquareGrid::step`] only runs the first sim.
+pub trait Compose<N>: Sim<N> + Sized
+where
+    N: Neighborhood,
+{
+    /// Run this sim and then `next` on the result in every step.
+    ///
+    /// This is how multi-phase models like fire spread followed by wind are written without
+    /// merging them into one sim.
+    fn then<B>(self, next: B) -> Then<Self, B>
+    where
+        B: Sim<N, Cell = Self::Cell, Diff = Self::Diff, Flow = Self::Flow>,
+    {
+        Then {
+            first: self,
+            second: next,
+            phase: Phase::First,
+        }
+    }
+
+    /// Run this sim on the cells whose `(y, x)` index is in `mask` and `outside` on the rest.
+    ///
+    /// Both sims see the same cells at the start of the step.
+    fn mask<F, B>(self, mask: F, outside: B) -> Masked<Self, B, F>
+    where
+        F: Fn((usize, usize)) -> bool,
+        B: Sim<N, Cell = Self::Cell, Diff = Self::Diff, Flow = Self::Flow>,
+    {
+        Masked {
+            inside: self,
+            outside,
+            mask,
+            phase: Phase::First,
+        }
+    }
+}
+
+impl<S, N> Compose<N> for S
+where
+    S: Sim<N>,
+    N: Neighborhood,
+{
+}
+
+/// Two sims run one after the other, made with [`Compose::then`].
+#[derive(Clone, Debug)]
+pub struct Then<A, B> {
+    /// The sim which runs first.
+    pub first: A,
+    /// The sim which runs on the cells left by the first.
+    pub second: B,
+    phase: Phase,
+}
+
+/// Two sims run on different regions of the grid, made with [`Compose::mask`].
+#[derive(Clone, Debug)]
+pub struct Masked<A, B, F> {
+    /// The sim which runs inside of the mask.
+    pub inside: A,
+    /// The sim which runs outside of the mask.
+    pub outside: B,
+    /// Whether a `(y, x)` index is inside of the mask.
+    pub mask: F,
+    phase: Phase,
+}
+
+/// Implement [`Sim`] by forwarding every stage to the sim of the current phase.
+macro_rules! phased_sim {
+    ($t:ident<$a:ident, $b:ident $(, $f:ident)?>, $first:ident, $second:ident) => {
+        impl<$a, $b, $($f,)? N> Sim<N> for $t<$a, $b $(, $f)?>
+        where
+            N: Neighborhood,
+            $a: Sim<N>,
+            $b: Sim<N, Cell = $a::Cell, Diff = $a::Diff, Flow = $a::Flow>,
+        {
+            type Cell = $a::Cell;
+            type Diff = $a::Diff;
+            type Flow = $a::Flow;
+
+            fn compute(&self, cells: N::Neighbors<'_, Self::Cell>) -> Self::Diff {
+                match self.phase {
+                    Phase::First => self.$first.compute(cells),
+                    Phase::Second => self.$second.compute(cells),
+                }
+            }
+
+            fn egress(
+                &self,
+                cell: &mut Self::Cell,
+                diffs: N::Neighbors<'_, Self::Diff>,
+            ) -> N::Edges<Self::Flow> {
+                match self.phase {
+                    Phase::First => self.$first.egress(cell, diffs),
+                    Phase::Second => self.$second.egress(cell, diffs),
+                }
+            }
+
+            fn ingress(&self, cell: &mut Self::Cell, flows: N::Edges<Self::Flow>) {
+                match self.phase {
+                    Phase::First => self.$first.ingress(cell, flows),
+                    Phase::Second => self.$second.ingress(cell, flows),
+                }
+            }
+
+            fn cell_padding(&self) -> Self::Cell {
+                self.$first.cell_padding()
+            }
+
+            fn diff_padding(&self) -> Self::Diff {
+                self.$first.diff_padding()
+            }
+
+            fn flow_padding(&self) -> Self::Flow {
+                self.$first.flow_padding()
+            }
+        }
+    };
+}
+
+phased_sim!(Then<A, B>, first, second);
+phased_sim!(Masked<A, B, F>, inside, outside);
+
+impl<A, B, N> SquareGrid<Then<A, B>, N>
+where
+    A: Sim<N> + Sync,
+    B: Sim<N, Cell = A::Cell, Diff = A::Diff, Flow = A::Flow> + Sync,
+    N: SquareNeighborhood,
+    A::Cell: Send + Sync + Clone,
+    A::Diff: Send + Sync + Clone,
+    N::Edges<A::Flow>: Send,
+{
+    /// Simulate one step by running the first sim and then the second.
+    ///
+    /// Hooks see this as a single step.
+    pub fn step_layers(&mut self) {
+        let before = self.begin_step();
+        self.changed = None;
+        self.sim.phase = Phase::First;
+        self.sweep();
+        self.sim.phase = Phase::Second;
+        self.sweep();
+        self.sim.phase = Phase::First;
+        self.generation += 1;
+        self.end_step(before);
+    }
+}
+
+impl<A, B, F, N> SquareGrid<Masked<A, B, F>, N>
+where
+    A: Sim<N> + Sync,
+    B: Sim<N, Cell = A::Cell, Diff = A::Diff, Flow = A::Flow> + Sync,
+    F: Fn((usize, usize)) -> bool + Sync,
+    N: SquareNeighborhood,
+    A::Cell: Send + Sync + Clone,
+    A::Diff: Send + Sync + Clone,
+    N::Edges<A::Flow>: Send,
+{
+    /// Simulate one step by running each sim on its region.
+    ///
+    /// Both sims are run on the whole grid from the same cells and the results are merged,
+    /// so flows sent across the edge of the mask are lost.
+    pub fn step_layers(&mut self) {
+        let before = self.begin_step();
+        self.changed = None;
+        let start = self.cells().to_owned();
+        self.sim.phase = Phase::First;
+        self.sweep();
+        let inside = self.cells().to_owned();
+        self.cells.slice_mut(&Self::inside()).assign(&start);
+        self.sim.phase = Phase::Second;
+        self.sweep();
+        self.sim.phase = Phase::First;
+        let mask = &self.sim.mask;
+        maybe_par_azip!((index index, cell in &mut self.cells.slice_mut(&Self::inside()), new in &inside) {
+            if mask(index) {
+                *cell = new.clone();
+            }
+        });
+        if let Some(population) = &mut self.population {
+            population.invalidate();
+        }
+        self.generation += 1;
+        self.end_step(before);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use std::{fmt, sync::mpsc::Sender};
+
+/// The part of a step in which a conserved quantity changed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// A cell and the flows it sent out didn't add up to the cell it started as.
+    Egress,
+    /// A cell didn't add up to the cell it started as and the flows it received.
+    Ingress,
+    /// The whole grid changed from one generation to the next, such as from flows lost across
+    /// an edge or padding flows which aren't empty.
+    Step,
+}
+
+/// A change of a conserved quantity found by a [`Conserved`] sim.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Leak {
+    pub stage: Stage,
+    /// The quantity before the stage.
+    pub before: f64,
+    /// The quantity after the stage.
+    pub after: f64,
+}
+
+impl fmt::Display for Leak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match self.stage {
+            Stage::Egress => "egress",
+            Stage::Ingress => "ingress",
+            Stage::Step => "the step",
+        };
+        write!(
+            f,
+            "conserved quantity changed from {} to {} during {}",
+            self.before, self.after, stage
+        )
+    }
+}
+
+/// A sim which checks that the sim inside of it conserves a quantity, like mass or money,
+/// which is measured on both cells and flows.
+///
+/// Every egress and ingress is checked on its own, so a leak is caught in the stage and cell
+/// where it happens. [`SquareGrid::step_conserved`] also checks the total of the whole grid,
+/// which catches flows lost across edges. Leaks panic unless they are sent to a channel with
+/// [`Conserved::report_to`].
+///
+/// The checks cost two measurements of each cell and its flows in each stage, so this is meant
+/// for debugging and tests.
+pub struct Conserved<S, C, F> {
+    /// The sim which is checked.
+    pub sim: S,
+    cell: C,
+    flow: F,
+    tolerance: f64,
+    reports: Option<Sender<Leak>>,
+}
+
+impl<S, C, F> Conserved<S, C, F> {
+    /// Check `sim`, measuring the quantity held by cells with `cell` and carried by flows with
+    /// `flow`.
+    ///
+    /// Changes are allowed up to a tolerance of `1e-9`.
+    pub fn new(sim: S, cell: C, flow: F) -> Self {
+        Self {
+            sim,
+            cell,
+            flow,
+            tolerance: 1e-9,
+            reports: None,
+        }
+    }
+
+    /// Allow the quantity to change by up to `tolerance`, such as from rounding.
+    pub fn tolerance(self, tolerance: f64) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    /// Send leaks to a channel instead of panicking.
+    pub fn report_to(self, reports: Sender<Leak>) -> Self {
+        Self {
+            reports: Some(reports),
+            ..self
+        }
+    }
+
+    fn check(&self, stage: Stage, before: f64, after: f64) {
+        if (after - before).abs() <= self.tolerance {
+            return;
+        }
+        let leak = Leak {
+            stage,
+            before,
+            after,
+        };
+        match &self.reports {
+            // The receiver may be gone, in which case nobody is listening for leaks.
+            Some(reports) => drop(reports.send(leak)),
+            None => panic!("{}", leak),
+        }
+    }
+}
+
+impl<S, C, F> Clone for Conserved<S, C, F>
+where
+    S: Clone,
+    C: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sim: self.sim.clone(),
+            cell: self.cell.clone(),
+            flow: self.flow.clone(),
+            tolerance: self.tolerance,
+            reports: self.reports.clone(),
+        }
+    }
+}
+
+impl<S, C, F> fmt::Debug for Conserved<S, C, F>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Conserved")
+            .field("sim", &self.sim)
+            .field("tolerance", &self.tolerance)
+            .field("reports", &self.reports.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, C, F, N> Sim<N> for Conserved<S, C, F>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    C: Fn(&S::Cell) -> f64,
+    F: Fn(&S::Flow) -> f64,
+{
+    type Cell = S::Cell;
+    type Diff = S::Diff;
+    type Flow = S::Flow;
+
+    fn compute(&self, cells: N::Neighbors<'_, S::Cell>) -> S::Diff {
+        self.sim.compute(cells)
+    }
+
+    fn egress(&self, cell: &mut S::Cell, diffs: N::Neighbors<'_, S::Diff>) -> N::Edges<S::Flow> {
+        let before = (self.cell)(cell);
+        let mut flows = self.sim.egress(cell, diffs);
+        let sent: f64 = N::edges_mut(&mut flows).iter().map(&self.flow).sum();
+        self.check(Stage::Egress, before, (self.cell)(cell) + sent);
+        flows
+    }
+
+    fn ingress(&self, cell: &mut S::Cell, mut flows: N::Edges<S::Flow>) {
+        let received: f64 = N::edges_mut(&mut flows).iter().map(&self.flow).sum();
+        let before = (self.cell)(cell) + received;
+        self.sim.ingress(cell, flows);
+        self.check(Stage::Ingress, before, (self.cell)(cell));
+    }
+
+    fn cell_padding(&self) -> S::Cell {
+        self.sim.cell_padding()
+    }
+
+    fn diff_padding(&self) -> S::Diff {
+        self.sim.diff_padding()
+    }
+
+    fn flow_padding(&self) -> S::Flow {
+        self.sim.flow_padding()
+    }
+}
+
+impl<S, C, F, N> SquareGrid<Conserved<S, C, F>, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    C: Fn(&S::Cell) -> f64 + Sync,
+    F: Fn(&S::Flow) -> f64 + Sync,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// The total of the conserved quantity over every cell of the grid.
+    pub fn total(&self) -> f64 {
+        let sim = self.sim();
+        self.cells().iter().map(|cell| (sim.cell)(cell)).sum()
+    }
+
+    /// Simulate one step and check that the total of the conserved quantity didn't change.
+    pub fn step_conserved(&mut self) {
+        let before = self.total();
+        self.step();
+        self.sim().check(Stage::Step, before, self.total());
+    }
+}
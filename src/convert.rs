@@ -0,0 +1,103 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+use std::{error::Error, fmt, mem};
+
+/// An error encountered while making a grid from rows of cells.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RowsError {
+    /// There are no rows or the first row has no cells.
+    Empty,
+    /// A row has a different length than the first row.
+    Ragged {
+        /// The index of the row.
+        row: usize,
+        /// The length of the row.
+        len: usize,
+        /// The length of the first row.
+        expected: usize,
+    },
+}
+
+impl fmt::Display for RowsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowsError::Empty => write!(f, "grid has no cells"),
+            RowsError::Ragged { row, len, expected } => write!(
+                f,
+                "row {} has {} cells, but the first row has {}",
+                row, len, expected
+            ),
+        }
+    }
+}
+
+impl Error for RowsError {}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid from rows of cells, which must all be the same length.
+    pub fn from_rows(sim: S, rows: Vec<Vec<S::Cell>>) -> Result<Self, RowsError> {
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(RowsError::Empty);
+        }
+        if let Some((row, cells)) = rows.iter().enumerate().find(|(_, r)| r.len() != width) {
+            return Err(RowsError::Ragged {
+                row,
+                len: cells.len(),
+                expected: width,
+            });
+        }
+        let height = rows.len();
+        let cells = Array2::from_shape_vec((height, width), rows.into_iter().flatten().collect())
+            .expect("rows are all the same length");
+        Ok(Self::new(sim, cells))
+    }
+
+    /// Copy the cells into rows, indexed by `[y][x]`.
+    pub fn to_rows(&self) -> Vec<Vec<S::Cell>>
+    where
+        S::Cell: Clone,
+    {
+        self.cells()
+            .genrows()
+            .into_iter()
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    /// Take the cells out of the grid without copying them.
+    pub fn into_cells(mut self) -> Array2<S::Cell> {
+        let sim = &self.sim;
+        self.cells
+            .slice_mut(&Self::inside())
+            .map_mut(|cell| mem::replace(cell, sim.cell_padding()))
+    }
+}
+
+/// Make a grid with the default sim.
+impl<S, N> From<Array2<S::Cell>> for SquareGrid<S, N>
+where
+    S: Sim<N> + Default,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    fn from(cells: Array2<S::Cell>) -> Self {
+        Self::new(S::default(), cells)
+    }
+}
+
+impl<S, N> From<SquareGrid<S, N>> for Array2<S::Cell>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    fn from(grid: SquareGrid<S, N>) -> Self {
+        grid.into_cells()
+    }
+}
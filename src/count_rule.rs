@@ -0,0 +1,248 @@
+#![allow(clippy::reversed_empty_ranges)]
+
+use crate::{GenerationsRule, LifeLikeRule, Moore, Sim, SquareGrid};
+use ndarray::{s, Array2, ArrayView2};
+
+/// The number of cells whose neighbors are counted at once by [`SquareGrid::step_simd`].
+const CHUNK: usize = 16;
+
+/// A rule where the next state of a cell only depends on its state and how many of its
+/// eight neighbors are alive.
+///
+/// These rules can be stepped with [`SquareGrid::step_simd`].
+pub trait CountRule: Sim<Moore> {
+    /// The number of states, which are numbered from `0`.
+    fn states(&self) -> u8;
+
+    /// Get the state of a cell.
+    fn state(&self, cell: &Self::Cell) -> u8;
+
+    /// Make a cell from a state.
+    fn cell(&self, state: u8) -> Self::Cell;
+
+    /// Check if a cell in a state counts as a live neighbor.
+    fn is_alive(&self, state: u8) -> bool;
+
+    /// Get the next state of a cell given its state and number of live neighbors.
+    fn next_state(&self, state: u8, n: usize) -> u8;
+}
+
+impl CountRule for LifeLikeRule {
+    fn states(&self) -> u8 {
+        2
+    }
+
+    fn state(&self, &cell: &bool) -> u8 {
+        cell as u8
+    }
+
+    fn cell(&self, state: u8) -> bool {
+        state == 1
+    }
+
+    fn is_alive(&self, state: u8) -> bool {
+        state == 1
+    }
+
+    fn next_state(&self, state: u8, n: usize) -> u8 {
+        self.next(state == 1, n) as u8
+    }
+}
+
+impl CountRule for GenerationsRule {
+    fn states(&self) -> u8 {
+        GenerationsRule::states(self)
+    }
+
+    fn state(&self, &cell: &u8) -> u8 {
+        cell
+    }
+
+    fn cell(&self, state: u8) -> u8 {
+        state
+    }
+
+    fn is_alive(&self, state: u8) -> bool {
+        state == 1
+    }
+
+    fn next_state(&self, state: u8, n: usize) -> u8 {
+        self.next(state, n)
+    }
+}
+
+/// The bytes which [`SquareGrid::step_simd`] reads, which are kept in the grid between steps.
+#[derive(Clone)]
+pub(crate) struct CountBuffers {
+    /// The state of every cell, including the padding.
+    states: Array2<u8>,
+    /// `1` for every cell which is alive, including the padding, and `0` for the rest.
+    alive: Array2<u8>,
+    /// The next state for each state and number of live neighbors, which is `9 * state + n`.
+    table: Vec<u8>,
+}
+
+impl<S> SquareGrid<S, Moore>
+where
+    S: CountRule + Sync,
+    S::Cell: Send + Sync + Clone,
+{
+    /// Simulate one step by counting the live neighbors of whole rows at once and looking up
+    /// the next states in a table.
+    ///
+    /// With the `simd` feature on `x86_64`, the neighbors of 16 cells are counted at once with
+    /// SSE2 instructions, and otherwise one cell at a time. The `throughput` example compares
+    /// this with [`SquareGrid::step`]. The states of the cells are copied into buffers which are
+    /// kept in the grid, so only the first step allocates. Cells outside of the
+    /// [domain](SquareGrid::set_domain) are left as they are.
+    pub fn step_simd(&mut self) {
+        let before = self.begin_step();
+        self.modified();
+        self.update_padding();
+        let sim = &self.sim;
+        let dims = self.cells.dim();
+        let buffers = match &mut self.counts {
+            Some(buffers) if buffers.states.dim() == dims => buffers,
+            counts => counts.insert(CountBuffers {
+                states: Array2::zeros(dims),
+                alive: Array2::zeros(dims),
+                table: vec![],
+            }),
+        };
+        buffers.table.clear();
+        buffers.table.extend(
+            (0..sim.states()).flat_map(|state| (0..=8).map(move |n| sim.next_state(state, n))),
+        );
+        maybe_par_azip!((state in &mut buffers.states, alive in &mut buffers.alive, cell in &self.cells) {
+            *state = sim.state(cell);
+            *alive = sim.is_alive(*state) as u8;
+        });
+        let (states, alive, table) = (&buffers.states, &buffers.alive, &buffers.table);
+        let width = dims.1 - 2;
+        let domain = self.domain.as_ref();
+        maybe_par_azip!((index y, mut row in self.cells.slice_mut(s![1..-1, 1..-1]).genrows_mut()) {
+            let rows = alive.slice(s![y..y + 3, ..]);
+            let (up, mid, down) = (row_slice(rows, 0), row_slice(rows, 1), row_slice(rows, 2));
+            let states = row_slice(states.slice(s![y + 1.., ..]), 0);
+            let inside = domain.map(|domain| domain.slice(s![y + 1, 1..-1]));
+            let mut counts = [0; CHUNK];
+            for x in (0..width).step_by(CHUNK) {
+                let counts = &mut counts[..CHUNK.min(width - x)];
+                count_neighbors([up, mid, down], x, counts);
+                for (i, &n) in counts.iter().enumerate() {
+                    if inside.as_ref().is_none_or(|inside| inside[x + i]) {
+                        let state = states[x + i + 1];
+                        row[x + i] = sim.cell(table[state as usize * 9 + n as usize]);
+                    }
+                }
+            }
+        });
+        self.generation += 1;
+        self.end_step(before);
+    }
+
+    /// Simulate one step, only updating the cells next to cells which changed in the last step.
+    ///
+    /// The changed cells are tracked from one call to the next, so sparse patterns on a large
+    /// grid only cost as much as the cells around them. Every cell is updated if the last step
+    /// wasn't taken with this method or the grid was modified since, which includes setting
+    /// halos, so grids with a [`Boundary::Halo`](crate::Boundary::Halo) don't benefit.
+    pub fn step_active(&mut self) {
+        let before = self.begin_step();
+        if let Some(population) = &mut self.population {
+            population.invalidate();
+        }
+        let mut candidates = match self.changed.take() {
+            Some(changed) => changed
+                .into_iter()
+                .flat_map(|(y, x)| {
+                    (-1..=1).flat_map(move |dy| {
+                        (-1..=1).map(move |dx| (y as isize + dy, x as isize + dx))
+                    })
+                })
+                .filter_map(|position| self.wrap(position))
+                .collect(),
+            None => {
+                let (h, w) = self.cells().dim();
+                (0..h)
+                    .flat_map(|y| (0..w).map(move |x| (y, x)))
+                    .collect::<Vec<_>>()
+            }
+        };
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.retain(|&index| self.in_domain(index));
+        self.update_padding();
+        let (sim, cells) = (&self.sim, &self.cells);
+        let next = |&(y, x): &(usize, usize)| {
+            let window = cells.slice(s![y..y + 3, x..x + 3]);
+            let state = sim.state(&window[(1, 1)]);
+            let n = window
+                .iter()
+                .filter(|cell| sim.is_alive(sim.state(cell)))
+                .count()
+                - sim.is_alive(state) as usize;
+            let next = sim.next_state(state, n);
+            if next != state {
+                Some(((y, x), next))
+            } else {
+                None
+            }
+        };
+        #[cfg(feature = "use-rayon")]
+        let updates: Vec<_> = {
+            use rayon::prelude::*;
+            candidates.par_iter().filter_map(next).collect()
+        };
+        #[cfg(not(feature = "use-rayon"))]
+        let updates: Vec<_> = candidates.iter().filter_map(next).collect();
+        for &((y, x), state) in &updates {
+            self.cells[(y + 1, x + 1)] = self.sim.cell(state);
+        }
+        self.changed = Some(updates.into_iter().map(|(index, _)| index).collect());
+        self.generation += 1;
+        self.end_step(before);
+    }
+}
+
+/// Get a row of a standard layout array as a slice.
+fn row_slice(rows: ArrayView2<'_, u8>, y: usize) -> &[u8] {
+    rows.slice_move(s![y, ..])
+        .to_slice()
+        .expect("rows are contiguous")
+}
+
+/// Count the live neighbors of the cells of the middle row from `x + 1`, given the rows above,
+/// at, and below them, where `1` is alive.
+fn count_neighbors([up, mid, down]: [&[u8]; 3], x: usize, counts: &mut [u8]) {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if counts.len() == CHUNK {
+        use std::arch::x86_64::{__m128i, _mm_add_epi8, _mm_loadu_si128, _mm_storeu_si128};
+        // SSE2 is always available on x86_64, and every load and store is of a slice which
+        // is checked to be 16 bytes long.
+        unsafe {
+            let load = |row: &[u8], x: usize| {
+                _mm_loadu_si128(row[x..x + CHUNK].as_ptr() as *const __m128i)
+            };
+            let mut sum = _mm_add_epi8(load(mid, x), load(mid, x + 2));
+            for row in [up, down] {
+                sum = _mm_add_epi8(sum, load(row, x));
+                sum = _mm_add_epi8(sum, load(row, x + 1));
+                sum = _mm_add_epi8(sum, load(row, x + 2));
+            }
+            _mm_storeu_si128(counts.as_mut_ptr() as *mut __m128i, sum);
+        }
+        return;
+    }
+    for (i, count) in counts.iter_mut().enumerate() {
+        let x = x + i;
+        *count = up[x]
+            + up[x + 1]
+            + up[x + 2]
+            + mid[x]
+            + mid[x + 2]
+            + down[x]
+            + down[x + 1]
+            + down[x + 2];
+    }
+}
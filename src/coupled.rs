@@ -0,0 +1,142 @@
+use crate::{Moore, Sim, SquareGrid, SquareNeighborhood};
+use std::fmt;
+
+/// Updates a cell of one layer from the cell of the other layer at the same index.
+type Coupling<C, O> = Box<dyn Fn(&mut C, &O) + Send + Sync>;
+
+/// Two grids over the same `(y, x)` indices whose sims can see each other's cells.
+///
+/// Before each step, every cell of each layer is given the cell of the other layer at the same
+/// index from the end of the last step, such as the heat under an agent. The sims then read it
+/// from their own cells, so both layers are stepped against the same snapshot no matter which
+/// one goes first.
+pub struct CoupledGrids<S, T, N = Moore>
+where
+    S: Sim<N>,
+    T: Sim<N>,
+    N: SquareNeighborhood,
+{
+    first: SquareGrid<S, N>,
+    second: SquareGrid<T, N>,
+    first_reads: Coupling<S::Cell, T::Cell>,
+    second_reads: Coupling<T::Cell, S::Cell>,
+}
+
+impl<S, T, N> CoupledGrids<S, T, N>
+where
+    S: Sim<N>,
+    T: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+    T::Cell: Send,
+{
+    /// Couple two grids with the same dimensions.
+    ///
+    /// `first_reads` updates a cell of the first grid from the cell of the second grid at the
+    /// same index, and `second_reads` does the opposite.
+    pub fn new(
+        first: SquareGrid<S, N>,
+        second: SquareGrid<T, N>,
+        first_reads: impl Fn(&mut S::Cell, &T::Cell) + Send + Sync + 'static,
+        second_reads: impl Fn(&mut T::Cell, &S::Cell) + Send + Sync + 'static,
+    ) -> Self {
+        assert_eq!(
+            first.cells().dim(),
+            second.cells().dim(),
+            "coupled grids must have the same dimensions"
+        );
+        Self {
+            first,
+            second,
+            first_reads: Box::new(first_reads),
+            second_reads: Box::new(second_reads),
+        }
+    }
+
+    /// Get the first grid.
+    pub fn first(&self) -> &SquareGrid<S, N> {
+        &self.first
+    }
+
+    /// Get the second grid.
+    pub fn second(&self) -> &SquareGrid<T, N> {
+        &self.second
+    }
+
+    /// Get the first grid mutably.
+    ///
+    /// It must not be resized.
+    pub fn first_mut(&mut self) -> &mut SquareGrid<S, N> {
+        &mut self.first
+    }
+
+    /// Get the second grid mutably.
+    ///
+    /// It must not be resized.
+    pub fn second_mut(&mut self) -> &mut SquareGrid<T, N> {
+        &mut self.second
+    }
+
+    /// Take the grids back out.
+    pub fn into_grids(self) -> (SquareGrid<S, N>, SquareGrid<T, N>) {
+        (self.first, self.second)
+    }
+}
+
+impl<S, T, N> CoupledGrids<S, T, N>
+where
+    S: Sim<N> + Sync,
+    T: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    T::Cell: Send + Sync + Clone,
+    T::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+    N::Edges<T::Flow>: Send,
+{
+    /// Give each layer the cells of the other from the last step and then step both.
+    ///
+    /// The cells are exchanged in parallel when the `use-rayon` feature is enabled.
+    pub fn step(&mut self) {
+        assert_eq!(
+            self.first.cells().dim(),
+            self.second.cells().dim(),
+            "coupled grids were resized"
+        );
+        let first = self.first.cells().to_owned();
+        let first_reads = &self.first_reads;
+        maybe_par_azip!((cell in &mut self.first.cells_mut(), other in self.second.cells()) {
+            first_reads(cell, other);
+        });
+        let second_reads = &self.second_reads;
+        maybe_par_azip!((cell in &mut self.second.cells_mut(), other in &first) {
+            second_reads(cell, other);
+        });
+        self.first.step();
+        self.second.step();
+    }
+
+    /// Simulate `n` steps.
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+}
+
+impl<S, T, N> fmt::Debug for CoupledGrids<S, T, N>
+where
+    S: Sim<N> + fmt::Debug,
+    T: Sim<N> + fmt::Debug,
+    N: SquareNeighborhood,
+    S::Cell: Send + fmt::Debug,
+    T::Cell: Send + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoupledGrids")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,226 @@
+use crate::Neighborhood;
+use ndarray::{ArrayView3, IndexLonger};
+
+/// A neighborhood that can be simulated on a [`CubeGrid`](crate::CubeGrid).
+///
+/// Edges are indexed the same way as `DELTAS`. On egress, each edge holds the flow sent
+/// towards that offset. On ingress, each edge holds the flow received from that offset.
+pub trait CubeNeighborhood: Neighborhood {
+    /// The `(z, y, x)` offset of each edge.
+    ///
+    /// These must be sorted so that the opposite of edge `i` is edge `DELTAS.len() - 1 - i`.
+    const DELTAS: &'static [(isize, isize, isize)];
+
+    /// Make the neighbors from a 3x3x3 window centered on the cell.
+    fn neighbors<T>(window: ArrayView3<'_, T>) -> Self::Neighbors<'_, T>;
+
+    /// Make edges by calling `f` once for each edge in order.
+    fn edges<T>(f: impl FnMut() -> T) -> Self::Edges<T>;
+
+    /// Get the edges as a slice indexed the same way as `DELTAS`.
+    fn edges_mut<T>(edges: &mut Self::Edges<T>) -> &mut [T];
+}
+
+/// The six face-adjacent cells of a cube.
+pub enum VonNeumann3d {}
+
+impl Neighborhood for VonNeumann3d {
+    type Neighbors<'a, T: 'a> = VonNeumann3dNeighbors<'a, T>;
+    type Edges<T> = [T; 6];
+}
+
+impl CubeNeighborhood for VonNeumann3d {
+    const DELTAS: &'static [(isize, isize, isize)] = &[
+        (-1, 0, 0),
+        (0, -1, 0),
+        (0, 0, -1),
+        (0, 0, 1),
+        (0, 1, 0),
+        (1, 0, 0),
+    ];
+
+    fn neighbors<T>(window: ArrayView3<'_, T>) -> VonNeumann3dNeighbors<'_, T> {
+        VonNeumann3dNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 6] {
+        [(); 6].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 6]) -> &mut [T] {
+        edges
+    }
+}
+
+/// The 26 cells sharing a face, edge, or corner with a cube.
+pub enum Moore3d {}
+
+impl Neighborhood for Moore3d {
+    type Neighbors<'a, T: 'a> = Moore3dNeighbors<'a, T>;
+    type Edges<T> = [T; 26];
+}
+
+impl CubeNeighborhood for Moore3d {
+    const DELTAS: &'static [(isize, isize, isize)] = &[
+        (-1, -1, -1),
+        (-1, -1, 0),
+        (-1, -1, 1),
+        (-1, 0, -1),
+        (-1, 0, 0),
+        (-1, 0, 1),
+        (-1, 1, -1),
+        (-1, 1, 0),
+        (-1, 1, 1),
+        (0, -1, -1),
+        (0, -1, 0),
+        (0, -1, 1),
+        (0, 0, -1),
+        (0, 0, 1),
+        (0, 1, -1),
+        (0, 1, 0),
+        (0, 1, 1),
+        (1, -1, -1),
+        (1, -1, 0),
+        (1, -1, 1),
+        (1, 0, -1),
+        (1, 0, 0),
+        (1, 0, 1),
+        (1, 1, -1),
+        (1, 1, 0),
+        (1, 1, 1),
+    ];
+
+    fn neighbors<T>(window: ArrayView3<'_, T>) -> Moore3dNeighbors<'_, T> {
+        Moore3dNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 26] {
+        [(); 26].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 26]) -> &mut [T] {
+        edges
+    }
+}
+
+/// A face of a cube, ordered the same way as the edges of [`VonNeumann3d`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CubeFace {
+    MinusZ,
+    MinusY,
+    MinusX,
+    PlusX,
+    PlusY,
+    PlusZ,
+}
+
+impl CubeFace {
+    /// All faces in edge order.
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::MinusZ,
+        CubeFace::MinusY,
+        CubeFace::MinusX,
+        CubeFace::PlusX,
+        CubeFace::PlusY,
+        CubeFace::PlusZ,
+    ];
+
+    /// The `(z, y, x)` offset of the neighbor across this face.
+    pub fn delta(self) -> (isize, isize, isize) {
+        VonNeumann3d::DELTAS[self as usize]
+    }
+
+    /// The face on the other side of the cube.
+    pub fn opposite(self) -> Self {
+        Self::ALL[5 - self as usize]
+    }
+}
+
+fn window_get<'a, T>(window: &ArrayView3<'a, T>, (z, y, x): (isize, isize, isize)) -> &'a T {
+    IndexLonger::index(
+        window,
+        ((1 + z) as usize, (1 + y) as usize, (1 + x) as usize),
+    )
+}
+
+/// The cells sharing a face with a cube, along with the cube itself.
+#[derive(Debug)]
+pub struct VonNeumann3dNeighbors<'a, T> {
+    window: ArrayView3<'a, T>,
+}
+
+impl<'a, T> Clone for VonNeumann3dNeighbors<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for VonNeumann3dNeighbors<'a, T> {}
+
+impl<'a, T> VonNeumann3dNeighbors<'a, T> {
+    /// Wraps a 3x3x3 window centered on the cube. Cells not sharing a face are ignored.
+    pub fn new(window: ArrayView3<'a, T>) -> Self {
+        assert_eq!(window.dim(), (3, 3, 3), "neighbors need a 3x3x3 window");
+        Self { window }
+    }
+
+    /// The cube in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        window_get(&self.window, (0, 0, 0))
+    }
+
+    /// The neighbor across the given face.
+    pub fn get(&self, face: CubeFace) -> &'a T {
+        window_get(&self.window, face.delta())
+    }
+
+    /// Iterate over the six neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = *self;
+        CubeFace::ALL.iter().map(move |&face| this.get(face))
+    }
+}
+
+/// The cells touching a cube, along with the cube itself.
+#[derive(Debug)]
+pub struct Moore3dNeighbors<'a, T> {
+    window: ArrayView3<'a, T>,
+}
+
+impl<'a, T> Clone for Moore3dNeighbors<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Moore3dNeighbors<'a, T> {}
+
+impl<'a, T> Moore3dNeighbors<'a, T> {
+    /// Wraps a 3x3x3 window centered on the cube.
+    pub fn new(window: ArrayView3<'a, T>) -> Self {
+        assert_eq!(window.dim(), (3, 3, 3), "neighbors need a 3x3x3 window");
+        Self { window }
+    }
+
+    /// The whole 3x3x3 window, indexed by `(z, y, x)`.
+    pub fn window(&self) -> ArrayView3<'a, T> {
+        self.window
+    }
+
+    /// The cube in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        window_get(&self.window, (0, 0, 0))
+    }
+
+    /// The neighbor at the given `(z, y, x)` offset, where each component is in `-1..=1`.
+    pub fn get(&self, delta: (isize, isize, isize)) -> &'a T {
+        window_get(&self.window, delta)
+    }
+
+    /// Iterate over the 26 neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = *self;
+        Moore3d::DELTAS.iter().map(move |&delta| this.get(delta))
+    }
+}
@@ -0,0 +1,120 @@
+#![allow(clippy::reversed_empty_ranges)]
+
+use crate::{
+    engine::{self, Lattice, Link},
+    CubeNeighborhood, Sim,
+};
+use ndarray::{s, Array3, ArrayView3, ArrayViewMut3, Ix3};
+use std::mem;
+
+/// Represents the state of a simulation on a grid of cubes.
+///
+/// Cells are indexed by `(z, y, x)`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Cell: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Cell: serde::Deserialize<'de>"
+    ))
+)]
+pub struct CubeGrid<S, N>
+where
+    S: Sim<N>,
+    N: CubeNeighborhood,
+{
+    sim: S,
+    cells: Array3<S::Cell>,
+    /// The diffs of the last step, which are kept so their allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    diffs: Option<Array3<S::Diff>>,
+}
+
+impl<S, N> CubeGrid<S, N>
+where
+    S: Sim<N>,
+    N: CubeNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid with the given cells.
+    pub fn new(sim: S, mut original_cells: Array3<S::Cell>) -> Self {
+        let dims = original_cells.dim();
+        assert!(
+            dims.0 >= 1 && dims.1 >= 1 && dims.2 >= 1,
+            "grid is empty, which isnt allowed"
+        );
+        let mut cells = Array3::from_shape_simple_fn((dims.0 + 2, dims.1 + 2, dims.2 + 2), || {
+            sim.cell_padding()
+        });
+        maybe_par_azip!((dest in &mut cells.slice_mut(s![1..-1, 1..-1, 1..-1]), cell in &mut original_cells) {
+            mem::swap(dest, cell);
+        });
+        Self {
+            sim,
+            cells,
+            diffs: None,
+        }
+    }
+
+    /// Get view of cells on the grid.
+    pub fn cells(&self) -> ArrayView3<'_, S::Cell> {
+        self.cells.slice(s![1..-1, 1..-1, 1..-1])
+    }
+
+    /// Get mutable view of cells on the grid.
+    pub fn cells_mut(&mut self) -> ArrayViewMut3<'_, S::Cell> {
+        self.cells.slice_mut(s![1..-1, 1..-1, 1..-1])
+    }
+}
+
+impl<S, N> CubeGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: CubeNeighborhood,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    N::Edges<S::Flow>: Send,
+{
+    engine::step_methods!(N);
+}
+
+impl<N> Lattice<Ix3> for N
+where
+    N: CubeNeighborhood,
+{
+    fn padding() -> Ix3 {
+        Ix3(1, 1, 1)
+    }
+
+    fn window() -> Ix3 {
+        Ix3(3, 3, 3)
+    }
+
+    fn neighbors<'a, T: 'a>(_: &[usize], window: ArrayView3<'a, T>) -> N::Neighbors<'a, T> {
+        <N as CubeNeighborhood>::neighbors(window)
+    }
+
+    fn edges<T>(f: impl FnMut() -> T) -> N::Edges<T> {
+        <N as CubeNeighborhood>::edges(f)
+    }
+
+    fn edges_mut<T>(edges: &mut N::Edges<T>) -> &mut [T] {
+        <N as CubeNeighborhood>::edges_mut(edges)
+    }
+
+    // Only the first half of the deltas are needed since each swap covers both directions.
+    fn links() -> Vec<Link> {
+        let edges = N::DELTAS.len();
+        N::DELTAS[..edges / 2]
+            .iter()
+            .enumerate()
+            .map(|(edge, &(z, y, x))| Link {
+                edge,
+                opposite: edges - 1 - edge,
+                delta: vec![z, y, x],
+                parity: None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// A loop which a simulation has entered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cycle {
+    /// The first generation of the loop.
+    pub start: u64,
+    /// The number of generations before the loop repeats, which is `1` for a fixed point.
+    pub period: u64,
+}
+
+/// Detects when a simulation enters a loop by remembering the hash of every state.
+///
+/// Different states can have the same hash, so a reported cycle is only certain up to the
+/// unlikely event of a hash collision.
+#[derive(Clone, Debug, Default)]
+pub struct CycleDetector {
+    seen: HashMap<u64, u64>,
+}
+
+impl CycleDetector {
+    /// Make a detector which hasn't seen any states.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of states seen.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Check if no states have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Forget every state seen.
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+
+    /// Observe the state at a generation, returning the cycle if it was seen before.
+    pub fn observe(&mut self, generation: u64, state: &impl Hash) -> Option<Cycle> {
+        self.observe_hash(generation, hash(state))
+    }
+
+    /// Observe the hash of the state at a generation, returning the cycle if it was seen before.
+    pub fn observe_hash(&mut self, generation: u64, hash: u64) -> Option<Cycle> {
+        let start = *self.seen.entry(hash).or_insert(generation);
+        if start == generation {
+            None
+        } else {
+            Some(Cycle {
+                start,
+                period: generation - start,
+            })
+        }
+    }
+}
+
+/// Hash a state with a hasher which gives the same hash every time.
+fn hash(state: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Hash,
+{
+    /// Hash the cells of the grid.
+    pub fn state_hash(&self) -> u64 {
+        let cells = self.cells();
+        hash(&(cells.dim(), cells.iter().collect::<Vec<_>>()))
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone + Hash,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Step until the cells repeat a state, taking at most `max_generations` steps.
+    ///
+    /// Returns the cycle the grid entered, or `None` if it didn't repeat within the limit.
+    pub fn run_until_cycle(&mut self, max_generations: u64) -> Option<Cycle> {
+        let mut detector = CycleDetector::new();
+        detector.observe_hash(self.generation, self.state_hash());
+        for _ in 0..max_generations {
+            self.step();
+            if let Some(cycle) = detector.observe_hash(self.generation, self.state_hash()) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+}
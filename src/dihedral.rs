@@ -0,0 +1,87 @@
+use crate::{ExtendedNeighbors, OffsetNeighbors, Offsets, RadiusNeighbors, VonNeumannNeighbors};
+use ndarray::{ArrayView2, Axis};
+
+/// Rotations and reflections of the neighbors of a square, which permute the neighbors without
+/// copying them.
+///
+/// A rule which must behave the same under every rotation and reflection can check a pattern
+/// against each of [`Dihedral::symmetries`] instead of spelling out all eight of them.
+pub trait Dihedral: Clone {
+    /// Turn the neighbors a quarter turn clockwise, so the neighbor above ends up to the right.
+    fn rotate_cw(&self) -> Self;
+
+    /// Turn the neighbors a quarter turn counter-clockwise, so the neighbor above ends up to the
+    /// left.
+    fn rotate_ccw(&self) -> Self;
+
+    /// Reverse the neighbors along an axis, so `Axis(0)` swaps the top and bottom and `Axis(1)`
+    /// swaps the left and right.
+    fn mirror(&self, axis: Axis) -> Self;
+
+    /// The eight rotations and reflections, which are the four quarter turns clockwise starting
+    /// from the neighbors as they are, and then the same four mirrored left to right.
+    fn symmetries(&self) -> [Self; 8] {
+        let turn1 = self.rotate_cw();
+        let turn2 = turn1.rotate_cw();
+        let turn3 = turn2.rotate_cw();
+        [
+            self.clone(),
+            turn1.clone(),
+            turn2.clone(),
+            turn3.clone(),
+            self.mirror(Axis(1)),
+            turn1.mirror(Axis(1)),
+            turn2.mirror(Axis(1)),
+            turn3.mirror(Axis(1)),
+        ]
+    }
+}
+
+/// Rotate and mirror a square window as a view with different strides.
+impl<'a, T> Dihedral for ArrayView2<'a, T> {
+    fn rotate_cw(&self) -> Self {
+        let mut view = (*self).reversed_axes();
+        view.invert_axis(Axis(1));
+        view
+    }
+
+    fn rotate_ccw(&self) -> Self {
+        let mut view = (*self).reversed_axes();
+        view.invert_axis(Axis(0));
+        view
+    }
+
+    fn mirror(&self, axis: Axis) -> Self {
+        let mut view = *self;
+        view.invert_axis(axis);
+        view
+    }
+}
+
+/// Implement [`Dihedral`] by transforming the window of each type.
+macro_rules! window_dihedral {
+    ($(impl[$($g:tt)*] $t:ty $([$($w:tt)*])?;)*) => {
+        $(
+            impl<'a, $($g)*> Dihedral for $t $(where $($w)*)? {
+                fn rotate_cw(&self) -> Self {
+                    Self::new(self.window().rotate_cw())
+                }
+
+                fn rotate_ccw(&self) -> Self {
+                    Self::new(self.window().rotate_ccw())
+                }
+
+                fn mirror(&self, axis: Axis) -> Self {
+                    Self::new(self.window().mirror(axis))
+                }
+            }
+        )*
+    };
+}
+
+window_dihedral! {
+    impl[T] VonNeumannNeighbors<'a, T>;
+    impl[T] ExtendedNeighbors<'a, T>;
+    impl[T, const R: usize] RadiusNeighbors<'a, T, R>;
+    impl[T, O] OffsetNeighbors<'a, T, O> [O: Offsets];
+}
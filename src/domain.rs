@@ -0,0 +1,60 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, ArrayView2};
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Only simulate the cells where `domain` is `true`, such as the passages of a maze, and
+    /// set every other cell to `wall`.
+    ///
+    /// Cells outside of the domain are skipped by steps, so they keep whatever they hold and
+    /// their neighbors see them as they are. They compute the padding diff, send the padding
+    /// flow, and drop the flows sent to them. Cells which are added by resizing the grid are
+    /// in the domain.
+    ///
+    /// Panics if the domain has different dimensions from the grid.
+    pub fn set_domain(&mut self, domain: ArrayView2<'_, bool>, wall: S::Cell)
+    where
+        S::Cell: Clone,
+    {
+        assert_eq!(
+            domain.dim(),
+            self.cells().dim(),
+            "domain has different dimensions from the grid"
+        );
+        let r = N::RADIUS;
+        let mut padded = Array2::from_elem(self.cells.dim(), false);
+        padded.slice_mut(&Self::inside()).assign(&domain);
+        for ((y, x), &inside) in domain.indexed_iter() {
+            if !inside {
+                self.cells[(y + r, x + r)] = wall.clone();
+            }
+        }
+        self.domain = Some(padded);
+        self.modified();
+        self.update_padding();
+    }
+
+    /// Simulate every cell again, leaving the cells outside of the domain as they are.
+    pub fn clear_domain(&mut self) {
+        self.domain = None;
+    }
+
+    /// Get which cells are in the domain, or `None` if every cell is.
+    pub fn domain(&self) -> Option<ArrayView2<'_, bool>> {
+        self.domain
+            .as_ref()
+            .map(|domain| domain.slice(&Self::inside()))
+    }
+
+    /// Check if the cell at a `(y, x)` index is in the domain, so it is simulated.
+    pub fn in_domain(&self, (y, x): (usize, usize)) -> bool {
+        let r = N::RADIUS;
+        self.domain
+            .as_ref()
+            .is_none_or(|domain| domain[(y + r, x + r)])
+    }
+}
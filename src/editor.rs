@@ -0,0 +1,160 @@
+use crate::{GridError, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, ArrayView2};
+use std::{fmt, mem, ops::Range};
+
+/// A paused grid whose cells are being edited, made with [`SquareGrid::pause`].
+///
+/// Every edit is checked against the grid before anything is changed, and the cells which
+/// were edited are remembered. Resuming the grid, or dropping the editor, rebuilds what the
+/// grid knows about its cells and calls the hooks registered with [`SquareGrid::on_edit`], so
+/// state kept alongside the grid can be brought up to date.
+pub struct Editor<'a, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Clone,
+{
+    grid: &'a mut SquareGrid<S, N>,
+    edited: Vec<(usize, usize)>,
+    /// Which cells were edited, once any are.
+    mask: Option<Array2<bool>>,
+}
+
+impl<S, N> Editor<'_, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Clone,
+{
+    /// Get view of cells on the grid, including the edits so far.
+    pub fn cells(&self) -> ArrayView2<'_, S::Cell> {
+        self.grid.cells()
+    }
+
+    /// Get the cell at a `(y, x)` index, or `None` if it is outside of the grid.
+    pub fn get(&self, index: (usize, usize)) -> Option<&S::Cell> {
+        self.grid.get(index)
+    }
+
+    /// Set the cell at a `(y, x)` index, or get [`GridError::OutOfBounds`] if it isn't on the
+    /// grid.
+    pub fn set(&mut self, index: (usize, usize), cell: S::Cell) -> Result<(), GridError> {
+        if self.grid.get(index).is_none() {
+            let dims = self.grid.cells().dim();
+            return Err(GridError::OutOfBounds { index, dims });
+        }
+        self.write(index, cell);
+        Ok(())
+    }
+
+    /// Set every cell in the `(y, x)` ranges of a region to a clone of `cell`, or get
+    /// [`GridError::Region`] if the region isn't on the grid.
+    pub fn fill_region(
+        &mut self,
+        region: (Range<usize>, Range<usize>),
+        cell: S::Cell,
+    ) -> Result<(), GridError> {
+        let (y, x) = self.grid.check_region(region)?;
+        for y in y {
+            for x in x.clone() {
+                self.write((y, x), cell.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy a pattern onto the grid with its `(0, 0)` cell at the `(y, x)` index `offset`, or
+    /// get [`GridError::Region`] if the pattern doesn't fit on the grid there.
+    ///
+    /// Unlike [`SquareGrid::stamp`], patterns never wrap around the edges.
+    pub fn stamp(
+        &mut self,
+        pattern: ArrayView2<'_, S::Cell>,
+        offset: (usize, usize),
+    ) -> Result<(), GridError> {
+        let (h, w) = pattern.dim();
+        self.grid
+            .check_region((offset.0..offset.0 + h, offset.1..offset.1 + w))?;
+        for ((y, x), cell) in pattern.indexed_iter() {
+            self.write((offset.0 + y, offset.1 + x), cell.clone());
+        }
+        Ok(())
+    }
+
+    /// Get the `(y, x)` indices of the cells edited so far, in the order they were first
+    /// edited.
+    pub fn edited(&self) -> &[(usize, usize)] {
+        &self.edited
+    }
+
+    /// Finish editing and let the grid step again, which is the same as dropping the editor.
+    pub fn resume(self) {}
+
+    /// Set a cell which is known to be on the grid.
+    fn write(&mut self, (y, x): (usize, usize), cell: S::Cell) {
+        let r = N::RADIUS;
+        self.grid.cells[(y + r, x + r)] = cell;
+        let dims = self.grid.cells().dim();
+        let mask = self
+            .mask
+            .get_or_insert_with(|| Array2::from_elem(dims, false));
+        if !mask[(y, x)] {
+            mask[(y, x)] = true;
+            self.edited.push((y, x));
+        }
+    }
+}
+
+impl<S, N> Drop for Editor<'_, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Clone,
+{
+    fn drop(&mut self) {
+        if self.edited.is_empty() {
+            return;
+        }
+        let edited = mem::take(&mut self.edited);
+        self.grid.modified();
+        self.grid.update_padding();
+        self.grid.call_edit_hooks(&edited);
+    }
+}
+
+impl<S, N> fmt::Debug for Editor<'_, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Editor")
+            .field("edited", &self.edited)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Pause the grid to edit its cells, such as from an interactive tool.
+    ///
+    /// The grid can't step until the editor is resumed or dropped, at which point everything
+    /// derived from the cells, such as the active cells, the population, and the padding, is
+    /// rebuilt. Edits aren't kept in the history, so they aren't undone by
+    /// [`SquareGrid::rollback`].
+    pub fn pause(&mut self) -> Editor<'_, S, N>
+    where
+        S::Cell: Clone,
+    {
+        Editor {
+            grid: self,
+            edited: vec![],
+            mask: None,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{Line, Sim};
+use ndarray::ArrayView1;
+
+/// An elementary cellular automaton identified by its Wolfram rule number.
+///
+/// Bit `n` of the rule number is the next state of a cell whose left, center, and right
+/// cells form the binary number `n`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementaryRule {
+    rule: u8,
+}
+
+impl ElementaryRule {
+    /// Make the rule with the given Wolfram rule number, such as `30` or `110`.
+    pub fn new(rule: u8) -> Self {
+        Self { rule }
+    }
+
+    /// The Wolfram rule number.
+    pub fn number(&self) -> u8 {
+        self.rule
+    }
+
+    /// Get the next state of a cell given the three cells centered on it.
+    pub fn apply(&self, left: bool, center: bool, right: bool) -> bool {
+        let index = (left as u8) << 2 | (center as u8) << 1 | right as u8;
+        self.rule >> index & 1 == 1
+    }
+}
+
+impl Sim<Line> for ElementaryRule {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView1<'_, bool>) -> bool {
+        self.apply(cells[0], cells[1], cells[2])
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: ArrayView1<'_, bool>) -> [(); 2] {
+        *cell = diffs[1];
+        [(); 2]
+    }
+
+    fn ingress(&self, _: &mut bool, _: [(); 2]) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
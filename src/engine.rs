@@ -0,0 +1,346 @@
+//! The parts of stepping a grid which don't depend on how many dimensions it has.
+
+use crate::{Neighborhood, Sim};
+use ndarray::{Array, ArrayView, ArrayViewMut, Axis, Dimension, IntoDimension, RemoveAxis, Slice};
+use std::{
+    cell::UnsafeCell,
+    mem::{self, ManuallyDrop},
+};
+
+/// The flows of a cell, which must be manually taken or dropped.
+pub(crate) type FlowSlot<E> = ManuallyDrop<UnsafeCell<E>>;
+
+/// An edge of a neighborhood which comes before its opposite edge.
+#[derive(Clone, Debug)]
+pub(crate) struct Link {
+    pub(crate) edge: usize,
+    /// The edge of the neighbor which points back.
+    pub(crate) opposite: usize,
+    /// The offset of the neighbor along each axis.
+    pub(crate) delta: Vec<isize>,
+    /// Whether only cells whose padded indices add up to an even (`0`) or odd (`1`) number have
+    /// this edge, for grids such as triangles whose cells have different neighbors.
+    pub(crate) parity: Option<usize>,
+}
+
+/// The slice of an axis of `len` cells without `padding` cells on each side.
+fn unpadded(len: usize, padding: usize) -> Slice {
+    Slice::from(padding..len - padding)
+}
+
+/// View the cells of an array without `padding[axis]` cells on each side of every axis.
+pub(crate) fn interior<'a, A, D>(
+    mut view: ArrayView<'a, A, D>,
+    padding: &[usize],
+) -> ArrayView<'a, A, D>
+where
+    D: Dimension,
+{
+    for (axis, &padding) in padding.iter().enumerate() {
+        let len = view.len_of(Axis(axis));
+        view.slice_axis_inplace(Axis(axis), unpadded(len, padding));
+    }
+    view
+}
+
+/// Mutably view the cells of an array without `padding[axis]` cells on each side of every
+/// axis.
+pub(crate) fn interior_mut<'a, A, D>(
+    mut view: ArrayViewMut<'a, A, D>,
+    padding: &[usize],
+) -> ArrayViewMut<'a, A, D>
+where
+    D: Dimension,
+{
+    for (axis, &padding) in padding.iter().enumerate() {
+        let len = view.len_of(Axis(axis));
+        view.slice_axis_inplace(Axis(axis), unpadded(len, padding));
+    }
+    view
+}
+
+/// How the cells of a grid stepped by [`step`] see their neighbors, which is all that differs
+/// between the grids whose cells are kept in an array with `D` axes surrounded by padding.
+pub(crate) trait Lattice<D>: Neighborhood
+where
+    D: Dimension,
+{
+    /// The number of cells of padding on each side of each axis.
+    fn padding() -> D;
+
+    /// The shape of the window of cells around each cell which its neighbors are made from.
+    fn window() -> D;
+
+    /// Make the neighbors of the cell at `index`, which doesn't count the padding, from the
+    /// window around it.
+    fn neighbors<'a, T: 'a>(index: &[usize], window: ArrayView<'a, T, D>)
+        -> Self::Neighbors<'a, T>;
+
+    /// Make edges by calling `f` once for each edge in order.
+    fn edges<T>(f: impl FnMut() -> T) -> Self::Edges<T>;
+
+    /// Get the edges as a slice.
+    fn edges_mut<T>(edges: &mut Self::Edges<T>) -> &mut [T];
+
+    /// The edges whose flows are swapped with the neighbor they point to.
+    fn links() -> Vec<Link>;
+}
+
+/// Generate `step`, `step_parallel`, and `step_in` for a grid with `sim`, `cells`, and `diffs`
+/// fields whose cells see their neighbors through the [`Lattice`] `$neighborhood`.
+macro_rules! step_methods {
+    ($neighborhood:ty) => {
+        /// Simulate one step, updating the cells in parallel when the `use-rayon` feature is
+        /// enabled and on the current thread otherwise.
+        pub fn step(&mut self) {
+            $crate::engine::step::<_, $neighborhood, _>(
+                &self.sim,
+                &mut self.cells,
+                &mut self.diffs,
+            );
+        }
+
+        /// Simulate one step, updating the cells in parallel.
+        #[cfg(feature = "use-rayon")]
+        pub fn step_parallel(&mut self) {
+            self.step();
+        }
+
+        /// Simulate one step, updating the cells in parallel on the threads of `pool`.
+        ///
+        /// This allows the number of threads used by the simulation to be limited.
+        #[cfg(feature = "use-rayon")]
+        pub fn step_in(&mut self, pool: &rayon::ThreadPool)
+        where
+            Self: Send,
+        {
+            pool.install(|| self.step());
+        }
+    };
+}
+
+pub(crate) use step_methods;
+
+/// Simulate one step of `cells`, which are surrounded by padding, overwriting the diffs of the
+/// last step if there are any and leaving the diffs of this one in their place.
+pub(crate) fn step<S, N, D>(
+    sim: &S,
+    cells: &mut Array<S::Cell, D>,
+    diffs: &mut Option<Array<S::Diff, D>>,
+) where
+    S: Sim<N> + Sync,
+    N: Lattice<D>,
+    D: RemoveAxis,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    N::Edges<S::Flow>: Send,
+{
+    let mut next = diffs
+        .take()
+        .unwrap_or_else(|| Array::from_shape_simple_fn(cells.raw_dim(), || sim.diff_padding()));
+    compute_diffs::<S, N, D>(sim, cells, &mut next);
+    let flows = perform_egress::<S, N, D>(sim, cells, next.view());
+    *diffs = Some(next);
+    perform_ingress::<S, N, D>(sim, cells, flows);
+}
+
+fn compute_diffs<S, N, D>(sim: &S, cells: &Array<S::Cell, D>, diffs: &mut Array<S::Diff, D>)
+where
+    S: Sim<N> + Sync,
+    N: Lattice<D>,
+    D: RemoveAxis,
+    S::Cell: Sync,
+    S::Diff: Send,
+{
+    let (padding, window) = (N::padding(), N::window());
+    let mut diffs = interior_mut(diffs.view_mut(), padding.slice());
+    maybe_par_azip!((index y, mut diffs in diffs.axis_iter_mut(Axis(0))) {
+        let cells = cells.slice_axis(Axis(0), Slice::from(y..y + window[0]));
+        for ((index, diff), cells) in diffs.indexed_iter_mut().zip(cells.windows(window.clone())) {
+            *diff = sim.compute(N::neighbors(full_index::<D>(y, index).slice(), cells));
+        }
+    });
+}
+
+fn perform_egress<S, N, D>(
+    sim: &S,
+    cells: &mut Array<S::Cell, D>,
+    diffs: ArrayView<'_, S::Diff, D>,
+) -> Array<FlowSlot<N::Edges<S::Flow>>, D>
+where
+    S: Sim<N> + Sync,
+    N: Lattice<D>,
+    D: RemoveAxis,
+    S::Cell: Send,
+    S::Diff: Sync,
+    N::Edges<S::Flow>: Send,
+{
+    let (padding, window) = (N::padding(), N::window());
+    let mut flows = Array::from_shape_simple_fn(cells.raw_dim(), || {
+        ManuallyDrop::new(UnsafeCell::new(N::edges(|| sim.flow_padding())))
+    });
+    let mut inner_flows = interior_mut(flows.view_mut(), padding.slice());
+    let mut inner_cells = interior_mut(cells.view_mut(), padding.slice());
+    maybe_par_azip!((index y, mut flows in inner_flows.axis_iter_mut(Axis(0)), mut cells in inner_cells.axis_iter_mut(Axis(0))) {
+        let diffs = diffs.slice_axis(Axis(0), Slice::from(y..y + window[0]));
+        let cells = flows.indexed_iter_mut().zip(cells.iter_mut());
+        for (((index, flow), cell), diffs) in cells.zip(diffs.windows(window.clone())) {
+            let index = full_index::<D>(y, index);
+            *flow.get_mut() = sim.egress(cell, N::neighbors(index.slice(), diffs));
+        }
+    });
+    exchange_flows(&mut flows, &N::links(), N::edges_mut::<S::Flow>);
+    flows
+}
+
+/// The index of a cell given its index along the first axis and its index within that slice.
+fn full_index<D>(y: usize, index: impl IntoDimension<Dim = D::Smaller>) -> D
+where
+    D: RemoveAxis,
+{
+    let index = index.into_dimension();
+    let mut full = D::zeros(index.ndim() + 1);
+    full[0] = y;
+    full.slice_mut()[1..].copy_from_slice(index.slice());
+    full
+}
+
+fn perform_ingress<S, N, D>(
+    sim: &S,
+    cells: &mut Array<S::Cell, D>,
+    flows: Array<FlowSlot<N::Edges<S::Flow>>, D>,
+) where
+    S: Sim<N> + Sync,
+    N: Lattice<D>,
+    D: RemoveAxis,
+    S::Cell: Send,
+    N::Edges<S::Flow>: Send,
+{
+    take_flows(flows, cells, N::padding().slice(), None, |cell, flows| {
+        sim.ingress(cell, flows)
+    });
+}
+
+/// Swap the flows which each pair of neighbors send each other.
+///
+/// The flows are split into chunks just big enough to contain any pair of neighbors, which is
+/// 2 cells along each axis for a radius of 1. Each pair of neighbors appears in exactly one spot
+/// in a chunk, so the flows of each pair are swapped in every chunk in parallel. Doing this again
+/// with the chunks offset by every position within a chunk exchanges all of the flows.
+///
+/// The padding must be at least as wide along each axis as the furthest link reaches.
+pub(crate) fn exchange_flows<E, F, D>(
+    flows: &mut Array<FlowSlot<E>, D>,
+    links: &[Link],
+    edges_mut: impl Fn(&mut E) -> &mut [F] + Sync,
+) where
+    E: Send,
+    D: Dimension,
+{
+    unsafe fn exchange_chunk<E, F, D>(
+        origin_parity: usize,
+        chunk: ArrayViewMut<'_, FlowSlot<E>, D>,
+        links: &[Link],
+        edges_mut: &impl Fn(&mut E) -> &mut [F],
+    ) where
+        D: Dimension,
+    {
+        for link in links {
+            // Pick the cell in the chunk so that its neighbor is also in the chunk.
+            let (mut from, mut to) = (D::zeros(chunk.ndim()), D::zeros(chunk.ndim()));
+            for (axis, &delta) in link.delta.iter().enumerate() {
+                from[axis] = (-delta).max(0) as usize;
+                to[axis] = (from[axis] as isize + delta) as usize;
+            }
+            if let Some(parity) = link.parity {
+                if (origin_parity + from.slice().iter().sum::<usize>()) % 2 != parity {
+                    continue;
+                }
+            }
+            let from = edges_mut(&mut *chunk[from].get());
+            let to = edges_mut(&mut *chunk[to].get());
+            mem::swap(&mut from[link.edge], &mut to[link.opposite]);
+        }
+    }
+
+    let ndim = flows.ndim();
+    let mut chunk_shape = D::zeros(ndim);
+    for (axis, size) in chunk_shape.slice_mut().iter_mut().enumerate() {
+        let reach = links.iter().map(|link| link.delta[axis].unsigned_abs());
+        *size = reach.max().unwrap_or(0) + 1;
+    }
+    let offsets: usize = chunk_shape.slice().iter().product();
+    for start in 0..offsets {
+        let mut view = flows.view_mut();
+        let mut offset = D::zeros(ndim);
+        let mut rest = start;
+        for axis in 0..ndim {
+            let size = chunk_shape[axis];
+            offset[axis] = rest % size;
+            view.slice_axis_inplace(Axis(axis), Slice::from(offset[axis]..));
+            rest /= size;
+        }
+        let edges_mut = &edges_mut;
+        if links.iter().all(|link| link.parity.is_none()) {
+            maybe_par_azip!((chunk in view.exact_chunks_mut(chunk_shape.clone())) {
+                unsafe { exchange_chunk(0, chunk, links, edges_mut); }
+            });
+            continue;
+        }
+        // Find the parity of the first cell of each chunk, for the links which depend on it.
+        let mut chunks = view.raw_dim();
+        for axis in 0..ndim {
+            chunks[axis] /= chunk_shape[axis];
+        }
+        let parities = Array::from_shape_fn(chunks, |index| {
+            let index = index.into_dimension();
+            let origin = (0..ndim).map(|axis| offset[axis] + index[axis] * chunk_shape[axis]);
+            origin.sum::<usize>() % 2
+        });
+        maybe_par_azip!((&parity in &parities, chunk in view.exact_chunks_mut(chunk_shape.clone())) {
+            unsafe { exchange_chunk(parity, chunk, links, edges_mut); }
+        });
+    }
+}
+
+/// Give the flows of every cell which isn't in the padding, which is `padding[axis]` cells wide
+/// on each side of every axis, to `ingress` and drop the flows of the padding and of any cell
+/// which is `false` in `active`.
+pub(crate) fn take_flows<C, E, D>(
+    mut flows: Array<FlowSlot<E>, D>,
+    cells: &mut Array<C, D>,
+    padding: &[usize],
+    active: Option<&Array<bool, D>>,
+    ingress: impl Fn(&mut C, E) + Sync,
+) where
+    C: Send,
+    E: Send,
+    D: RemoveAxis,
+{
+    let shape = cells.raw_dim();
+    let inside = |axis: usize, i: usize| (padding[axis]..shape[axis] - padding[axis]).contains(&i);
+    let take_slice = |y: usize,
+                      mut flows: ArrayViewMut<'_, FlowSlot<E>, D::Smaller>,
+                      mut cells: ArrayViewMut<'_, C, D::Smaller>| {
+        let active = active.map(|active| active.index_axis(Axis(0), y));
+        for ((index, flow), cell) in flows.indexed_iter_mut().zip(cells.iter_mut()) {
+            let index = index.into_dimension();
+            let inside = inside(0, y)
+                && (index.slice().iter().enumerate()).all(|(axis, &i)| inside(axis + 1, i))
+                && active.as_ref().is_none_or(|active| active[index]);
+            unsafe {
+                if inside {
+                    // If its not part of the padding, we run the sim here.
+                    ingress(cell, ManuallyDrop::take(flow).into_inner());
+                } else {
+                    // If this is part of the padding, we must manually drop.
+                    ManuallyDrop::drop(flow);
+                }
+            }
+        }
+    };
+    // At the end of this line, all of the manually drops MUST have been taken or dropped.
+    maybe_par_azip!((index y, flows in flows.axis_iter_mut(Axis(0)), cells in cells.axis_iter_mut(Axis(0))) {
+        take_slice(y, flows, cells);
+    });
+}
@@ -0,0 +1,43 @@
+use std::{error::Error, fmt, ops::Range};
+
+/// An error from an operation on a grid which was given dimensions, an index, or a region
+/// that doesn't fit it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridError {
+    /// The grid would have no cells.
+    Empty,
+    /// A `(y, x)` index is outside of the grid.
+    OutOfBounds {
+        /// The index.
+        index: (usize, usize),
+        /// The `(height, width)` of the grid.
+        dims: (usize, usize),
+    },
+    /// The `(y, x)` ranges of a region are reversed, or reach outside of the grid.
+    Region {
+        /// The region.
+        region: (Range<usize>, Range<usize>),
+        /// The `(height, width)` of the grid.
+        dims: (usize, usize),
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid has no cells"),
+            GridError::OutOfBounds { index, dims } => write!(
+                f,
+                "index {:?} is outside of the grid of {}x{} cells",
+                index, dims.0, dims.1
+            ),
+            GridError::Region { region, dims } => write!(
+                f,
+                "region is outside of the grid: {:?} on {}x{} cells",
+                region, dims.0, dims.1
+            ),
+        }
+    }
+}
+
+impl Error for GridError {}
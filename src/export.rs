@@ -0,0 +1,239 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, ArrayView2};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Write values as a NumPy `.npy` array of little-endian `f64` with the shape `(height, width)`,
+/// which `numpy.load` reads.
+pub fn write_npy(writer: &mut impl Write, values: ArrayView2<'_, f64>) -> io::Result<()> {
+    let (h, w) = values.dim();
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}",
+        h, w
+    );
+    // The magic string, version, header length, and header are padded to a multiple of 64
+    // bytes with spaces and end in a newline.
+    let unpadded = 10 + header.len() + 1;
+    header.extend(std::iter::repeat_n(' ', (64 - unpadded % 64) % 64));
+    header.push('\n');
+    writer.write_all(b"\x93NUMPY\x01\x00")?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for value in values {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Write named fields of values as a VTK ImageData `.vti` file, which ParaView reads, with
+/// one VTK cell of unit size per cell of the grid.
+///
+/// The `x` index of a cell is its VTK `x` coordinate and its `y` index is its VTK `y`
+/// coordinate. Panics if the fields have different dimensions.
+pub fn write_vti(
+    writer: &mut impl Write,
+    fields: &[(&str, ArrayView2<'_, f64>)],
+) -> io::Result<()> {
+    let (h, w) = fields.first().map_or((0, 0), |(_, values)| values.dim());
+    writeln!(writer, "<?xml version=\"1.0\"?>")?;
+    writeln!(
+        writer,
+        "<VTKFile type=\"ImageData\" version=\"0.1\" byte_order=\"LittleEndian\">"
+    )?;
+    let extent = format!("0 {} 0 {} 0 0", w, h);
+    writeln!(
+        writer,
+        "  <ImageData WholeExtent=\"{}\" Origin=\"0 0 0\" Spacing=\"1 1 1\">",
+        extent
+    )?;
+    writeln!(writer, "    <Piece Extent=\"{}\">", extent)?;
+    writeln!(writer, "      <CellData>")?;
+    for (name, values) in fields {
+        assert_eq!(values.dim(), (h, w), "fields have different dimensions");
+        writeln!(
+            writer,
+            "        <DataArray type=\"Float64\" Name=\"{}\" format=\"ascii\">",
+            Xml(name)
+        )?;
+        for row in values.outer_iter() {
+            write!(writer, "         ")?;
+            for value in row {
+                write!(writer, " {}", value)?;
+            }
+            writeln!(writer)?;
+        }
+        writeln!(writer, "        </DataArray>")?;
+    }
+    writeln!(writer, "      </CellData>")?;
+    writeln!(writer, "    </Piece>")?;
+    writeln!(writer, "  </ImageData>")?;
+    writeln!(writer, "</VTKFile>")
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Write a number for each cell as a NumPy `.npy` array like [`write_npy`].
+    pub fn write_npy(
+        &self,
+        writer: &mut impl Write,
+        value: impl Fn(&S::Cell) -> f64,
+    ) -> io::Result<()> {
+        write_npy(writer, self.cells().map(value).view())
+    }
+
+    /// Save a number for each cell as a NumPy `.npy` file like [`write_npy`].
+    pub fn save_npy(
+        &self,
+        path: impl AsRef<Path>,
+        value: impl Fn(&S::Cell) -> f64,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_npy(&mut writer, value)?;
+        writer.flush()
+    }
+}
+
+/// The file format that an [`Exporter`] writes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// One NumPy `.npy` file for each field.
+    Npy,
+    /// One VTK ImageData `.vti` file with every field.
+    Vti,
+}
+
+/// A field of a `C` with its name.
+type Field<C> = (String, Box<dyn Fn(&C) -> f64>);
+
+/// Writes numeric fields of the cells of a grid to files for every generation it is given,
+/// to be analyzed with tools such as ParaView or NumPy.
+///
+/// Files are named after the prefix and the generation, such as `fire_000012.vti`. NumPy files
+/// also have the name of the field, such as `fire_heat_000012.npy`.
+pub struct Exporter<C> {
+    directory: PathBuf,
+    prefix: String,
+    format: ExportFormat,
+    fields: Vec<Field<C>>,
+}
+
+impl<C> Exporter<C> {
+    /// Make an exporter which writes files starting with `prefix` into `directory`.
+    pub fn new(
+        directory: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        format: ExportFormat,
+    ) -> Self {
+        Self {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            format,
+            fields: vec![],
+        }
+    }
+
+    /// Add a field called `name` with a number for each cell.
+    pub fn add_field(&mut self, name: impl Into<String>, field: impl Fn(&C) -> f64 + 'static) {
+        self.fields.push((name.into(), Box::new(field)));
+    }
+
+    /// Write the fields of the cells of a grid to the files of its generation, returning the
+    /// paths written.
+    pub fn export<S, N>(&self, grid: &SquareGrid<S, N>) -> io::Result<Vec<PathBuf>>
+    where
+        S: Sim<N, Cell = C>,
+        N: SquareNeighborhood,
+        C: Send,
+    {
+        let generation = grid.generation();
+        let values: Vec<Array2<f64>> = self
+            .fields
+            .iter()
+            .map(|(_, field)| grid.cells().map(field))
+            .collect();
+        let mut paths = vec![];
+        match self.format {
+            ExportFormat::Npy => {
+                for ((name, _), values) in self.fields.iter().zip(&values) {
+                    let path =
+                        self.path(&format!("{}_{}_{:06}.npy", self.prefix, name, generation));
+                    let mut writer = BufWriter::new(File::create(&path)?);
+                    write_npy(&mut writer, values.view())?;
+                    writer.flush()?;
+                    paths.push(path);
+                }
+            }
+            ExportFormat::Vti => {
+                let path = self.path(&format!("{}_{:06}.vti", self.prefix, generation));
+                let fields: Vec<(&str, ArrayView2<'_, f64>)> = self
+                    .fields
+                    .iter()
+                    .zip(&values)
+                    .map(|((name, _), values)| (name.as_str(), values.view()))
+                    .collect();
+                let mut writer = BufWriter::new(File::create(&path)?);
+                write_vti(&mut writer, &fields)?;
+                writer.flush()?;
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Step the grid, then write the files of its new generation.
+    pub fn step<S, N>(&self, grid: &mut SquareGrid<S, N>) -> io::Result<Vec<PathBuf>>
+    where
+        S: Sim<N, Cell = C> + Sync,
+        N: SquareNeighborhood,
+        C: Send + Sync + Clone,
+        S::Diff: Send + Sync + Clone,
+        N::Edges<S::Flow>: Send,
+    {
+        grid.step();
+        self.export(grid)
+    }
+
+    fn path(&self, file: &str) -> PathBuf {
+        self.directory.join(file)
+    }
+}
+
+impl<C> fmt::Debug for Exporter<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Exporter")
+            .field("directory", &self.directory)
+            .field("prefix", &self.prefix)
+            .field("format", &self.format)
+            .field(
+                "fields",
+                &self.fields.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Displays text escaped for an XML attribute.
+struct Xml<'a>(&'a str);
+
+impl fmt::Display for Xml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{Neighborhood, SquareNeighborhood};
+use ndarray::{ArrayView2, IndexLonger};
+use std::ops::Index;
+
+/// The 24 cells within two cells of a square on both axes, which is the 5x5 square around it.
+pub enum Extended {}
+
+impl Neighborhood for Extended {
+    type Neighbors<'a, T: 'a> = ExtendedNeighbors<'a, T>;
+    type Edges<T> = [T; 24];
+}
+
+impl SquareNeighborhood for Extended {
+    const RADIUS: usize = 2;
+    const EDGES: usize = 24;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        ExtendedDirection::ALL[edge].delta()
+    }
+
+    fn opposite(edge: usize) -> usize {
+        ExtendedDirection::ALL[edge].opposite() as usize
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> ExtendedNeighbors<'_, T> {
+        ExtendedNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 24] {
+        [(); 24].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 24]) -> &mut [T] {
+        edges
+    }
+}
+
+/// A direction in the extended neighborhood.
+///
+/// The inner ring comes first, ordered counter-clockwise starting from east like [`Moore`](crate::Moore),
+/// followed by the outer ring ordered counter-clockwise starting from two cells east, named after
+/// the points of a sixteen-wind compass. Edges are indexed by `direction as usize`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExtendedDirection {
+    East,
+    NorthEast,
+    North,
+    NorthWest,
+    West,
+    SouthWest,
+    South,
+    SouthEast,
+    FarEast,
+    EastNorthEast,
+    FarNorthEast,
+    NorthNorthEast,
+    FarNorth,
+    NorthNorthWest,
+    FarNorthWest,
+    WestNorthWest,
+    FarWest,
+    WestSouthWest,
+    FarSouthWest,
+    SouthSouthWest,
+    FarSouth,
+    SouthSouthEast,
+    FarSouthEast,
+    EastSouthEast,
+}
+
+impl ExtendedDirection {
+    /// All directions in edge order.
+    pub const ALL: [ExtendedDirection; 24] = [
+        ExtendedDirection::East,
+        ExtendedDirection::NorthEast,
+        ExtendedDirection::North,
+        ExtendedDirection::NorthWest,
+        ExtendedDirection::West,
+        ExtendedDirection::SouthWest,
+        ExtendedDirection::South,
+        ExtendedDirection::SouthEast,
+        ExtendedDirection::FarEast,
+        ExtendedDirection::EastNorthEast,
+        ExtendedDirection::FarNorthEast,
+        ExtendedDirection::NorthNorthEast,
+        ExtendedDirection::FarNorth,
+        ExtendedDirection::NorthNorthWest,
+        ExtendedDirection::FarNorthWest,
+        ExtendedDirection::WestNorthWest,
+        ExtendedDirection::FarWest,
+        ExtendedDirection::WestSouthWest,
+        ExtendedDirection::FarSouthWest,
+        ExtendedDirection::SouthSouthWest,
+        ExtendedDirection::FarSouth,
+        ExtendedDirection::SouthSouthEast,
+        ExtendedDirection::FarSouthEast,
+        ExtendedDirection::EastSouthEast,
+    ];
+
+    /// The `(y, x)` offset of the neighbor in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            ExtendedDirection::East => (0, 1),
+            ExtendedDirection::NorthEast => (-1, 1),
+            ExtendedDirection::North => (-1, 0),
+            ExtendedDirection::NorthWest => (-1, -1),
+            ExtendedDirection::West => (0, -1),
+            ExtendedDirection::SouthWest => (1, -1),
+            ExtendedDirection::South => (1, 0),
+            ExtendedDirection::SouthEast => (1, 1),
+            ExtendedDirection::FarEast => (0, 2),
+            ExtendedDirection::EastNorthEast => (-1, 2),
+            ExtendedDirection::FarNorthEast => (-2, 2),
+            ExtendedDirection::NorthNorthEast => (-2, 1),
+            ExtendedDirection::FarNorth => (-2, 0),
+            ExtendedDirection::NorthNorthWest => (-2, -1),
+            ExtendedDirection::FarNorthWest => (-2, -2),
+            ExtendedDirection::WestNorthWest => (-1, -2),
+            ExtendedDirection::FarWest => (0, -2),
+            ExtendedDirection::WestSouthWest => (1, -2),
+            ExtendedDirection::FarSouthWest => (2, -2),
+            ExtendedDirection::SouthSouthWest => (2, -1),
+            ExtendedDirection::FarSouth => (2, 0),
+            ExtendedDirection::SouthSouthEast => (2, 1),
+            ExtendedDirection::FarSouthEast => (2, 2),
+            ExtendedDirection::EastSouthEast => (1, 2),
+        }
+    }
+
+    /// Check if the direction is in the inner ring.
+    pub fn is_inner(self) -> bool {
+        (self as usize) < 8
+    }
+
+    /// The direction pointing the opposite way.
+    pub fn opposite(self) -> Self {
+        let index = self as usize;
+        if index < 8 {
+            Self::ALL[(index + 4) % 8]
+        } else {
+            Self::ALL[8 + index % 16]
+        }
+    }
+}
+
+/// The cells within two cells of a square, along with the square itself.
+#[derive(Debug)]
+pub struct ExtendedNeighbors<'a, T> {
+    window: ArrayView2<'a, T>,
+}
+
+impl<'a, T> Clone for ExtendedNeighbors<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for ExtendedNeighbors<'a, T> {}
+
+impl<'a, T> ExtendedNeighbors<'a, T> {
+    /// Wraps a 5x5 window centered on the square.
+    pub fn new(window: ArrayView2<'a, T>) -> Self {
+        assert_eq!(window.dim(), (5, 5), "extended neighbors need a 5x5 window");
+        Self { window }
+    }
+
+    /// The square in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        IndexLonger::index(&self.window, (2, 2))
+    }
+
+    /// The neighbor in the given direction.
+    pub fn get(&self, direction: ExtendedDirection) -> &'a T {
+        let (y, x) = direction.delta();
+        IndexLonger::index(&self.window, ((2 + y) as usize, (2 + x) as usize))
+    }
+
+    /// Iterate over the 24 neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = *self;
+        ExtendedDirection::ALL.iter().map(move |&d| this.get(d))
+    }
+
+    /// Iterate over the eight neighbors in the inner ring in edge order.
+    pub fn inner(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.iter().take(8)
+    }
+
+    /// Iterate over the sixteen neighbors in the outer ring in edge order.
+    pub fn outer(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.iter().skip(8)
+    }
+
+    /// The 5x5 window indexed by `(y, x)`.
+    pub fn window(&self) -> ArrayView2<'a, T> {
+        self.window
+    }
+}
+
+impl<'a, T> Index<ExtendedDirection> for ExtendedNeighbors<'a, T> {
+    type Output = T;
+
+    fn index(&self, direction: ExtendedDirection) -> &T {
+        self.get(direction)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{GridSnapshot, Sim, SquareGrid, SquareNeighborhood};
+use std::{fmt, iter::FusedIterator};
+
+/// The frames of a [`SquareGrid`], made with [`SquareGrid::frames`].
+///
+/// The grid is only stepped when the next frame is asked for, so a consumer which falls
+/// behind, such as an encoder or a network sender, holds back the simulation instead of
+/// frames piling up.
+pub struct Frames<'a, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    grid: &'a mut SquareGrid<S, N>,
+    every: u64,
+    started: bool,
+}
+
+impl<'a, S, N> Frames<'a, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    /// Step the grid `n` times between frames instead of once.
+    ///
+    /// Panics if `n` is zero.
+    pub fn every(mut self, n: u64) -> Self {
+        assert!(n > 0, "frames must be at least one step apart");
+        self.every = n;
+        self
+    }
+
+    /// Get the grid, such as to look at it or change it between frames.
+    pub fn grid(&mut self) -> &mut SquareGrid<S, N> {
+        self.grid
+    }
+}
+
+impl<S, N> Iterator for Frames<'_, S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    type Item = GridSnapshot<S::Cell>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            self.grid.step_n(self.every);
+        }
+        self.started = true;
+        Some(self.grid.snapshot())
+    }
+}
+
+impl<S, N> FusedIterator for Frames<'_, S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+}
+
+impl<S, N> fmt::Debug for Frames<'_, S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Frames")
+            .field("generation", &self.grid.generation)
+            .field("every", &self.every)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Get an endless iterator of snapshots of the grid, starting with the current generation
+    /// and stepping once before each frame after it.
+    ///
+    /// Frames are made lazily, so the iterator can be fed into a pipeline of adapters such as
+    /// `take`, `step_by`, or `filter`, and the grid is left at the last frame taken.
+    pub fn frames(&mut self) -> Frames<'_, S, N> {
+        Frames {
+            grid: self,
+            every: 1,
+            started: false,
+        }
+    }
+}
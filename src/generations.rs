@@ -0,0 +1,164 @@
+use crate::{Moore, ParseRuleError, Sim};
+use ndarray::ArrayView2;
+use std::{fmt, str::FromStr};
+
+/// A life-like rule where cells that die decay through extra states before becoming dead.
+///
+/// State `0` is dead, `1` is alive, and the states after that are decaying. Only live
+/// cells count as neighbors, and decaying cells always move on to the next state.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenerationsRule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+    states: u8,
+}
+
+impl GenerationsRule {
+    /// Make a rule from the live neighbor counts that cause birth and survival,
+    /// along with the total number of states including dead and alive.
+    ///
+    /// Panics if there are fewer than two states or a count is more than `8`, the number of
+    /// neighbors.
+    pub fn new(birth: &[usize], survival: &[usize], states: u8) -> Self {
+        assert!(states >= 2, "there must be at least two states");
+        for &n in birth.iter().chain(survival) {
+            assert!(n <= 8, "neighbor count {} is more than 8", n);
+        }
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+            states,
+        };
+        for &n in birth {
+            rule.birth[n] = true;
+        }
+        for &n in survival {
+            rule.survival[n] = true;
+        }
+        rule
+    }
+
+    /// Parse a rulestring in `345/2/4` survival/birth/states notation or `B2/S345/C4` notation.
+    pub fn parse(rule: &str) -> Result<Self, ParseRuleError> {
+        let invalid = || ParseRuleError::InvalidFormat(rule.to_owned());
+        let parts: Vec<&str> = rule.trim().split('/').collect();
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let (mut birth, mut survival, mut states) = (None, None, None);
+        if parts
+            .iter()
+            .all(|part| !part.starts_with(char::is_alphabetic))
+        {
+            survival = Some(parts[0]);
+            birth = Some(parts[1]);
+            states = Some(parts[2]);
+        } else {
+            for part in parts {
+                let rest = part.get(1..).ok_or_else(invalid)?;
+                match part.chars().next().map(|c| c.to_ascii_uppercase()) {
+                    Some('B') => birth = Some(rest),
+                    Some('S') => survival = Some(rest),
+                    Some('C') | Some('G') => states = Some(rest),
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+        let states: u8 = states.ok_or_else(invalid)?.parse().map_err(|_| invalid())?;
+        if states < 2 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            birth: parse_counts(birth.ok_or_else(invalid)?)?,
+            survival: parse_counts(survival.ok_or_else(invalid)?)?,
+            states,
+        })
+    }
+
+    /// The total number of states including dead and alive.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Get the next state of a cell given its state and number of live neighbors.
+    pub fn next(&self, state: u8, n: usize) -> u8 {
+        next_generation(state, self.states, self.birth[n], self.survival[n])
+    }
+}
+
+/// Get the next state of a cell that decays through `states` states.
+pub(crate) fn next_generation(state: u8, states: u8, birth: bool, survival: bool) -> u8 {
+    match state {
+        0 => birth as u8,
+        1 if survival => 1,
+        _ => ((state as u16 + 1) % states as u16) as u8,
+    }
+}
+
+fn parse_counts(counts: &str) -> Result<[bool; 9], ParseRuleError> {
+    let mut set = [false; 9];
+    for c in counts.chars() {
+        match c.to_digit(10) {
+            Some(n) if n <= 8 => set[n as usize] = true,
+            _ => return Err(ParseRuleError::InvalidCount(c)),
+        }
+    }
+    Ok(set)
+}
+
+impl FromStr for GenerationsRule {
+    type Err = ParseRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, ParseRuleError> {
+        Self::parse(rule)
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    /// Writes the rule in `B2/S345/C4` notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| set[n])
+                .map(|n| std::char::from_digit(n as u32, 10).unwrap())
+                .collect()
+        };
+        write!(
+            f,
+            "B{}/S{}/C{}",
+            counts(&self.birth),
+            counts(&self.survival),
+            self.states
+        )
+    }
+}
+
+impl Sim<Moore> for GenerationsRule {
+    type Cell = u8;
+    type Diff = u8;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, u8>) -> u8 {
+        let center = cells[(1, 1)];
+        let n = cells.iter().filter(|&&c| c == 1).count() - (center == 1) as usize;
+        self.next(center, n)
+    }
+
+    fn egress(&self, cell: &mut u8, diffs: ArrayView2<'_, u8>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut u8, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> u8 {
+        0
+    }
+
+    fn diff_padding(&self) -> u8 {
+        0
+    }
+
+    fn flow_padding(&self) {}
+}
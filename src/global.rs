@@ -0,0 +1,78 @@
+use crate::{Neighborhood, Sim, SquareGrid, SquareNeighborhood};
+
+/// A simulation which also uses a global quantity computed from every cell, such as the
+/// total population or average temperature.
+///
+/// Before each step, the cells are reduced in parallel by folding each cell into the
+/// identity and combining the partial results. The result is given to the sim, which can
+/// then use it from every cell on that step.
+pub trait GlobalSim<N>: Sim<N>
+where
+    N: Neighborhood,
+{
+    /// The global quantity.
+    type Global: Send;
+
+    /// The global quantity of no cells.
+    fn identity(&self) -> Self::Global;
+
+    /// Add a cell to a global quantity.
+    fn fold(&self, global: Self::Global, cell: &Self::Cell) -> Self::Global;
+
+    /// Combine global quantities of two separate sets of cells.
+    fn combine(&self, a: Self::Global, b: Self::Global) -> Self::Global;
+
+    /// Receive the global quantity of all cells before a step.
+    fn set_global(&mut self, global: Self::Global);
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: GlobalSim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync,
+{
+    /// Reduce the cells to their global quantity, in parallel when the `use-rayon` feature
+    /// is enabled.
+    #[cfg(feature = "use-rayon")]
+    pub fn global(&self) -> S::Global {
+        use ndarray::parallel::prelude::*;
+        let sim = self.sim();
+        self.cells()
+            .into_par_iter()
+            .fold(|| sim.identity(), |global, cell| sim.fold(global, cell))
+            .reduce(|| sim.identity(), |a, b| sim.combine(a, b))
+    }
+
+    /// Reduce the cells to their global quantity, in parallel when the `use-rayon` feature
+    /// is enabled.
+    #[cfg(not(feature = "use-rayon"))]
+    pub fn global(&self) -> S::Global {
+        let sim = self.sim();
+        self.cells()
+            .iter()
+            .fold(sim.identity(), |global, cell| sim.fold(global, cell))
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: GlobalSim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Compute the global quantity and give it to the sim, then step.
+    pub fn step_global(&mut self) {
+        let global = self.global();
+        self.sim_mut().set_global(global);
+        self.step();
+    }
+
+    /// Compute the global quantity and give it to the sim, then step in parallel.
+    #[cfg(feature = "use-rayon")]
+    pub fn step_parallel_global(&mut self) {
+        self.step_global();
+    }
+}
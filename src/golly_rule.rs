@@ -0,0 +1,444 @@
+use crate::{BoxedRule, Moore, Sim};
+use ndarray::ArrayView2;
+use std::{collections::HashMap, error::Error, fmt, str::FromStr};
+
+/// An error encountered while parsing a Golly `.rule` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GollyRuleError {
+    /// There is no `@TABLE` or `@TREE` section.
+    MissingRule,
+    /// A line, counted from `1`, could not be understood.
+    InvalidLine(usize),
+    /// The rule uses a neighborhood or symmetry which isn't supported, such as `hexagonal`.
+    Unsupported(String),
+}
+
+impl fmt::Display for GollyRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GollyRuleError::MissingRule => write!(f, "missing @TABLE or @TREE section"),
+            GollyRuleError::InvalidLine(line) => write!(f, "invalid rule file line {}", line),
+            GollyRuleError::Unsupported(what) => write!(f, "unsupported rule feature: {}", what),
+        }
+    }
+}
+
+impl Error for GollyRuleError {}
+
+/// The `(y, x)` index in a 3x3 window of each neighbor in the order Golly lists them, which
+/// is clockwise from north.
+const MOORE: [(usize, usize); 8] = [
+    (0, 1),
+    (0, 2),
+    (1, 2),
+    (2, 2),
+    (2, 1),
+    (2, 0),
+    (1, 0),
+    (0, 0),
+];
+
+/// The `(y, x)` index in a 3x3 window of each von Neumann neighbor, clockwise from north.
+const VON_NEUMANN: [(usize, usize); 4] = [(0, 1), (1, 2), (2, 1), (1, 0)];
+
+/// The order rule trees read the neighbors in, as indices into [`MOORE`] or [`VON_NEUMANN`].
+const MOORE_TREE: [usize; 8] = [7, 1, 5, 3, 0, 6, 2, 4];
+const VON_NEUMANN_TREE: [usize; 4] = [0, 3, 1, 2];
+
+/// One state or variable in a line of a rule table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Term {
+    State(u8),
+    /// A variable, with the slot it is bound to if it appears more than once in the line.
+    Var {
+        var: usize,
+        slot: Option<usize>,
+    },
+}
+
+/// The transitions of a rule, as a table or a tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Transitions {
+    Table {
+        /// The states of each variable, as a flag for every state.
+        vars: Vec<Vec<bool>>,
+        /// The center, the neighbors, and the next state of each line.
+        lines: Vec<Vec<Term>>,
+        /// The orders the neighbors of a line are matched in, unless the neighbors are
+        /// permuted.
+        symmetries: Vec<Vec<usize>>,
+        permute: bool,
+    },
+    Tree {
+        /// The children of each node, which are states at the first level.
+        nodes: Vec<Vec<u32>>,
+    },
+}
+
+/// A rule over small integer states read from a Golly `.rule` file, so rules written for
+/// Golly run without porting them.
+///
+/// Both the `@TABLE` and `@TREE` sections are supported, on the Moore and von Neumann
+/// neighborhoods. Tables support variables, which are bound when they appear more than once
+/// in a line, and every symmetry Golly has for those neighborhoods. A cell which no line of
+/// a table matches keeps its state. Other sections, such as `@COLORS`, are ignored.
+///
+/// Tables are matched line by line for each cell, so large tables are faster as trees.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GollyRule {
+    name: Option<String>,
+    states: u8,
+    /// The neighbors, as [`MOORE`] or [`VON_NEUMANN`].
+    neighbors: &'static [(usize, usize)],
+    transitions: Transitions,
+}
+
+impl GollyRule {
+    /// Parse the contents of a `.rule` file.
+    pub fn parse(text: &str) -> Result<Self, GollyRuleError> {
+        let mut name = None;
+        let mut section = "";
+        let mut body = vec![];
+        let mut rule = None;
+        let lines = text
+            .lines()
+            .map(|line| line.split('#').next().unwrap().trim());
+        for (number, line) in lines.enumerate().chain(Some((0, "@END"))) {
+            if line.starts_with('@') {
+                match section {
+                    "@TABLE" => rule = Some(parse_table(&body)?),
+                    "@TREE" => rule = Some(parse_tree(&body)?),
+                    _ => {}
+                }
+                let mut words = line.split_whitespace();
+                section = words.next().unwrap();
+                if section == "@RULE" {
+                    name = words.next().map(str::to_owned);
+                }
+                body.clear();
+            } else if !line.is_empty() {
+                body.push((number + 1, line));
+            }
+        }
+        let mut rule = rule.ok_or(GollyRuleError::MissingRule)?;
+        rule.name = name;
+        Ok(rule)
+    }
+
+    /// The name of the rule from its `@RULE` line.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The number of states, which are `0` to `states - 1`.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Get the next state of the cell in the center of a 3x3 window, whose states must all be
+    /// below [`GollyRule::states`].
+    pub fn next(&self, window: ArrayView2<'_, u8>) -> u8 {
+        let center = window[(1, 1)];
+        let mut neighbors = [0; 8];
+        for (neighbor, &index) in neighbors.iter_mut().zip(self.neighbors) {
+            *neighbor = window[index];
+        }
+        let neighbors = &neighbors[..self.neighbors.len()];
+        match &self.transitions {
+            Transitions::Table {
+                vars,
+                lines,
+                symmetries,
+                permute,
+            } => lines
+                .iter()
+                .find_map(|line| {
+                    let mut slots = [None; 10];
+                    if !matches(vars, line[0], center, &mut slots) {
+                        return None;
+                    }
+                    let terms = &line[1..line.len() - 1];
+                    let found = if *permute {
+                        permuted(vars, terms, neighbors, 0, slots)
+                    } else {
+                        symmetries.iter().find_map(|order| {
+                            let mut slots = slots;
+                            terms
+                                .iter()
+                                .zip(order)
+                                .all(|(&term, &i)| matches(vars, term, neighbors[i], &mut slots))
+                                .then_some(slots)
+                        })
+                    };
+                    found.map(|slots| match line[line.len() - 1] {
+                        Term::State(state) => state,
+                        Term::Var { slot, .. } => slots[slot.unwrap()].unwrap(),
+                    })
+                })
+                .unwrap_or(center),
+            Transitions::Tree { nodes } => {
+                let order: &[usize] = if neighbors.len() == 8 {
+                    &MOORE_TREE
+                } else {
+                    &VON_NEUMANN_TREE
+                };
+                let states = order.iter().map(|&i| neighbors[i]).chain(Some(center));
+                let node = states.fold(nodes.len() as u32 - 1, |node, state| {
+                    nodes[node as usize][state as usize]
+                });
+                node as u8
+            }
+        }
+    }
+}
+
+/// Check if a state matches a term, binding the slot of a variable to it.
+fn matches(vars: &[Vec<bool>], term: Term, state: u8, slots: &mut [Option<u8>; 10]) -> bool {
+    match term {
+        Term::State(expected) => state == expected,
+        Term::Var { var, slot } => {
+            vars[var].get(state as usize) == Some(&true)
+                && slot.is_none_or(|slot| *slots[slot].get_or_insert(state) == state)
+        }
+    }
+}
+
+/// Match the remaining terms to the remaining neighbors in any order, and get the slots they
+/// bound.
+fn permuted(
+    vars: &[Vec<bool>],
+    terms: &[Term],
+    neighbors: &[u8],
+    used: u8,
+    slots: [Option<u8>; 10],
+) -> Option<[Option<u8>; 10]> {
+    let (&term, rest) = match terms.split_first() {
+        Some(split) => split,
+        None => return Some(slots),
+    };
+    (0..neighbors.len())
+        .filter(|&i| used & 1 << i == 0)
+        .find_map(|i| {
+            let mut slots = slots;
+            if matches(vars, term, neighbors[i], &mut slots) {
+                permuted(vars, rest, neighbors, used | 1 << i, slots)
+            } else {
+                None
+            }
+        })
+}
+
+/// Get the value of a `key:value` or `key=value` line.
+fn setting<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(key)?.trim_start();
+    let rest = rest.strip_prefix(':').or_else(|| rest.strip_prefix('='))?;
+    Some(rest.trim())
+}
+
+fn parse_states(value: &str, number: usize) -> Result<u8, GollyRuleError> {
+    match value.parse::<u8>() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(GollyRuleError::InvalidLine(number)),
+    }
+}
+
+/// Get the orders of the neighbors matching each symmetric version of a line.
+fn symmetries(symmetry: &str, n: usize) -> Result<Vec<Vec<usize>>, GollyRuleError> {
+    let rotate = |step: usize| -> Vec<Vec<usize>> {
+        (0..n)
+            .step_by(step)
+            .map(|k| (0..n).map(|i| (i + k) % n).collect())
+            .collect()
+    };
+    let reflect = |orders: Vec<Vec<usize>>| -> Vec<Vec<usize>> {
+        let reflected: Vec<Vec<usize>> = orders
+            .iter()
+            .map(|order| order.iter().map(|&i| (n - i) % n).collect())
+            .collect();
+        orders.into_iter().chain(reflected).collect()
+    };
+    Ok(match (symmetry, n) {
+        ("none", _) | ("permute", _) => rotate(n),
+        ("rotate4", 8) => rotate(2),
+        ("rotate4", 4) | ("rotate8", 8) => rotate(1),
+        ("reflect_horizontal", _) => reflect(rotate(n)),
+        ("rotate4reflect", 8) => reflect(rotate(2)),
+        ("rotate4reflect", 4) | ("rotate8reflect", 8) => reflect(rotate(1)),
+        _ => return Err(GollyRuleError::Unsupported(symmetry.to_owned())),
+    })
+}
+
+fn parse_neighborhood(value: &str) -> Result<&'static [(usize, usize)], GollyRuleError> {
+    match value {
+        "Moore" => Ok(&MOORE),
+        "vonNeumann" => Ok(&VON_NEUMANN),
+        _ => Err(GollyRuleError::Unsupported(value.to_owned())),
+    }
+}
+
+fn parse_table(body: &[(usize, &str)]) -> Result<GollyRule, GollyRuleError> {
+    let mut states = None;
+    let mut neighbors: &'static [(usize, usize)] = &MOORE;
+    let mut symmetry = "none";
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    let mut vars: Vec<Vec<bool>> = vec![];
+    let mut lines = vec![];
+    for &(number, line) in body {
+        let invalid = GollyRuleError::InvalidLine(number);
+        if let Some(value) = setting(line, "n_states") {
+            states = Some(parse_states(value, number)?);
+        } else if let Some(value) = setting(line, "neighborhood") {
+            neighbors = parse_neighborhood(value)?;
+        } else if let Some(value) = setting(line, "symmetries") {
+            symmetry = value;
+        } else if let Some(var) = line.strip_prefix("var ") {
+            let (name, values) = var.split_once('=').ok_or_else(|| invalid.clone())?;
+            let values = values
+                .trim()
+                .strip_prefix('{')
+                .and_then(|values| values.strip_suffix('}'))
+                .ok_or_else(|| invalid.clone())?;
+            let mut set = vec![false; 256];
+            for value in values.split(',').map(str::trim) {
+                match names.get(value) {
+                    Some(&var) => {
+                        for (state, &included) in vars[var].iter().enumerate() {
+                            set[state] |= included;
+                        }
+                    }
+                    None => set[value.parse::<u8>().map_err(|_| invalid.clone())? as usize] = true,
+                }
+            }
+            names.insert(name.trim(), vars.len());
+            vars.push(set);
+        } else {
+            let words: Vec<&str> = if line.contains(',') {
+                line.split(',').map(str::trim).collect()
+            } else {
+                line.matches(|c: char| !c.is_whitespace()).collect()
+            };
+            if words.len() != neighbors.len() + 2 {
+                return Err(invalid);
+            }
+            let mut slots: HashMap<&str, usize> = HashMap::new();
+            let mut terms = vec![];
+            for &word in &words {
+                terms.push(match names.get(word) {
+                    Some(&var) => {
+                        let repeated = words.iter().filter(|&&other| other == word).count() > 1;
+                        let next = slots.len();
+                        let slot = repeated.then(|| *slots.entry(word).or_insert(next));
+                        Term::Var { var, slot }
+                    }
+                    None => Term::State(word.parse().map_err(|_| invalid.clone())?),
+                });
+            }
+            if let Some(Term::Var { slot: None, .. }) = terms.last() {
+                return Err(invalid);
+            }
+            lines.push(terms);
+        }
+    }
+    let states = states.ok_or(GollyRuleError::MissingRule)?;
+    Ok(GollyRule {
+        name: None,
+        states,
+        neighbors,
+        transitions: Transitions::Table {
+            vars,
+            lines,
+            symmetries: symmetries(symmetry, neighbors.len())?,
+            permute: symmetry == "permute",
+        },
+    })
+}
+
+fn parse_tree(body: &[(usize, &str)]) -> Result<GollyRule, GollyRuleError> {
+    let mut states = None;
+    let mut neighbors: &'static [(usize, usize)] = &MOORE;
+    let mut nodes: Vec<Vec<u32>> = vec![];
+    for &(number, line) in body {
+        let invalid = GollyRuleError::InvalidLine(number);
+        if let Some(value) = setting(line, "num_states") {
+            states = Some(parse_states(value, number)?);
+        } else if let Some(value) = setting(line, "num_neighbors") {
+            neighbors = match value {
+                "8" => &MOORE,
+                "4" => &VON_NEUMANN,
+                _ => return Err(GollyRuleError::Unsupported(format!("{} neighbors", value))),
+            };
+        } else if setting(line, "num_nodes").is_some() {
+            continue;
+        } else {
+            let states = states.ok_or_else(|| invalid.clone())? as usize;
+            let numbers = line
+                .split_whitespace()
+                .map(|n| n.parse().ok())
+                .collect::<Option<Vec<u32>>>()
+                .ok_or_else(|| invalid.clone())?;
+            let (&level, children) = numbers.split_first().ok_or_else(|| invalid.clone())?;
+            let valid = children.len() == states
+                && children.iter().all(|&child| {
+                    if level == 1 {
+                        (child as usize) < states
+                    } else {
+                        (child as usize) < nodes.len()
+                    }
+                });
+            if !valid || level == 0 || level as usize > neighbors.len() + 1 {
+                return Err(invalid);
+            }
+            nodes.push(children.to_vec());
+        }
+    }
+    if nodes.is_empty() {
+        return Err(GollyRuleError::MissingRule);
+    }
+    Ok(GollyRule {
+        name: None,
+        states: states.ok_or(GollyRuleError::MissingRule)?,
+        neighbors,
+        transitions: Transitions::Tree { nodes },
+    })
+}
+
+impl FromStr for GollyRule {
+    type Err = GollyRuleError;
+
+    fn from_str(text: &str) -> Result<Self, GollyRuleError> {
+        Self::parse(text)
+    }
+}
+
+impl From<GollyRule> for BoxedRule<u8> {
+    fn from(rule: GollyRule) -> Self {
+        Self::new(0, move |window| rule.next(window))
+    }
+}
+
+impl Sim<Moore> for GollyRule {
+    type Cell = u8;
+    type Diff = u8;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, u8>) -> u8 {
+        self.next(cells)
+    }
+
+    fn egress(&self, cell: &mut u8, diffs: ArrayView2<'_, u8>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut u8, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> u8 {
+        0
+    }
+
+    fn diff_padding(&self) -> u8 {
+        0
+    }
+
+    fn flow_padding(&self) {}
+}
@@ -0,0 +1,400 @@
+//! Stepping grids with WGSL compute kernels on the GPU with `wgpu`, behind the `gpu` feature.
+//!
+//! A [`GpuGrid`] keeps its cells in two buffers on the GPU and runs a kernel which reads one to
+//! write the other in each step, so the cells only leave the GPU when they are read. The kernels
+//! are WGSL source, so they can also be run with any other WebGPU implementation. Every kernel
+//! uses the same bindings:
+//!
+//! * `@binding(0)`: a uniform `Params { width: u32, height: u32 }`
+//! * `@binding(1)`: the cells as a read-only storage `array<u32>` in row-major order
+//! * `@binding(2)`: the next cells as a read-write storage `array<u32>` in row-major order
+//!
+//! Dispatch `(width + 15) / 16` by `(height + 15) / 16` workgroups, then swap the buffers to
+//! step again.
+
+use crate::LifeLikeRule;
+use ndarray::{Array2, ArrayView2};
+use std::{error::Error, fmt, sync::mpsc};
+use wgpu::{naga, util::DeviceExt};
+
+/// The `(x, y)` size of the workgroups of the kernels.
+pub const WORKGROUP_SIZE: (u32, u32) = (16, 16);
+
+/// The bindings shared by every kernel.
+const HEADER: &str = "struct Params {
+    width: u32,
+    height: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> cells: array<u32>;
+@group(0) @binding(2) var<storage, read_write> next: array<u32>;
+";
+
+/// Make a kernel which steps a life-like rule where cells are `0` or `1`.
+///
+/// If `wrap` is `true`, opposite edges are connected. Otherwise, cells beyond the edges are dead.
+pub fn life_like_kernel(rule: &LifeLikeRule, wrap: bool) -> String {
+    let mask = |is_set: &dyn Fn(usize) -> bool| {
+        (0..9).filter(|&n| is_set(n)).fold(0u32, |m, n| m | 1 << n)
+    };
+    let birth = mask(&|n| rule.is_birth(n));
+    let survival = mask(&|n| rule.is_survival(n));
+    let lookup = if wrap {
+        "    let wy = u32((y + i32(params.height)) % i32(params.height));
+    let wx = u32((x + i32(params.width)) % i32(params.width));
+    return cells[wy * params.width + wx];"
+    } else {
+        "    if (y < 0 || x < 0 || y >= i32(params.height) || x >= i32(params.width)) {
+        return 0u;
+    }
+    return cells[u32(y) * params.width + u32(x)];"
+    };
+    format!(
+        "// {rule}
+{header}
+const BIRTH: u32 = {birth}u;
+const SURVIVAL: u32 = {survival}u;
+
+fn cell(y: i32, x: i32) -> u32 {{
+{lookup}
+}}
+
+@compute @workgroup_size({wx}, {wy})
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
+    if (id.x >= params.width || id.y >= params.height) {{
+        return;
+    }}
+    let y = i32(id.y);
+    let x = i32(id.x);
+    var n = 0u;
+    for (var dy = -1; dy <= 1; dy = dy + 1) {{
+        for (var dx = -1; dx <= 1; dx = dx + 1) {{
+            if (dy != 0 || dx != 0) {{
+                n = n + cell(y + dy, x + dx);
+            }}
+        }}
+    }}
+    let index = id.y * params.width + id.x;
+    let counts = select(BIRTH, SURVIVAL, cells[index] == 1u);
+    next[index] = (counts >> n) & 1u;
+}}
+",
+        rule = rule,
+        header = HEADER,
+        birth = birth,
+        survival = survival,
+        lookup = lookup,
+        wx = WORKGROUP_SIZE.0,
+        wy = WORKGROUP_SIZE.1,
+    )
+}
+
+/// Make a kernel from the body of a function which computes the next state of a cell.
+///
+/// The body can use `cell(y: i32, x: i32) -> u32` to read any cell, where cells beyond the
+/// edges are `0`, along with `y` and `x`, and must return the next state as a `u32`.
+pub fn custom_kernel(body: &str) -> String {
+    format!(
+        "{header}
+fn cell(y: i32, x: i32) -> u32 {{
+    if (y < 0 || x < 0 || y >= i32(params.height) || x >= i32(params.width)) {{
+        return 0u;
+    }}
+    return cells[u32(y) * params.width + u32(x)];
+}}
+
+fn step(y: i32, x: i32) -> u32 {{
+{body}
+}}
+
+@compute @workgroup_size({wx}, {wy})
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
+    if (id.x >= params.width || id.y >= params.height) {{
+        return;
+    }}
+    next[id.y * params.width + id.x] = step(i32(id.y), i32(id.x));
+}}
+",
+        header = HEADER,
+        body = body,
+        wx = WORKGROUP_SIZE.0,
+        wy = WORKGROUP_SIZE.1,
+    )
+}
+
+/// Convert cells into the contents of a cell buffer.
+pub fn to_buffer<C>(cells: ArrayView2<'_, C>, mut state: impl FnMut(&C) -> u32) -> Vec<u32> {
+    cells.iter().map(&mut state).collect()
+}
+
+/// Convert the contents of a cell buffer read back from the GPU into cells.
+pub fn from_buffer<C>(
+    buffer: &[u32],
+    dim: (usize, usize),
+    cell: impl FnMut(u32) -> C,
+) -> Array2<C> {
+    assert_eq!(
+        buffer.len(),
+        dim.0 * dim.1,
+        "buffer does not have a cell for every index"
+    );
+    Array2::from_shape_vec(dim, buffer.iter().copied().map(cell).collect()).unwrap()
+}
+
+/// Check that a kernel is valid WGSL with the bindings and entry point every kernel has,
+/// without needing a GPU.
+pub fn validate_kernel(kernel: &str) -> Result<(), GpuError> {
+    let module = naga::front::wgsl::parse_str(kernel)
+        .map_err(|error| GpuError::Kernel(error.emit_to_string(kernel)))?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .map_err(|error| GpuError::Kernel(error.emit_to_string(kernel)))?;
+    let main = module
+        .entry_points
+        .iter()
+        .find(|entry| entry.name == "main");
+    match main {
+        Some(entry) if entry.stage == naga::ShaderStage::Compute => Ok(()),
+        _ => Err(GpuError::Kernel(
+            "kernel has no compute entry point `main`".to_owned(),
+        )),
+    }
+}
+
+/// An error encountered while setting up a [`GpuGrid`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GpuError {
+    /// No GPU or software adapter is available.
+    NoAdapter,
+    /// The adapter couldn't give a device which can run compute kernels.
+    Device(String),
+    /// The kernel isn't valid.
+    Kernel(String),
+    /// The grid has no cells, or is too large for the GPU.
+    Dimensions((usize, usize)),
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::NoAdapter => write!(f, "no GPU adapter is available"),
+            GpuError::Device(error) => write!(f, "failed to get a GPU device: {}", error),
+            GpuError::Kernel(error) => write!(f, "invalid kernel: {}", error),
+            GpuError::Dimensions((h, w)) => {
+                write!(f, "a {}x{} grid can't be stepped on the GPU", h, w)
+            }
+        }
+    }
+}
+
+impl Error for GpuError {}
+
+/// A grid of `u32` cells which is stepped by a kernel on the GPU.
+pub struct GpuGrid {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    buffers: [wgpu::Buffer; 2],
+    /// The bind group which steps the cells in each buffer into the other.
+    bind_groups: [wgpu::BindGroup; 2],
+    /// The buffer holding the cells.
+    current: usize,
+    dim: (usize, usize),
+    generation: u64,
+}
+
+impl GpuGrid {
+    /// Make a grid which steps `cells` with `kernel` on the default adapter, which may be a
+    /// software renderer if there is no GPU.
+    pub fn new(kernel: &str, cells: ArrayView2<'_, u32>) -> Result<Self, GpuError> {
+        let (h, w) = cells.dim();
+        let too_large = |len: usize| len > u32::MAX as usize;
+        if h == 0 || w == 0 || too_large(h) || too_large(w) {
+            return Err(GpuError::Dimensions((h, w)));
+        }
+        validate_kernel(kernel)?;
+
+        let instance = wgpu::Instance::default();
+        let adapter =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+                .map_err(|_| GpuError::NoAdapter)?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("gridsim"),
+            required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+            ..Default::default()
+        }))
+        .map_err(|error| GpuError::Device(error.to_string()))?;
+        let size = (h * w * 4) as u64;
+        if size > device.limits().max_storage_buffer_binding_size as u64 {
+            return Err(GpuError::Dimensions((h, w)));
+        }
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("gridsim kernel"),
+            source: wgpu::ShaderSource::Wgsl(kernel.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("gridsim kernel"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(GpuError::Kernel(error.to_string()));
+        }
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("gridsim params"),
+            contents: &to_bytes(&[w as u32, h as u32]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let cells = to_bytes(&to_buffer(cells, |&c| c));
+        let buffer = || {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("gridsim cells"),
+                contents: &cells,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::COPY_DST,
+            })
+        };
+        let buffers = [buffer(), buffer()];
+        let bind_group = |from: usize| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("gridsim step"),
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: buffers[from].as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: buffers[1 - from].as_entire_binding(),
+                    },
+                ],
+            })
+        };
+        let bind_groups = [bind_group(0), bind_group(1)];
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            buffers,
+            bind_groups,
+            current: 0,
+            dim: (h, w),
+            generation: 0,
+        })
+    }
+
+    /// Simulate one step.
+    pub fn step(&mut self) {
+        self.step_n(1);
+    }
+
+    /// Simulate `n` steps, submitting them to the GPU together.
+    pub fn step_n(&mut self, n: u64) {
+        let (h, w) = self.dim;
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        for _ in 0..n {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_groups[self.current], &[]);
+            pass.dispatch_workgroups(
+                (w as u32).div_ceil(WORKGROUP_SIZE.0),
+                (h as u32).div_ceil(WORKGROUP_SIZE.1),
+                1,
+            );
+            self.current = 1 - self.current;
+        }
+        self.queue.submit(Some(encoder.finish()));
+        self.generation += n;
+    }
+
+    /// Get the number of steps simulated.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Get the `(height, width)` of the grid.
+    pub fn dim(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    /// Read the cells back from the GPU, waiting for any steps in progress.
+    pub fn cells(&self) -> Array2<u32> {
+        let size = self.buffers[self.current].size();
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gridsim read"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&self.buffers[self.current], 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = mpsc::channel();
+        staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        self.device
+            .poll(wgpu::PollType::Wait)
+            .expect("GPU device was lost");
+        receiver
+            .recv()
+            .expect("buffer was never mapped")
+            .expect("failed to read the cells from the GPU");
+        let buffer: Vec<u32> = staging
+            .slice(..)
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        from_buffer(&buffer, self.dim, |state| state)
+    }
+
+    /// Replace the cells, which must have the dimensions of the grid.
+    pub fn set_cells(&mut self, cells: ArrayView2<'_, u32>) {
+        assert_eq!(
+            cells.dim(),
+            self.dim,
+            "cells must match the grid dimensions"
+        );
+        let cells = to_bytes(&to_buffer(cells, |&c| c));
+        self.queue
+            .write_buffer(&self.buffers[self.current], 0, &cells);
+    }
+}
+
+impl fmt::Debug for GpuGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuGrid")
+            .field("dim", &self.dim)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+/// The bytes of a buffer of `u32`s as the GPU reads them.
+fn to_bytes(buffer: &[u32]) -> Vec<u8> {
+    buffer.iter().flat_map(|n| n.to_le_bytes()).collect()
+}
@@ -0,0 +1,210 @@
+use crate::{
+    moore::MOORE_DELTAS, BitGrid, Hex, HexDirection, HexGrid, InfiniteGrid, Line, LineGrid, Moore,
+    Position, Sim, SquareGrid, SquareNeighborhood,
+};
+
+/// A grid of cells which can be stepped, so tools such as renderers, recorders, and detectors
+/// can be written once for every kind of grid.
+///
+/// Cells are returned by value, since some grids like [`BitGrid`] don't store them directly.
+pub trait Grid {
+    /// The cells of the grid.
+    type Cell;
+    /// The index of a cell, such as `(y, x)`.
+    type Index: Copy;
+
+    /// Simulate one step.
+    fn step(&mut self);
+
+    /// Get the cell at an index, or `None` if it is outside of the grid.
+    fn cell(&self, index: Self::Index) -> Option<Self::Cell>;
+
+    /// Iterate over the indices of the cells in the grid.
+    ///
+    /// Unbounded grids only give the cells which are stored.
+    fn indices(&self) -> Box<dyn Iterator<Item = Self::Index> + '_>;
+
+    /// Get the indices of the neighbors of a cell which are on the grid, in edge order.
+    fn neighbors(&self, index: Self::Index) -> Vec<Self::Index>;
+}
+
+/// Iterate over the `(y, x)` indices of a `(height, width)` array in row-major order.
+fn indices_2d((h, w): (usize, usize)) -> Box<dyn Iterator<Item = (usize, usize)>> {
+    Box::new((0..h).flat_map(move |y| (0..w).map(move |x| (y, x))))
+}
+
+/// Get the `(y, x)` index of the neighbor at a delta, if it is within a `(height, width)`.
+fn offset(
+    (y, x): (usize, usize),
+    (dy, dx): (isize, isize),
+    (h, w): (usize, usize),
+) -> Option<(usize, usize)> {
+    let (y, x) = (y as isize + dy, x as isize + dx);
+    if (0..h as isize).contains(&y) && (0..w as isize).contains(&x) {
+        Some((y as usize, x as usize))
+    } else {
+        None
+    }
+}
+
+/// Neighbors are found across the boundaries of the grid, so cells on the edges of a wrapped
+/// grid have as many neighbors as the rest.
+impl<S, N> Grid for SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    type Cell = S::Cell;
+    type Index = (usize, usize);
+
+    fn step(&mut self) {
+        SquareGrid::step(self);
+    }
+
+    fn cell(&self, index: (usize, usize)) -> Option<S::Cell> {
+        self.get(index).cloned()
+    }
+
+    fn indices(&self) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
+        indices_2d(self.cells().dim())
+    }
+
+    fn neighbors(&self, (y, x): (usize, usize)) -> Vec<(usize, usize)> {
+        (0..N::EDGES)
+            .filter_map(|edge| {
+                let (dy, dx) = N::delta(edge);
+                self.wrap((y as isize + dy, x as isize + dx))
+            })
+            .collect()
+    }
+}
+
+/// Indices are `(r, q)` in axial coordinates, like [`HexGrid::cells`].
+impl<S> Grid for HexGrid<S>
+where
+    S: Sim<Hex> + Sync,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync,
+    S::Flow: Send,
+{
+    type Cell = S::Cell;
+    type Index = (usize, usize);
+
+    fn step(&mut self) {
+        HexGrid::step(self);
+    }
+
+    fn cell(&self, index: (usize, usize)) -> Option<S::Cell> {
+        self.cells().get(index).cloned()
+    }
+
+    fn indices(&self) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
+        indices_2d(self.cells().dim())
+    }
+
+    fn neighbors(&self, index: (usize, usize)) -> Vec<(usize, usize)> {
+        let dim = self.cells().dim();
+        HexDirection::ALL
+            .iter()
+            .filter_map(|direction| {
+                let (dq, dr) = direction.delta();
+                offset(index, (dr, dq), dim)
+            })
+            .collect()
+    }
+}
+
+impl<S> Grid for LineGrid<S>
+where
+    S: Sim<Line> + Sync,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync,
+    S::Flow: Send,
+{
+    type Cell = S::Cell;
+    type Index = usize;
+
+    fn step(&mut self) {
+        LineGrid::step(self);
+    }
+
+    fn cell(&self, index: usize) -> Option<S::Cell> {
+        self.cells().get(index).cloned()
+    }
+
+    fn indices(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(0..self.cells().len())
+    }
+
+    fn neighbors(&self, index: usize) -> Vec<usize> {
+        let len = self.cells().len();
+        index
+            .checked_sub(1)
+            .into_iter()
+            .chain(Some(index + 1).filter(|&right| right < len))
+            .collect()
+    }
+}
+
+impl Grid for BitGrid {
+    type Cell = bool;
+    type Index = (usize, usize);
+
+    fn step(&mut self) {
+        BitGrid::step(self);
+    }
+
+    fn cell(&self, (y, x): (usize, usize)) -> Option<bool> {
+        let (h, w) = self.dim();
+        if y < h && x < w {
+            Some(self.get((y, x)))
+        } else {
+            None
+        }
+    }
+
+    fn indices(&self) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
+        indices_2d(self.dim())
+    }
+
+    fn neighbors(&self, index: (usize, usize)) -> Vec<(usize, usize)> {
+        MOORE_DELTAS
+            .iter()
+            .filter_map(|&delta| offset(index, delta, self.dim()))
+            .collect()
+    }
+}
+
+/// Every position is on the grid, but only the cells which differ from the background are
+/// iterated over.
+impl<S> Grid for InfiniteGrid<S>
+where
+    S: Sim<Moore>,
+    S::Cell: Clone + PartialEq,
+    S::Diff: Clone + PartialEq,
+{
+    type Cell = S::Cell;
+    type Index = Position;
+
+    fn step(&mut self) {
+        InfiniteGrid::step(self);
+    }
+
+    fn cell(&self, position: Position) -> Option<S::Cell> {
+        Some(self.get(position).clone())
+    }
+
+    fn indices(&self) -> Box<dyn Iterator<Item = Position> + '_> {
+        Box::new(self.iter().map(|(position, _)| position))
+    }
+
+    fn neighbors(&self, (y, x): Position) -> Vec<Position> {
+        MOORE_DELTAS
+            .iter()
+            .map(|&(dy, dx)| (y + dy as i64, x + dx as i64))
+            .collect()
+    }
+}
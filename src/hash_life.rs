@@ -0,0 +1,385 @@
+use crate::Position;
+use ndarray::{s, Array2, ArrayView2};
+use std::collections::HashMap;
+
+pub(crate) type NodeId = usize;
+
+pub(crate) const DEAD: NodeId = 0;
+pub(crate) const ALIVE: NodeId = 1;
+
+/// The number of nodes a [`HashLifeGrid`] can hold before it collects garbage by default.
+pub const DEFAULT_NODE_LIMIT: usize = 1 << 20;
+
+/// A square of cells made from four quadrants, or a single cell at level `0`.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Node {
+    pub(crate) level: u32,
+    /// The north-west, north-east, south-west, and south-east quadrants.
+    pub(crate) children: [NodeId; 4],
+    pub(crate) population: u64,
+}
+
+/// An unbounded two-state grid which uses quadtree memoization to skip ahead many generations.
+///
+/// The rule maps a 3x3 window, indexed by `(y, x)`, to the next state of the center cell.
+/// It must keep empty space empty, so a window with no live cells must produce a dead cell.
+///
+/// Nodes and the results memoized for them are kept between advances, so patterns which repeat
+/// themselves are fast to skip ahead. Once there are more nodes than the limit set with
+/// [`HashLifeGrid::set_node_limit`], those which aren't part of the grid are dropped.
+#[derive(Clone, Debug)]
+pub struct HashLifeGrid<F> {
+    rule: F,
+    pub(crate) nodes: Vec<Node>,
+    lookup: HashMap<[NodeId; 4], NodeId>,
+    /// The empty node of each level.
+    empty: Vec<NodeId>,
+    /// The center of a node advanced by a power of two generations.
+    results: HashMap<(NodeId, u32), NodeId>,
+    node_limit: usize,
+    pub(crate) root: NodeId,
+    /// The `(y, x)` position of the top-left cell of the root, which is always centered on
+    /// `(0, 0)`.
+    pub(crate) origin: Position,
+    generation: u64,
+}
+
+impl<F> HashLifeGrid<F>
+where
+    F: Fn(ArrayView2<'_, bool>) -> bool,
+{
+    /// Make a new grid with no live cells.
+    pub fn new(rule: F) -> Self {
+        assert!(
+            !rule(Array2::from_elem((3, 3), false).view()),
+            "rule must keep empty space empty"
+        );
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+        let mut grid = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            lookup: HashMap::new(),
+            empty: vec![DEAD],
+            results: HashMap::new(),
+            node_limit: DEFAULT_NODE_LIMIT,
+            root: DEAD,
+            origin: (-4, -4),
+            generation: 0,
+        };
+        grid.root = grid.empty(3);
+        grid
+    }
+
+    /// Make a new grid with the given cells placed with their top-left corner at `(0, 0)`.
+    pub fn from_cells(rule: F, cells: ArrayView2<'_, bool>) -> Self {
+        let mut grid = Self::new(rule);
+        for ((y, x), _) in cells.indexed_iter().filter(|&(_, &alive)| alive) {
+            grid.set((y as i64, x as i64), true);
+        }
+        grid
+    }
+
+    /// The number of generations that have been simulated.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of live cells.
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    /// The number of nodes kept for the grid and the results of earlier advances.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Set how many nodes can be kept before garbage is collected after an advance, which is
+    /// [`DEFAULT_NODE_LIMIT`] to begin with.
+    ///
+    /// If more than half of the limit is still used by the grid after collecting, the limit is
+    /// doubled so that garbage isn't collected after every advance.
+    pub fn set_node_limit(&mut self, limit: usize) {
+        self.node_limit = limit;
+    }
+
+    /// Drop every node which isn't part of the grid, along with the results memoized for them.
+    pub fn collect_garbage(&mut self) {
+        let mut ids = vec![NodeId::MAX; self.nodes.len()];
+        let mut nodes = Vec::new();
+        for id in [DEAD, ALIVE, self.root] {
+            self.keep(id, &mut ids, &mut nodes);
+        }
+        for &id in &self.empty {
+            self.keep(id, &mut ids, &mut nodes);
+        }
+        let kept = |id: NodeId| Some(ids[id]).filter(|&id| id != NodeId::MAX);
+        self.results = self
+            .results
+            .iter()
+            .filter_map(|(&(id, j), &result)| Some(((kept(id)?, j), kept(result)?)))
+            .collect();
+        self.lookup = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.level > 0)
+            .map(|(id, node)| (node.children, id))
+            .collect();
+        self.empty = self.empty.iter().map(|&id| ids[id]).collect();
+        self.root = ids[self.root];
+        self.nodes = nodes;
+    }
+
+    /// Check if the cell at a position is alive.
+    pub fn get(&self, (y, x): Position) -> bool {
+        if !self.contains((y, x)) {
+            return false;
+        }
+        let mut id = self.root;
+        let (mut y, mut x) = (y - self.origin.0, x - self.origin.1);
+        while self.nodes[id].level > 0 {
+            let half = 1 << (self.nodes[id].level - 1);
+            let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+            id = self.nodes[id].children[quadrant];
+            y %= half;
+            x %= half;
+        }
+        id == ALIVE
+    }
+
+    /// Set whether the cell at a position is alive.
+    pub fn set(&mut self, position: Position, alive: bool) {
+        while !self.contains(position) {
+            self.expand();
+        }
+        let (y, x) = (position.0 - self.origin.0, position.1 - self.origin.1);
+        self.root = self.set_in(self.root, y, x, alive);
+    }
+
+    /// Get the positions of all live cells.
+    pub fn live_cells(&self) -> Vec<Position> {
+        let mut cells = Vec::with_capacity(self.population() as usize);
+        self.collect_live(self.root, self.origin, &mut cells);
+        cells
+    }
+
+    /// Get the cells in the rectangle with the given top-left position and `(height, width)`.
+    pub fn cells(&self, (y, x): Position, dim: (usize, usize)) -> Array2<bool> {
+        Array2::from_shape_fn(dim, |(dy, dx)| self.get((y + dy as i64, x + dx as i64)))
+    }
+
+    /// Advance the grid by any number of generations.
+    pub fn advance(&mut self, generations: u64) {
+        for k in 0..64 {
+            if generations >> k & 1 == 1 {
+                self.advance_pow2(k);
+            }
+        }
+    }
+
+    /// Advance the grid by `2^k` generations.
+    pub fn advance_pow2(&mut self, k: u32) {
+        // Remove the unnecessary padding from the last advancement.
+        while self.nodes[self.root].level > 3 && self.is_centered(self.root) {
+            self.root = self.center(self.root);
+            let quarter = 1 << (self.nodes[self.root].level - 1);
+            self.origin = (self.origin.0 + quarter, self.origin.1 + quarter);
+        }
+        // The live cells cannot travel further than 2^k cells, so two extra levels of
+        // padding guarantee that none of them escape the result.
+        while self.nodes[self.root].level < k + 2 || !self.is_centered(self.root) {
+            self.expand();
+        }
+        self.expand();
+        self.expand();
+        assert!(
+            self.nodes[self.root].level < 62,
+            "grid has grown too large to simulate"
+        );
+        let quarter = 1 << (self.nodes[self.root].level - 2);
+        self.root = self.result(self.root, k);
+        self.origin = (self.origin.0 + quarter, self.origin.1 + quarter);
+        self.generation += 1 << k;
+        if self.nodes.len() > self.node_limit {
+            self.collect_garbage();
+            if self.nodes.len() > self.node_limit / 2 {
+                self.node_limit = self.node_limit.saturating_mul(2);
+            }
+        }
+    }
+
+    fn contains(&self, (y, x): Position) -> bool {
+        let size = 1 << self.nodes[self.root].level;
+        (self.origin.0..self.origin.0 + size).contains(&y)
+            && (self.origin.1..self.origin.1 + size).contains(&x)
+    }
+
+    pub(crate) fn node(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.lookup.get(&children) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            level: self.nodes[children[0]].level + 1,
+            children,
+            population: children.iter().map(|&c| self.nodes[c].population).sum(),
+        });
+        self.lookup.insert(children, id);
+        id
+    }
+
+    pub(crate) fn empty(&mut self, level: u32) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let last = *self.empty.last().unwrap();
+            let next = self.node([last; 4]);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Copy a node and its descendants into `nodes` for [`HashLifeGrid::collect_garbage`],
+    /// children first, and get its new id.
+    fn keep(&self, id: NodeId, ids: &mut [NodeId], nodes: &mut Vec<Node>) -> NodeId {
+        if ids[id] != NodeId::MAX {
+            return ids[id];
+        }
+        let mut node = self.nodes[id];
+        if node.level > 0 {
+            node.children = node.children.map(|child| self.keep(child, ids, nodes));
+        }
+        ids[id] = nodes.len();
+        nodes.push(node);
+        ids[id]
+    }
+
+    /// Check if all live cells are in the central half of a node.
+    fn is_centered(&self, id: NodeId) -> bool {
+        let [nw, ne, sw, se] = self.nodes[id].children;
+        let inner = |quadrant: NodeId, corner: usize| {
+            let node = &self.nodes[quadrant];
+            node.population == self.nodes[node.children[corner]].population
+        };
+        inner(nw, 3) && inner(ne, 2) && inner(sw, 1) && inner(se, 0)
+    }
+
+    /// Surround the root with empty space, doubling its size.
+    fn expand(&mut self) {
+        let level = self.nodes[self.root].level;
+        let e = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.nodes[self.root].children;
+        let children = [
+            self.node([e, e, e, nw]),
+            self.node([e, e, ne, e]),
+            self.node([e, sw, e, e]),
+            self.node([se, e, e, e]),
+        ];
+        self.root = self.node(children);
+        let half = 1 << (level - 1);
+        self.origin = (self.origin.0 - half, self.origin.1 - half);
+    }
+
+    /// The central half of a node, one level down.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.nodes[id].children;
+        let children = [
+            self.nodes[nw].children[3],
+            self.nodes[ne].children[2],
+            self.nodes[sw].children[1],
+            self.nodes[se].children[0],
+        ];
+        self.node(children)
+    }
+
+    fn set_in(&mut self, id: NodeId, y: i64, x: i64, alive: bool) -> NodeId {
+        let level = self.nodes[id].level;
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = self.nodes[id].children;
+        children[quadrant] = self.set_in(children[quadrant], y % half, x % half, alive);
+        self.node(children)
+    }
+
+    fn collect_live(&self, id: NodeId, (y, x): Position, cells: &mut Vec<Position>) {
+        let node = self.nodes[id];
+        if node.population == 0 {
+            return;
+        }
+        if node.level == 0 {
+            cells.push((y, x));
+            return;
+        }
+        let half = 1 << (node.level - 1);
+        for (quadrant, &child) in node.children.iter().enumerate() {
+            let offset = ((quadrant / 2) as i64 * half, (quadrant % 2) as i64 * half);
+            self.collect_live(child, (y + offset.0, x + offset.1), cells);
+        }
+    }
+
+    /// Get the central half of a node of level `n` advanced by `2^j` generations, where `j <= n - 2`.
+    fn result(&mut self, id: NodeId, j: u32) -> NodeId {
+        let level = self.nodes[id].level;
+        if self.nodes[id].population == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+        let result = if level == 2 {
+            self.base_result(id)
+        } else {
+            // Split the node into nine overlapping nodes one level down.
+            let [a, b, c, d] = self.nodes[id]
+                .children
+                .map(|child| self.nodes[child].children);
+            let n01 = self.node([a[1], b[0], a[3], b[2]]);
+            let n10 = self.node([a[2], a[3], c[0], c[1]]);
+            let n11 = self.node([a[3], b[2], c[1], d[0]]);
+            let n12 = self.node([b[2], b[3], d[0], d[1]]);
+            let n21 = self.node([c[1], d[0], c[3], d[2]]);
+            let [nw, ne, sw, se] = self.nodes[id].children;
+            let nine = [nw, n01, ne, n10, n11, n12, sw, n21, se];
+            // At full speed, both halves of the time are advanced, otherwise only the second half is.
+            let r = if j == level - 2 {
+                nine.map(|n| self.result(n, level - 3))
+            } else {
+                nine.map(|n| self.center(n))
+            };
+            let quadrants = [
+                self.node([r[0], r[1], r[3], r[4]]),
+                self.node([r[1], r[2], r[4], r[5]]),
+                self.node([r[3], r[4], r[6], r[7]]),
+                self.node([r[4], r[5], r[7], r[8]]),
+            ];
+            let step = j.min(level - 3);
+            let children = quadrants.map(|q| self.result(q, step));
+            self.node(children)
+        };
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Advance the central 2x2 cells of a 4x4 node by one generation.
+    fn base_result(&mut self, id: NodeId) -> NodeId {
+        let quadrants = self.nodes[id].children;
+        let cells = Array2::from_shape_fn((4, 4), |(y, x)| {
+            let quadrant = quadrants[(y / 2) * 2 + x / 2];
+            self.nodes[quadrant].children[(y % 2) * 2 + x % 2] == ALIVE
+        });
+        let next = |y: usize, x: usize| {
+            if (self.rule)(cells.slice(s![y - 1..y + 2, x - 1..x + 2])) {
+                ALIVE
+            } else {
+                DEAD
+            }
+        };
+        let children = [next(1, 1), next(1, 2), next(2, 1), next(2, 2)];
+        self.node(children)
+    }
+}
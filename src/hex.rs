@@ -0,0 +1,113 @@
+use crate::Neighborhood;
+use ndarray::{ArrayView2, IndexLonger};
+use std::ops::Index;
+
+/// The six-cell neighborhood of a hexagonal grid using axial coordinates.
+///
+/// Cells are stored with the axial `r` coordinate as the row and the axial `q`
+/// coordinate as the column, so a rectangular array describes a rhombus of hexagons.
+pub enum Hex {}
+
+impl Neighborhood for Hex {
+    type Neighbors<'a, T: 'a> = HexNeighbors<'a, T>;
+    type Edges<T> = [T; 6];
+}
+
+/// A direction on a hex grid, ordered counter-clockwise starting from east.
+///
+/// Edges are indexed by `direction as usize`. On egress, each edge holds the flow sent
+/// towards that direction. On ingress, each edge holds the flow received from that direction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl HexDirection {
+    /// All directions in edge order.
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::NorthEast,
+        HexDirection::NorthWest,
+        HexDirection::West,
+        HexDirection::SouthWest,
+        HexDirection::SouthEast,
+    ];
+
+    /// The axial `(q, r)` offset of the neighbor in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            HexDirection::East => (1, 0),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::NorthWest => (0, -1),
+            HexDirection::West => (-1, 0),
+            HexDirection::SouthWest => (-1, 1),
+            HexDirection::SouthEast => (0, 1),
+        }
+    }
+
+    /// The cube `(x, y, z)` offset of the neighbor in this direction, where `x + y + z == 0`.
+    pub fn cube_delta(self) -> (isize, isize, isize) {
+        let (q, r) = self.delta();
+        (q, -q - r, r)
+    }
+
+    /// The direction pointing the opposite way.
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 3) % 6]
+    }
+}
+
+/// The cells surrounding a hexagon, along with the hexagon itself.
+#[derive(Debug)]
+pub struct HexNeighbors<'a, T> {
+    window: ArrayView2<'a, T>,
+}
+
+impl<'a, T> Clone for HexNeighbors<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for HexNeighbors<'a, T> {}
+
+impl<'a, T> HexNeighbors<'a, T> {
+    /// Wraps a 3x3 window of axial storage centered on the hexagon.
+    ///
+    /// The top-left and bottom-right corners of the window are not neighbors and are ignored.
+    pub fn new(window: ArrayView2<'a, T>) -> Self {
+        assert_eq!(window.dim(), (3, 3), "hex neighbors need a 3x3 window");
+        Self { window }
+    }
+
+    /// The hexagon in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        IndexLonger::index(&self.window, (1, 1))
+    }
+
+    /// The neighbor in the given direction.
+    pub fn get(&self, direction: HexDirection) -> &'a T {
+        let (q, r) = direction.delta();
+        IndexLonger::index(&self.window, ((1 + r) as usize, (1 + q) as usize))
+    }
+
+    /// Iterate over the six neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = *self;
+        HexDirection::ALL.iter().map(move |&d| this.get(d))
+    }
+}
+
+impl<'a, T> Index<HexDirection> for HexNeighbors<'a, T> {
+    type Output = T;
+
+    fn index(&self, direction: HexDirection) -> &T {
+        self.get(direction)
+    }
+}
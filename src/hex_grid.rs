@@ -0,0 +1,115 @@
+#![allow(clippy::reversed_empty_ranges)]
+
+use crate::{
+    engine::{self, Lattice, Link},
+    Hex, HexDirection, HexNeighbors, Sim,
+};
+use ndarray::{s, Array2, ArrayView2, ArrayViewMut2, Ix2};
+use std::mem;
+
+/// Represents the state of a simulation on a hexagonal grid.
+///
+/// Cells are indexed by `(r, q)` in axial coordinates, so the grid is a rhombus of hexagons.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Cell: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Cell: serde::Deserialize<'de>"
+    ))
+)]
+pub struct HexGrid<S>
+where
+    S: Sim<Hex>,
+{
+    sim: S,
+    cells: Array2<S::Cell>,
+    /// The diffs of the last step, which are kept so their allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    diffs: Option<Array2<S::Diff>>,
+}
+
+impl<S> HexGrid<S>
+where
+    S: Sim<Hex>,
+    S::Cell: Send,
+{
+    /// Make a new grid with the given cells.
+    pub fn new(sim: S, mut original_cells: Array2<S::Cell>) -> Self {
+        let dims = original_cells.dim();
+        assert!(
+            dims.0 >= 1 && dims.1 >= 1,
+            "grid is empty, which isnt allowed"
+        );
+        let mut cells =
+            Array2::from_shape_simple_fn((dims.0 + 2, dims.1 + 2), || sim.cell_padding());
+        maybe_par_azip!((dest in &mut cells.slice_mut(s![1..-1, 1..-1]), cell in &mut original_cells) {
+            mem::swap(dest, cell);
+        });
+        Self {
+            sim,
+            cells,
+            diffs: None,
+        }
+    }
+
+    /// Get view of cells on the grid, indexed by `(r, q)`.
+    pub fn cells(&self) -> ArrayView2<'_, S::Cell> {
+        self.cells.slice(s![1..-1, 1..-1])
+    }
+
+    /// Get mutable view of cells on the grid, indexed by `(r, q)`.
+    pub fn cells_mut(&mut self) -> ArrayViewMut2<'_, S::Cell> {
+        self.cells.slice_mut(s![1..-1, 1..-1])
+    }
+}
+
+impl<S> HexGrid<S>
+where
+    S: Sim<Hex> + Sync,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    S::Flow: Send,
+{
+    engine::step_methods!(Hex);
+}
+
+impl Lattice<Ix2> for Hex {
+    fn padding() -> Ix2 {
+        Ix2(1, 1)
+    }
+
+    fn window() -> Ix2 {
+        Ix2(3, 3)
+    }
+
+    fn neighbors<'a, T: 'a>(_: &[usize], window: ArrayView2<'a, T>) -> HexNeighbors<'a, T> {
+        HexNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 6] {
+        [(); 6].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 6]) -> &mut [T] {
+        edges
+    }
+
+    // The top-left and bottom-right hexagons of each 2x2 chunk aren't adjacent, so only the
+    // first three directions need to be swapped with their opposites.
+    fn links() -> Vec<Link> {
+        HexDirection::ALL[..3]
+            .iter()
+            .map(|&direction| {
+                let (q, r) = direction.delta();
+                Link {
+                    edge: direction as usize,
+                    opposite: direction.opposite() as usize,
+                    delta: vec![r, q],
+                    parity: None,
+                }
+            })
+            .collect()
+    }
+}
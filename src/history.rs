@@ -0,0 +1,184 @@
+use crate::{GridError, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, ArrayView2};
+use std::collections::VecDeque;
+
+/// A copy of the cells of a grid at a generation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridSnapshot<C> {
+    generation: u64,
+    cells: Array2<C>,
+}
+
+impl<C> GridSnapshot<C> {
+    /// Make a snapshot of cells at a generation, or get [`GridError::Empty`] if there are no
+    /// cells.
+    pub fn new(generation: u64, cells: Array2<C>) -> Result<Self, GridError> {
+        if cells.is_empty() {
+            return Err(GridError::Empty);
+        }
+        Ok(Self { generation, cells })
+    }
+
+    /// The generation of the grid.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The cells of the grid.
+    pub fn cells(&self) -> ArrayView2<'_, C> {
+        self.cells.view()
+    }
+
+    /// Take the cells of the grid.
+    pub fn into_cells(self) -> Array2<C> {
+        self.cells
+    }
+}
+
+/// The cells changed by a step, which are enough to undo it.
+#[derive(Clone, Debug)]
+struct Delta<C> {
+    /// The generation before the step.
+    generation: u64,
+    /// The `(y, x)` index and old cell of every cell which changed.
+    old: Vec<((usize, usize), C)>,
+}
+
+/// The changes of the last steps, oldest first.
+#[derive(Clone, Debug)]
+pub(crate) struct History<C> {
+    limit: usize,
+    deltas: VecDeque<Delta<C>>,
+    eq: fn(&C, &C) -> bool,
+}
+
+impl<C> History<C> {
+    /// Forget every step.
+    pub(crate) fn clear(&mut self) {
+        self.deltas.clear();
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Copy the cells and generation of the grid.
+    pub fn snapshot(&self) -> GridSnapshot<S::Cell>
+    where
+        S::Cell: Clone,
+    {
+        GridSnapshot {
+            generation: self.generation,
+            cells: self.cells().to_owned(),
+        }
+    }
+
+    /// Set the cells and generation of the grid from a snapshot, which can have other dimensions.
+    ///
+    /// This forgets the history kept for [`SquareGrid::rollback`]. If the snapshot has no cells,
+    /// which can happen when it was deserialized, the grid isn't changed and
+    /// [`GridError::Empty`] is returned.
+    pub fn restore(&mut self, snapshot: &GridSnapshot<S::Cell>) -> Result<(), GridError>
+    where
+        S::Cell: Clone,
+    {
+        let fill = snapshot.cells.first().ok_or(GridError::Empty)?;
+        if self.cells().dim() != snapshot.cells.dim() {
+            self.try_resize(snapshot.cells.dim(), (0, 0), fill.clone())?;
+        }
+        self.cells_mut().assign(&snapshot.cells);
+        self.generation = snapshot.generation;
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+        Ok(())
+    }
+
+    /// Keep the changes of the last `n` steps so they can be undone with [`SquareGrid::rollback`],
+    /// or stop keeping them if `n` is `0`.
+    ///
+    /// Only the old cells which each step changes are kept, so slowly changing grids need little
+    /// memory. Changes made between steps aren't kept, so they aren't undone.
+    pub fn keep_last(&mut self, n: usize)
+    where
+        S::Cell: Clone + PartialEq,
+    {
+        self.copier.cell = Some(S::Cell::clone);
+        if n == 0 {
+            self.history = None;
+            return;
+        }
+        let history = self.history.get_or_insert_with(|| History {
+            limit: n,
+            deltas: VecDeque::new(),
+            eq: PartialEq::eq,
+        });
+        history.limit = n;
+        while history.deltas.len() > n {
+            history.deltas.pop_front();
+        }
+    }
+
+    /// The number of steps which can be undone with [`SquareGrid::rollback`].
+    pub fn history_len(&self) -> usize {
+        self.history
+            .as_ref()
+            .map(|history| history.deltas.len())
+            .unwrap_or(0)
+    }
+
+    /// Undo up to `k` of the last steps kept with [`SquareGrid::keep_last`], returning the
+    /// number of steps undone.
+    pub fn rollback(&mut self, k: usize) -> usize {
+        let mut undone = 0;
+        while undone < k {
+            let delta = match self.history.as_mut().and_then(|h| h.deltas.pop_back()) {
+                Some(delta) => delta,
+                None => break,
+            };
+            let mut cells = self.cells_mut();
+            for (index, cell) in delta.old {
+                cells[index] = cell;
+            }
+            self.generation = delta.generation;
+            undone += 1;
+        }
+        undone
+    }
+
+    /// Check if steps need the cells from before them to keep history.
+    pub(crate) fn keeps_history(&self) -> bool {
+        self.history.is_some()
+    }
+
+    /// Keep the cells which the last step changed, given the cells from before it.
+    pub(crate) fn record_history(&mut self, before: &Array2<S::Cell>) {
+        let cells = self.cells.slice(&Self::inside());
+        let (history, copy) = match (&mut self.history, self.copier.cell) {
+            (Some(history), Some(copy)) => (history, copy),
+            _ => return,
+        };
+        if before.dim() != cells.dim() {
+            history.clear();
+            return;
+        }
+        let eq = history.eq;
+        let old = before
+            .indexed_iter()
+            .zip(cells)
+            .filter(|&((_, old), new)| !eq(old, new))
+            .map(|((index, old), _)| (index, copy(old)))
+            .collect();
+        history.deltas.push_back(Delta {
+            generation: self.generation - 1,
+            old,
+        });
+        if history.deltas.len() > history.limit {
+            history.deltas.pop_front();
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, ArrayView2};
+use std::{fmt, mem, sync::Mutex};
+
+/// A hook called with the grid before or after a step.
+type StepHook<S, N> = Box<dyn FnMut(&SquareGrid<S, N>) + Send>;
+
+/// A hook called with the cells before and after a step.
+type ChangeHook<C> = Box<dyn FnMut(ArrayView2<'_, C>, ArrayView2<'_, C>) + Send>;
+
+/// A hook called with the grid and the cells edited while it was paused.
+type EditHook<S, N> = Box<dyn FnMut(&SquareGrid<S, N>, &[(usize, usize)]) + Send>;
+
+/// The hooks registered on a grid.
+///
+/// They are only called from the sequential parts of a step. The mutex is never locked and
+/// only makes the grid [`Sync`] without requiring the hooks to be.
+pub(crate) struct Hooks<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    inner: Mutex<HookList<S, N>>,
+}
+
+struct HookList<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    before: Vec<StepHook<S, N>>,
+    after: Vec<StepHook<S, N>>,
+    changed: Vec<ChangeHook<S::Cell>>,
+    edited: Vec<EditHook<S, N>>,
+}
+
+impl<S, N> Hooks<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn list(&mut self) -> &mut HookList<S, N> {
+        self.inner
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S, N> Default for Hooks<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(HookList {
+                before: vec![],
+                after: vec![],
+                changed: vec![],
+                edited: vec![],
+            }),
+        }
+    }
+}
+
+/// Hooks can't be cloned, so clones of a grid start without any.
+impl<S, N> Clone for Hooks<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<S, N> fmt::Debug for Hooks<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner.try_lock() {
+            Ok(list) => f
+                .debug_struct("Hooks")
+                .field("before", &list.before.len())
+                .field("after", &list.after.len())
+                .field("changed", &list.changed.len())
+                .field("edited", &list.edited.len())
+                .finish(),
+            Err(_) => f.write_str("Hooks { .. }"),
+        }
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Call `hook` with the grid at the start of every step.
+    ///
+    /// Hooks are called on the thread which steps the grid, so they don't need to be [`Sync`].
+    /// Clones of the grid don't have its hooks.
+    pub fn on_before_step(&mut self, hook: impl FnMut(&Self) + Send + 'static) {
+        self.hooks.list().before.push(Box::new(hook));
+    }
+
+    /// Call `hook` with the grid at the end of every step.
+    pub fn on_after_step(&mut self, hook: impl FnMut(&Self) + Send + 'static) {
+        self.hooks.list().after.push(Box::new(hook));
+    }
+
+    /// Call `hook` with the `(y, x)` index, old cell, and new cell of every cell which a step
+    /// changed, in row-major order.
+    ///
+    /// The cells are copied before every step while any of these hooks are registered.
+    pub fn on_cell_changed(
+        &mut self,
+        mut hook: impl FnMut((usize, usize), &S::Cell, &S::Cell) + Send + 'static,
+    ) where
+        S::Cell: Clone + PartialEq,
+    {
+        self.copier.cell = Some(S::Cell::clone);
+        self.hooks
+            .list()
+            .changed
+            .push(Box::new(move |before, after| {
+                for ((index, old), new) in before.indexed_iter().zip(after) {
+                    if old != new {
+                        hook(index, old, new);
+                    }
+                }
+            }));
+    }
+
+    /// Call `hook` with the grid and the `(y, x)` indices of the cells which were edited
+    /// whenever an [`Editor`](crate::Editor) made with [`SquareGrid::pause`] is resumed.
+    pub fn on_edit(&mut self, hook: impl FnMut(&Self, &[(usize, usize)]) + Send + 'static) {
+        self.hooks.list().edited.push(Box::new(hook));
+    }
+
+    /// Remove every hook.
+    pub fn clear_hooks(&mut self) {
+        self.hooks = Hooks::default();
+    }
+
+    /// Call the hooks for the cells edited while the grid was paused.
+    pub(crate) fn call_edit_hooks(&mut self, edited: &[(usize, usize)]) {
+        let mut hooks = mem::take(&mut self.hooks);
+        for hook in &mut hooks.list().edited {
+            hook(self, edited);
+        }
+        self.hooks = hooks;
+    }
+
+    /// Call the hooks for the start of a step, returning a copy of the cells if any hooks
+    /// or the history need to know which cells change.
+    pub(crate) fn begin_step(&mut self) -> Option<Array2<S::Cell>> {
+        // Stepping another way abandons a partial step.
+        self.partial = None;
+        self.start_timings();
+        let mut hooks = mem::take(&mut self.hooks);
+        let list = hooks.list();
+        for hook in &mut list.before {
+            hook(self);
+        }
+        let before = match self.copier.cell {
+            Some(copy) if !list.changed.is_empty() || self.keeps_history() => {
+                Some(self.cells().map(copy))
+            }
+            _ => None,
+        };
+        self.hooks = hooks;
+        before
+    }
+
+    /// Call the hooks for the end of a step and keep its changes in the history, given the
+    /// cells from [`SquareGrid::begin_step`].
+    pub(crate) fn end_step(&mut self, before: Option<Array2<S::Cell>>) {
+        let mut hooks = mem::take(&mut self.hooks);
+        let list = hooks.list();
+        if let Some(before) = before {
+            for hook in &mut list.changed {
+                hook(before.view(), self.cells());
+            }
+            self.record_history(&before);
+        }
+        self.finish_timings();
+        for hook in &mut list.after {
+            hook(self);
+        }
+        self.hooks = hooks;
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{CellColor, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + CellColor,
+{
+    /// Draw the grid with one pixel per cell, indexed by `(y, x)` like the cells.
+    pub fn to_image(&self) -> Array2<[u8; 4]> {
+        self.cells().map(CellColor::color)
+    }
+
+    /// Save the grid as a PNG image with one pixel per cell.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_png(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Write the grid as a PNG image with one pixel per cell.
+    pub fn write_png(&self, writer: &mut impl Write) -> io::Result<()> {
+        write_png(writer, &self.to_image())
+    }
+}
+
+/// Write pixels as an uncompressed 8-bit RGBA PNG image.
+pub fn write_png(writer: &mut impl Write, pixels: &Array2<[u8; 4]>) -> io::Result<()> {
+    write_header(writer, pixels.dim())?;
+    write_chunk(writer, b"IDAT", &image_data(pixels))?;
+    write_chunk(writer, b"IEND", &[])
+}
+
+/// Write the PNG signature and the header of an image of `(height, width)` pixels.
+fn write_header(writer: &mut impl Write, (h, w): (usize, usize)) -> io::Result<()> {
+    writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+    let mut header = vec![];
+    header.extend(&(w as u32).to_be_bytes());
+    header.extend(&(h as u32).to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, adaptive filtering, no interlacing.
+    header.extend(&[8, 6, 0, 0, 0]);
+    write_chunk(writer, b"IHDR", &header)
+}
+
+/// Compress the pixels of an image into the data of its `IDAT` chunks.
+fn image_data(pixels: &Array2<[u8; 4]>) -> Vec<u8> {
+    let (h, w) = pixels.dim();
+    // Every row starts with the byte of the filter used on it, which is none.
+    let mut raw = Vec::with_capacity(h * (1 + 4 * w));
+    for row in pixels.genrows() {
+        raw.push(0);
+        row.iter().for_each(|pixel| raw.extend(pixel));
+    }
+    zlib_stored(&raw)
+}
+
+/// Captures frames of a simulation every few generations and writes them as an animated PNG.
+#[derive(Clone, Debug)]
+pub struct Animator {
+    every: u64,
+    scale: usize,
+    delay: Duration,
+    frames: Vec<Array2<[u8; 4]>>,
+}
+
+impl Animator {
+    /// Make an animator which captures every `every` generations and draws each cell as a
+    /// `scale` by `scale` square, with 100 milliseconds between frames.
+    pub fn new(every: u64, scale: usize) -> Self {
+        assert!(every >= 1, "must capture at least every generation");
+        assert!(scale >= 1, "cells must be at least one pixel");
+        Self {
+            every,
+            scale,
+            delay: Duration::from_millis(100),
+            frames: vec![],
+        }
+    }
+
+    /// Set the time between frames, which is rounded to milliseconds.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// The frames captured so far.
+    pub fn frames(&self) -> &[Array2<[u8; 4]>] {
+        &self.frames
+    }
+
+    /// Capture the grid if its generation is a multiple of `every`, returning whether it was.
+    pub fn capture<S, N>(&mut self, grid: &SquareGrid<S, N>) -> bool
+    where
+        S: Sim<N>,
+        N: SquareNeighborhood,
+        S::Cell: Send + CellColor,
+    {
+        self.capture_with(grid, CellColor::color)
+    }
+
+    /// Like [`Animator::capture`], but with the colors of the cells given by `palette`.
+    pub fn capture_with<S, N>(
+        &mut self,
+        grid: &SquareGrid<S, N>,
+        palette: impl Fn(&S::Cell) -> [u8; 4],
+    ) -> bool
+    where
+        S: Sim<N>,
+        N: SquareNeighborhood,
+        S::Cell: Send,
+    {
+        if !grid.generation().is_multiple_of(self.every) {
+            return false;
+        }
+        let cells = grid.cells();
+        let (h, w) = cells.dim();
+        let frame = Array2::from_shape_fn((h * self.scale, w * self.scale), |(y, x)| {
+            palette(&cells[(y / self.scale, x / self.scale)])
+        });
+        if let Some(first) = self.frames.first() {
+            assert_eq!(
+                first.dim(),
+                frame.dim(),
+                "every frame must be the same size"
+            );
+        }
+        self.frames.push(frame);
+        true
+    }
+
+    /// Save the frames as an animated PNG which loops forever.
+    pub fn save_apng(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_apng(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Write the frames as an animated PNG which loops forever.
+    ///
+    /// Viewers which don't support animation show the first frame.
+    pub fn write_apng(&self, writer: &mut impl Write) -> io::Result<()> {
+        let first = self
+            .frames
+            .first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no frames captured"))?;
+        let (h, w) = first.dim();
+        write_header(writer, (h, w))?;
+        let mut control = vec![];
+        control.extend(&(self.frames.len() as u32).to_be_bytes());
+        control.extend(&0u32.to_be_bytes());
+        write_chunk(writer, b"acTL", &control)?;
+
+        let delay = self.delay.as_millis().min(u16::MAX as u128) as u16;
+        let mut sequence = 0u32;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let mut control = sequence.to_be_bytes().to_vec();
+            control.extend(&(w as u32).to_be_bytes());
+            control.extend(&(h as u32).to_be_bytes());
+            control.extend(&[0; 8]);
+            control.extend(&delay.to_be_bytes());
+            control.extend(&1000u16.to_be_bytes());
+            // Leave the frame as it is and replace the pixels under it.
+            control.extend(&[0, 0]);
+            write_chunk(writer, b"fcTL", &control)?;
+            sequence += 1;
+            if i == 0 {
+                write_chunk(writer, b"IDAT", &image_data(frame))?;
+            } else {
+                let mut data = sequence.to_be_bytes().to_vec();
+                data.extend(image_data(frame));
+                write_chunk(writer, b"fdAT", &data)?;
+                sequence += 1;
+            }
+        }
+        write_chunk(writer, b"IEND", &[])
+    }
+}
+
+/// Write a PNG chunk with its length and checksum.
+fn write_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = !crc32(crc32(!0, kind), data);
+    writer.write_all(&crc.to_be_bytes())
+}
+
+/// Update a CRC-32 with bytes.
+fn crc32(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                crc >> 1 ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Wrap data in a zlib stream of stored deflate blocks, which aren't compressed.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut chunks = data.chunks(0xffff).peekable();
+    if chunks.peek().is_none() {
+        out.extend(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        out.push(chunks.peek().is_none() as u8);
+        let len = chunk.len() as u16;
+        out.extend(&len.to_le_bytes());
+        out.extend(&(!len).to_le_bytes());
+        out.extend(chunk);
+    }
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    out.extend(&(b << 16 | a).to_be_bytes());
+    out
+}
@@ -0,0 +1,148 @@
+use crate::{moore::MOORE_DELTAS, Moore, Sim};
+use itertools::Itertools;
+use ndarray::Array2;
+use std::collections::{HashMap, HashSet};
+
+/// A position on an [`InfiniteGrid`] as `(y, x)`.
+pub type Position = (i64, i64);
+
+/// An unbounded grid that only stores cells which differ from the padding cell.
+///
+/// The padding cell of the sim is used as the background that fills all of space.
+/// The sim must keep the background quiescent: a padding cell whose neighbors are all
+/// padding cells must compute the padding diff, and a cell whose neighboring diffs are all
+/// the padding diff must remain a padding cell and only send padding flows.
+#[derive(Clone, Debug)]
+pub struct InfiniteGrid<S>
+where
+    S: Sim<Moore>,
+{
+    sim: S,
+    background: S::Cell,
+    cells: HashMap<Position, S::Cell>,
+}
+
+impl<S> InfiniteGrid<S>
+where
+    S: Sim<Moore>,
+    S::Cell: Clone + PartialEq,
+    S::Diff: Clone,
+{
+    /// Make a new grid where every cell is the padding cell.
+    pub fn new(sim: S) -> Self {
+        let background = sim.cell_padding();
+        Self {
+            sim,
+            background,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Make a new grid with the given cells, with all others being the padding cell.
+    pub fn from_cells(sim: S, cells: impl IntoIterator<Item = (Position, S::Cell)>) -> Self {
+        let mut grid = Self::new(sim);
+        for (position, cell) in cells {
+            grid.set(position, cell);
+        }
+        grid
+    }
+
+    /// Get the cell at a position.
+    pub fn get(&self, position: Position) -> &S::Cell {
+        self.cells.get(&position).unwrap_or(&self.background)
+    }
+
+    /// Set the cell at a position.
+    pub fn set(&mut self, position: Position, cell: S::Cell) {
+        if cell == self.background {
+            self.cells.remove(&position);
+        } else {
+            self.cells.insert(position, cell);
+        }
+    }
+
+    /// Iterate over all cells that differ from the padding cell in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &S::Cell)> {
+        self.cells.iter().map(|(&position, cell)| (position, cell))
+    }
+
+    /// The number of cells that differ from the padding cell.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Check if every cell is the padding cell.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Perform one step of the simulation.
+    pub fn step(&mut self) {
+        // Only cells near non-background cells need to compute.
+        let compute_positions = dilate(self.cells.keys().copied());
+        let diffs: HashMap<Position, S::Diff> = compute_positions
+            .iter()
+            .map(|&position| {
+                let window = window(position, |p| self.get(p).clone());
+                (position, self.sim.compute(window.view()))
+            })
+            .collect();
+
+        // Only cells near non-background diffs need to egress.
+        let background_diff = self
+            .sim
+            .compute(Array2::from_shape_simple_fn((3, 3), || self.background.clone()).view());
+        let egress_positions = dilate(compute_positions.iter().copied());
+        let mut egressed: HashMap<_, _> = egress_positions
+            .iter()
+            .map(|&position| {
+                let window = window(position, |p| {
+                    diffs.get(&p).unwrap_or(&background_diff).clone()
+                });
+                let mut cell = self.get(position).clone();
+                let flows = self.sim.egress(&mut cell, window.view());
+                (position, (cell, flows.map(Some)))
+            })
+            .collect();
+
+        // Cells that didn't egress may still receive flows from cells that did.
+        let mut cells = HashMap::new();
+        for position in dilate(egress_positions.iter().copied()) {
+            let mut flows = [(); 8].map(|_| self.sim.flow_padding());
+            for (i, flow) in flows.iter_mut().enumerate() {
+                let (dy, dx) = MOORE_DELTAS[i];
+                let neighbor = (position.0 + dy as i64, position.1 + dx as i64);
+                if let Some((_, neighbor_flows)) = egressed.get_mut(&neighbor) {
+                    *flow = neighbor_flows[(i + 4) % 8]
+                        .take()
+                        .expect("flow was taken twice");
+                }
+            }
+            let mut cell = match egressed.get_mut(&position) {
+                Some((cell, _)) => std::mem::replace(cell, self.background.clone()),
+                None => self.background.clone(),
+            };
+            self.sim.ingress(&mut cell, flows);
+            if cell != self.background {
+                cells.insert(position, cell);
+            }
+        }
+        self.cells = cells;
+    }
+}
+
+/// Make the 3x3 window centered on a position.
+fn window<T>((y, x): Position, mut f: impl FnMut(Position) -> T) -> Array2<T> {
+    Array2::from_shape_fn((3, 3), |(dy, dx)| f((y + dy as i64 - 1, x + dx as i64 - 1)))
+}
+
+/// Get the positions which are at or next to any of the given positions.
+fn dilate(positions: impl Iterator<Item = Position>) -> HashSet<Position> {
+    positions
+        .flat_map(|(y, x)| {
+            (-1..=1)
+                .cartesian_product(-1..=1)
+                .map(move |(dy, dx)| (y + dy, x + dx))
+        })
+        .collect()
+}
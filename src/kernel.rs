@@ -0,0 +1,305 @@
+use ndarray::{s, Array2, ArrayView2, IndexLonger};
+use std::{
+    f64::consts::PI,
+    ops::{Add, Mul, Sub},
+};
+
+/// Square weights centered on a cell, used to take weighted sums of the cells around it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kernel {
+    weights: Array2<f64>,
+}
+
+impl Kernel {
+    /// Make a kernel from square weights indexed by `(y, x)` with an odd width.
+    pub fn from_weights(weights: Array2<f64>) -> Self {
+        let (h, w) = weights.dim();
+        assert!(
+            h == w && h % 2 == 1,
+            "kernel must be square with an odd width"
+        );
+        Self { weights }
+    }
+
+    /// Make a kernel from a function of the distance from the center, out to `radius`.
+    pub fn radial(radius: usize, mut f: impl FnMut(f64) -> f64) -> Self {
+        let r = radius as f64;
+        Self::from_weights(Array2::from_shape_fn(
+            (2 * radius + 1, 2 * radius + 1),
+            |(y, x)| {
+                let (dy, dx) = (y as f64 - r, x as f64 - r);
+                f((dy * dy + dx * dx).sqrt())
+            },
+        ))
+    }
+
+    /// A disk where cells are weighted by how much of them is inside the radius.
+    pub fn disk(radius: f64) -> Self {
+        Self::radial(radius.ceil() as usize, |d| {
+            (radius + 0.5 - d).clamp(0.0, 1.0)
+        })
+    }
+
+    /// A ring between two radii where cells are weighted by how much of them is inside the ring.
+    pub fn annulus(inner: f64, outer: f64) -> Self {
+        Self::radial(outer.ceil() as usize, |d| {
+            (outer + 0.5 - d).clamp(0.0, 1.0) * (d - inner + 0.5).clamp(0.0, 1.0)
+        })
+    }
+
+    /// The smooth concentric shells used by Lenia, normalized so the weights sum to `1`.
+    ///
+    /// Each peak is the height of one shell, from the innermost to the outermost.
+    pub fn lenia(radius: usize, peaks: &[f64]) -> Self {
+        let shells = peaks.len() as f64;
+        Self::radial(radius, |d| {
+            let r = d / radius as f64 * shells;
+            if r >= shells || peaks.is_empty() {
+                return 0.0;
+            }
+            let shell = r.floor();
+            let r = r - shell;
+            let bump = if r > 0.0 && r < 1.0 {
+                (4.0 - 1.0 / (r * (1.0 - r))).exp()
+            } else {
+                0.0
+            };
+            peaks[shell as usize] * bump
+        })
+        .normalized()
+    }
+
+    /// A gaussian blur with the given standard deviation, normalized so the weights sum to `1`.
+    pub fn gaussian(radius: usize, sigma: f64) -> Self {
+        Self::radial(radius, |d| (-d * d / (2.0 * sigma * sigma)).exp()).normalized()
+    }
+
+    /// The discrete laplacian, which is how much the neighbors differ from the center on average.
+    ///
+    /// The orthogonal neighbors have weight `0.2`, the diagonal neighbors have weight `0.05`,
+    /// and the center has weight `-1`.
+    pub fn laplacian() -> Self {
+        Self::from_weights(ndarray::arr2(&[
+            [0.05, 0.2, 0.05],
+            [0.2, -1.0, 0.2],
+            [0.05, 0.2, 0.05],
+        ]))
+    }
+
+    /// Scale the weights so that they sum to `1`.
+    pub fn normalized(self) -> Self {
+        let sum = self.weights.sum();
+        Self::from_weights(self.weights / sum)
+    }
+
+    /// How far the kernel reaches from the center on each axis.
+    pub fn radius(&self) -> usize {
+        self.weights.nrows() / 2
+    }
+
+    /// The weights indexed by `(y, x)`.
+    pub fn weights(&self) -> ArrayView2<'_, f64> {
+        self.weights.view()
+    }
+
+    /// Take the weighted sum of a square window centered on a cell.
+    ///
+    /// The window may be larger than the kernel, in which case only the middle is used.
+    pub fn apply(&self, window: ArrayView2<'_, f64>) -> f64 {
+        self.neighbors(window).weighted_sum()
+    }
+
+    /// Pair the cells of a square window centered on a cell with their weights.
+    ///
+    /// The window may be larger than the kernel, in which case only the middle is used.
+    pub fn neighbors<'a, 'w: 'a, T>(&'a self, window: ArrayView2<'w, T>) -> KernelNeighbors<'a, T> {
+        let width = self.weights.nrows();
+        assert!(
+            window.nrows() >= width && window.ncols() == window.nrows(),
+            "window is smaller than the kernel"
+        );
+        let offset = (window.nrows() - width) / 2;
+        KernelNeighbors {
+            window: window
+                .reborrow()
+                .slice_move(s![offset..offset + width, offset..offset + width]),
+            weights: self.weights.view(),
+        }
+    }
+
+    /// Apply the kernel centered on every cell at once, treating everything outside as `0`.
+    ///
+    /// This uses the fast Fourier transform, so it is much faster than applying the kernel
+    /// to every cell when the kernel is large.
+    pub fn convolve(&self, cells: ArrayView2<'_, f64>) -> Array2<f64> {
+        let (h, w) = cells.dim();
+        let r = self.radius();
+        let size = (
+            (h + 2 * r).next_power_of_two(),
+            (w + 2 * r).next_power_of_two(),
+        );
+        let mut image = Array2::from_elem(size, Complex::default());
+        for ((y, x), &cell) in cells.indexed_iter() {
+            image[(y, x)].re = cell;
+        }
+        // The kernel is flipped and wrapped around the origin so that the circular
+        // convolution lines each weight up with the cell at its offset.
+        let mut kernel = Array2::from_elem(size, Complex::default());
+        for ((y, x), &weight) in self.weights.indexed_iter() {
+            let ky = (size.0 + r - y) % size.0;
+            let kx = (size.1 + r - x) % size.1;
+            kernel[(ky, kx)].re = weight;
+        }
+        fft2(&mut image, false);
+        fft2(&mut kernel, false);
+        image.zip_mut_with(&kernel, |a, &b| *a = *a * b);
+        fft2(&mut image, true);
+        let scale = 1.0 / (size.0 * size.1) as f64;
+        image.slice(s![..h, ..w]).map(|c| c.re * scale)
+    }
+}
+
+/// The cells around a cell paired with the weights of a [`Kernel`].
+#[derive(Debug)]
+pub struct KernelNeighbors<'a, T> {
+    window: ArrayView2<'a, T>,
+    weights: ArrayView2<'a, f64>,
+}
+
+impl<'a, T> Clone for KernelNeighbors<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for KernelNeighbors<'a, T> {}
+
+impl<'a, T> KernelNeighbors<'a, T> {
+    /// The cell in the middle of the kernel.
+    pub fn center(&self) -> &'a T {
+        let r = self.window.nrows() / 2;
+        IndexLonger::index(&self.window, (r, r))
+    }
+
+    /// Iterate over the weights and cells, including the center, in `(y, x)` order.
+    pub fn iter(&self) -> impl Iterator<Item = (f64, &'a T)> + 'a {
+        let (window, weights) = (self.window, self.weights);
+        weights.into_iter().copied().zip(window)
+    }
+
+    /// Sum every cell multiplied by its weight.
+    pub fn weighted_sum(&self) -> f64
+    where
+        T: Copy + Into<f64>,
+    {
+        self.weighted_sum_by(|&cell| cell.into())
+    }
+
+    /// Sum the value of every cell multiplied by its weight.
+    pub fn weighted_sum_by(&self, mut value: impl FnMut(&T) -> f64) -> f64 {
+        self.iter()
+            .filter(|&(weight, _)| weight != 0.0)
+            .map(|(weight, cell)| weight * value(cell))
+            .sum()
+    }
+
+    /// The cells, cropped to the size of the kernel.
+    pub fn window(&self) -> ArrayView2<'a, T> {
+        self.window
+    }
+
+    /// The weights of the kernel.
+    pub fn weights(&self) -> ArrayView2<'a, f64> {
+        self.weights
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            re: self.re + other.re,
+            im: self.im + other.im,
+        }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            re: self.re - other.re,
+            im: self.im - other.im,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+/// Unscaled in-place radix-2 FFT of a power of two length.
+fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * PI / len as f64;
+        let step = Complex {
+            re: angle.cos(),
+            im: angle.sin(),
+        };
+        for chunk in data.chunks_mut(len) {
+            let mut twiddle = Complex { re: 1.0, im: 0.0 };
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for (a, b) in low.iter_mut().zip(high) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a = *a + t;
+                twiddle = twiddle * step;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Unscaled FFT along both axes.
+fn fft2(data: &mut Array2<Complex>, inverse: bool) {
+    for mut row in data.genrows_mut() {
+        fft(row.as_slice_mut().unwrap(), inverse);
+    }
+    let mut buffer = vec![Complex::default(); data.nrows()];
+    for mut column in data.gencolumns_mut() {
+        buffer.iter_mut().zip(&column).for_each(|(b, &c)| *b = c);
+        fft(&mut buffer, inverse);
+        column.iter_mut().zip(&buffer).for_each(|(c, &b)| *c = b);
+    }
+}
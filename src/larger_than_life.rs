@@ -0,0 +1,234 @@
+use crate::{
+    generations::next_generation, ParseRuleError, Radius, RadiusEdges, RadiusNeighbors, Sim,
+    SquareNeighborhood,
+};
+use ndarray::{s, ArrayView2};
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+/// The shape of the cells counted by a [`LargerThanLife`] rule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LtlShape {
+    /// Every cell within the radius on both axes.
+    Moore,
+    /// Every cell within the radius in taxicab distance.
+    VonNeumann,
+}
+
+/// A life-like rule which counts live cells over a large radius, with optional decaying states.
+///
+/// The rule can be simulated with any [`Radius`] neighborhood at least as big as its radius.
+/// Making a grid with a smaller neighborhood panics.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LargerThanLife {
+    radius: usize,
+    states: u8,
+    middle: bool,
+    shape: LtlShape,
+    birth: Vec<RangeInclusive<usize>>,
+    survival: Vec<RangeInclusive<usize>>,
+}
+
+impl LargerThanLife {
+    /// The largest radius which [`LargerThanLife::parse`] accepts, which is the same as Golly's.
+    pub const MAX_RADIUS: usize = 500;
+
+    /// Make a rule with the given radius and ranges of live cell counts causing birth and survival.
+    ///
+    /// The rule has two states, uses the Moore shape, and doesn't count the middle cell.
+    pub fn new(
+        radius: usize,
+        birth: Vec<RangeInclusive<usize>>,
+        survival: Vec<RangeInclusive<usize>>,
+    ) -> Self {
+        assert!(radius >= 1, "radius must be at least 1");
+        Self {
+            radius,
+            states: 2,
+            middle: false,
+            shape: LtlShape::Moore,
+            birth,
+            survival,
+        }
+    }
+
+    /// Set the total number of states including dead and alive.
+    pub fn with_states(self, states: u8) -> Self {
+        assert!(states >= 2, "there must be at least two states");
+        Self { states, ..self }
+    }
+
+    /// Set whether the middle cell is counted along with its neighbors.
+    pub fn with_middle(self, middle: bool) -> Self {
+        Self { middle, ..self }
+    }
+
+    /// Set the shape of the counted cells.
+    pub fn with_shape(self, shape: LtlShape) -> Self {
+        Self { shape, ..self }
+    }
+
+    /// Parse a rulestring in `R5,C0,M1,S34..58,B34..45,NM` notation.
+    ///
+    /// Ranges may also be written as `34-58`, and several ranges may follow `S` or `B`
+    /// separated by commas. `C0`, `C1`, and `C2` all mean two states. Ranges which end before
+    /// they start and radii over [`LargerThanLife::MAX_RADIUS`] are rejected.
+    pub fn parse(rule: &str) -> Result<Self, ParseRuleError> {
+        let invalid = || ParseRuleError::InvalidFormat(rule.to_owned());
+        let mut parsed = Self::new(1, vec![], vec![]);
+        let mut radius = None;
+        // Which list bare ranges are added to.
+        let mut list: Option<bool> = None;
+        for token in rule.trim().split(',').map(str::trim) {
+            let (key, value) = match token.chars().next() {
+                Some(c) if c.is_ascii_alphabetic() => (Some(c.to_ascii_uppercase()), &token[1..]),
+                Some(_) => (None, token),
+                None => return Err(invalid()),
+            };
+            let number = || value.parse::<usize>().map_err(|_| invalid());
+            match key {
+                Some('R') => radius = Some(number()?),
+                Some('C') => {
+                    parsed.states = match number()? {
+                        0..=2 => 2,
+                        n @ 3..=255 => n as u8,
+                        _ => return Err(invalid()),
+                    }
+                }
+                Some('M') => parsed.middle = number()? == 1,
+                Some('N') => {
+                    parsed.shape = match value {
+                        "M" | "m" => LtlShape::Moore,
+                        "N" | "n" => LtlShape::VonNeumann,
+                        _ => return Err(invalid()),
+                    }
+                }
+                Some('S') => list = Some(false),
+                Some('B') => list = Some(true),
+                _ if key.is_none() && list.is_some() => {}
+                _ => return Err(invalid()),
+            }
+            if matches!(key, Some('S') | Some('B') | None) && !value.is_empty() {
+                let range = parse_range(value).ok_or_else(invalid)?;
+                match list {
+                    Some(true) => parsed.birth.push(range),
+                    _ => parsed.survival.push(range),
+                }
+            }
+        }
+        parsed.radius = radius
+            .filter(|r| (1..=Self::MAX_RADIUS).contains(r))
+            .ok_or_else(invalid)?;
+        Ok(parsed)
+    }
+
+    /// The radius of the counted cells.
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// Get the next state of a cell given its state and number of counted live cells.
+    pub fn next(&self, state: u8, n: usize) -> u8 {
+        let contains = |ranges: &[RangeInclusive<usize>]| ranges.iter().any(|r| r.contains(&n));
+        next_generation(
+            state,
+            self.states,
+            contains(&self.birth),
+            contains(&self.survival),
+        )
+    }
+
+    /// Count the live cells in a window centered on a cell.
+    fn count(&self, window: ArrayView2<'_, u8>) -> usize {
+        let r = self.radius as isize;
+        let center = (window.nrows() / 2) as isize;
+        let window = window.slice(s![center - r..=center + r, center - r..=center + r]);
+        let counted = window
+            .indexed_iter()
+            .filter(|&((y, x), &state)| {
+                let distance = (y as isize - r).abs() + (x as isize - r).abs();
+                state == 1 && (self.shape == LtlShape::Moore || distance <= r)
+            })
+            .count();
+        let middle = window[(self.radius, self.radius)] == 1;
+        counted - (middle && !self.middle) as usize
+    }
+}
+
+fn parse_range(range: &str) -> Option<RangeInclusive<usize>> {
+    let mut bounds = range.splitn(2, "..").flat_map(|s| s.split('-'));
+    let start = bounds.next()?.parse().ok()?;
+    let end = match bounds.next() {
+        Some(end) => end.parse().ok()?,
+        None => start,
+    };
+    (start <= end).then_some(start..=end)
+}
+
+impl FromStr for LargerThanLife {
+    type Err = ParseRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, ParseRuleError> {
+        Self::parse(rule)
+    }
+}
+
+impl fmt::Display for LargerThanLife {
+    /// Writes the rule in `R5,C0,M1,S34..58,B34..45,NM` notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges = |ranges: &[RangeInclusive<usize>]| -> String {
+            ranges
+                .iter()
+                .map(|r| format!("{}..{}", r.start(), r.end()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(
+            f,
+            "R{},C{},M{},S{},B{},N{}",
+            self.radius,
+            if self.states == 2 { 0 } else { self.states },
+            self.middle as u8,
+            ranges(&self.survival),
+            ranges(&self.birth),
+            match self.shape {
+                LtlShape::Moore => 'M',
+                LtlShape::VonNeumann => 'N',
+            }
+        )
+    }
+}
+
+impl<const R: usize> Sim<Radius<R>> for LargerThanLife {
+    type Cell = u8;
+    type Diff = u8;
+    type Flow = ();
+
+    fn compute(&self, cells: RadiusNeighbors<'_, u8, R>) -> u8 {
+        self.next(*cells.center(), self.count(cells.window()))
+    }
+
+    fn egress(&self, cell: &mut u8, diffs: RadiusNeighbors<'_, u8, R>) -> RadiusEdges<()> {
+        *cell = *diffs.center();
+        Radius::<R>::edges(|| ())
+    }
+
+    fn ingress(&self, _: &mut u8, _: RadiusEdges<()>) {}
+
+    /// Grids get their padding from this when they are made, so the radius is checked once
+    /// here instead of for every cell in every step.
+    fn cell_padding(&self) -> u8 {
+        assert!(
+            self.radius <= R,
+            "rule radius is larger than the neighborhood radius"
+        );
+        0
+    }
+
+    fn diff_padding(&self) -> u8 {
+        0
+    }
+
+    fn flow_padding(&self) {}
+}
@@ -0,0 +1,201 @@
+use crate::{Hex, HexDirection, HexNeighbors, Moore, Sim};
+use ndarray::ArrayView2;
+
+/// Maps the particles in a cell before a collision to the particles after it.
+///
+/// States are bit fields with bit `i` set if a particle is moving in direction `i`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollisionTable {
+    table: Vec<u8>,
+}
+
+impl CollisionTable {
+    /// Make a table for `directions` directions from a function of each state.
+    pub fn from_fn(directions: u32, f: impl FnMut(u8) -> u8) -> Self {
+        assert!(directions <= 8, "states must fit in a byte");
+        Self {
+            table: (0..1u16 << directions)
+                .map(|state| state as u8)
+                .map(f)
+                .collect(),
+        }
+    }
+
+    /// The collisions of the HPP gas on a square grid, where two particles meeting head-on
+    /// leave at right angles.
+    ///
+    /// The directions are east, north, west, and south.
+    pub fn hpp() -> Self {
+        Self::from_fn(4, |state| match state {
+            0b0101 => 0b1010,
+            0b1010 => 0b0101,
+            state => state,
+        })
+    }
+
+    /// The collisions of the FHP gas on a hex grid, in the order of [`HexDirection`].
+    ///
+    /// Two particles meeting head-on are turned by 60 degrees, counter-clockwise if `left`
+    /// and clockwise otherwise, and three particles meeting at 120 degrees bounce back.
+    pub fn fhp(left: bool) -> Self {
+        let rotate = |state: u8, by: u32| ((state << by) | (state >> (6 - by))) & 0b11_1111;
+        Self::from_fn(6, |state| match state {
+            0b00_1001 | 0b01_0010 | 0b10_0100 => rotate(state, if left { 1 } else { 5 }),
+            0b01_0101 | 0b10_1010 => rotate(state, 1),
+            state => state,
+        })
+    }
+
+    /// The number of directions.
+    pub fn directions(&self) -> u32 {
+        self.table.len().trailing_zeros()
+    }
+
+    /// Get the particles after a collision.
+    pub fn collide(&self, state: u8) -> u8 {
+        self.table[state as usize]
+    }
+
+    /// Check that every collision keeps the number of particles and their momentum, given the
+    /// `(y, x)` velocity of each direction.
+    pub fn is_conservative(&self, velocities: &[(f64, f64)]) -> bool {
+        assert_eq!(velocities.len(), self.directions() as usize);
+        let momentum = |state: u8| {
+            velocities
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| state >> i & 1 == 1)
+                .fold((0.0, 0.0), |(y, x), (_, &(vy, vx))| (y + vy, x + vx))
+        };
+        self.table.iter().enumerate().all(|(before, &after)| {
+            let (before, after) = (before as u8, after);
+            let ((by, bx), (ay, ax)) = (momentum(before), momentum(after));
+            before.count_ones() == after.count_ones()
+                && (by - ay).abs() < 1e-9
+                && (bx - ax).abs() < 1e-9
+        })
+    }
+}
+
+/// The HPP lattice gas, where particles move east, north, west, or south on a square grid.
+///
+/// Cells are bit fields of the directions of their particles, as in [`CollisionTable::hpp`].
+/// Each step, particles collide within each cell and then move one cell in their direction.
+/// Particles which leave an edge that doesn't wrap are lost, so wrap every edge to keep them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hpp {
+    pub collisions: CollisionTable,
+}
+
+impl Default for Hpp {
+    fn default() -> Self {
+        Self {
+            collisions: CollisionTable::hpp(),
+        }
+    }
+}
+
+impl Sim<Moore> for Hpp {
+    type Cell = u8;
+    type Diff = u8;
+    type Flow = bool;
+
+    fn compute(&self, cells: ArrayView2<'_, u8>) -> u8 {
+        self.collisions.collide(cells[(1, 1)])
+    }
+
+    fn egress(&self, cell: &mut u8, diffs: ArrayView2<'_, u8>) -> [bool; 8] {
+        let state = diffs[(1, 1)];
+        *cell = 0;
+        let mut flows = [false; 8];
+        for direction in 0..4 {
+            flows[2 * direction] = state >> direction & 1 == 1;
+        }
+        flows
+    }
+
+    fn ingress(&self, cell: &mut u8, flows: [bool; 8]) {
+        for direction in 0..4 {
+            // A particle moving in a direction comes from the opposite side.
+            if flows[(2 * direction + 4) % 8] {
+                *cell |= 1 << direction;
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> u8 {
+        0
+    }
+
+    fn diff_padding(&self) -> u8 {
+        0
+    }
+
+    fn flow_padding(&self) -> bool {
+        false
+    }
+}
+
+/// The FHP lattice gas, where particles move in the six directions of a hex grid.
+///
+/// The low six bits of a cell are the directions of its particles, as in
+/// [`CollisionTable::fhp`], and the high bit picks the way head-on collisions turn. It flips
+/// every step, so cells which start with the same bit alternate between turning left and right.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fhp {
+    /// The collisions when the high bit is clear and set.
+    pub collisions: [CollisionTable; 2],
+}
+
+/// The bit of an FHP cell which picks the way head-on collisions turn.
+pub const FHP_CHIRALITY: u8 = 0x80;
+
+impl Default for Fhp {
+    fn default() -> Self {
+        Self {
+            collisions: [CollisionTable::fhp(true), CollisionTable::fhp(false)],
+        }
+    }
+}
+
+impl Sim<Hex> for Fhp {
+    type Cell = u8;
+    type Diff = u8;
+    type Flow = bool;
+
+    fn compute(&self, cells: HexNeighbors<'_, u8>) -> u8 {
+        let cell = *cells.center();
+        let chirality = cell & FHP_CHIRALITY;
+        let table = &self.collisions[(chirality != 0) as usize];
+        table.collide(cell & !FHP_CHIRALITY) | chirality
+    }
+
+    fn egress(&self, cell: &mut u8, diffs: HexNeighbors<'_, u8>) -> [bool; 6] {
+        let state = *diffs.center();
+        *cell = (state & FHP_CHIRALITY) ^ FHP_CHIRALITY;
+        HexDirection::ALL.map(|direction| state >> direction as u8 & 1 == 1)
+    }
+
+    fn ingress(&self, cell: &mut u8, flows: [bool; 6]) {
+        for &direction in &HexDirection::ALL {
+            if flows[direction.opposite() as usize] {
+                *cell |= 1 << direction as u8;
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> u8 {
+        0
+    }
+
+    fn diff_padding(&self) -> u8 {
+        0
+    }
+
+    fn flow_padding(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{Kernel, Radius, RadiusEdges, RadiusNeighbors, Sim, SquareNeighborhood};
+use ndarray::{ArrayViewMut2, Zip};
+
+/// Lenia, a continuous automaton where each cell grows or shrinks based on a smooth kernel.
+///
+/// Cells are between `0` and `1`. Each step, the kernel is applied around a cell and the
+/// result is fed through a gaussian growth function, which is added to the cell over a time step.
+/// The rule can be simulated with any [`Radius`] neighborhood at least as big as the kernel.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lenia {
+    pub kernel: Kernel,
+    /// The kernel value where growth is highest.
+    pub mu: f64,
+    /// The width of the growth function.
+    pub sigma: f64,
+    /// The time step.
+    pub dt: f64,
+}
+
+impl Lenia {
+    /// Make a rule from its kernel and growth parameters, with a time step of `0.1`.
+    pub fn new(kernel: Kernel, mu: f64, sigma: f64) -> Self {
+        Self {
+            kernel,
+            mu,
+            sigma,
+            dt: 0.1,
+        }
+    }
+
+    /// The growth for a given kernel value, between `-1` and `1`.
+    pub fn growth(&self, u: f64) -> f64 {
+        let d = (u - self.mu) / self.sigma;
+        2.0 * (-d * d / 2.0).exp() - 1.0
+    }
+
+    /// Get the next state of a cell given its state and kernel value.
+    pub fn next(&self, cell: f64, u: f64) -> f64 {
+        (cell + self.dt * self.growth(u)).clamp(0.0, 1.0)
+    }
+
+    /// Step cells directly using the fast Fourier transform, treating everything outside as `0`.
+    ///
+    /// This gives the same result as stepping a grid with padding of `0`, but is much faster
+    /// for large kernels.
+    pub fn step_fft(&self, mut cells: ArrayViewMut2<'_, f64>) {
+        let u = self.kernel.convolve(cells.view());
+        Zip::from(&mut cells)
+            .and(&u)
+            .apply(|cell, &u| *cell = self.next(*cell, u));
+    }
+}
+
+impl<const R: usize> Sim<Radius<R>> for Lenia {
+    type Cell = f64;
+    type Diff = f64;
+    type Flow = ();
+
+    fn compute(&self, cells: RadiusNeighbors<'_, f64, R>) -> f64 {
+        assert!(
+            self.kernel.radius() <= R,
+            "kernel radius is larger than the neighborhood radius"
+        );
+        self.next(*cells.center(), self.kernel.apply(cells.window()))
+    }
+
+    fn egress(&self, cell: &mut f64, diffs: RadiusNeighbors<'_, f64, R>) -> RadiusEdges<()> {
+        *cell = *diffs.center();
+        Radius::<R>::edges(|| ())
+    }
+
+    fn ingress(&self, _: &mut f64, _: RadiusEdges<()>) {}
+
+    fn cell_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn diff_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn flow_padding(&self) {}
+}
+
+/// SmoothLife, a continuous generalization of Life using the filling of a disk and the ring around it.
+///
+/// Cells are between `0` and `1`. If `dt` is `None`, the next state is the transition function
+/// itself, otherwise the transition function is used as a rate of change over a time step.
+/// The rule can be simulated with any [`Radius`] neighborhood at least as big as the outer radius.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmoothLife {
+    inner: Kernel,
+    outer: Kernel,
+    /// The range of ring fillings where dead cells are born.
+    pub birth: (f64, f64),
+    /// The range of ring fillings where live cells survive.
+    pub death: (f64, f64),
+    /// The smoothness of the ring filling transition.
+    pub alpha_n: f64,
+    /// The smoothness of the disk filling transition.
+    pub alpha_m: f64,
+    pub dt: Option<f64>,
+}
+
+impl SmoothLife {
+    /// Make a rule with the standard parameters and an outer radius of `radius`.
+    pub fn new(radius: f64) -> Self {
+        let inner = radius / 3.0;
+        Self {
+            inner: Kernel::disk(inner).normalized(),
+            outer: Kernel::annulus(inner, radius).normalized(),
+            birth: (0.278, 0.365),
+            death: (0.267, 0.445),
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+            dt: None,
+        }
+    }
+
+    /// How far the rule reaches from the center on each axis.
+    pub fn radius(&self) -> usize {
+        self.outer.radius()
+    }
+
+    /// The transition function of the disk filling `m` and ring filling `n`.
+    pub fn transition(&self, m: f64, n: f64) -> f64 {
+        let sigma = |x: f64, a: f64, alpha: f64| 1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp());
+        let alive = sigma(m, 0.5, self.alpha_m);
+        let mix = |dead: f64, live: f64| dead * (1.0 - alive) + live * alive;
+        let low = mix(self.birth.0, self.death.0);
+        let high = mix(self.birth.1, self.death.1);
+        sigma(n, low, self.alpha_n) * (1.0 - sigma(n, high, self.alpha_n))
+    }
+
+    /// Get the next state of a cell given its state and the disk and ring fillings.
+    pub fn next(&self, cell: f64, m: f64, n: f64) -> f64 {
+        let s = self.transition(m, n);
+        match self.dt {
+            Some(dt) => (cell + dt * (2.0 * s - 1.0)).clamp(0.0, 1.0),
+            None => s,
+        }
+    }
+
+    /// Step cells directly using the fast Fourier transform, treating everything outside as `0`.
+    pub fn step_fft(&self, mut cells: ArrayViewMut2<'_, f64>) {
+        let m = self.inner.convolve(cells.view());
+        let n = self.outer.convolve(cells.view());
+        Zip::from(&mut cells)
+            .and(&m)
+            .and(&n)
+            .apply(|cell, &m, &n| *cell = self.next(*cell, m, n));
+    }
+}
+
+impl<const R: usize> Sim<Radius<R>> for SmoothLife {
+    type Cell = f64;
+    type Diff = f64;
+    type Flow = ();
+
+    fn compute(&self, cells: RadiusNeighbors<'_, f64, R>) -> f64 {
+        assert!(
+            self.radius() <= R,
+            "rule radius is larger than the neighborhood radius"
+        );
+        self.next(
+            *cells.center(),
+            self.inner.apply(cells.window()),
+            self.outer.apply(cells.window()),
+        )
+    }
+
+    fn egress(&self, cell: &mut f64, diffs: RadiusNeighbors<'_, f64, R>) -> RadiusEdges<()> {
+        *cell = *diffs.center();
+        Radius::<R>::edges(|| ())
+    }
+
+    fn ingress(&self, _: &mut f64, _: RadiusEdges<()>) {}
+
+    fn cell_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn diff_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn flow_padding(&self) {}
+}
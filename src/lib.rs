@@ -2,17 +2,210 @@
 //!
 //! The new generics introduced in gridsim 0.2.0 make it possible to implement hex grids,
 //! rhombic dodecahedral honeycombs(in its multiple tight-pack layer patterns), square grids, cube grids,
-//! and even n-dimensional grids, but they are currently not yet implemented.
+//! and even n-dimensional grids. Line, square, triangle, hex, cube, and n-dimensional grids are currently implemented.
 
-#![feature(type_alias_impl_trait)]
-#![feature(generic_associated_types)]
-#![allow(incomplete_features)]
+/// Zip over producers in parallel when the `use-rayon` feature is enabled and on the current
+/// thread otherwise.
+#[cfg(feature = "use-rayon")]
+macro_rules! maybe_par_azip {
+    ($($t:tt)*) => { ndarray::par_azip!($($t)*) };
+}
+
+/// Zip over producers in parallel when the `use-rayon` feature is enabled and on the current
+/// thread otherwise.
+#[cfg(not(feature = "use-rayon"))]
+macro_rules! maybe_par_azip {
+    ($($t:tt)*) => { ndarray::azip!($($t)*) };
+}
 
-mod neumann;
+mod adaptive_grid;
+mod agents;
+mod aging;
+mod async_grid;
+mod bit_grid;
+mod boundary;
+mod bounded;
+mod boxed_rule;
+mod broadcast;
+mod buffers;
+mod builder;
+mod cancel;
+mod channels;
+mod checkpoint;
+mod combinators;
+mod compare;
+mod compose;
+mod conserved;
+mod convert;
+mod count_rule;
+mod coupled;
+mod cube;
+mod cube_grid;
+mod cycle;
+mod dihedral;
+mod domain;
+mod editor;
+mod elementary;
+mod engine;
+mod error;
+#[cfg(feature = "export")]
+mod export;
+mod extended;
+mod frames;
+mod generations;
+mod global;
+mod golly_rule;
+mod grid;
+mod hash_life;
+mod hex;
+mod hex_grid;
+mod history;
+mod hooks;
+#[cfg(feature = "image")]
+mod image;
+mod infinite_grid;
+mod kernel;
+mod larger_than_life;
+mod lattice_gas;
+mod lenia;
+mod life_format;
+mod life_like;
+mod line;
+mod line_grid;
+mod macrocell;
+mod margolus;
+mod moore;
+mod nd;
+mod nd_grid;
+mod offset;
+mod partial;
+mod phased;
+mod pipeline;
+mod plaintext;
+mod population;
+mod profiler;
+mod project;
+mod random;
+mod recorder;
+mod reference;
+mod region;
+mod render;
+mod replay;
+mod resolve;
+mod rle;
+mod second_order;
+mod soa;
+mod square;
 mod square_grid;
+mod term;
+mod timings;
+mod transform;
+mod tri;
+mod tri_grid;
+mod turmite;
+mod update_order;
+mod von_neumann;
+
+pub mod bench;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod noise;
+pub mod pde;
+pub mod rules;
+pub mod stats;
+pub mod testing;
+pub mod web;
 
-pub use neumann::*;
+pub use adaptive_grid::*;
+pub use agents::*;
+pub use aging::*;
+pub use async_grid::*;
+pub use bit_grid::*;
+pub use boundary::*;
+pub use bounded::*;
+pub use boxed_rule::*;
+pub use broadcast::*;
+pub use buffers::*;
+pub use builder::*;
+pub use cancel::*;
+pub use channels::*;
+pub use checkpoint::*;
+pub use combinators::*;
+pub use compare::*;
+pub use compose::*;
+pub use conserved::*;
+pub use convert::*;
+pub use count_rule::*;
+pub use coupled::*;
+pub use cube::*;
+pub use cube_grid::*;
+pub use cycle::*;
+pub use dihedral::*;
+pub use editor::*;
+pub use elementary::*;
+pub use error::*;
+#[cfg(feature = "export")]
+pub use export::*;
+pub use extended::*;
+pub use frames::*;
+pub use generations::*;
+pub use global::*;
+pub use golly_rule::*;
+pub use grid::*;
+pub use hash_life::*;
+pub use hex::*;
+pub use hex_grid::*;
+pub use history::*;
+#[cfg(feature = "image")]
+pub use image::*;
+pub use infinite_grid::*;
+pub use kernel::*;
+pub use larger_than_life::*;
+pub use lattice_gas::*;
+pub use lenia::*;
+pub use life_format::*;
+pub use life_like::*;
+pub use line::*;
+pub use line_grid::*;
+pub use macrocell::*;
+pub use margolus::*;
+pub use moore::*;
+pub use nd::*;
+pub use nd_grid::*;
+pub use offset::*;
+pub use phased::*;
+pub use plaintext::*;
+pub use population::*;
+pub use profiler::*;
+pub use random::*;
+pub use recorder::*;
+pub use render::*;
+pub use replay::*;
+pub use resolve::*;
+pub use rle::*;
+pub use second_order::*;
+pub use soa::*;
+pub use square::*;
 pub use square_grid::*;
+pub use term::*;
+pub use timings::*;
+pub use tri::*;
+pub use tri_grid::*;
+pub use turmite::*;
+pub use update_order::*;
+pub use von_neumann::*;
+
+#[cfg(feature = "derive")]
+pub use gridsim_derive::{Neighborhood, SoaCell};
+#[cfg(feature = "use-rayon")]
+pub use rayon;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use ndarray;
+}
 
 pub trait Neighborhood {
     type Neighbors<'a, T: 'a>;
@@ -23,6 +216,9 @@ pub trait Neighborhood {
 ///
 /// This enforces a rule in that all new cells are only produced from old board state. This prevents the
 /// update order from breaking the simulation.
+///
+/// The sim is owned by the grid and passed by reference to every stage, including from the
+/// parallel workers, so it can carry runtime parameters such as thresholds or lookup tables.
 pub trait Sim<N>
 where
     N: Neighborhood,
@@ -49,6 +245,9 @@ where
 
     /// At this stage, the flow is received from all neighbors, allowing state
     /// to be added to this cell.
+    ///
+    /// Every flow sent to this cell is given here by value exactly once, so owned state
+    /// can be moved out of it without cloning or `unsafe`. Flows sent off the grid are dropped.
     fn ingress(&self, cell: &mut Self::Cell, flows: N::Edges<Self::Flow>);
 
     /// The cell used as padding.
@@ -0,0 +1,197 @@
+use crate::{Position, RleCell, Sim, SquareGrid, SquareNeighborhood};
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::{self, Write},
+    iter,
+};
+
+/// An error encountered while parsing a pattern in the Life 1.05 or 1.06 format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifeError {
+    /// The `#Life 1.05` or `#Life 1.06` header line is missing.
+    MissingHeader,
+    /// A line, counted from `1`, could not be understood.
+    InvalidLine(usize),
+    /// The cell type does not support the live state `1`.
+    UnsupportedState,
+}
+
+impl fmt::Display for LifeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifeError::MissingHeader => write!(f, "missing Life 1.05 or 1.06 header"),
+            LifeError::InvalidLine(line) => write!(f, "invalid Life pattern line {}", line),
+            LifeError::UnsupportedState => write!(f, "cell type does not support state 1"),
+        }
+    }
+}
+
+impl Error for LifeError {}
+
+/// A pattern read from the Life 1.05 or 1.06 format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifePattern {
+    /// The rule of a Life 1.05 pattern, as `survival/birth` like `23/3`, if present.
+    pub rule: Option<String>,
+    /// The `(y, x)` coordinates of the live cells, which may be negative.
+    pub cells: Vec<Position>,
+}
+
+/// Parse a pattern in the Life 1.06 format, which lists the `x y` coordinates of each live
+/// cell, or the Life 1.05 format, which draws blocks of cells placed by `#P x y` lines.
+pub fn parse_life(text: &str) -> Result<LifePattern, LifeError> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty());
+    let version = match lines.next() {
+        Some((_, header)) if header.starts_with("#Life 1.05") => 5,
+        Some((_, header)) if header.starts_with("#Life 1.06") => 6,
+        _ => return Err(LifeError::MissingHeader),
+    };
+    let mut pattern = LifePattern {
+        rule: None,
+        cells: vec![],
+    };
+    // The position of the next row of the current Life 1.05 block.
+    let mut row = (0, 0);
+    for (number, line) in lines {
+        let invalid = LifeError::InvalidLine(number + 1);
+        let pair = |fields: &str| -> Result<Position, LifeError> {
+            let (x, y) = fields
+                .split_whitespace()
+                .map(|n| n.parse::<i64>())
+                .collect_tuple()
+                .ok_or_else(|| invalid.clone())?;
+            Ok((
+                y.map_err(|_| invalid.clone())?,
+                x.map_err(|_| invalid.clone())?,
+            ))
+        };
+        if version == 6 {
+            pattern.cells.push(pair(line)?);
+        } else if let Some(position) = line.strip_prefix("#P") {
+            row = pair(position)?;
+        } else if let Some(rule) = line.strip_prefix("#R") {
+            pattern.rule = Some(rule.trim().to_owned());
+        } else if line.starts_with("#N") {
+            pattern.rule = Some("23/3".to_owned());
+        } else if line.starts_with('#') {
+            continue;
+        } else {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '*' => pattern.cells.push((row.0, row.1 + x as i64)),
+                    '.' => {}
+                    _ => return Err(invalid),
+                }
+            }
+            row.0 += 1;
+        }
+    }
+    Ok(pattern)
+}
+
+/// Write the `(y, x)` coordinates of live cells in the Life 1.06 format.
+pub fn write_life_106(cells: impl IntoIterator<Item = Position>) -> String {
+    let mut text = "#Life 1.06\n".to_owned();
+    for (y, x) in cells {
+        writeln!(text, "{} {}", x, y).unwrap();
+    }
+    text
+}
+
+/// The widest block written to a Life 1.05 pattern, which keeps lines within 80 characters.
+const BLOCK_WIDTH: i64 = 80;
+
+/// Write the `(y, x)` coordinates of live cells in the Life 1.05 format, with a rule such as
+/// `23/3` if one is given.
+///
+/// Each band of up to 80 columns which has live cells is written as one block.
+pub fn write_life_105(cells: impl IntoIterator<Item = Position>, rule: Option<&str>) -> String {
+    let mut text = "#Life 1.05\n".to_owned();
+    if let Some(rule) = rule {
+        writeln!(text, "#R {}", rule).unwrap();
+    }
+    let cells: Vec<Position> = cells.into_iter().collect();
+    let left = match cells.iter().map(|&(_, x)| x).min() {
+        Some(left) => left,
+        None => return text,
+    };
+    let mut bands: BTreeMap<i64, BTreeSet<Position>> = BTreeMap::new();
+    for &(y, x) in &cells {
+        let band = (x - left).div_euclid(BLOCK_WIDTH);
+        bands.entry(band).or_default().insert((y, x));
+    }
+    for (band, cells) in bands {
+        let block_left = left + band * BLOCK_WIDTH;
+        let (mut y, _) = *cells.iter().next().unwrap();
+        writeln!(text, "#P {} {}", block_left, y).unwrap();
+        let mut x = block_left;
+        for (cell_y, cell_x) in cells {
+            if cell_y != y {
+                text.push('\n');
+                text.extend(iter::repeat_n(".\n", (cell_y - y - 1) as usize));
+                y = cell_y;
+                x = block_left;
+            }
+            text.extend(iter::repeat_n('.', (cell_x - x) as usize));
+            text.push('*');
+            x = cell_x + 1;
+        }
+        text.push('\n');
+    }
+    text
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + RleCell,
+{
+    /// Set the live cells of a Life 1.05 or 1.06 pattern to state `1`, with the coordinate
+    /// `(0, 0)` of the pattern at the `(y, x)` index `offset`. The rule is ignored.
+    ///
+    /// Cells beyond an edge wrap around if the edge is connected and are left out otherwise,
+    /// like with [`SquareGrid::stamp`].
+    pub fn place_life(&mut self, text: &str, offset: (isize, isize)) -> Result<(), LifeError> {
+        let pattern = parse_life(text)?;
+        for (y, x) in pattern.cells {
+            let index = (y as isize + offset.0, x as isize + offset.1);
+            if let Some(index) = self.wrap(index) {
+                self[index] = S::Cell::from_state(1).ok_or(LifeError::UnsupportedState)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the cells with a state other than `0` in the Life 1.06 format, with the
+    /// `(y, x)` index `offset` as the coordinate `(0, 0)`.
+    pub fn to_life_106(&self, offset: (isize, isize)) -> String {
+        write_life_106(self.live_positions(offset))
+    }
+
+    /// Write the cells with a state other than `0` in the Life 1.05 format, with the
+    /// `(y, x)` index `offset` as the coordinate `(0, 0)`.
+    pub fn to_life_105(&self, offset: (isize, isize)) -> String {
+        write_life_105(self.live_positions(offset), None)
+    }
+
+    /// Get the coordinates of the live cells relative to `offset`.
+    fn live_positions(&self, offset: (isize, isize)) -> Vec<Position> {
+        self.cells()
+            .indexed_iter()
+            .filter(|(_, cell)| cell.to_state() != 0)
+            .map(move |((y, x), _)| {
+                (
+                    (y as isize - offset.0) as i64,
+                    (x as isize - offset.1) as i64,
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{Moore, Sim};
+use ndarray::ArrayView2;
+use std::{error::Error, fmt, str::FromStr};
+
+/// An error encountered while parsing a rulestring.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRuleError {
+    /// The rulestring does not have the expected parts.
+    InvalidFormat(String),
+    /// A neighbor count was not a valid digit for the neighborhood.
+    InvalidCount(char),
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRuleError::InvalidFormat(rule) => write!(f, "invalid rulestring: {:?}", rule),
+            ParseRuleError::InvalidCount(c) => write!(f, "invalid neighbor count: {:?}", c),
+        }
+    }
+}
+
+impl Error for ParseRuleError {}
+
+/// A two-state rule on the eight-cell neighborhood defined by which live neighbor
+/// counts cause a dead cell to be born and a live cell to survive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LifeLikeRule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl LifeLikeRule {
+    /// Make a rule from the live neighbor counts that cause birth and survival.
+    ///
+    /// Panics if a count is more than `8`, the number of neighbors.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        for &n in birth.iter().chain(survival) {
+            assert!(n <= 8, "neighbor count {} is more than 8", n);
+        }
+        for &n in birth {
+            rule.birth[n] = true;
+        }
+        for &n in survival {
+            rule.survival[n] = true;
+        }
+        rule
+    }
+
+    /// Parse a rulestring in `B3/S23` notation, or the older `23/3` survival/birth notation.
+    pub fn parse(rule: &str) -> Result<Self, ParseRuleError> {
+        let invalid = || ParseRuleError::InvalidFormat(rule.to_owned());
+        let mut parts = rule.trim().split('/');
+        let (first, second) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(first), Some(second), None) => (first, second),
+            _ => return Err(invalid()),
+        };
+        let prefix = |part: &str| part.chars().next().map(|c| c.to_ascii_uppercase());
+        let (birth, survival) = match (prefix(first), prefix(second)) {
+            (Some('B'), Some('S')) => (&first[1..], &second[1..]),
+            (Some('S'), Some('B')) => (&second[1..], &first[1..]),
+            _ if !first.starts_with(char::is_alphabetic)
+                && !second.starts_with(char::is_alphabetic) =>
+            {
+                (second, first)
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            birth: parse_counts(birth)?,
+            survival: parse_counts(survival)?,
+        })
+    }
+
+    /// Check if a dead cell with `n` live neighbors comes alive.
+    pub fn is_birth(&self, n: usize) -> bool {
+        self.birth.get(n).copied().unwrap_or(false)
+    }
+
+    /// Check if a live cell with `n` live neighbors stays alive.
+    pub fn is_survival(&self, n: usize) -> bool {
+        self.survival.get(n).copied().unwrap_or(false)
+    }
+
+    /// Get the next state of a cell given its state and number of live neighbors.
+    pub fn next(&self, alive: bool, n: usize) -> bool {
+        if alive {
+            self.is_survival(n)
+        } else {
+            self.is_birth(n)
+        }
+    }
+}
+
+fn parse_counts(counts: &str) -> Result<[bool; 9], ParseRuleError> {
+    let mut set = [false; 9];
+    for c in counts.chars() {
+        match c.to_digit(10) {
+            Some(n) if n <= 8 => set[n as usize] = true,
+            _ => return Err(ParseRuleError::InvalidCount(c)),
+        }
+    }
+    Ok(set)
+}
+
+impl FromStr for LifeLikeRule {
+    type Err = ParseRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, ParseRuleError> {
+        Self::parse(rule)
+    }
+}
+
+impl fmt::Display for LifeLikeRule {
+    /// Writes the rule in `B3/S23` notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| set[n])
+                .map(|n| std::char::from_digit(n as u32, 10).unwrap())
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+impl Sim<Moore> for LifeLikeRule {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        let center = cells[(1, 1)];
+        let n = cells.iter().filter(|&&c| c).count() - center as usize;
+        self.next(center, n)
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut bool, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
@@ -0,0 +1,14 @@
+use crate::Neighborhood;
+use ndarray::ArrayView1;
+
+/// The two-cell neighborhood of a one-dimensional grid.
+///
+/// The neighbors are a window of three cells with the cell itself in the middle.
+/// Edge `0` is to the right and edge `1` is to the left. On egress, each edge holds the
+/// flow sent towards that side. On ingress, each edge holds the flow received from that side.
+pub enum Line {}
+
+impl Neighborhood for Line {
+    type Neighbors<'a, T: 'a> = ArrayView1<'a, T>;
+    type Edges<T> = [T; 2];
+}
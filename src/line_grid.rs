@@ -0,0 +1,102 @@
+#![allow(clippy::reversed_empty_ranges)]
+
+use crate::{
+    engine::{self, Lattice, Link},
+    Line, Sim,
+};
+use ndarray::{s, Array1, ArrayView1, ArrayViewMut1, Ix1};
+use std::mem;
+
+/// Represents the state of a simulation on a one-dimensional grid.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Cell: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Cell: serde::Deserialize<'de>"
+    ))
+)]
+pub struct LineGrid<S>
+where
+    S: Sim<Line>,
+{
+    sim: S,
+    cells: Array1<S::Cell>,
+    /// The diffs of the last step, which are kept so their allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    diffs: Option<Array1<S::Diff>>,
+}
+
+impl<S> LineGrid<S>
+where
+    S: Sim<Line>,
+    S::Cell: Send,
+{
+    /// Make a new grid with the given cells.
+    pub fn new(sim: S, mut original_cells: Array1<S::Cell>) -> Self {
+        let len = original_cells.len();
+        assert!(len >= 1, "grid is empty, which isnt allowed");
+        let mut cells = Array1::from_shape_simple_fn(len + 2, || sim.cell_padding());
+        maybe_par_azip!((dest in &mut cells.slice_mut(s![1..-1]), cell in &mut original_cells) {
+            mem::swap(dest, cell);
+        });
+        Self {
+            sim,
+            cells,
+            diffs: None,
+        }
+    }
+
+    /// Get view of cells on the grid.
+    pub fn cells(&self) -> ArrayView1<'_, S::Cell> {
+        self.cells.slice(s![1..-1])
+    }
+
+    /// Get mutable view of cells on the grid.
+    pub fn cells_mut(&mut self) -> ArrayViewMut1<'_, S::Cell> {
+        self.cells.slice_mut(s![1..-1])
+    }
+}
+
+impl<S> LineGrid<S>
+where
+    S: Sim<Line> + Sync,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    S::Flow: Send,
+{
+    engine::step_methods!(Line);
+}
+
+impl Lattice<Ix1> for Line {
+    fn padding() -> Ix1 {
+        Ix1(1)
+    }
+
+    fn window() -> Ix1 {
+        Ix1(3)
+    }
+
+    fn neighbors<'a, T: 'a>(_: &[usize], window: ArrayView1<'a, T>) -> ArrayView1<'a, T> {
+        window
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 2] {
+        [f(), f()]
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 2]) -> &mut [T] {
+        edges
+    }
+
+    // Each pair of cells exchanges the flows sent towards each other.
+    fn links() -> Vec<Link> {
+        vec![Link {
+            edge: 0,
+            opposite: 1,
+            delta: vec![1],
+            parity: None,
+        }]
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    hash_life::{NodeId, ALIVE, DEAD},
+    HashLifeGrid,
+};
+use ndarray::ArrayView2;
+use std::{collections::HashMap, error::Error, fmt, fmt::Write};
+
+/// An error encountered while parsing a pattern in Golly's macrocell format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MacrocellError {
+    /// The `[M2]` header line is missing.
+    MissingHeader,
+    /// A line, counted from `1`, is not a valid leaf or node.
+    InvalidLine(usize),
+}
+
+impl fmt::Display for MacrocellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacrocellError::MissingHeader => write!(f, "missing macrocell header"),
+            MacrocellError::InvalidLine(line) => write!(f, "invalid macrocell line {}", line),
+        }
+    }
+}
+
+impl Error for MacrocellError {}
+
+/// The level of the 8x8 leaves of a macrocell pattern.
+const LEAF: u32 = 3;
+
+impl<F> HashLifeGrid<F>
+where
+    F: Fn(ArrayView2<'_, bool>) -> bool,
+{
+    /// Make a new grid from a two-state pattern in Golly's macrocell format, which stores
+    /// the quadtree itself so patterns too large for RLE can be read without expanding them.
+    ///
+    /// The root is centered on `(0, 0)`, like in Golly. The rule in the file is ignored.
+    pub fn from_macrocell(rule: F, text: &str) -> Result<Self, MacrocellError> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty());
+        match lines.next() {
+            Some((_, header)) if header.starts_with("[M2]") => {}
+            _ => return Err(MacrocellError::MissingHeader),
+        }
+        let mut grid = Self::new(rule);
+        // Node `n` of the file is `ids[n - 1]`.
+        let mut ids = vec![];
+        for (number, line) in lines.filter(|(_, line)| !line.starts_with('#')) {
+            let id = if line.starts_with(|c: char| c.is_ascii_digit()) {
+                grid.parse_node(line, &ids)
+            } else {
+                grid.parse_leaf(line)
+            };
+            ids.push(id.ok_or(MacrocellError::InvalidLine(number + 1))?);
+        }
+        if let Some(&root) = ids.last() {
+            let half = 1 << (grid.nodes[root].level - 1);
+            grid.root = root;
+            grid.origin = (-half, -half);
+        }
+        Ok(grid)
+    }
+
+    /// Write the live cells as a pattern in Golly's macrocell format, with the rule in its
+    /// header if one is given.
+    ///
+    /// Every distinct part of the quadtree is written once, so this is as large as the
+    /// quadtree rather than the area of the pattern.
+    pub fn to_macrocell(&self, rule: Option<&str>) -> String {
+        let mut text = "[M2] (gridsim)\n".to_owned();
+        if let Some(rule) = rule {
+            writeln!(text, "#R {}", rule).unwrap();
+        }
+        if self.population() == 0 {
+            text += "$\n";
+        } else {
+            self.write_node(self.root, &mut HashMap::new(), &mut text);
+        }
+        text
+    }
+
+    /// Parse an 8x8 leaf made of `.` for dead cells, `*` for live cells, and `$` to end rows.
+    fn parse_leaf(&mut self, line: &str) -> Option<NodeId> {
+        let mut cells = [[false; 8]; 8];
+        let (mut y, mut x) = (0, 0);
+        for c in line.chars() {
+            match c {
+                '.' | '*' if y < 8 && x < 8 => {
+                    cells[y][x] = c == '*';
+                    x += 1;
+                }
+                '$' => {
+                    y += 1;
+                    x = 0;
+                }
+                _ => return None,
+            }
+        }
+        Some(self.build(LEAF, (0, 0), &cells))
+    }
+
+    /// Make the node of a level covering the cells of a leaf from a `(y, x)` corner.
+    fn build(&mut self, level: u32, (y, x): (usize, usize), cells: &[[bool; 8]; 8]) -> NodeId {
+        if level == 0 {
+            return if cells[y][x] { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let children = [
+            self.build(level - 1, (y, x), cells),
+            self.build(level - 1, (y, x + half), cells),
+            self.build(level - 1, (y + half, x), cells),
+            self.build(level - 1, (y + half, x + half), cells),
+        ];
+        self.node(children)
+    }
+
+    /// Parse a node of the form `level nw ne sw se`, where each quadrant is the number of an
+    /// earlier line or `0` for empty space.
+    fn parse_node(&mut self, line: &str, ids: &[NodeId]) -> Option<NodeId> {
+        let numbers = line
+            .split_whitespace()
+            .map(|n| n.parse().ok())
+            .collect::<Option<Vec<usize>>>()?;
+        let (&level, quadrants) = numbers.split_first()?;
+        if quadrants.len() != 4 || !(LEAF as usize + 1..62).contains(&level) {
+            return None;
+        }
+        let level = level as u32;
+        let mut children = [DEAD; 4];
+        for (child, &n) in children.iter_mut().zip(quadrants) {
+            *child = match n {
+                0 => self.empty(level - 1),
+                n => *ids.get(n - 1)?,
+            };
+            if self.nodes[*child].level != level - 1 {
+                return None;
+            }
+        }
+        Some(self.node(children))
+    }
+
+    /// Write a node after the nodes it is made of, and get its number, or `0` if it is empty.
+    fn write_node(
+        &self,
+        id: NodeId,
+        numbers: &mut HashMap<NodeId, usize>,
+        text: &mut String,
+    ) -> usize {
+        let node = self.nodes[id];
+        if node.population == 0 {
+            return 0;
+        }
+        if let Some(&number) = numbers.get(&id) {
+            return number;
+        }
+        if node.level == LEAF {
+            let rows: Vec<String> = (0..8)
+                .map(|y| {
+                    let row: String = (0..8)
+                        .map(|x| if self.leaf_cell(id, (y, x)) { '*' } else { '.' })
+                        .collect();
+                    row.trim_end_matches('.').to_owned()
+                })
+                .collect();
+            let end = rows.iter().rposition(|row| !row.is_empty()).unwrap() + 1;
+            for row in &rows[..end] {
+                *text += row;
+                text.push('$');
+            }
+            text.push('\n');
+        } else {
+            let quadrants = node
+                .children
+                .map(|child| self.write_node(child, numbers, text));
+            writeln!(
+                text,
+                "{} {} {} {} {}",
+                node.level, quadrants[0], quadrants[1], quadrants[2], quadrants[3]
+            )
+            .unwrap();
+        }
+        let number = numbers.len() + 1;
+        numbers.insert(id, number);
+        number
+    }
+
+    /// Check if the cell at a `(y, x)` index within a leaf is alive.
+    fn leaf_cell(&self, mut id: NodeId, (mut y, mut x): (usize, usize)) -> bool {
+        while self.nodes[id].level > 0 {
+            let half = 1 << (self.nodes[id].level - 1);
+            id = self.nodes[id].children[(y >= half) as usize * 2 + (x >= half) as usize];
+            y %= half;
+            x %= half;
+        }
+        id == ALIVE
+    }
+}
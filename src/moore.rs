@@ -0,0 +1,64 @@
+use crate::{Neighborhood, SquareNeighborhood};
+use ndarray::ArrayView2;
+
+/// The eight cells surrounding a square, seen as a 3x3 window indexed by `(y, x)`.
+///
+/// Edges are ordered counter-clockwise starting from the right, so edge `0` is to the
+/// right, `2` is above, `4` is to the left, and `6` is below. On egress, each edge holds
+/// the flow sent towards that side. On ingress, each edge holds the flow received from that side.
+pub enum Moore {}
+
+/// The old name of [`Moore`], which is the eight-cell neighborhood rather than the four-cell
+/// von Neumann neighborhood.
+#[deprecated(note = "this is the Moore neighborhood, use `Moore`, or `VonNeumann` for four cells")]
+pub type Neumann = Moore;
+
+/// The neighbors of a [`Moore`] neighborhood, which are the whole 3x3 window.
+pub type MooreNeighbors<'a, T> = ArrayView2<'a, T>;
+
+impl Moore {
+    /// The `(y, x)` offset of each edge, which can be used to pick a neighbor to move to.
+    pub const DELTAS: [(isize, isize); 8] = MOORE_DELTAS;
+}
+
+impl Neighborhood for Moore {
+    type Neighbors<'a, T: 'a> = MooreNeighbors<'a, T>;
+    type Edges<T> = [T; 8];
+}
+
+impl SquareNeighborhood for Moore {
+    const RADIUS: usize = 1;
+    const EDGES: usize = 8;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        MOORE_DELTAS[edge]
+    }
+
+    fn opposite(edge: usize) -> usize {
+        (edge + 4) % 8
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> MooreNeighbors<'_, T> {
+        window
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 8] {
+        [(); 8].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 8]) -> &mut [T] {
+        edges
+    }
+}
+
+/// The `(y, x)` offset of each edge of [`Moore`].
+pub(crate) const MOORE_DELTAS: [(isize, isize); 8] = [
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
@@ -0,0 +1,83 @@
+use crate::Neighborhood;
+use ndarray::{ArrayViewD, IndexLonger};
+
+/// The `2 * D` cells one step along each axis of a `D` dimensional grid, which can be
+/// simulated on an [`NdGrid`](crate::NdGrid).
+///
+/// Edge `2 * axis` is one cell back along an axis and edge `2 * axis + 1` is one cell forward.
+/// On egress, each edge holds the flow sent towards that neighbor. On ingress, each edge holds
+/// the flow received from that neighbor. There are always `2 * D` edges.
+pub enum VonNeumannNd<const D: usize> {}
+
+impl<const D: usize> Neighborhood for VonNeumannNd<D> {
+    type Neighbors<'a, T: 'a> = NdNeighbors<'a, T, D>;
+    type Edges<T> = Vec<T>;
+}
+
+impl<const D: usize> VonNeumannNd<D> {
+    /// The number of edges.
+    pub const EDGES: usize = 2 * D;
+
+    /// The offset of an edge along each axis.
+    pub fn delta(edge: usize) -> [isize; D] {
+        assert!(edge < Self::EDGES, "edge is out of range");
+        let mut delta = [0; D];
+        delta[edge / 2] = if edge.is_multiple_of(2) { -1 } else { 1 };
+        delta
+    }
+
+    /// The edge of the neighbor that points back at this cell.
+    pub fn opposite(edge: usize) -> usize {
+        edge ^ 1
+    }
+
+    /// Make edges by calling `f` once for each edge in order.
+    pub fn edges<T>(f: impl FnMut() -> T) -> Vec<T> {
+        std::iter::repeat_with(f).take(Self::EDGES).collect()
+    }
+}
+
+/// The cells surrounding a cell of a `D` dimensional grid, along with the cell itself.
+#[derive(Debug)]
+pub struct NdNeighbors<'a, T, const D: usize> {
+    window: ArrayViewD<'a, T>,
+}
+
+impl<'a, T, const D: usize> Clone for NdNeighbors<'a, T, D> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window.clone(),
+        }
+    }
+}
+
+impl<'a, T, const D: usize> NdNeighbors<'a, T, D> {
+    /// Wraps a window 3 cells wide along every axis centered on the cell.
+    pub fn new(window: ArrayViewD<'a, T>) -> Self {
+        assert!(
+            window.ndim() == D && window.shape().iter().all(|&len| len == 3),
+            "nd neighbors need a window 3 cells wide along every axis"
+        );
+        Self { window }
+    }
+
+    /// The cell in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        IndexLonger::index(&self.window, &[1; D][..])
+    }
+
+    /// The neighbor across an edge.
+    pub fn get(&self, edge: usize) -> &'a T {
+        let mut index = [1; D];
+        for (i, delta) in index.iter_mut().zip(VonNeumannNd::<D>::delta(edge)) {
+            *i = (*i as isize + delta) as usize;
+        }
+        IndexLonger::index(&self.window, &index[..])
+    }
+
+    /// Iterate over the neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = self.clone();
+        (0..VonNeumannNd::<D>::EDGES).map(move |edge| this.get(edge))
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    engine::{self, Lattice, Link},
+    NdNeighbors, Sim, VonNeumannNd,
+};
+use ndarray::{ArrayD, ArrayViewD, ArrayViewMutD, Dimension, IxDyn};
+use std::{
+    mem,
+    ops::{Index, IndexMut},
+};
+
+/// Represents the state of a simulation on a grid with any number of dimensions `D`.
+///
+/// Cells are indexed by `[usize; D]` and see their [`VonNeumannNd`] neighbors, which makes
+/// 4D and higher experiments possible. The cells beyond the edges are padding.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Cell: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Cell: serde::Deserialize<'de>"
+    ))
+)]
+pub struct NdGrid<S, const D: usize>
+where
+    S: Sim<VonNeumannNd<D>>,
+{
+    sim: S,
+    cells: ArrayD<S::Cell>,
+    /// The diffs of the last step, which are kept so their allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    diffs: Option<ArrayD<S::Diff>>,
+}
+
+impl<S, const D: usize> NdGrid<S, D>
+where
+    S: Sim<VonNeumannNd<D>>,
+    S::Cell: Send,
+{
+    /// Make a new grid with the given cells, which must have `D` axes.
+    pub fn new(sim: S, mut original_cells: ArrayD<S::Cell>) -> Self {
+        assert_eq!(original_cells.ndim(), D, "cells must have D axes");
+        assert!(
+            original_cells.shape().iter().all(|&len| len >= 1),
+            "grid is empty, which isnt allowed"
+        );
+        let shape: Vec<usize> = original_cells.shape().iter().map(|len| len + 2).collect();
+        let mut cells = ArrayD::from_shape_simple_fn(shape, || sim.cell_padding());
+        maybe_par_azip!((dest in engine::interior_mut(cells.view_mut(), &[1; D]), cell in &mut original_cells) {
+            mem::swap(dest, cell);
+        });
+        Self {
+            sim,
+            cells,
+            diffs: None,
+        }
+    }
+
+    /// Make a new grid of the given shape with a function of each index.
+    pub fn from_shape_fn(
+        sim: S,
+        shape: [usize; D],
+        mut f: impl FnMut([usize; D]) -> S::Cell,
+    ) -> Self {
+        let cells = ArrayD::from_shape_fn(IxDyn(&shape), |index| {
+            let mut at = [0; D];
+            at.copy_from_slice(index.slice());
+            f(at)
+        });
+        Self::new(sim, cells)
+    }
+
+    /// The length of each axis.
+    pub fn shape(&self) -> [usize; D] {
+        let mut shape = [0; D];
+        for (len, &padded) in shape.iter_mut().zip(self.cells.shape()) {
+            *len = padded - 2;
+        }
+        shape
+    }
+
+    /// Get view of cells on the grid.
+    pub fn cells(&self) -> ArrayViewD<'_, S::Cell> {
+        engine::interior(self.cells.view(), &[1; D])
+    }
+
+    /// Get mutable view of cells on the grid.
+    pub fn cells_mut(&mut self) -> ArrayViewMutD<'_, S::Cell> {
+        engine::interior_mut(self.cells.view_mut(), &[1; D])
+    }
+
+    /// Get a cell, or `None` if it is off the grid.
+    pub fn get(&self, index: [usize; D]) -> Option<&S::Cell> {
+        let inside = index.iter().zip(self.shape()).all(|(&i, len)| i < len);
+        inside.then(|| &self.cells[&padded(index)[..]])
+    }
+
+    /// Get a cell mutably, or `None` if it is off the grid.
+    pub fn get_mut(&mut self, index: [usize; D]) -> Option<&mut S::Cell> {
+        let inside = index.iter().zip(self.shape()).all(|(&i, len)| i < len);
+        inside.then(move || &mut self.cells[&padded(index)[..]])
+    }
+}
+
+/// The index of a cell in the padded cells.
+fn padded<const D: usize>(index: [usize; D]) -> [usize; D] {
+    index.map(|i| i + 1)
+}
+
+impl<S, const D: usize> NdGrid<S, D>
+where
+    S: Sim<VonNeumannNd<D>> + Sync,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    S::Flow: Send,
+{
+    engine::step_methods!(VonNeumannNd<D>);
+
+    /// Simulate `n` steps, reusing the same buffers for each of them.
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+}
+
+impl<const D: usize> Lattice<IxDyn> for VonNeumannNd<D> {
+    fn padding() -> IxDyn {
+        IxDyn(&[1; D])
+    }
+
+    fn window() -> IxDyn {
+        IxDyn(&[3; D])
+    }
+
+    fn neighbors<'a, T: 'a>(_: &[usize], window: ArrayViewD<'a, T>) -> NdNeighbors<'a, T, D> {
+        NdNeighbors::new(window)
+    }
+
+    fn edges<T>(f: impl FnMut() -> T) -> Vec<T> {
+        Self::edges(f)
+    }
+
+    fn edges_mut<T>(edges: &mut Vec<T>) -> &mut [T] {
+        edges
+    }
+
+    fn links() -> Vec<Link> {
+        (0..D)
+            .map(|axis| Link {
+                edge: 2 * axis,
+                opposite: 2 * axis + 1,
+                delta: Self::delta(2 * axis).to_vec(),
+                parity: None,
+            })
+            .collect()
+    }
+}
+
+impl<S, const D: usize> Index<[usize; D]> for NdGrid<S, D>
+where
+    S: Sim<VonNeumannNd<D>>,
+    S::Cell: Send,
+{
+    type Output = S::Cell;
+
+    fn index(&self, index: [usize; D]) -> &S::Cell {
+        self.get(index).expect("index is off the grid")
+    }
+}
+
+impl<S, const D: usize> IndexMut<[usize; D]> for NdGrid<S, D>
+where
+    S: Sim<VonNeumannNd<D>>,
+    S::Cell: Send,
+{
+    fn index_mut(&mut self, index: [usize; D]) -> &mut S::Cell {
+        self.get_mut(index).expect("index is off the grid")
+    }
+}
@@ -0,0 +1,154 @@
+//! Keeping grids on different machines in lockstep by exchanging their halos over TCP.
+//!
+//! Each machine simulates one part of the world on a [`SquareGrid`] with [`Boundary::Halo`]
+//! on the axes where it has neighbors. Every step, each grid sends the cells along its
+//! connected sides to its neighbors and receives their cells as its ghost cells.
+//!
+//! Cells are sent as their [`RleCell`] states, one byte per cell, with each strip preceded by
+//! its length as a little-endian `u32`.
+//!
+//! [`Boundary::Halo`]: crate::Boundary::Halo
+
+use crate::{RleCell, Side, Sim, SquareGrid, SquareNeighborhood};
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+    thread,
+};
+
+/// The connections to the grids on each side of a grid.
+#[derive(Debug, Default)]
+pub struct HaloLink {
+    north: Option<TcpStream>,
+    south: Option<TcpStream>,
+    west: Option<TcpStream>,
+    east: Option<TcpStream>,
+}
+
+impl HaloLink {
+    /// Make a link with no connections.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect to the grid on a side, replacing any previous connection.
+    ///
+    /// The other grid must connect the same stream on the opposite side.
+    pub fn connect(&mut self, side: Side, stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        *self.stream_mut(side) = Some(stream);
+        Ok(())
+    }
+
+    /// Remove the connection to the grid on a side, if any.
+    pub fn disconnect(&mut self, side: Side) -> Option<TcpStream> {
+        self.stream_mut(side).take()
+    }
+
+    /// Check if there is a connection on a side.
+    pub fn is_connected(&self, side: Side) -> bool {
+        self.stream(side).is_some()
+    }
+
+    fn stream(&self, side: Side) -> Option<&TcpStream> {
+        match side {
+            Side::North => self.north.as_ref(),
+            Side::South => self.south.as_ref(),
+            Side::West => self.west.as_ref(),
+            Side::East => self.east.as_ref(),
+        }
+    }
+
+    fn stream_mut(&mut self, side: Side) -> &mut Option<TcpStream> {
+        match side {
+            Side::North => &mut self.north,
+            Side::South => &mut self.south,
+            Side::West => &mut self.west,
+            Side::East => &mut self.east,
+        }
+    }
+
+    /// Send strips to the connected sides and receive the strips of the grids on those sides.
+    ///
+    /// The strips are sent from other threads while receiving so that neither grid waits on
+    /// the other to receive before it can receive.
+    fn exchange<C>(&self, strips: Vec<(Side, Vec<C>)>) -> io::Result<Vec<(Side, Vec<C>)>>
+    where
+        C: RleCell,
+    {
+        let strips: Vec<(&TcpStream, Side, usize, Vec<u8>)> = strips
+            .into_iter()
+            .filter_map(|(side, strip)| {
+                let stream = self.stream(side)?;
+                let len = strip.len();
+                let mut message = (len as u32).to_le_bytes().to_vec();
+                message.extend(strip.iter().map(RleCell::to_state));
+                Some((stream, side, len, message))
+            })
+            .collect();
+        thread::scope(|scope| {
+            let senders: Vec<_> = strips
+                .iter()
+                .map(|(mut stream, _, _, message)| {
+                    scope.spawn(move || stream.write_all(message).and_then(|_| stream.flush()))
+                })
+                .collect();
+            let received = strips
+                .iter()
+                .map(|&(stream, side, len, _)| Ok((side, receive(stream, len)?)))
+                .collect::<io::Result<Vec<_>>>();
+            for sender in senders {
+                sender.join().expect("halo sender panicked")?;
+            }
+            received
+        })
+    }
+}
+
+/// Receive a strip of `len` cells.
+fn receive<C>(mut stream: &TcpStream, len: usize) -> io::Result<Vec<C>>
+where
+    C: RleCell,
+{
+    let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut header = [0; 4];
+    stream.read_exact(&mut header)?;
+    if u32::from_le_bytes(header) as usize != len {
+        return Err(invalid("received halo is the wrong size"));
+    }
+    let mut states = vec![0; len];
+    stream.read_exact(&mut states)?;
+    states
+        .into_iter()
+        .map(|state| C::from_state(state).ok_or_else(|| invalid("received unsupported cell state")))
+        .collect()
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone + RleCell,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Exchange halos with the grids connected to `link`, then step.
+    ///
+    /// Every connected grid must call this once per step, so the grids stay in lockstep.
+    /// The west and east sides are exchanged before the north and south sides so the corners
+    /// are filled. Ghost cells on sides which aren't connected are left alone.
+    pub fn step_linked(&mut self, link: &HaloLink) -> io::Result<()> {
+        for sides in [[Side::West, Side::East], [Side::North, Side::South]] {
+            let strips = sides
+                .iter()
+                .filter(|&&side| link.is_connected(side))
+                .map(|&side| (side, self.halo(side)))
+                .collect();
+            for (side, halo) in link.exchange(strips)? {
+                self.set_halo(side, &halo);
+            }
+        }
+        self.step();
+        Ok(())
+    }
+}
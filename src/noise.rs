@@ -0,0 +1,186 @@
+//! Smooth random noise for making terrain-like initial conditions, such as forests or
+//! elevation, which are then thresholded into cell states.
+
+use crate::{CellRng, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+
+/// The algorithm which makes the noise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoiseKind {
+    /// Random values at the corners of a lattice, smoothly interpolated between them.
+    Value,
+    /// Random gradients at the corners of a square lattice.
+    Perlin,
+    /// Random gradients at the corners of a triangular lattice, which has fewer
+    /// axis-aligned artifacts than Perlin noise.
+    Simplex,
+}
+
+/// Noise over the plane, made of octaves which each double the frequency and halve
+/// the amplitude of the last.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Noise {
+    /// The algorithm which makes the noise.
+    pub kind: NoiseKind,
+    /// The seed of the random lattice.
+    pub seed: u64,
+    /// The number of features per cell of the first octave, so `0.05` gives features about
+    /// twenty cells across.
+    pub frequency: f64,
+    /// The number of octaves, which adds finer detail.
+    pub octaves: u32,
+}
+
+impl Noise {
+    /// Make noise with a frequency of `0.05` and a single octave.
+    pub fn new(kind: NoiseKind, seed: u64) -> Self {
+        Self {
+            kind,
+            seed,
+            frequency: 0.05,
+            octaves: 1,
+        }
+    }
+
+    /// Sample the noise at a `(y, x)` position, giving a value in about `[-1, 1]`.
+    pub fn sample(&self, (y, x): (f64, f64)) -> f64 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut norm = 0.0;
+        let mut frequency = self.frequency;
+        for octave in 0..self.octaves.max(1) {
+            let seed = self.seed.wrapping_add(octave as u64);
+            let position = (y * frequency, x * frequency);
+            total += amplitude
+                * match self.kind {
+                    NoiseKind::Value => value(seed, position),
+                    NoiseKind::Perlin => perlin(seed, position),
+                    NoiseKind::Simplex => simplex(seed, position),
+                };
+            norm += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        total / norm
+    }
+
+    /// Sample the noise at the center of every cell of a `(height, width)` grid.
+    pub fn sample_grid(&self, dims: (usize, usize)) -> Array2<f64> {
+        let mut values = Array2::zeros(dims);
+        maybe_par_azip!((index (y, x), v in &mut values) {
+            *v = self.sample((y as f64, x as f64));
+        });
+        values
+    }
+}
+
+/// Get the index of the band a value is in, where `cuts` are the sorted values between
+/// the bands, so values below `cuts[0]` are in band `0` and values above every cut are in
+/// band `cuts.len()`.
+pub fn band(value: f64, cuts: &[f64]) -> usize {
+    cuts.iter().take_while(|&&cut| value >= cut).count()
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid of `(height, width)` cells made by `cell` from the noise at each cell.
+    pub fn new_noise(
+        sim: S,
+        dims: (usize, usize),
+        noise: &Noise,
+        cell: impl Fn(f64) -> S::Cell + Sync + Send,
+    ) -> Self {
+        let values = noise.sample_grid(dims);
+        Self::new(sim, values.map(|&v| cell(v)))
+    }
+
+    /// Replace every cell with one made by `cell` from the noise at the cell.
+    pub fn fill_noise(&mut self, noise: &Noise, cell: impl Fn(f64) -> S::Cell + Sync + Send) {
+        maybe_par_azip!((index (y, x), c in &mut self.cells_mut()) {
+            *c = cell(noise.sample((y as f64, x as f64)));
+        });
+    }
+}
+
+/// Get a random generator for a lattice point.
+fn lattice(seed: u64, (y, x): (f64, f64)) -> CellRng {
+    CellRng::new(seed, 0, (y as i64 as usize, x as i64 as usize))
+}
+
+/// Smoothly interpolate from `0` to `1`.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Interpolate a function of the corners of the lattice cell around a position.
+fn interpolate((y, x): (f64, f64), corner: impl Fn((f64, f64), (f64, f64)) -> f64) -> f64 {
+    let (y0, x0) = (y.floor(), x.floor());
+    let (fy, fx) = (y - y0, x - x0);
+    let at = |dy: f64, dx: f64| corner((y0 + dy, x0 + dx), (fy - dy, fx - dx));
+    let (ty, tx) = (fade(fy), fade(fx));
+    lerp(
+        lerp(at(0.0, 0.0), at(0.0, 1.0), tx),
+        lerp(at(1.0, 0.0), at(1.0, 1.0), tx),
+        ty,
+    )
+}
+
+fn value(seed: u64, position: (f64, f64)) -> f64 {
+    interpolate(position, |corner, _| {
+        lattice(seed, corner).next_f64() * 2.0 - 1.0
+    })
+}
+
+/// Take the dot product of a random unit gradient at a lattice point with an offset.
+fn gradient(seed: u64, corner: (f64, f64), (dy, dx): (f64, f64)) -> f64 {
+    let angle = lattice(seed, corner).next_f64() * std::f64::consts::TAU;
+    angle.sin() * dy + angle.cos() * dx
+}
+
+fn perlin(seed: u64, position: (f64, f64)) -> f64 {
+    // Gradient noise only reaches about half of the unit vectors' length in practice.
+    interpolate(position, |corner, offset| gradient(seed, corner, offset))
+        * std::f64::consts::SQRT_2
+}
+
+fn simplex(seed: u64, (y, x): (f64, f64)) -> f64 {
+    let skew = (3f64.sqrt() - 1.0) / 2.0;
+    let unskew = (3.0 - 3f64.sqrt()) / 6.0;
+    // Find the triangle of the lattice the position is in.
+    let s = (x + y) * skew;
+    let (i, j) = ((x + s).floor(), (y + s).floor());
+    let t = (i + j) * unskew;
+    let (x0, y0) = (x - (i - t), y - (j - t));
+    let (i1, j1) = if x0 > y0 { (1.0, 0.0) } else { (0.0, 1.0) };
+    let corners = [
+        ((j, i), (y0, x0)),
+        ((j + j1, i + i1), (y0 - j1 + unskew, x0 - i1 + unskew)),
+        (
+            (j + 1.0, i + 1.0),
+            (y0 - 1.0 + 2.0 * unskew, x0 - 1.0 + 2.0 * unskew),
+        ),
+    ];
+    let total: f64 = corners
+        .iter()
+        .map(|&(corner, (dy, dx))| {
+            let falloff = 0.5 - dx * dx - dy * dy;
+            if falloff > 0.0 {
+                falloff.powi(4) * gradient(seed, corner, (dy, dx))
+            } else {
+                0.0
+            }
+        })
+        .sum();
+    // Scale the largest possible value to about one.
+    70.0 * total
+}
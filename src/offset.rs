@@ -0,0 +1,177 @@
+use crate::{Neighborhood, SquareNeighborhood};
+use ndarray::{ArrayView2, IndexLonger};
+use smallvec::SmallVec;
+use std::{marker::PhantomData, ops::Index};
+
+/// A list of `(y, x)` offsets which make up a neighborhood.
+///
+/// The offsets must not contain `(0, 0)` or duplicates, and the negation of every offset
+/// must also be in the list so that flows can be sent back.
+pub trait Offsets {
+    const OFFSETS: &'static [(isize, isize)];
+}
+
+/// The eight cells a knight can move to in chess.
+pub enum KnightMoves {}
+
+impl Offsets for KnightMoves {
+    const OFFSETS: &'static [(isize, isize)] = &[
+        (-1, 2),
+        (-2, 1),
+        (-2, -1),
+        (-1, -2),
+        (1, -2),
+        (2, -1),
+        (2, 1),
+        (1, 2),
+    ];
+}
+
+/// A neighborhood made up of an arbitrary list of offsets, which need not be contiguous.
+///
+/// Edges are in the same order as the offsets.
+pub struct OffsetNeighborhood<O>(PhantomData<O>);
+
+impl<O> Neighborhood for OffsetNeighborhood<O>
+where
+    O: Offsets,
+{
+    type Neighbors<'a, T: 'a> = OffsetNeighbors<'a, T, O>;
+    type Edges<T> = OffsetEdges<T>;
+}
+
+/// The edges of an [`OffsetNeighborhood`], in the same order as the offsets.
+///
+/// Lists of up to 24 offsets, which includes any list within two cells of the center, are
+/// stored without allocating.
+pub type OffsetEdges<T> = SmallVec<[T; 24]>;
+
+impl<O> SquareNeighborhood for OffsetNeighborhood<O>
+where
+    O: Offsets,
+{
+    const RADIUS: usize = radius(O::OFFSETS);
+    const EDGES: usize = O::OFFSETS.len();
+
+    fn delta(edge: usize) -> (isize, isize) {
+        O::OFFSETS[edge]
+    }
+
+    fn opposite(edge: usize) -> usize {
+        let (y, x) = O::OFFSETS[edge];
+        O::OFFSETS
+            .iter()
+            .position(|&delta| delta == (-y, -x))
+            .expect("the negation of every offset must also be an offset")
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> OffsetNeighbors<'_, T, O> {
+        OffsetNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> OffsetEdges<T> {
+        (0..Self::EDGES).map(|_| f()).collect()
+    }
+
+    fn edges_mut<T>(edges: &mut OffsetEdges<T>) -> &mut [T] {
+        edges
+    }
+}
+
+/// The largest distance of any offset from the center on either axis.
+const fn radius(offsets: &[(isize, isize)]) -> usize {
+    let mut radius = 0;
+    let mut i = 0;
+    while i < offsets.len() {
+        let (y, x) = offsets[i];
+        if y.unsigned_abs() > radius {
+            radius = y.unsigned_abs();
+        }
+        if x.unsigned_abs() > radius {
+            radius = x.unsigned_abs();
+        }
+        i += 1;
+    }
+    radius
+}
+
+/// The cells at each offset of a square, along with the square itself.
+#[derive(Debug)]
+pub struct OffsetNeighbors<'a, T, O> {
+    window: ArrayView2<'a, T>,
+    _offsets: PhantomData<O>,
+}
+
+impl<'a, T, O> Clone for OffsetNeighbors<'a, T, O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, O> Copy for OffsetNeighbors<'a, T, O> {}
+
+impl<'a, T, O> OffsetNeighbors<'a, T, O>
+where
+    O: Offsets,
+{
+    /// Wraps a square window centered on the square that reaches every offset.
+    pub fn new(window: ArrayView2<'a, T>) -> Self {
+        let width = 2 * OffsetNeighborhood::<O>::RADIUS + 1;
+        assert_eq!(
+            window.dim(),
+            (width, width),
+            "offset neighbors need a window that exactly reaches the furthest offset"
+        );
+        Self {
+            window,
+            _offsets: PhantomData,
+        }
+    }
+
+    /// The square in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        let r = OffsetNeighborhood::<O>::RADIUS;
+        IndexLonger::index(&self.window, (r, r))
+    }
+
+    /// The neighbor at an edge.
+    pub fn get(&self, edge: usize) -> &'a T {
+        let r = OffsetNeighborhood::<O>::RADIUS as isize;
+        let (y, x) = O::OFFSETS[edge];
+        IndexLonger::index(&self.window, ((r + y) as usize, (r + x) as usize))
+    }
+
+    /// Iterate over the neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a
+    where
+        O: 'a,
+    {
+        let this = *self;
+        (0..O::OFFSETS.len()).map(move |edge| this.get(edge))
+    }
+
+    /// Iterate over the `(y, x)` offsets and neighbors in edge order.
+    pub fn iter_deltas(&self) -> impl Iterator<Item = ((isize, isize), &'a T)> + 'a
+    where
+        O: 'a,
+    {
+        let this = *self;
+        (0..O::OFFSETS.len()).map(move |edge| (O::OFFSETS[edge], this.get(edge)))
+    }
+
+    /// The square window indexed by `(y, x)`.
+    pub fn window(&self) -> ArrayView2<'a, T> {
+        self.window
+    }
+}
+
+impl<'a, T, O> Index<usize> for OffsetNeighbors<'a, T, O>
+where
+    O: Offsets,
+{
+    type Output = T;
+
+    fn index(&self, edge: usize) -> &T {
+        self.get(edge)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood, UpdateOrder};
+use ndarray::{s, Array2};
+use std::time::{Duration, Instant};
+
+/// A step of a [`SquareGrid`] which has computed the diffs of some of its rows.
+#[derive(Clone)]
+pub(crate) struct PartialStep<C, D> {
+    diffs: Array2<D>,
+    /// The number of rows whose diffs are computed.
+    rows: usize,
+    /// The cells from the start of the step, if hooks or the history need them.
+    before: Option<Array2<C>>,
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    N::Edges<S::Flow>: Send,
+{
+    /// Compute the diffs of up to `rows` more rows of a step, starting a new step if none is
+    /// in progress, and finish the step once the diffs of every row are computed.
+    ///
+    /// Returns `true` if the step finished. The cells don't change until then, so they can be
+    /// read between calls, such as to draw them while a large grid is being stepped over many
+    /// frames. Modifying the cells or stepping another way abandons the step, except for
+    /// [`SquareGrid::step`], which finishes it.
+    ///
+    /// Rows are computed in parallel when the `use-rayon` feature is enabled. The whole step is
+    /// taken at once if the update order isn't [`UpdateOrder::Synchronous`].
+    pub fn step_rows(&mut self, rows: usize) -> bool {
+        let mut partial = match self.partial.take() {
+            Some(partial) => partial,
+            None if self.update_order == UpdateOrder::Synchronous => {
+                let before = self.begin_step();
+                self.changed = None;
+                let clock = self.clock();
+                self.refresh_padding();
+                self.record(clock, |timings| &mut timings.padding);
+                PartialStep {
+                    diffs: self.take_diffs(),
+                    rows: 0,
+                    before,
+                }
+            }
+            None => {
+                self.step();
+                return true;
+            }
+        };
+
+        let r = N::RADIUS;
+        let d = 2 * r + 1;
+        let (h, w) = self.cells().dim();
+        let start = partial.rows;
+        let end = start.saturating_add(rows).min(h);
+        let cells = self.cells.slice(s![start..end + 2 * r, ..]);
+        let mut diffs = partial.diffs.slice_mut(s![start + r..end + r, r..w + r]);
+        let clock = self.clock();
+        let sim = &self.sim;
+        maybe_par_azip!((index (y, x), diff in &mut diffs, window in cells.windows((d, d))) {
+            let index = (start + y, x);
+            *diff = if self.in_domain(index) {
+                sim.compute(N::located_neighbors(window, self.locate(index)))
+            } else {
+                sim.diff_padding()
+            };
+        });
+        partial.rows = end;
+        self.record(clock, |timings| &mut timings.compute);
+
+        if end < h {
+            self.partial = Some(partial);
+            return false;
+        }
+        let PartialStep {
+            mut diffs, before, ..
+        } = partial;
+        self.pad_diffs(&mut diffs);
+        self.update(Some(diffs));
+        self.generation += 1;
+        self.end_step(before);
+        true
+    }
+
+    /// Work on a step for about as long as `budget`, computing the diffs of as many rows as
+    /// seem to fit, and finish the step once the diffs of every row are computed.
+    ///
+    /// Returns `true` if the step finished. This is [`SquareGrid::step_rows`] with the number
+    /// of rows picked from how long the last rows took. At least one row is computed in each
+    /// call, and the egress and ingress which finish the step aren't split up, so the budget
+    /// can be overrun.
+    pub fn step_partial(&mut self, budget: Duration) -> bool {
+        let start = Instant::now();
+        let mut rows = 1;
+        loop {
+            let chunk = Instant::now();
+            if self.step_rows(rows) {
+                return true;
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= budget {
+                return false;
+            }
+            let per_row = chunk.elapsed().as_secs_f64() / rows as f64;
+            let left = (budget - elapsed).as_secs_f64();
+            rows = ((left / per_row.max(1e-9)) as usize).max(1);
+        }
+    }
+
+    /// Get the number of rows whose diffs are computed by the step in progress, or `None` if
+    /// no step is in progress.
+    pub fn rows_computed(&self) -> Option<usize> {
+        self.partial.as_ref().map(|partial| partial.rows)
+    }
+}
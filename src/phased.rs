@@ -0,0 +1,191 @@
+use crate::{Boundary, Moore, Sim, SquareGrid, SquareNeighborhood, VonNeumann};
+use ndarray::{Array2, ArrayView2, ArrayViewMut2};
+use std::{fmt, mem};
+
+/// A grid whose generations are made of two phases run by different sims over different
+/// neighborhoods, such as movement to the four sides followed by interaction with all eight
+/// neighbors.
+///
+/// The cells are held by the grid of the phase which is running, so each phase sees every cell
+/// as the last phase left it and is double buffered on its own, and nothing is copied between
+/// phases. Each phase can use its own diffs and flows. Sims with the same neighborhood can be
+/// combined into one phase with [`Compose::then`](crate::Compose::then).
+pub struct PhasedGrid<A, B, NA = VonNeumann, NB = Moore>
+where
+    A: Sim<NA>,
+    B: Sim<NB, Cell = A::Cell>,
+    NA: SquareNeighborhood,
+    NB: SquareNeighborhood,
+{
+    first: SquareGrid<A, NA>,
+    /// Holds the cells during the second phase and padding cells otherwise.
+    second: SquareGrid<B, NB>,
+}
+
+impl<A, B, NA, NB> PhasedGrid<A, B, NA, NB>
+where
+    A: Sim<NA>,
+    B: Sim<NB, Cell = A::Cell>,
+    NA: SquareNeighborhood,
+    NB: SquareNeighborhood,
+    A::Cell: Send + Clone,
+{
+    /// Make a new grid which runs `first` and then `second` on the cells in each step.
+    pub fn new(first: A, second: B, cells: Array2<A::Cell>) -> Self {
+        let padding = Array2::from_shape_simple_fn(cells.dim(), || second.cell_padding());
+        Self {
+            first: SquareGrid::new(first, cells),
+            second: SquareGrid::new(second, padding),
+        }
+    }
+
+    /// Make a new grid with the same boundary on every edge in both phases.
+    ///
+    /// Panics if the boundary is [`Boundary::Shift`] or [`Boundary::Twist`], which can't be used
+    /// on both axes.
+    pub fn with_boundary(
+        first: A,
+        second: B,
+        cells: Array2<A::Cell>,
+        boundary: Boundary<A::Cell>,
+    ) -> Self
+    where
+        A::Diff: Clone,
+        B::Diff: Clone,
+    {
+        Boundary::check_pair(&boundary, &boundary);
+        let mut grid = Self::new(first, second, cells);
+        grid.first
+            .set_boundaries(boundary.clone(), boundary.clone());
+        grid.second.set_boundaries(boundary.clone(), boundary);
+        grid
+    }
+
+    /// Get the grid of the first phase, which holds the cells between steps.
+    pub fn first(&self) -> &SquareGrid<A, NA> {
+        &self.first
+    }
+
+    /// Get the grid of the first phase mutably.
+    ///
+    /// It must not be resized. Hooks added to it are called once for each whole step.
+    pub fn first_mut(&mut self) -> &mut SquareGrid<A, NA> {
+        &mut self.first
+    }
+
+    /// Get the sim of the second phase.
+    pub fn second_sim(&self) -> &B {
+        self.second.sim()
+    }
+
+    /// Get the sim of the second phase mutably.
+    pub fn second_sim_mut(&mut self) -> &mut B {
+        self.second.sim_mut()
+    }
+
+    /// Get the cells.
+    pub fn cells(&self) -> ArrayView2<'_, A::Cell> {
+        self.first.cells()
+    }
+
+    /// Get the cells mutably.
+    pub fn cells_mut(&mut self) -> ArrayViewMut2<'_, A::Cell> {
+        self.first.cells_mut()
+    }
+
+    /// Get the number of steps taken so far.
+    pub fn generation(&self) -> u64 {
+        self.first.generation()
+    }
+
+    /// Set the seed given to random sims in both phases.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.first.set_seed(seed);
+        self.second.set_seed(seed);
+    }
+
+    /// Take the grid of the first phase back out.
+    pub fn into_grid(self) -> SquareGrid<A, NA> {
+        self.first
+    }
+
+    /// Move the cells between the grids of the two phases.
+    fn exchange(&mut self) {
+        let first = &mut self.first.cells;
+        let second = &mut self.second.cells;
+        maybe_par_azip!((a in &mut first.slice_mut(&SquareGrid::<A, NA>::inside()),
+                b in &mut second.slice_mut(&SquareGrid::<B, NB>::inside())) {
+            mem::swap(a, b);
+        });
+    }
+}
+
+impl<A, B, NA, NB> PhasedGrid<A, B, NA, NB>
+where
+    A: Sim<NA> + Sync,
+    B: Sim<NB, Cell = A::Cell> + Sync,
+    NA: SquareNeighborhood,
+    NB: SquareNeighborhood,
+    A::Cell: Send + Sync + Clone,
+    A::Diff: Send + Sync + Clone,
+    B::Diff: Send + Sync + Clone,
+    NA::Edges<A::Flow>: Send,
+    NB::Edges<B::Flow>: Send,
+{
+    /// Simulate one step by running the first phase and then the second.
+    ///
+    /// Hooks see this as a single step.
+    pub fn step(&mut self) {
+        let before = self.first.begin_step();
+        self.first.changed = None;
+        self.first.sweep();
+        self.exchange();
+        self.second.generation = self.first.generation;
+        self.second.sweep();
+        self.exchange();
+        self.first.modified();
+        self.first.generation += 1;
+        self.first.end_step(before);
+    }
+
+    /// Simulate `n` steps.
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+}
+
+impl<A, B, NA, NB> Clone for PhasedGrid<A, B, NA, NB>
+where
+    A: Sim<NA> + Clone,
+    B: Sim<NB, Cell = A::Cell> + Clone,
+    NA: SquareNeighborhood,
+    NB: SquareNeighborhood,
+    A::Cell: Clone,
+    A::Diff: Clone,
+    B::Diff: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+        }
+    }
+}
+
+impl<A, B, NA, NB> fmt::Debug for PhasedGrid<A, B, NA, NB>
+where
+    A: Sim<NA> + fmt::Debug,
+    B: Sim<NB, Cell = A::Cell> + fmt::Debug,
+    NA: SquareNeighborhood,
+    NB: SquareNeighborhood,
+    A::Cell: Send + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PhasedGrid")
+            .field("first", &self.first)
+            .field("second", self.second.sim())
+            .finish()
+    }
+}
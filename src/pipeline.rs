@@ -0,0 +1,45 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::ArrayView2;
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Simulate one step while `read` is given the cells from before it, such as to render or
+    /// analyze a generation while the next one is computed.
+    ///
+    /// The cells are copied into a buffer which is kept between steps, and `read` runs on
+    /// another thread during the step when the `use-rayon` feature is enabled. Otherwise it
+    /// runs before the step on the current thread. Returns what `read` returns.
+    pub fn step_pipelined<R>(&mut self, read: impl FnOnce(ArrayView2<'_, S::Cell>) -> R + Send) -> R
+    where
+        R: Send,
+        Self: Send,
+    {
+        let previous = match self.previous.take() {
+            Some(mut previous) if previous.dim() == self.cells().dim() => {
+                maybe_par_azip!((previous in &mut previous, cell in self.cells()) {
+                    previous.clone_from(cell);
+                });
+                previous
+            }
+            _ => self.cells().to_owned(),
+        };
+
+        #[cfg(feature = "use-rayon")]
+        let value = rayon::join(|| read(previous.view()), || self.step()).0;
+        #[cfg(not(feature = "use-rayon"))]
+        let value = {
+            let value = read(previous.view());
+            self.step();
+            value
+        };
+
+        self.previous = Some(previous);
+        value
+    }
+}
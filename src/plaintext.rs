@@ -0,0 +1,125 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{s, Array2, ArrayView2};
+use std::fmt;
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid from text where each line is a row and each character is a cell.
+    ///
+    /// Lines shorter than the longest line are filled with padding cells.
+    pub fn from_chars(sim: S, text: &str, mut cell: impl FnMut(char) -> S::Cell) -> Self {
+        let rows: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let cells = Array2::from_shape_fn((rows.len(), width), |(y, x)| match rows[y].get(x) {
+            Some(&c) => cell(c),
+            None => sim.cell_padding(),
+        });
+        Self::new(sim, cells)
+    }
+
+    /// Write the grid as text where each row is a line and each cell is a character.
+    pub fn to_chars(&self, cell: impl FnMut(&S::Cell) -> char) -> String {
+        write_chars(self.cells(), cell)
+    }
+
+    /// Display the grid as text where each row is a line and each cell is a character.
+    ///
+    /// Nothing is written until the result is formatted, so this can be put straight into
+    /// `println!` or an assertion message.
+    pub fn display_with<F>(&self, cell: F) -> GridDisplay<'_, S::Cell, F>
+    where
+        F: Fn(&S::Cell) -> char,
+    {
+        GridDisplay {
+            cells: self.cells(),
+            cell,
+            limit: None,
+        }
+    }
+}
+
+/// Displays cells as text where each row is a line and each cell is a character.
+///
+/// Made with [`SquareGrid::display_with`].
+pub struct GridDisplay<'a, C, F> {
+    cells: ArrayView2<'a, C>,
+    cell: F,
+    limit: Option<(usize, usize)>,
+}
+
+impl<'a, C, F> GridDisplay<'a, C, F> {
+    /// Only show the first `(height, width)` cells, ending cut off rows with `...` and adding
+    /// a line of `...` if rows are cut off.
+    pub fn truncate(mut self, limit: (usize, usize)) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl<'a, C, F> fmt::Display for GridDisplay<'a, C, F>
+where
+    F: Fn(&C) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (h, w) = self.cells.dim();
+        let (lh, lw) = self.limit.unwrap_or((h, w));
+        let (lh, lw) = (lh.min(h), lw.min(w));
+        for row in self.cells.slice(s![..lh, ..lw]).genrows() {
+            for c in row {
+                write!(f, "{}", (self.cell)(c))?;
+            }
+            if lw < w {
+                write!(f, "...")?;
+            }
+            writeln!(f)?;
+        }
+        if lh < h {
+            writeln!(f, "...")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, C, F> fmt::Debug for GridDisplay<'a, C, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GridDisplay")
+            .field("dims", &self.cells.dim())
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Shows the dimensions and settings of the grid along with its cells, which are cut off by
+/// ndarray when there are many of them unless the alternate `{:#?}` format is used.
+impl<S, N> fmt::Debug for SquareGrid<S, N>
+where
+    S: Sim<N> + fmt::Debug,
+    N: SquareNeighborhood,
+    S::Cell: Send + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SquareGrid")
+            .field("dims", &self.cells().dim())
+            .field("generation", &self.generation())
+            .field("seed", &self.seed())
+            .field("boundaries", &self.boundaries())
+            .field("tile_size", &self.tile_size())
+            .field("sim", self.sim())
+            .field("cells", &self.cells())
+            .finish()
+    }
+}
+
+/// Write cells as text where each row is a line and each cell is a character.
+pub fn write_chars<C>(cells: ArrayView2<'_, C>, mut cell: impl FnMut(&C) -> char) -> String {
+    let mut text = String::new();
+    for row in cells.genrows() {
+        text.extend(row.iter().map(&mut cell));
+        text.push('\n');
+    }
+    text
+}
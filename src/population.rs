@@ -0,0 +1,164 @@
+use crate::{stats, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::ArrayView2;
+use std::{
+    fmt,
+    sync::atomic::{AtomicIsize, Ordering},
+};
+
+/// A projection of cells onto a small number of classes, such as their states, which a grid
+/// can keep counts of with [`SquareGrid::track_population`].
+pub trait Class {
+    /// The number of classes.
+    const CLASSES: usize;
+
+    /// Get the class of the cell, which must be less than [`Class::CLASSES`].
+    fn class(&self) -> usize;
+}
+
+impl Class for bool {
+    const CLASSES: usize = 2;
+
+    fn class(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// The number of cells of a grid in each class.
+pub(crate) struct Population<C> {
+    class: fn(&C) -> usize,
+    counts: Vec<usize>,
+    /// Whether the cells were modified outside of a step since they were counted.
+    stale: bool,
+}
+
+impl<C> Population<C> {
+    fn new(class: fn(&C) -> usize, classes: usize) -> Self {
+        Self {
+            class,
+            counts: vec![0; classes],
+            stale: true,
+        }
+    }
+
+    /// Forget the counts until the cells are counted again.
+    pub(crate) fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Count the cells again if they were modified since they were last counted.
+    fn refresh(&mut self, cells: ArrayView2<'_, C>)
+    where
+        C: Sync,
+    {
+        if self.stale {
+            let histogram = stats::histogram(cells, self.class);
+            for (class, count) in self.counts.iter_mut().enumerate() {
+                *count = histogram.get(&class).copied().unwrap_or(0);
+            }
+            self.stale = false;
+        }
+    }
+
+    /// Start a tally of the changes made by a step to cells counted from `cells`.
+    pub(crate) fn tally(&mut self, cells: ArrayView2<'_, C>) -> Tally<C>
+    where
+        C: Sync,
+    {
+        self.refresh(cells);
+        Tally {
+            class: self.class,
+            deltas: self.counts.iter().map(|_| AtomicIsize::new(0)).collect(),
+        }
+    }
+
+    /// Apply the changes of a finished tally to the counts.
+    pub(crate) fn apply(&mut self, tally: Tally<C>) {
+        for (count, delta) in self.counts.iter_mut().zip(tally.deltas) {
+            *count = (*count as isize + delta.into_inner()) as usize;
+        }
+    }
+}
+
+/// The function pointer is `Clone` no matter what the cell is.
+impl<C> Clone for Population<C> {
+    fn clone(&self) -> Self {
+        Self {
+            class: self.class,
+            counts: self.counts.clone(),
+            stale: self.stale,
+        }
+    }
+}
+
+impl<C> fmt::Debug for Population<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Population")
+            .field("counts", &self.counts)
+            .field("stale", &self.stale)
+            .finish()
+    }
+}
+
+/// The changes to the number of cells in each class during a step.
+///
+/// Only cells which change class touch the shared counters, so the tally adds little to the
+/// stages of a step which already visit every cell.
+pub(crate) struct Tally<C> {
+    class: fn(&C) -> usize,
+    deltas: Vec<AtomicIsize>,
+}
+
+impl<C> Tally<C> {
+    /// Run `f` on a cell and count the change of its class, if there is a tally.
+    pub(crate) fn track<R>(tally: Option<&Self>, cell: &mut C, f: impl FnOnce(&mut C) -> R) -> R {
+        let tally = match tally {
+            Some(tally) => tally,
+            None => return f(cell),
+        };
+        let old = (tally.class)(cell);
+        let result = f(cell);
+        let new = (tally.class)(cell);
+        if old != new {
+            tally.deltas[old].fetch_sub(1, Ordering::Relaxed);
+            tally.deltas[new].fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Keep counts of the cells in each [`Class`], which are updated while the cells are
+    /// updated during each step instead of by going over the grid again.
+    ///
+    /// Cells modified outside of a step, such as with [`SquareGrid::cells_mut`], are counted
+    /// again the next time the counts are needed. Clones of the grid keep tracking, but
+    /// deserialized grids don't.
+    pub fn track_population(&mut self)
+    where
+        S::Cell: Class,
+    {
+        self.population = Some(Population::new(S::Cell::class, S::Cell::CLASSES));
+    }
+
+    /// Stop keeping counts of the cells in each class.
+    pub fn untrack_population(&mut self) {
+        self.population = None;
+    }
+
+    /// Get the number of cells in each class, indexed by [`Class::class`], or `None` if the
+    /// population isn't tracked.
+    pub fn population(&mut self) -> Option<&[usize]>
+    where
+        S::Cell: Sync,
+    {
+        let cells = self.cells.slice(&Self::inside());
+        let population = self.population.as_mut()?;
+        population.refresh(cells);
+        Some(&population.counts)
+    }
+}
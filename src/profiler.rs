@@ -0,0 +1,143 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood, StepTimings};
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// Collects the [`StepTimings`] of every step of the grids it is attached to with
+/// [`SquareGrid::attach_profiler`], to find where the time goes for a sim.
+///
+/// Clones of a profiler share the same timings, so one can be attached to a grid while
+/// another is kept to read the timings.
+#[derive(Clone, Debug, Default)]
+pub struct Profiler {
+    steps: Arc<Mutex<Vec<(u64, StepTimings)>>>,
+}
+
+impl Profiler {
+    /// Make a profiler without any timings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the generation each step reached and how long its stages took, in the order they
+    /// were taken.
+    pub fn steps(&self) -> Vec<(u64, StepTimings)> {
+        self.lock().clone()
+    }
+
+    /// Add up the timings of every step.
+    pub fn summary(&self) -> ProfileSummary {
+        let steps = self.lock();
+        let mut summary = ProfileSummary::default();
+        for (_, timings) in steps.iter() {
+            summary.steps += 1;
+            summary.cells += timings.cells as u64;
+            summary.padding += timings.padding;
+            summary.compute += timings.compute;
+            summary.egress += timings.egress;
+            summary.ingress += timings.ingress;
+            summary.total += timings.total;
+        }
+        summary
+    }
+
+    /// Forget every step.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Write the stages of every step in the folded stack format read by flame graph tools
+    /// such as `inferno-flamegraph`, with `name` at the root of each stack.
+    ///
+    /// Each line is a stage and the number of microseconds it took over every step, such as
+    /// `life;compute 1250`. Time in the step outside of the stages, such as in hooks, is
+    /// counted for `name` on its own.
+    pub fn write_folded(&self, writer: &mut impl Write, name: &str) -> io::Result<()> {
+        let summary = self.summary();
+        writeln!(writer, "{} {}", name, summary.other().as_micros())?;
+        for (stage, time) in summary.stages() {
+            writeln!(writer, "{};{} {}", name, stage, time.as_micros())?;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(u64, StepTimings)>> {
+        self.steps
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The timings of the steps collected by a [`Profiler`], added up.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ProfileSummary {
+    /// The number of steps.
+    pub steps: u64,
+    /// The number of cells stepped over every step.
+    pub cells: u64,
+    /// Updating the padding cells.
+    pub padding: Duration,
+    /// Computing the diffs of every cell.
+    pub compute: Duration,
+    /// Performing egress and moving the flows.
+    pub egress: Duration,
+    /// Performing ingress.
+    pub ingress: Duration,
+    /// The whole steps.
+    pub total: Duration,
+}
+
+impl ProfileSummary {
+    /// The time in the steps which wasn't spent in any stage, such as in hooks.
+    pub fn other(&self) -> Duration {
+        self.total
+            .saturating_sub(self.padding + self.compute + self.egress + self.ingress)
+    }
+
+    /// The average time of a step, or zero if there were none.
+    pub fn mean(&self) -> Duration {
+        match self.steps {
+            0 => Duration::ZERO,
+            steps => self.total.div_f64(steps as f64),
+        }
+    }
+
+    /// The number of cells stepped per second over every step.
+    pub fn cells_per_second(&self) -> f64 {
+        self.cells as f64 / self.total.as_secs_f64()
+    }
+
+    /// Get the name and time of each stage, in the order they happen in a step.
+    pub fn stages(&self) -> [(&'static str, Duration); 4] {
+        [
+            ("padding", self.padding),
+            ("compute", self.compute),
+            ("egress", self.egress),
+            ("ingress", self.ingress),
+        ]
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Time the stages of every step and give the timings to `profiler`.
+    ///
+    /// This turns on [`SquareGrid::time_steps`] and adds a hook with
+    /// [`SquareGrid::on_after_step`], so it is undone by [`SquareGrid::untime_steps`] and
+    /// [`SquareGrid::clear_hooks`].
+    pub fn attach_profiler(&mut self, profiler: &Profiler) {
+        self.time_steps();
+        let profiler = profiler.clone();
+        self.on_after_step(move |grid| {
+            if let Some(timings) = grid.step_timings() {
+                profiler.lock().push((grid.generation(), *timings));
+            }
+        });
+    }
+}
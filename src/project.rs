@@ -0,0 +1,42 @@
+//! Views of one field of every cell in a window, which let wrapper sims give the cells inside
+//! of their cells to the sims they wrap without cloning them.
+
+use ndarray::{ArrayView2, ShapeBuilder};
+use std::mem;
+
+/// Get a view of the field that `field` picks out of every cell of `window`, or `None` if the
+/// cells can't be stepped through in whole fields, in which case the window must be cloned.
+///
+/// # Safety
+///
+/// `field` must return a reference to the same field of every cell, such as `|cell|
+/// &cell.inner`, and never a field of an enum variant or anything reached through a pointer.
+pub(crate) unsafe fn project<'a, W, T>(
+    window: ArrayView2<'a, W>,
+    field: impl Fn(&W) -> &T,
+) -> Option<ArrayView2<'a, T>> {
+    let (outer, inner) = (mem::size_of::<W>(), mem::size_of::<T>());
+    if inner == 0 || outer % inner != 0 || window.is_empty() {
+        return None;
+    }
+    // Only the address of the field is taken from `field`, since the reference it returns is
+    // only allowed to reach that one field. The view is made from the pointer of the window,
+    // which is allowed to reach every cell of it, moved to the field by its offset in the cell.
+    let first = &window[(0, 0)];
+    let base = first as *const W as usize;
+    let target = field(first) as *const T as usize;
+    if target < base || target + inner > base + outer {
+        return None;
+    }
+    let pointer = (window.as_ptr() as *const u8).add(target - base) as *const T;
+    // Each cell is a whole number of fields long, so moving one cell over moves that many
+    // fields over and lands on the same field of the next cell.
+    let scale = (outer / inner) as isize;
+    let strides = window.strides();
+    let (sy, sx) = (strides[0] * scale, strides[1] * scale);
+    if sy < 0 || sx < 0 {
+        return None;
+    }
+    let shape = window.dim().strides((sy as usize, sx as usize));
+    Some(ArrayView2::from_shape_ptr(shape, pointer))
+}
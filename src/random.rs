@@ -0,0 +1,189 @@
+use crate::{Location, Moore, Neighborhood, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, ArrayView2};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, Range},
+};
+
+/// The generation given to the generators of random cells, which keeps them from repeating the
+/// numbers given to sims on any real generation when the seeds are the same.
+pub(crate) const INITIAL: u64 = u64::MAX;
+
+/// A small random number generator for a single cell on a single step.
+///
+/// It is seeded only from the seed of the grid, the generation, and the index of the cell,
+/// so stochastic rules are reproducible regardless of how the work is split between threads.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CellRng {
+    state: u64,
+}
+
+impl CellRng {
+    /// Make a generator from a seed, generation, and `(y, x)` index.
+    pub fn new(seed: u64, generation: u64, (y, x): (usize, usize)) -> Self {
+        let mut rng = Self { state: seed };
+        for value in [generation, y as u64, x as u64].iter() {
+            rng.state ^= value;
+            rng.state = rng.next_u64();
+        }
+        rng
+    }
+
+    /// Get a uniformly random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        // SplitMix64
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Get a uniformly random `f64` in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Get `true` with probability `p`.
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Get a uniformly random number in `0..n`.
+    pub fn gen_range(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "range is empty");
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid of `(height, width)` cells made by `cell` from a generator seeded with
+    /// `seed` and the index of the cell.
+    ///
+    /// The cells are made in parallel when the `use-rayon` feature is enabled, and are the same
+    /// no matter how the work is split. The seed of the grid is left at `0`.
+    pub fn new_random(
+        sim: S,
+        dims: (usize, usize),
+        seed: u64,
+        cell: impl Fn(&mut CellRng) -> S::Cell + Sync + Send,
+    ) -> Self {
+        let mut cells = Array2::from_shape_simple_fn(dims, || sim.cell_padding());
+        maybe_par_azip!((index index, c in &mut cells) {
+            *c = cell(&mut CellRng::new(seed, INITIAL, index));
+        });
+        Self::new(sim, cells)
+    }
+
+    /// Replace the cells in the `(y, x)` ranges of a region with cells made by `cell` from a
+    /// generator seeded with `seed` and the index of the cell.
+    ///
+    /// Each cell gets the same generator as it would from [`SquareGrid::new_random`], so
+    /// randomizing part of a grid matches the same part of a fully random grid.
+    pub fn randomize_region(
+        &mut self,
+        region: (Range<usize>, Range<usize>),
+        seed: u64,
+        cell: impl Fn(&mut CellRng) -> S::Cell + Sync + Send,
+    ) {
+        let (oy, ox) = (region.0.start, region.1.start);
+        maybe_par_azip!((index (y, x), c in &mut self.window_mut(region)) {
+            *c = cell(&mut CellRng::new(seed, INITIAL, (y + oy, x + ox)));
+        });
+    }
+}
+
+/// A neighborhood which comes with a [`CellRng`] for the cell in the center.
+///
+/// The edges are the same as the wrapped neighborhood. The random numbers depend on the seed
+/// of the grid, so set it with [`SquareGrid::set_seed`](crate::SquareGrid::set_seed).
+/// The compute and egress stages get generators with the same numbers.
+pub struct Random<N = Moore>(PhantomData<N>);
+
+impl<N> Neighborhood for Random<N>
+where
+    N: SquareNeighborhood,
+{
+    type Neighbors<'a, T: 'a> = RandomNeighbors<'a, T, N>;
+    type Edges<T> = N::Edges<T>;
+}
+
+impl<N> SquareNeighborhood for Random<N>
+where
+    N: SquareNeighborhood,
+{
+    const RADIUS: usize = N::RADIUS;
+    const EDGES: usize = N::EDGES;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        N::delta(edge)
+    }
+
+    fn opposite(edge: usize) -> usize {
+        N::opposite(edge)
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> RandomNeighbors<'_, T, N> {
+        RandomNeighbors {
+            neighbors: N::neighbors(window),
+            rng: CellRng::new(0, 0, (0, 0)),
+        }
+    }
+
+    fn located_neighbors<T>(
+        window: ArrayView2<'_, T>,
+        location: Location,
+    ) -> RandomNeighbors<'_, T, N> {
+        RandomNeighbors {
+            rng: location.rng(),
+            neighbors: N::located_neighbors(window, location),
+        }
+    }
+
+    fn edges<T>(f: impl FnMut() -> T) -> N::Edges<T> {
+        N::edges(f)
+    }
+
+    fn edges_mut<T>(edges: &mut N::Edges<T>) -> &mut [T] {
+        N::edges_mut(edges)
+    }
+}
+
+/// The neighbors of the wrapped neighborhood along with a random number generator.
+///
+/// This dereferences to the neighbors of the wrapped neighborhood.
+pub struct RandomNeighbors<'a, T: 'a, N: Neighborhood = Moore> {
+    neighbors: N::Neighbors<'a, T>,
+    rng: CellRng,
+}
+
+impl<'a, T, N> RandomNeighbors<'a, T, N>
+where
+    N: Neighborhood,
+{
+    /// Get the random number generator of the cell.
+    pub fn rng(&mut self) -> &mut CellRng {
+        &mut self.rng
+    }
+
+    /// Get the neighbors of the wrapped neighborhood.
+    pub fn into_neighbors(self) -> N::Neighbors<'a, T> {
+        self.neighbors
+    }
+}
+
+impl<'a, T, N> Deref for RandomNeighbors<'a, T, N>
+where
+    N: Neighborhood,
+{
+    type Target = N::Neighbors<'a, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.neighbors
+    }
+}
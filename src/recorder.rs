@@ -0,0 +1,186 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use std::{
+    fmt,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+/// The format that a [`Recorder`] writes rows in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RecordFormat {
+    /// Comma-separated values with a header row of the column names.
+    Csv,
+    /// One JSON object per line with the column names as keys.
+    JsonLines,
+}
+
+/// A metric of a `T` with its column name.
+type Metric<T> = (String, Box<dyn FnMut(&T) -> f64>);
+
+/// Writes a row of metrics of a `T`, usually a grid, for every generation.
+///
+/// Every row has the generation, the time the step took in seconds, and then each metric
+/// in the order they were added.
+pub struct Recorder<T, W> {
+    writer: W,
+    format: RecordFormat,
+    metrics: Vec<Metric<T>>,
+    wrote_header: bool,
+}
+
+impl<T, W> Recorder<T, W>
+where
+    W: Write,
+{
+    /// Make a recorder which writes rows to `writer`, such as a buffered file.
+    pub fn new(writer: W, format: RecordFormat) -> Self {
+        Self {
+            writer,
+            format,
+            metrics: vec![],
+            wrote_header: false,
+        }
+    }
+
+    /// Add a metric which is written in a column called `name`.
+    ///
+    /// Metrics must be added before the first row is recorded.
+    pub fn add_metric(&mut self, name: impl Into<String>, metric: impl FnMut(&T) -> f64 + 'static) {
+        assert!(
+            !self.wrote_header,
+            "metrics must be added before the first row is recorded"
+        );
+        self.metrics.push((name.into(), Box::new(metric)));
+    }
+
+    /// Write the row of a generation.
+    pub fn record(&mut self, generation: u64, step_time: Duration, subject: &T) -> io::Result<()> {
+        let values: Vec<f64> = self
+            .metrics
+            .iter_mut()
+            .map(|(_, metric)| metric(subject))
+            .collect();
+        match self.format {
+            RecordFormat::Csv => {
+                if !self.wrote_header {
+                    write!(self.writer, "generation,step_time")?;
+                    for (name, _) in &self.metrics {
+                        write!(self.writer, ",{}", Csv(name))?;
+                    }
+                    writeln!(self.writer)?;
+                }
+                write!(self.writer, "{},{}", generation, step_time.as_secs_f64())?;
+                for value in values {
+                    write!(self.writer, ",{}", value)?;
+                }
+                writeln!(self.writer)?;
+            }
+            RecordFormat::JsonLines => {
+                write!(
+                    self.writer,
+                    "{{\"generation\":{},\"step_time\":{}",
+                    generation,
+                    Json(step_time.as_secs_f64())
+                )?;
+                for ((name, _), value) in self.metrics.iter().zip(values) {
+                    write!(self.writer, ",{}:{}", JsonString(name), Json(value))?;
+                }
+                writeln!(self.writer, "}}")?;
+            }
+        }
+        self.wrote_header = true;
+        Ok(())
+    }
+
+    /// Flush the writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Get the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<S, N, W> Recorder<SquareGrid<S, N>, W>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+    W: Write,
+{
+    /// Step the grid, timing how long it takes, then write the row of its new generation.
+    pub fn step(&mut self, grid: &mut SquareGrid<S, N>) -> io::Result<()> {
+        let start = Instant::now();
+        grid.step();
+        let step_time = start.elapsed();
+        self.record(grid.generation(), step_time, grid)
+    }
+}
+
+impl<T, W> fmt::Debug for Recorder<T, W>
+where
+    W: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("writer", &self.writer)
+            .field("format", &self.format)
+            .field(
+                "metrics",
+                &self
+                    .metrics
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Displays a CSV field, quoting it if needed.
+struct Csv<'a>(&'a str);
+
+impl fmt::Display for Csv<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.contains(&[',', '"', '\n', '\r'][..]) {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        } else {
+            f.write_str(self.0)
+        }
+    }
+}
+
+/// Displays a JSON string.
+struct JsonString<'a>(&'a str);
+
+impl fmt::Display for JsonString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+/// Displays a JSON number, or `null` if it isn't finite.
+struct Json(f64);
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_finite() {
+            write!(f, "{}", self.0)
+        } else {
+            f.write_str("null")
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{s, Array2};
+use std::mem;
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Simulate one synchronous step one cell at a time on the current thread, as plainly as
+    /// possible, to check [`SquareGrid::step`] against when a sim behaves unexpectedly.
+    ///
+    /// This ignores the update order and tile size of the grid and doesn't time the step, but
+    /// otherwise counts as a step and calls the hooks. It is much slower than the other ways of
+    /// stepping. See [`testing::assert_matches_reference`](crate::testing::assert_matches_reference)
+    /// to compare the two for many steps.
+    pub fn step_reference(&mut self) {
+        let before = self.begin_step();
+        self.changed = None;
+        self.update_padding();
+        let r = N::RADIUS;
+        let d = 2 * r + 1;
+        let (h, w) = self.cells().dim();
+        let indices: Vec<(usize, usize)> = (0..h)
+            .flat_map(|y| (0..w).map(move |x| (y, x)))
+            .filter(|&index| self.in_domain(index))
+            .collect();
+
+        // Every cell computes its diff from the cells around it.
+        let mut diffs = Array2::from_shape_simple_fn(self.cells.dim(), || self.sim.diff_padding());
+        for &(y, x) in &indices {
+            let window = self.cells.slice(s![y..y + d, x..x + d]);
+            let location = self.locate((y, x));
+            diffs[(y + r, x + r)] = self.sim.compute(N::located_neighbors(window, location));
+        }
+        self.pad_diffs(&mut diffs);
+
+        // Every cell sends its flows to the neighbor on each edge, which receives them on the
+        // opposite edge.
+        let mut received =
+            Array2::from_shape_simple_fn((h, w), || N::edges(|| self.sim.flow_padding()));
+        for &(y, x) in &indices {
+            let window = diffs.slice(s![y..y + d, x..x + d]);
+            let location = self.locate((y, x));
+            let mut flows = self.sim.egress(
+                &mut self.cells[(y + r, x + r)],
+                N::located_neighbors(window, location),
+            );
+            for (edge, flow) in N::edges_mut(&mut flows).iter_mut().enumerate() {
+                let (dy, dx) = N::delta(edge);
+                let to = (y as isize + dy, x as isize + dx);
+                let target = match self.wrap(to) {
+                    Some(target) if self.in_domain(target) => target,
+                    _ => continue,
+                };
+                let to = self.shifted(to, (h, w));
+                let flip_y = self.boundary.1.twists(to.1, w);
+                let flip_x = self.boundary.0.twists(to.0, h);
+                let delta = (if flip_y { -dy } else { dy }, if flip_x { -dx } else { dx });
+                let arriving = N::edge(delta).unwrap();
+                mem::swap(
+                    &mut N::edges_mut(&mut received[target])[N::opposite(arriving)],
+                    flow,
+                );
+            }
+        }
+
+        // Every cell takes the flows it received.
+        let mut received = received.into_raw_vec();
+        for &(y, x) in &indices {
+            let flows = mem::replace(
+                &mut received[y * w + x],
+                N::edges(|| self.sim.flow_padding()),
+            );
+            self.sim.ingress(&mut self.cells[(y + r, x + r)], flows);
+        }
+
+        self.modified();
+        self.generation += 1;
+        self.end_step(before);
+    }
+
+    /// Simulate `n` steps like [`SquareGrid::step_reference`].
+    pub fn step_reference_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step_reference();
+        }
+    }
+}
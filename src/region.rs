@@ -0,0 +1,78 @@
+use crate::{Boundary, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::s;
+use std::{mem, ops::Range};
+
+/// The grid around a region which is being stepped on its own, so the cells of the region are
+/// located where they are on the grid.
+pub(crate) struct Focus<C> {
+    /// The `(y, x)` index of the first cell of the region.
+    pub(crate) corner: (usize, usize),
+    /// The `(height, width)` of the grid.
+    pub(crate) dims: (usize, usize),
+    /// The boundaries of the grid.
+    pub(crate) boundary: (Boundary<C>, Boundary<C>),
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Clone,
+    S::Diff: Send + Sync + Clone,
+    N::Edges<S::Flow>: Send,
+{
+    /// Simulate one step of the cells in the `(y, x)` ranges of a region, leaving every other
+    /// cell as it is.
+    ///
+    /// Cells at the border of the region read the cells around it, including those the
+    /// boundaries give beyond the edges of the grid, so a region sees what it would if the
+    /// whole grid stepped. The cells around the region don't send flows into it, and flows
+    /// which would leave it are lost. This counts as a step and calls the hooks.
+    ///
+    /// Panics if the region isn't on the grid.
+    pub fn step_region(&mut self, region: (Range<usize>, Range<usize>)) {
+        let (y, x) = self
+            .check_region(region)
+            .unwrap_or_else(|error| panic!("{}", error));
+        let before = self.begin_step();
+        self.update_padding();
+        // The region and a ring of the cells around it are stepped as a grid whose padding is
+        // a halo, with the cells of the whole grid set aside.
+        let r = N::RADIUS;
+        let ring_slice = s![y.start..y.end + 2 * r, x.start..x.end + 2 * r];
+        let ring = self.cells.slice(ring_slice).to_owned();
+        let ring_domain = self
+            .domain
+            .as_ref()
+            .map(|domain| domain.slice(ring_slice).to_owned());
+        let domain = mem::replace(&mut self.domain, ring_domain);
+        let dims = self.cells().dim();
+        let cells = mem::replace(&mut self.cells, ring);
+        let boundary = mem::replace(&mut self.boundary, (Boundary::Halo, Boundary::Halo));
+        let population = self.population.take();
+        self.focus = Some(Focus {
+            corner: (y.start, x.start),
+            dims,
+            boundary,
+        });
+        self.sweep();
+        let focus = self.focus.take().unwrap();
+        self.boundary = focus.boundary;
+        self.population = population;
+        self.domain = domain;
+        let ring = mem::replace(&mut self.cells, cells);
+        self.cells
+            .slice_mut(s![y.start + r..y.end + r, x.start + r..x.end + r])
+            .assign(&ring.slice(&Self::inside()));
+        self.modified();
+        self.generation += 1;
+        self.end_step(before);
+    }
+
+    /// Simulate `n` steps of the cells in a region like [`SquareGrid::step_region`].
+    pub fn step_region_n(&mut self, region: (Range<usize>, Range<usize>), n: u64) {
+        for _ in 0..n {
+            self.step_region(region.clone());
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    rules::{BrainCell, ForestCell, WireworldCell},
+    BitGrid, Sim, SquareGrid, SquareNeighborhood,
+};
+
+/// A cell which can be drawn as a pixel.
+pub trait CellColor {
+    /// The `[red, green, blue, alpha]` color of the cell.
+    fn color(&self) -> [u8; 4];
+}
+
+impl CellColor for bool {
+    fn color(&self) -> [u8; 4] {
+        if *self {
+            [255, 255, 255, 255]
+        } else {
+            [0, 0, 0, 255]
+        }
+    }
+}
+
+impl CellColor for BrainCell {
+    fn color(&self) -> [u8; 4] {
+        match self {
+            BrainCell::Off => [0, 0, 0, 255],
+            BrainCell::On => [255, 255, 255, 255],
+            BrainCell::Dying => [0, 0, 255, 255],
+        }
+    }
+}
+
+impl CellColor for WireworldCell {
+    fn color(&self) -> [u8; 4] {
+        match self {
+            WireworldCell::Empty => [0, 0, 0, 255],
+            WireworldCell::Head => [0, 128, 255, 255],
+            WireworldCell::Tail => [255, 64, 0, 255],
+            WireworldCell::Conductor => [255, 200, 0, 255],
+        }
+    }
+}
+
+impl CellColor for ForestCell {
+    fn color(&self) -> [u8; 4] {
+        match self {
+            ForestCell::Empty => [64, 40, 16, 255],
+            ForestCell::Tree => [0, 160, 0, 255],
+            ForestCell::Fire => [255, 96, 0, 255],
+        }
+    }
+}
+
+/// A grid which can be drawn into an RGBA framebuffer with one pixel per cell.
+///
+/// This lets frontends draw any grid without knowing how it stores its cells.
+pub trait Render {
+    /// The `(height, width)` of the image in pixels.
+    fn image_dim(&self) -> (usize, usize);
+
+    /// Write the `[red, green, blue, alpha]` bytes of every pixel in row-major order into
+    /// `buffer`, which must have `4 * height * width` bytes.
+    ///
+    /// The rows are drawn in parallel when the `use-rayon` feature is enabled.
+    fn render_into(&self, buffer: &mut [u8]);
+
+    /// Draw the grid into a new framebuffer.
+    fn render(&self) -> Vec<u8> {
+        let (h, w) = self.image_dim();
+        let mut buffer = vec![0; 4 * h * w];
+        self.render_into(&mut buffer);
+        buffer
+    }
+}
+
+/// Draw each row of a framebuffer with `color`, given the row and the index of a pixel in it.
+pub(crate) fn render_rows(
+    buffer: &mut [u8],
+    (h, w): (usize, usize),
+    color: impl Fn(usize, usize) -> [u8; 4] + Send + Sync,
+) {
+    assert_eq!(buffer.len(), 4 * h * w, "buffer is the wrong size");
+    let row = |(y, row): (usize, &mut [u8])| {
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            pixel.copy_from_slice(&color(y, x));
+        }
+    };
+    #[cfg(feature = "use-rayon")]
+    {
+        use rayon::prelude::*;
+        buffer.par_chunks_mut(4 * w).enumerate().for_each(row);
+    }
+    #[cfg(not(feature = "use-rayon"))]
+    buffer.chunks_mut(4 * w).enumerate().for_each(row);
+}
+
+impl<S, N> Render for SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + CellColor,
+{
+    fn image_dim(&self) -> (usize, usize) {
+        self.cells().dim()
+    }
+
+    fn render_into(&self, buffer: &mut [u8]) {
+        let cells = self.cells();
+        render_rows(buffer, cells.dim(), |y, x| cells[(y, x)].color());
+    }
+}
+
+impl Render for BitGrid {
+    fn image_dim(&self) -> (usize, usize) {
+        self.dim()
+    }
+
+    fn render_into(&self, buffer: &mut [u8]) {
+        render_rows(buffer, self.dim(), |y, x| self.get((y, x)).color());
+    }
+}
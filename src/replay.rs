@@ -0,0 +1,179 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use std::{
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// The 64-bit FNV-1a hash, which unlike the hasher of the standard library is specified,
+/// so checksums stay the same across Rust versions.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync + Hash,
+{
+    /// Compute a checksum of the dimensions and cells of the grid.
+    ///
+    /// The rows are hashed in parallel when the `use-rayon` feature is enabled and their hashes
+    /// are combined in order, so the checksum doesn't depend on how the threads were scheduled.
+    /// It is the same across runs and Rust versions, but can differ between platforms if the
+    /// cells hash values like `usize` whose size depends on the platform.
+    pub fn checksum(&self) -> u64 {
+        let cells = self.cells();
+        let row = |y: usize| {
+            let mut hasher = Fnv::new();
+            cells.row(y).iter().for_each(|cell| cell.hash(&mut hasher));
+            hasher.finish()
+        };
+        #[cfg(feature = "use-rayon")]
+        let rows: Vec<u64> = {
+            use rayon::prelude::*;
+            (0..cells.nrows()).into_par_iter().map(row).collect()
+        };
+        #[cfg(not(feature = "use-rayon"))]
+        let rows: Vec<u64> = (0..cells.nrows()).map(row).collect();
+        let mut hasher = Fnv::new();
+        (cells.nrows() as u64, cells.ncols() as u64).hash(&mut hasher);
+        rows.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// The checksums of a run of a simulation, which a replay can be verified against.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplayTrace {
+    /// The seed of the grid.
+    pub seed: u64,
+    /// The generation of the first checksum.
+    pub start: u64,
+    /// The checksum of every generation from `start` on.
+    pub checksums: Vec<u64>,
+}
+
+/// The first generation where a replay didn't match its trace.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReplayMismatch {
+    /// The generation which didn't match.
+    pub generation: u64,
+    /// The checksum in the trace.
+    pub expected: u64,
+    /// The checksum of the replay.
+    pub actual: u64,
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "replay diverged at generation {}: expected checksum {:#018x}, got {:#018x}",
+            self.generation, self.expected, self.actual
+        )
+    }
+}
+
+impl Error for ReplayMismatch {}
+
+impl ReplayTrace {
+    /// Step a grid `generations` times, recording the checksum of its state before the first
+    /// step and after every step.
+    pub fn record<S, N>(grid: &mut SquareGrid<S, N>, generations: u64) -> Self
+    where
+        S: Sim<N> + Sync,
+        N: SquareNeighborhood,
+        S::Cell: Send + Sync + Clone + Hash,
+        S::Diff: Send + Sync + Clone,
+        N::Edges<S::Flow>: Send,
+    {
+        Self::record_with(grid, generations, SquareGrid::step)
+    }
+
+    /// Like [`ReplayTrace::record`], but stepping the grid with `step`, such as
+    /// [`SquareGrid::step_simd`].
+    pub fn record_with<S, N>(
+        grid: &mut SquareGrid<S, N>,
+        generations: u64,
+        mut step: impl FnMut(&mut SquareGrid<S, N>),
+    ) -> Self
+    where
+        S: Sim<N>,
+        N: SquareNeighborhood,
+        S::Cell: Send + Sync + Hash,
+    {
+        let mut checksums = vec![grid.checksum()];
+        for _ in 0..generations {
+            step(grid);
+            checksums.push(grid.checksum());
+        }
+        Self {
+            seed: grid.seed(),
+            start: grid.generation() - generations,
+            checksums,
+        }
+    }
+
+    /// Step a grid in the same starting state as the recorded one and check that every
+    /// generation has the recorded checksum.
+    ///
+    /// The grid is given the seed of the trace first. It is left at the first generation which
+    /// didn't match, if any.
+    pub fn verify<S, N>(&self, grid: &mut SquareGrid<S, N>) -> Result<(), ReplayMismatch>
+    where
+        S: Sim<N> + Sync,
+        N: SquareNeighborhood,
+        S::Cell: Send + Sync + Clone + Hash,
+        S::Diff: Send + Sync + Clone,
+        N::Edges<S::Flow>: Send,
+    {
+        self.verify_with(grid, SquareGrid::step)
+    }
+
+    /// Like [`ReplayTrace::verify`], but stepping the grid with `step`.
+    pub fn verify_with<S, N>(
+        &self,
+        grid: &mut SquareGrid<S, N>,
+        mut step: impl FnMut(&mut SquareGrid<S, N>),
+    ) -> Result<(), ReplayMismatch>
+    where
+        S: Sim<N>,
+        N: SquareNeighborhood,
+        S::Cell: Send + Sync + Hash,
+    {
+        grid.set_seed(self.seed);
+        for (i, &expected) in self.checksums.iter().enumerate() {
+            if i != 0 {
+                step(grid);
+            }
+            let actual = grid.checksum();
+            if actual != expected {
+                return Err(ReplayMismatch {
+                    generation: self.start + i as u64,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::CellRng;
+
+/// The outcome of resolving several flows that arrived at the same cell, such as agents
+/// trying to move into it.
+///
+/// Edges are the edges the flows arrived from, as given to [`Sim::ingress`](crate::Sim::ingress).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resolved<T> {
+    /// The flow which was accepted, if any arrived.
+    pub winner: Option<(usize, T)>,
+    /// The flows which were not accepted, in edge order.
+    pub rejected: Vec<(usize, T)>,
+}
+
+impl<T> Resolved<T> {
+    /// Resolve by taking every flow and accepting the one at the given position among them.
+    fn pick(flows: &mut [Option<T>], pick: impl FnOnce(&[(usize, T)]) -> usize) -> Self {
+        let mut rejected: Vec<(usize, T)> = flows
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(edge, flow)| flow.take().map(|flow| (edge, flow)))
+            .collect();
+        let winner = if rejected.is_empty() {
+            None
+        } else {
+            let index = pick(&rejected);
+            Some(rejected.remove(index))
+        };
+        Self { winner, rejected }
+    }
+
+    /// Check if more than one flow arrived.
+    pub fn is_conflict(&self) -> bool {
+        !self.rejected.is_empty()
+    }
+
+    /// Get the accepted flow without the edge it arrived from.
+    pub fn into_winner(self) -> Option<T> {
+        self.winner.map(|(_, flow)| flow)
+    }
+}
+
+/// Accept the flow from the lowest edge.
+pub fn resolve_first<T>(flows: &mut [Option<T>]) -> Resolved<T> {
+    Resolved::pick(flows, |_| 0)
+}
+
+/// Accept the flow from the edge which comes first in `order`.
+///
+/// Edges that aren't in `order` are only accepted if no edge in `order` has a flow, in
+/// which case the lowest edge is accepted.
+pub fn resolve_priority<T>(flows: &mut [Option<T>], order: &[usize]) -> Resolved<T> {
+    Resolved::pick(flows, |arrived| {
+        order
+            .iter()
+            .find_map(|&edge| arrived.iter().position(|&(e, _)| e == edge))
+            .unwrap_or(0)
+    })
+}
+
+/// Accept the flow with the largest key, preferring the lowest edge on ties.
+///
+/// A random tiebreak which is decided by the senders can be made by sending a random key
+/// from the [`CellRng`] of the sender along with the flow.
+pub fn resolve_max_by_key<T, K>(
+    flows: &mut [Option<T>],
+    mut key: impl FnMut(usize, &T) -> K,
+) -> Resolved<T>
+where
+    K: Ord,
+{
+    Resolved::pick(flows, |arrived| {
+        let mut best = 0;
+        let mut best_key = None;
+        for (index, (edge, flow)) in arrived.iter().enumerate() {
+            let key = key(*edge, flow);
+            match &best_key {
+                Some(best_key) if key <= *best_key => {}
+                _ => {
+                    best = index;
+                    best_key = Some(key);
+                }
+            }
+        }
+        best
+    })
+}
+
+/// Accept a flow uniformly at random.
+pub fn resolve_random<T>(flows: &mut [Option<T>], rng: &mut CellRng) -> Resolved<T> {
+    Resolved::pick(flows, |arrived| {
+        rng.gen_range(arrived.len() as u64) as usize
+    })
+}
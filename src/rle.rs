@@ -0,0 +1,280 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{s, Array2, ArrayView2};
+use std::{error::Error, fmt};
+
+/// A cell that can be stored in a run-length encoded pattern.
+pub trait RleCell: Sized {
+    /// The state number of the cell, where `0` is the dead state.
+    fn to_state(&self) -> u8;
+
+    /// Make a cell from a state number, if the state is supported.
+    fn from_state(state: u8) -> Option<Self>;
+}
+
+impl RleCell for bool {
+    fn to_state(&self) -> u8 {
+        *self as u8
+    }
+
+    fn from_state(state: u8) -> Option<Self> {
+        match state {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl RleCell for u8 {
+    fn to_state(&self) -> u8 {
+        *self
+    }
+
+    fn from_state(state: u8) -> Option<Self> {
+        Some(state)
+    }
+}
+
+/// An error encountered while parsing a run-length encoded pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RleError {
+    /// The `x = .., y = ..` header line is missing.
+    MissingHeader,
+    /// The header line could not be understood.
+    InvalidHeader(String),
+    /// An unexpected character was found in the pattern.
+    InvalidTag(char),
+    /// The cell type does not support a state used in the pattern.
+    UnsupportedState(u8),
+    /// The pattern contains cells outside of the dimensions in the header.
+    OutOfBounds,
+    /// The dimensions in the header have more than [`MAX_RLE_CELLS`] cells.
+    TooLarge,
+    /// The pattern has no cells, so a grid can't be made from it.
+    Empty,
+}
+
+/// The most cells a pattern read by [`parse_rle`] may have, so that a header with huge
+/// dimensions can't exhaust the memory.
+pub const MAX_RLE_CELLS: usize = 1 << 30;
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "missing RLE header"),
+            RleError::InvalidHeader(header) => write!(f, "invalid RLE header: {:?}", header),
+            RleError::InvalidTag(c) => write!(f, "invalid character in RLE pattern: {:?}", c),
+            RleError::UnsupportedState(state) => {
+                write!(f, "cell type does not support state {}", state)
+            }
+            RleError::OutOfBounds => write!(f, "RLE pattern exceeds the dimensions in its header"),
+            RleError::TooLarge => write!(f, "RLE pattern has too many cells"),
+            RleError::Empty => write!(f, "RLE pattern has no cells"),
+        }
+    }
+}
+
+impl Error for RleError {}
+
+/// A pattern read from the run-length encoded format used by Golly and the Life community.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RlePattern<C> {
+    /// The rule from the header, if present.
+    pub rule: Option<String>,
+    /// The cells of the pattern, indexed by `(y, x)`.
+    pub cells: Array2<C>,
+}
+
+/// Parse a run-length encoded pattern, including multi-state extended RLE.
+///
+/// Patterns with more than [`MAX_RLE_CELLS`] cells are rejected with [`RleError::TooLarge`].
+pub fn parse_rle<C>(text: &str) -> Result<RlePattern<C>, RleError>
+where
+    C: RleCell,
+{
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    let header = lines.next().ok_or(RleError::MissingHeader)?;
+    let (width, height, rule) = parse_header(header)?;
+    match width.checked_mul(height) {
+        Some(len) if len <= MAX_RLE_CELLS => {}
+        _ => return Err(RleError::TooLarge),
+    }
+
+    let mut states = Array2::<u8>::zeros((height, width));
+    let (mut y, mut x): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+    let mut prefix: Option<u8> = None;
+    for c in lines.flat_map(str::chars) {
+        if prefix.is_some() && !c.is_ascii_uppercase() {
+            return Err(RleError::InvalidTag(c));
+        }
+        let state = match c {
+            '0'..='9' => {
+                // No run can be longer than the pattern is wide or tall.
+                count = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add(c.to_digit(10).unwrap() as usize));
+                if count.is_none() {
+                    return Err(RleError::OutOfBounds);
+                }
+                continue;
+            }
+            '!' => break,
+            '$' => {
+                y = y
+                    .checked_add(count.take().unwrap_or(1))
+                    .ok_or(RleError::OutOfBounds)?;
+                x = 0;
+                continue;
+            }
+            'b' | '.' => 0,
+            'o' => 1,
+            'p'..='y' => {
+                prefix = Some(c as u8 - b'p' + 1);
+                continue;
+            }
+            'A'..='X' => {
+                let state =
+                    prefix.take().unwrap_or(0) as usize * 24 + (c as u8 - b'A') as usize + 1;
+                if state > 255 {
+                    return Err(RleError::InvalidTag(c));
+                }
+                state as u8
+            }
+            c if c.is_whitespace() => continue,
+            c => return Err(RleError::InvalidTag(c)),
+        };
+        let run = count.take().unwrap_or(1);
+        let end = x.checked_add(run).ok_or(RleError::OutOfBounds)?;
+        if state != 0 {
+            if y >= height || end > width {
+                return Err(RleError::OutOfBounds);
+            }
+            states.slice_mut(s![y, x..end]).fill(state);
+        }
+        x = end;
+    }
+
+    let cells = states
+        .iter()
+        .map(|&state| C::from_state(state).ok_or(RleError::UnsupportedState(state)))
+        .collect::<Result<Vec<C>, RleError>>()?;
+    let cells = Array2::from_shape_vec((height, width), cells).unwrap();
+    Ok(RlePattern { rule, cells })
+}
+
+fn parse_header(header: &str) -> Result<(usize, usize, Option<String>), RleError> {
+    let invalid = || RleError::InvalidHeader(header.to_owned());
+    let (mut width, mut height, mut rule) = (None, None, None);
+    for field in header.split(',') {
+        let mut parts = field.splitn(2, '=');
+        let key = parts.next().ok_or_else(invalid)?.trim();
+        let value = parts.next().ok_or_else(invalid)?.trim();
+        match key {
+            "x" => width = Some(value.parse().map_err(|_| invalid())?),
+            "y" => height = Some(value.parse().map_err(|_| invalid())?),
+            "rule" => rule = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+    Ok((
+        width.ok_or_else(invalid)?,
+        height.ok_or_else(invalid)?,
+        rule,
+    ))
+}
+
+/// Write cells as a run-length encoded pattern.
+///
+/// Extended RLE tags are only used if a cell has a state other than `0` or `1`.
+pub fn write_rle<C>(cells: ArrayView2<'_, C>, rule: Option<&str>) -> String
+where
+    C: RleCell,
+{
+    let (height, width) = cells.dim();
+    let states = cells.map(RleCell::to_state);
+    let extended = states.iter().any(|&state| state > 1);
+    let tag = |state: u8| match (extended, state) {
+        (false, 0) => "b".to_owned(),
+        (false, _) => "o".to_owned(),
+        (true, 0) => ".".to_owned(),
+        (true, s) if s <= 24 => ((b'A' + s - 1) as char).to_string(),
+        (true, s) => {
+            let (prefix, letter) = ((s - 1) / 24, (s - 1) % 24);
+            format!("{}{}", (b'p' + prefix - 1) as char, (b'A' + letter) as char)
+        }
+    };
+
+    // Collect the runs, merging the ends of rows so that blank rows are skipped.
+    let mut runs: Vec<(usize, String)> = vec![];
+    let mut pending_rows = 0;
+    for row in states.genrows() {
+        let end = row
+            .iter()
+            .rposition(|&state| state != 0)
+            .map_or(0, |i| i + 1);
+        if end > 0 && pending_rows > 0 {
+            runs.push((pending_rows, "$".to_owned()));
+            pending_rows = 0;
+        }
+        let mut x = 0;
+        while x < end {
+            let run = row
+                .iter()
+                .skip(x)
+                .take_while(|&&state| state == row[x])
+                .count();
+            runs.push((run.min(end - x), tag(row[x])));
+            x += run;
+        }
+        pending_rows += 1;
+    }
+    runs.push((1, "!".to_owned()));
+
+    let mut text = format!("x = {}, y = {}", width, height);
+    if let Some(rule) = rule {
+        text += &format!(", rule = {}", rule);
+    }
+    text.push('\n');
+    let mut line = String::new();
+    for (run, tag) in runs {
+        let token = if run == 1 {
+            tag
+        } else {
+            format!("{}{}", run, tag)
+        };
+        if line.len() + token.len() > 70 {
+            text += &line;
+            text.push('\n');
+            line.clear();
+        }
+        line += &token;
+    }
+    text += &line;
+    text.push('\n');
+    text
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + RleCell,
+{
+    /// Make a new grid from a run-length encoded pattern. The rule in the header is ignored.
+    ///
+    /// Returns [`RleError::Empty`] if the header gives the pattern no cells.
+    pub fn from_rle(sim: S, text: &str) -> Result<Self, RleError> {
+        // An empty grid is the only reason a grid can't be made.
+        Self::try_new(sim, parse_rle(text)?.cells).map_err(|_| RleError::Empty)
+    }
+
+    /// Write the cells of the grid as a run-length encoded pattern.
+    pub fn to_rle(&self) -> String {
+        write_rle(self.cells(), None)
+    }
+}
@@ -0,0 +1,233 @@
+//! Ready-made rules for well-known cellular automata.
+
+use crate::{checkpoint::state_cells, LifeLikeRule, Moore, Random, RandomNeighbors, RleCell, Sim};
+use ndarray::ArrayView2;
+
+/// Conway's Game of Life (`B3/S23`).
+pub fn life() -> LifeLikeRule {
+    LifeLikeRule::new(&[3], &[2, 3])
+}
+
+/// HighLife (`B36/S23`), which is like Life but has a small replicator.
+pub fn high_life() -> LifeLikeRule {
+    LifeLikeRule::new(&[3, 6], &[2, 3])
+}
+
+/// Seeds (`B2/S`), where every live cell dies each generation.
+pub fn seeds() -> LifeLikeRule {
+    LifeLikeRule::new(&[2], &[])
+}
+
+/// A cell of [`BriansBrain`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BrainCell {
+    Off,
+    On,
+    Dying,
+}
+
+impl RleCell for BrainCell {
+    fn to_state(&self) -> u8 {
+        *self as u8
+    }
+
+    fn from_state(state: u8) -> Option<Self> {
+        [BrainCell::Off, BrainCell::On, BrainCell::Dying]
+            .get(state as usize)
+            .copied()
+    }
+}
+
+state_cells!(BrainCell);
+
+/// Brian's Brain, where cells turn on with exactly two neighbors that are on and then
+/// spend a generation dying before turning off.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BriansBrain;
+
+impl Sim<Moore> for BriansBrain {
+    type Cell = BrainCell;
+    type Diff = BrainCell;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, BrainCell>) -> BrainCell {
+        match cells[(1, 1)] {
+            BrainCell::Off => {
+                if cells.iter().filter(|&&c| c == BrainCell::On).count() == 2 {
+                    BrainCell::On
+                } else {
+                    BrainCell::Off
+                }
+            }
+            BrainCell::On => BrainCell::Dying,
+            BrainCell::Dying => BrainCell::Off,
+        }
+    }
+
+    fn egress(&self, cell: &mut BrainCell, diffs: ArrayView2<'_, BrainCell>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut BrainCell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> BrainCell {
+        BrainCell::Off
+    }
+
+    fn diff_padding(&self) -> BrainCell {
+        BrainCell::Off
+    }
+
+    fn flow_padding(&self) {}
+}
+
+/// A cell of [`Wireworld`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WireworldCell {
+    Empty,
+    Head,
+    Tail,
+    Conductor,
+}
+
+impl RleCell for WireworldCell {
+    fn to_state(&self) -> u8 {
+        *self as u8
+    }
+
+    fn from_state(state: u8) -> Option<Self> {
+        [
+            WireworldCell::Empty,
+            WireworldCell::Head,
+            WireworldCell::Tail,
+            WireworldCell::Conductor,
+        ]
+        .get(state as usize)
+        .copied()
+    }
+}
+
+state_cells!(WireworldCell);
+
+/// Wireworld, where electrons made of a head and a tail travel along conductors.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wireworld;
+
+impl Sim<Moore> for Wireworld {
+    type Cell = WireworldCell;
+    type Diff = WireworldCell;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, WireworldCell>) -> WireworldCell {
+        match cells[(1, 1)] {
+            WireworldCell::Empty => WireworldCell::Empty,
+            WireworldCell::Head => WireworldCell::Tail,
+            WireworldCell::Tail => WireworldCell::Conductor,
+            WireworldCell::Conductor => {
+                let heads = cells.iter().filter(|&&c| c == WireworldCell::Head).count();
+                if heads == 1 || heads == 2 {
+                    WireworldCell::Head
+                } else {
+                    WireworldCell::Conductor
+                }
+            }
+        }
+    }
+
+    fn egress(&self, cell: &mut WireworldCell, diffs: ArrayView2<'_, WireworldCell>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut WireworldCell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> WireworldCell {
+        WireworldCell::Empty
+    }
+
+    fn diff_padding(&self) -> WireworldCell {
+        WireworldCell::Empty
+    }
+
+    fn flow_padding(&self) {}
+}
+
+/// A cell of [`ForestFire`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForestCell {
+    Empty,
+    Tree,
+    Fire,
+}
+
+impl RleCell for ForestCell {
+    fn to_state(&self) -> u8 {
+        *self as u8
+    }
+
+    fn from_state(state: u8) -> Option<Self> {
+        [ForestCell::Empty, ForestCell::Tree, ForestCell::Fire]
+            .get(state as usize)
+            .copied()
+    }
+}
+
+state_cells!(ForestCell);
+
+/// The Drossel-Schwabl forest-fire model, where trees grow at random, are struck by
+/// lightning at random, and catch fire from burning neighbors.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForestFire {
+    /// The probability that a tree grows in an empty cell.
+    pub growth: f64,
+    /// The probability that a tree with no burning neighbors catches fire.
+    pub lightning: f64,
+}
+
+impl Sim<Random> for ForestFire {
+    type Cell = ForestCell;
+    type Diff = ForestCell;
+    type Flow = ();
+
+    fn compute(&self, mut cells: RandomNeighbors<'_, ForestCell>) -> ForestCell {
+        let center = cells[(1, 1)];
+        match center {
+            ForestCell::Empty if cells.rng().gen_bool(self.growth) => ForestCell::Tree,
+            ForestCell::Empty => ForestCell::Empty,
+            ForestCell::Tree => {
+                if cells.iter().any(|&c| c == ForestCell::Fire)
+                    || cells.rng().gen_bool(self.lightning)
+                {
+                    ForestCell::Fire
+                } else {
+                    ForestCell::Tree
+                }
+            }
+            ForestCell::Fire => ForestCell::Empty,
+        }
+    }
+
+    fn egress(&self, cell: &mut ForestCell, diffs: RandomNeighbors<'_, ForestCell>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut ForestCell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> ForestCell {
+        ForestCell::Empty
+    }
+
+    fn diff_padding(&self) -> ForestCell {
+        ForestCell::Empty
+    }
+
+    fn flow_padding(&self) {}
+}
@@ -0,0 +1,124 @@
+use crate::{project::project, Moore, Sim, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+use std::mem;
+
+/// A rule where the next state of a cell depends on the 3x3 window of the current generation
+/// and on the state of the cell in the previous generation.
+///
+/// Rules of the form `next = f(current) - previous`, where `-` is subtraction in any group such
+/// as exclusive or, are reversible in the way of Fredkin: running the generations backwards is
+/// the same rule with the current and previous generations swapped. Grids run them with
+/// [`SecondOrder`].
+pub trait SecondOrderRule {
+    type Cell: Clone;
+
+    /// Get the next state of the cell in the center of the window of the current generation.
+    fn next(&self, previous: &Self::Cell, current: ArrayView2<'_, Self::Cell>) -> Self::Cell;
+
+    /// The cell used as padding in both generations.
+    fn cell_padding(&self) -> Self::Cell;
+}
+
+/// A cell of a [`SecondOrder`] sim, which is the state of a cell in two generations.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecondOrderCell<C> {
+    pub previous: C,
+    pub current: C,
+}
+
+impl<C> SecondOrderCell<C> {
+    /// Swap the generations, which reverses time for reversible rules.
+    pub fn reverse(&mut self) {
+        mem::swap(&mut self.previous, &mut self.current);
+    }
+}
+
+/// A sim which runs a [`SecondOrderRule`] by keeping the previous generation in every cell.
+///
+/// The rule is given a view of the current generation in place, since every cell holds two
+/// states of the same type, so no cells are cloned to compute a step.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecondOrder<R>(pub R);
+
+impl<R> Sim<Moore> for SecondOrder<R>
+where
+    R: SecondOrderRule,
+    R::Cell: 'static,
+{
+    type Cell = SecondOrderCell<R::Cell>;
+    type Diff = R::Cell;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, SecondOrderCell<R::Cell>>) -> R::Cell {
+        // The current generation is a plain field of every cell.
+        if let Some(window) = unsafe { project(cells, |cell| &cell.current) } {
+            return self.0.next(&cells[(1, 1)].previous, window);
+        }
+        let mut window = cells.iter().map(|cell| cell.current.clone());
+        let window = [(); 9].map(|_| window.next().unwrap());
+        let window = ArrayView2::from_shape((3, 3), &window[..]).unwrap();
+        self.0.next(&cells[(1, 1)].previous, window)
+    }
+
+    fn egress(
+        &self,
+        cell: &mut SecondOrderCell<R::Cell>,
+        diffs: ArrayView2<'_, R::Cell>,
+    ) -> [(); 8] {
+        cell.previous = mem::replace(&mut cell.current, diffs[(1, 1)].clone());
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut SecondOrderCell<R::Cell>, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> SecondOrderCell<R::Cell> {
+        SecondOrderCell {
+            previous: self.0.cell_padding(),
+            current: self.0.cell_padding(),
+        }
+    }
+
+    fn diff_padding(&self) -> R::Cell {
+        self.0.cell_padding()
+    }
+
+    fn flow_padding(&self) {}
+}
+
+impl<R> SquareGrid<SecondOrder<R>>
+where
+    R: SecondOrderRule,
+    R::Cell: Send + 'static,
+{
+    /// Make a new grid from the cells of the previous and current generations.
+    pub fn second_order(rule: R, previous: Array2<R::Cell>, current: Array2<R::Cell>) -> Self {
+        assert_eq!(
+            previous.dim(),
+            current.dim(),
+            "generations have different dimensions"
+        );
+        let cells = Array2::from_shape_fn(current.dim(), |index| SecondOrderCell {
+            previous: previous[index].clone(),
+            current: current[index].clone(),
+        });
+        Self::new(SecondOrder(rule), cells)
+    }
+
+    /// Get the cells of the current generation.
+    pub fn current(&self) -> Array2<R::Cell> {
+        self.cells().map(|cell| cell.current.clone())
+    }
+
+    /// Swap the current and previous generations of every cell, so stepping a reversible rule
+    /// goes back in time.
+    ///
+    /// Stepping `n` times, reversing, stepping `n` times again, and reversing again gives back
+    /// the cells which were started with. The generation of the grid keeps counting up.
+    pub fn reverse(&mut self) {
+        maybe_par_azip!((cell in &mut self.cells_mut()) {
+            cell.reverse();
+        });
+    }
+}
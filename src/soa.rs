@@ -0,0 +1,161 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, Array3, ArrayView2, ArrayViewMut2, Axis};
+
+/// A cell made of numeric fields of one type, which can be stored with one array per field in
+/// a [`SoaArray`].
+///
+/// With the `derive` feature, this can be derived for structs whose fields all have the same
+/// type, which are numbered in the order they are declared.
+pub trait SoaCell: Sized {
+    /// The type of every field.
+    type Scalar: Copy;
+
+    /// The number of fields.
+    const FIELDS: usize;
+
+    /// Get a field of the cell, numbered from `0`.
+    fn field(&self, field: usize) -> Self::Scalar;
+
+    /// Make a cell from the value of each field.
+    fn from_fields(field: impl FnMut(usize) -> Self::Scalar) -> Self;
+}
+
+impl SoaCell for f32 {
+    type Scalar = f32;
+    const FIELDS: usize = 1;
+
+    fn field(&self, _: usize) -> f32 {
+        *self
+    }
+
+    fn from_fields(mut field: impl FnMut(usize) -> f32) -> Self {
+        field(0)
+    }
+}
+
+impl SoaCell for f64 {
+    type Scalar = f64;
+    const FIELDS: usize = 1;
+
+    fn field(&self, _: usize) -> f64 {
+        *self
+    }
+
+    fn from_fields(mut field: impl FnMut(usize) -> f64) -> Self {
+        field(0)
+    }
+}
+
+impl<T: Copy, const N: usize> SoaCell for [T; N] {
+    type Scalar = T;
+    const FIELDS: usize = N;
+
+    fn field(&self, field: usize) -> T {
+        self[field]
+    }
+
+    fn from_fields(mut field: impl FnMut(usize) -> T) -> Self {
+        let mut next = 0..N;
+        [(); N].map(|_| field(next.next().unwrap()))
+    }
+}
+
+/// A 2D array of cells stored as one contiguous array for each field of the cells, so each
+/// field can be read and written on its own with good cache behavior and vectorized loops.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoaArray<C: SoaCell> {
+    /// The fields, indexed by `(field, y, x)`.
+    fields: Array3<C::Scalar>,
+}
+
+impl<C: SoaCell> SoaArray<C> {
+    /// Make an array with the `(height, width)` given, where every cell is `cell`.
+    pub fn from_elem((h, w): (usize, usize), cell: C) -> Self {
+        Self {
+            fields: Array3::from_shape_fn((C::FIELDS, h, w), |(field, _, _)| cell.field(field)),
+        }
+    }
+
+    /// Split the fields of each cell into their own arrays.
+    pub fn from_cells(cells: ArrayView2<'_, C>) -> Self {
+        let (h, w) = cells.dim();
+        Self {
+            fields: Array3::from_shape_fn((C::FIELDS, h, w), |(field, y, x)| {
+                cells[(y, x)].field(field)
+            }),
+        }
+    }
+
+    /// Put the fields back together into cells.
+    pub fn to_cells(&self) -> Array2<C> {
+        Array2::from_shape_fn(self.dim(), |index| self.get(index))
+    }
+
+    /// Get the `(height, width)` of the array.
+    pub fn dim(&self) -> (usize, usize) {
+        let (_, h, w) = self.fields.dim();
+        (h, w)
+    }
+
+    /// Get the cell at a `(y, x)` index.
+    ///
+    /// Panics if the index is outside of the array.
+    pub fn get(&self, (y, x): (usize, usize)) -> C {
+        C::from_fields(|field| self.fields[(field, y, x)])
+    }
+
+    /// Set the cell at a `(y, x)` index.
+    ///
+    /// Panics if the index is outside of the array.
+    pub fn set(&mut self, (y, x): (usize, usize), cell: C) {
+        for field in 0..C::FIELDS {
+            self.fields[(field, y, x)] = cell.field(field);
+        }
+    }
+
+    /// Get a view of one field of every cell.
+    ///
+    /// Panics if there is no such field.
+    pub fn field(&self, field: usize) -> ArrayView2<'_, C::Scalar> {
+        self.fields.index_axis(Axis(0), field)
+    }
+
+    /// Get a mutable view of one field of every cell.
+    ///
+    /// Panics if there is no such field.
+    pub fn field_mut(&mut self, field: usize) -> ArrayViewMut2<'_, C::Scalar> {
+        self.fields.index_axis_mut(Axis(0), field)
+    }
+
+    /// Get mutable views of every field at once, in order.
+    pub fn fields_mut(&mut self) -> Vec<ArrayViewMut2<'_, C::Scalar>> {
+        self.fields.outer_iter_mut().collect()
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + SoaCell,
+{
+    /// Copy the cells into a [`SoaArray`] with one array for each of their fields.
+    pub fn to_soa(&self) -> SoaArray<S::Cell> {
+        SoaArray::from_cells(self.cells())
+    }
+
+    /// Set every cell from a [`SoaArray`], such as one made with [`SquareGrid::to_soa`]
+    /// whose fields have been updated.
+    ///
+    /// Panics if the array has different dimensions from the grid.
+    pub fn load_soa(&mut self, soa: &SoaArray<S::Cell>) {
+        assert_eq!(
+            soa.dim(),
+            self.cells().dim(),
+            "array has different dimensions from the grid"
+        );
+        for ((y, x), cell) in self.cells_mut().indexed_iter_mut() {
+            *cell = soa.get((y, x));
+        }
+    }
+}
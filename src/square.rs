@@ -0,0 +1,233 @@
+use crate::{CellRng, Neighborhood};
+use ndarray::{Array2, ArrayView2, IndexLonger};
+use smallvec::SmallVec;
+use std::ops::{Index, Range};
+
+/// The part of a neighborhood window which is on the grid, as ranges of window indices.
+///
+/// Cells outside of these ranges are padding beyond an edge of the grid which doesn't wrap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowBounds {
+    pub y: Range<usize>,
+    pub x: Range<usize>,
+}
+
+impl WindowBounds {
+    /// Check if a window index is on the grid.
+    pub fn contains(&self, (y, x): (usize, usize)) -> bool {
+        self.y.contains(&y) && self.x.contains(&x)
+    }
+}
+
+/// Where a neighborhood window is on the grid and when.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The `(y, x)` index of the center cell on the grid.
+    pub index: (usize, usize),
+    /// The part of the window which is on the grid.
+    pub bounds: WindowBounds,
+    /// The number of steps the grid has taken.
+    pub generation: u64,
+    /// The seed of the grid.
+    pub seed: u64,
+}
+
+impl Location {
+    /// Make a random number generator which only depends on the seed, generation, and index.
+    pub fn rng(&self) -> CellRng {
+        CellRng::new(self.seed, self.generation, self.index)
+    }
+}
+
+/// A neighborhood that can be simulated on a [`SquareGrid`](crate::SquareGrid).
+///
+/// On egress, each edge holds the flow sent towards the cell at its offset.
+/// On ingress, each edge holds the flow received from the cell at its offset.
+pub trait SquareNeighborhood: Neighborhood {
+    /// How far the neighborhood reaches from the center on each axis.
+    const RADIUS: usize;
+
+    /// The number of edges.
+    const EDGES: usize;
+
+    /// The `(y, x)` offset of an edge.
+    fn delta(edge: usize) -> (isize, isize);
+
+    /// Get the edge with a `(y, x)` offset, if there is one.
+    ///
+    /// By default, this searches the offsets of every edge.
+    fn edge(delta: (isize, isize)) -> Option<usize> {
+        (0..Self::EDGES).find(|&edge| Self::delta(edge) == delta)
+    }
+
+    /// The edge of the neighbor that points back at this cell.
+    ///
+    /// By default, this is the edge with the negated offset, so neighborhoods only need to
+    /// override it to avoid the search. Every offset must have its negation as an edge.
+    fn opposite(edge: usize) -> usize {
+        let (dy, dx) = Self::delta(edge);
+        Self::edge((-dy, -dx)).expect("every edge must have an opposite edge")
+    }
+
+    /// Make the neighbors from a square window of width `2 * RADIUS + 1` centered on the cell.
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> Self::Neighbors<'_, T>;
+
+    /// Make the neighbors from a window at a location on the grid.
+    ///
+    /// By default, the location is ignored.
+    fn located_neighbors<T>(
+        window: ArrayView2<'_, T>,
+        location: Location,
+    ) -> Self::Neighbors<'_, T> {
+        let _ = location;
+        Self::neighbors(window)
+    }
+
+    /// Make edges by calling `f` once for each edge in order.
+    fn edges<T>(f: impl FnMut() -> T) -> Self::Edges<T>;
+
+    /// Get the edges as a slice indexed by edge.
+    fn edges_mut<T>(edges: &mut Self::Edges<T>) -> &mut [T];
+}
+
+/// Make a window of width `2 * N::RADIUS + 1` from the cell at each `(y, x)` offset from its
+/// center, which can be made into neighbors with [`SquareNeighborhood::neighbors`].
+///
+/// This allows neighborhoods to be looked at from cells which aren't on a grid, such as in
+/// analysis code or tests.
+pub fn window_from<N, C>(mut cell: impl FnMut((isize, isize)) -> C) -> Array2<C>
+where
+    N: SquareNeighborhood,
+{
+    let r = N::RADIUS as isize;
+    let width = 2 * N::RADIUS + 1;
+    Array2::from_shape_fn((width, width), |(y, x)| {
+        cell((y as isize - r, x as isize - r))
+    })
+}
+
+/// Every cell within `R` cells on both axes.
+///
+/// Edges are ordered by their `(y, x)` offset, skipping the center, so the opposite of
+/// edge `i` is edge `EDGES - 1 - i`.
+pub enum Radius<const R: usize> {}
+
+impl<const R: usize> Neighborhood for Radius<R> {
+    type Neighbors<'a, T: 'a> = RadiusNeighbors<'a, T, R>;
+    type Edges<T> = RadiusEdges<T>;
+}
+
+/// The edges of a [`Radius`] neighborhood, in the same order as [`SquareNeighborhood::delta`].
+///
+/// The edges of radii up to 2 are kept inline, so steps don't allocate them for every cell.
+/// Make them with [`SquareNeighborhood::edges`].
+pub type RadiusEdges<T> = SmallVec<[T; 24]>;
+
+impl<const R: usize> SquareNeighborhood for Radius<R> {
+    const RADIUS: usize = R;
+    const EDGES: usize = (2 * R + 1) * (2 * R + 1) - 1;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        let width = 2 * R + 1;
+        let index = if edge < Self::EDGES / 2 {
+            edge
+        } else {
+            edge + 1
+        };
+        (
+            (index / width) as isize - R as isize,
+            (index % width) as isize - R as isize,
+        )
+    }
+
+    fn opposite(edge: usize) -> usize {
+        Self::EDGES - 1 - edge
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> RadiusNeighbors<'_, T, R> {
+        RadiusNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> RadiusEdges<T> {
+        (0..Self::EDGES).map(|_| f()).collect()
+    }
+
+    fn edges_mut<T>(edges: &mut RadiusEdges<T>) -> &mut [T] {
+        edges
+    }
+}
+
+/// The cells within `R` cells of a square on both axes, along with the square itself.
+#[derive(Debug)]
+pub struct RadiusNeighbors<'a, T, const R: usize> {
+    window: ArrayView2<'a, T>,
+}
+
+impl<'a, T, const R: usize> Clone for RadiusNeighbors<'a, T, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, const R: usize> Copy for RadiusNeighbors<'a, T, R> {}
+
+impl<'a, T, const R: usize> RadiusNeighbors<'a, T, R> {
+    /// Wraps a square window of width `2 * R + 1` centered on the square.
+    pub fn new(window: ArrayView2<'a, T>) -> Self {
+        let width = 2 * R + 1;
+        assert_eq!(
+            window.dim(),
+            (width, width),
+            "radius neighbors need a square window of width 2 * R + 1"
+        );
+        Self { window }
+    }
+
+    /// The square in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        IndexLonger::index(&self.window, (R, R))
+    }
+
+    /// The neighbor at a `(y, x)` offset from the center.
+    pub fn get(&self, (y, x): (isize, isize)) -> &'a T {
+        let r = R as isize;
+        assert!(
+            y.abs() <= r && x.abs() <= r,
+            "offset is outside of the neighborhood"
+        );
+        IndexLonger::index(&self.window, ((r + y) as usize, (r + x) as usize))
+    }
+
+    /// The neighbor at an edge.
+    pub fn edge(&self, edge: usize) -> &'a T {
+        self.get(Radius::<R>::delta(edge))
+    }
+
+    /// Iterate over the neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = *self;
+        (0..Radius::<R>::EDGES).map(move |edge| this.edge(edge))
+    }
+
+    /// Iterate over the `(y, x)` offsets and neighbors in edge order.
+    pub fn iter_deltas(&self) -> impl Iterator<Item = ((isize, isize), &'a T)> + 'a {
+        let this = *self;
+        (0..Radius::<R>::EDGES).map(move |edge| {
+            let delta = Radius::<R>::delta(edge);
+            (delta, this.get(delta))
+        })
+    }
+
+    /// The square window indexed by `(y, x)`.
+    pub fn window(&self) -> ArrayView2<'a, T> {
+        self.window
+    }
+}
+
+impl<'a, T, const R: usize> Index<(isize, isize)> for RadiusNeighbors<'a, T, R> {
+    type Output = T;
+
+    fn index(&self, delta: (isize, isize)) -> &T {
+        self.get(delta)
+    }
+}
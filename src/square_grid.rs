@@ -1,142 +1,1332 @@
-#![allow(clippy::reversed_empty_ranges)]
-
-use crate::{Neumann, Sim};
+use crate::{
+    count_rule::CountBuffers,
+    engine::{self, FlowSlot, Link},
+    history::History,
+    hooks::Hooks,
+    partial::PartialStep,
+    population::{Population, Tally},
+    region::Focus,
+    stats::{self, Rect},
+    window_from, Boundary, GridError, Location, Moore, Neighborhood, Side, Sim, SquareNeighborhood,
+    StepTimings, UpdateOrder, WindowBounds,
+};
 use itertools::Itertools;
-use ndarray::{par_azip, s, Array2, ArrayView2, ArrayViewMut2};
+use ndarray::{s, Array2, ArrayView2, ArrayViewMut2, Axis};
 use std::{
     cell::UnsafeCell,
     mem::{self, ManuallyDrop},
+    ops::{Index, IndexMut, Range},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
+/// Flows of each cell, which must be manually taken or dropped.
+type FlowBuffer<N, F> = Array2<FlowSlot<<N as Neighborhood>::Edges<F>>>;
+
 /// Represents the state of the simulation.
-#[derive(Clone, Debug)]
-pub struct SquareGrid<S>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Cell: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Cell: serde::Deserialize<'de> + Clone, \
+                       S::Diff: Clone"
+    ))
+)]
+pub struct SquareGrid<S, N = Moore>
 where
-    S: Sim<Neumann>,
+    S: Sim<N>,
+    N: SquareNeighborhood,
 {
-    sim: S,
-    cells: Array2<S::Cell>,
+    pub(crate) sim: S,
+    pub(crate) cells: Array2<S::Cell>,
+    /// The diffs of the last step, which are kept so their allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) diffs: Option<Array2<S::Diff>>,
+    /// The cells which changed in the last step, if it was taken with
+    /// [`SquareGrid::step_active`] and the grid hasn't been modified since.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) changed: Option<Vec<(usize, usize)>>,
+    pub(crate) boundary: (Boundary<S::Cell>, Boundary<S::Cell>),
+    pub(crate) generation: u64,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tile_size: Option<(usize, usize)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) update_order: UpdateOrder,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) hooks: Hooks<S, N>,
+    /// The changes of the last steps, if they are being kept.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) history: Option<History<S::Cell>>,
+    /// The number of cells in each class, if they are being tracked.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) population: Option<Population<S::Cell>>,
+    /// The step in progress, if it was started with [`SquareGrid::step_rows`] or
+    /// [`SquareGrid::step_partial`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) partial: Option<PartialStep<S::Cell, S::Diff>>,
+    /// The copy of the cells read during the last pipelined step, which is kept so its
+    /// allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) previous: Option<Array2<S::Cell>>,
+    /// The bytes read during the last step taken with [`SquareGrid::step_simd`], which are kept
+    /// so their allocations can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) counts: Option<CountBuffers>,
+    /// How long the stages of the last step took, if steps are being timed.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) timings: Option<StepTimings>,
+    /// The grid around the region being stepped, if only a region is being stepped.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) focus: Option<Focus<S::Cell>>,
+    /// Which cells are simulated, including the padding, which is `false`, if only some are.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) domain: Option<Array2<bool>>,
+    /// How to copy cells and diffs for the boundaries, hooks, and history.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Copier::cloned"))]
+    pub(crate) copier: Copier<S::Cell, S::Diff>,
+}
+
+/// Copies the cells and diffs of a grid, which is only possible once the grid has been used in
+/// a way which requires them to be [`Clone`], so that grids of cells which aren't can still be
+/// stepped.
+///
+/// Grids which can't copy cells or diffs have constant boundaries, whose padding is set when
+/// the grid is made and never changes.
+pub(crate) struct Copier<C, D> {
+    pub(crate) cell: Option<fn(&C) -> C>,
+    pub(crate) diff: Option<fn(&D) -> D>,
 }
 
-impl<S> SquareGrid<S>
+impl<C, D> Copier<C, D> {
+    fn none() -> Self {
+        Self {
+            cell: None,
+            diff: None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn cloned() -> Self
+    where
+        C: Clone,
+        D: Clone,
+    {
+        Self {
+            cell: Some(C::clone),
+            diff: Some(D::clone),
+        }
+    }
+}
+
+/// The function pointers are `Copy` no matter what the cells and diffs are.
+impl<C, D> Clone for Copier<C, D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C, D> Copy for Copier<C, D> {}
+
+/// This is written out so the neighborhood, which is usually an empty enum, doesn't need to be
+/// `Clone`.
+impl<S, N> Clone for SquareGrid<S, N>
 where
-    S: Sim<Neumann>,
+    S: Sim<N> + Clone,
+    N: SquareNeighborhood,
+    S::Cell: Clone,
+    S::Diff: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sim: self.sim.clone(),
+            cells: self.cells.clone(),
+            diffs: self.diffs.clone(),
+            changed: self.changed.clone(),
+            boundary: self.boundary.clone(),
+            generation: self.generation,
+            seed: self.seed,
+            tile_size: self.tile_size,
+            update_order: self.update_order,
+            hooks: self.hooks.clone(),
+            history: self.history.clone(),
+            population: self.population.clone(),
+            partial: self.partial.clone(),
+            previous: self.previous.clone(),
+            counts: self.counts.clone(),
+            timings: self.timings,
+            focus: None,
+            domain: self.domain.clone(),
+            copier: self.copier,
+        }
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
     S::Cell: Send,
 {
-    /// Make a new grid with the given cells.
-    pub fn new(sim: S, mut original_cells: Array2<S::Cell>) -> Self {
+    /// Make a new grid with the given cells, surrounded by the padding cell of the sim.
+    ///
+    /// Panics if there are no cells. See [`SquareGrid::try_new`] to get an error instead.
+    pub fn new(sim: S, cells: Array2<S::Cell>) -> Self {
+        Self::try_new(sim, cells).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Make a new grid with the given cells, or get [`GridError::Empty`] if there are none.
+    pub fn try_new(sim: S, mut original_cells: Array2<S::Cell>) -> Result<Self, GridError> {
         let dims = original_cells.dim();
-        assert!(
-            dims.0 >= 1 && dims.1 >= 1,
-            "grid is empty, which isnt allowed"
-        );
+        if dims.0 == 0 || dims.1 == 0 {
+            return Err(GridError::Empty);
+        }
+        assert!(N::RADIUS >= 1, "neighborhood radius must be at least 1");
+        let r = N::RADIUS;
         let mut cells =
-            Array2::from_shape_simple_fn((dims.0 + 2, dims.1 + 2), || sim.cell_padding());
-        par_azip!((dest in &mut cells.slice_mut(s![1..-1, 1..-1]), cell in &mut original_cells) {
+            Array2::from_shape_simple_fn((dims.0 + 2 * r, dims.1 + 2 * r), || sim.cell_padding());
+        maybe_par_azip!((dest in &mut cells.slice_mut(&Self::inside()), cell in &mut original_cells) {
             mem::swap(dest, cell);
         });
-        Self { sim, cells }
+        Ok(Self::from_padded(sim, cells))
+    }
+
+    /// Make a new grid from cells which are already surrounded by padding.
+    pub(crate) fn from_padded(sim: S, cells: Array2<S::Cell>) -> Self {
+        let boundary = (
+            Boundary::Constant(sim.cell_padding()),
+            Boundary::Constant(sim.cell_padding()),
+        );
+        Self {
+            sim,
+            cells,
+            boundary,
+            diffs: None,
+            changed: None,
+            generation: 0,
+            seed: 0,
+            tile_size: None,
+            update_order: UpdateOrder::Synchronous,
+            hooks: Hooks::default(),
+            history: None,
+            population: None,
+            partial: None,
+            previous: None,
+            counts: None,
+            timings: None,
+            focus: None,
+            domain: None,
+            copier: Copier::none(),
+        }
+    }
+
+    /// Make a new grid with the given cells and the same boundary on every edge.
+    ///
+    /// Panics if the boundary is [`Boundary::Shift`] or [`Boundary::Twist`], which can't be used
+    /// on both axes.
+    pub fn with_boundary(sim: S, cells: Array2<S::Cell>, boundary: Boundary<S::Cell>) -> Self
+    where
+        S::Cell: Clone,
+        S::Diff: Clone,
+    {
+        Self::with_boundaries(sim, cells, boundary.clone(), boundary)
+    }
+
+    /// Make a new grid with the given cells and a boundary for each axis.
+    ///
+    /// For example, wrapping only `x` makes a cylinder and twisting only `x` makes a Möbius strip.
+    ///
+    /// Panics if both axes are [`Boundary::Shift`] or [`Boundary::Twist`].
+    pub fn with_boundaries(
+        sim: S,
+        cells: Array2<S::Cell>,
+        y: Boundary<S::Cell>,
+        x: Boundary<S::Cell>,
+    ) -> Self
+    where
+        S::Cell: Clone,
+        S::Diff: Clone,
+    {
+        Boundary::check_pair(&y, &x);
+        let mut grid = Self::new(sim, cells);
+        grid.set_boundaries(y, x);
+        grid
+    }
+
+    /// Get the boundaries of the `y` and `x` axes of the grid.
+    pub fn boundaries(&self) -> (&Boundary<S::Cell>, &Boundary<S::Cell>) {
+        (&self.boundary.0, &self.boundary.1)
+    }
+
+    /// The number of steps the grid has taken.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The seed used to make random numbers for each cell, which is `0` by default.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Set the seed used to make random numbers for each cell.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// The `(y, x)` indices of the cells which changed in the last step, if it was taken with
+    /// [`SquareGrid::step_active`] and the grid hasn't been modified since.
+    pub fn changed_cells(&self) -> Option<&[(usize, usize)]> {
+        self.changed.as_deref()
+    }
+
+    /// The `(height, width)` of the tiles the grid is stepped in, if it is tiled.
+    pub fn tile_size(&self) -> Option<(usize, usize)> {
+        self.tile_size
+    }
+
+    /// Step the grid in tiles of a `(height, width)`, or all at once if `None`, which is the default.
+    ///
+    /// Each tile is processed by a single thread, so the cells around it stay in cache while it is
+    /// processed. This can be faster on grids that are too big to fit in cache, as long as the
+    /// tiles are small enough to fit.
+    pub fn set_tile_size(&mut self, tile_size: Option<(usize, usize)>) {
+        if let Some((h, w)) = tile_size {
+            assert!(h >= 1 && w >= 1, "tiles must not be empty");
+        }
+        self.tile_size = tile_size;
+    }
+
+    /// Get the sim.
+    pub fn sim(&self) -> &S {
+        &self.sim
+    }
+
+    /// Get the sim mutably, which allows its parameters to be changed between steps.
+    pub fn sim_mut(&mut self) -> &mut S {
+        self.changed = None;
+        &mut self.sim
     }
 
     /// Get view of cells on the grid.
     pub fn cells(&self) -> ArrayView2<'_, S::Cell> {
-        self.cells.slice(s![1..-1, 1..-1])
+        self.cells.slice(&Self::inside())
     }
 
     /// Get mutable view of cells on the grid.
     pub fn cells_mut(&mut self) -> ArrayViewMut2<'_, S::Cell> {
-        self.cells.slice_mut(s![1..-1, 1..-1])
+        self.modified();
+        self.cells.slice_mut(&Self::inside())
+    }
+
+    /// Iterate over the `(y, x)` indices and cells of the grid in row-major order.
+    pub fn iter_coords(&self) -> impl Iterator<Item = ((usize, usize), &S::Cell)> {
+        let (h, w) = self.cells().dim();
+        (0..h).cartesian_product(0..w).zip(self.cells())
+    }
+
+    /// Iterate over the `(y, x)` indices and mutable cells of the grid in row-major order.
+    pub fn iter_mut_coords(&mut self) -> impl Iterator<Item = ((usize, usize), &mut S::Cell)> {
+        let (h, w) = self.cells().dim();
+        (0..h).cartesian_product(0..w).zip(self.cells_mut())
+    }
+
+    /// Iterate over the rows of the grid from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[S::Cell]> {
+        let cells = self.cells();
+        (0..cells.nrows()).map(move |y| {
+            cells
+                .slice_move(s![y, ..])
+                .to_slice()
+                .expect("rows of the grid are contiguous")
+        })
+    }
+
+    /// Get the cell at a `(y, x)` index, or `None` if it is outside of the grid.
+    pub fn get(&self, (y, x): (usize, usize)) -> Option<&S::Cell> {
+        let r = N::RADIUS;
+        let (h, w) = self.cells.dim();
+        if y < h - 2 * r && x < w - 2 * r {
+            Some(&self.cells[(y + r, x + r)])
+        } else {
+            None
+        }
+    }
+
+    /// Get the cell at a `(y, x)` index mutably, or `None` if it is outside of the grid.
+    pub fn get_mut(&mut self, (y, x): (usize, usize)) -> Option<&mut S::Cell> {
+        let r = N::RADIUS;
+        let (h, w) = self.cells.dim();
+        if y < h - 2 * r && x < w - 2 * r {
+            self.modified();
+            Some(&mut self.cells[(y + r, x + r)])
+        } else {
+            None
+        }
+    }
+
+    /// Set the cell at a `(y, x)` index.
+    pub fn set_cell(&mut self, index: (usize, usize), cell: S::Cell) {
+        self[index] = cell;
+    }
+
+    /// Set the cell at a `(y, x)` index, or get [`GridError::OutOfBounds`] if it isn't on the
+    /// grid.
+    pub fn try_set_cell(&mut self, index: (usize, usize), cell: S::Cell) -> Result<(), GridError> {
+        let dims = self.cells().dim();
+        *self
+            .get_mut(index)
+            .ok_or(GridError::OutOfBounds { index, dims })? = cell;
+        Ok(())
+    }
+
+    /// Set every cell on the grid to a clone of `cell`.
+    pub fn fill(&mut self, cell: S::Cell)
+    where
+        S::Cell: Clone,
+    {
+        self.cells_mut().fill(cell);
+    }
+
+    /// Set every cell in the `(y, x)` ranges of a region to a clone of `cell`.
+    pub fn fill_region(&mut self, region: (Range<usize>, Range<usize>), cell: S::Cell)
+    where
+        S::Cell: Clone,
+    {
+        self.modify_region(region, |c| *c = cell.clone());
+    }
+
+    /// Call `f` on every cell in the `(y, x)` ranges of a region.
+    pub fn modify_region(
+        &mut self,
+        region: (Range<usize>, Range<usize>),
+        f: impl FnMut(&mut S::Cell),
+    ) {
+        self.window_mut(region).map_inplace(f);
+    }
+
+    /// Get a view of the cells in the `(y, x)` ranges of a region, indexed from its corner.
+    pub fn window(&self, region: (Range<usize>, Range<usize>)) -> ArrayView2<'_, S::Cell> {
+        self.try_window(region)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Get a view of the cells in a region like [`SquareGrid::window`], or an error if the
+    /// region isn't on the grid.
+    pub fn try_window(
+        &self,
+        region: (Range<usize>, Range<usize>),
+    ) -> Result<ArrayView2<'_, S::Cell>, GridError> {
+        let (y, x) = self.check_region(region)?;
+        Ok(self.cells().slice_move(s![y, x]))
+    }
+
+    /// Get the window of width `2 * N::RADIUS + 1` centered on the cell at a `(y, x)` index,
+    /// with the cells beyond the edges of the grid that the boundaries give it.
+    ///
+    /// This is the window the cell would see if the grid stepped now, but nothing is stepped.
+    pub fn window_at(&self, (y, x): (usize, usize)) -> Array2<S::Cell>
+    where
+        S::Cell: Clone,
+    {
+        assert!(self.get((y, x)).is_some(), "index is outside of the grid");
+        let r = N::RADIUS as isize;
+        window_from::<N, _>(|(dy, dx)| {
+            let index = (
+                (y as isize + r + dy) as usize,
+                (x as isize + r + dx) as usize,
+            );
+            match self.source(index) {
+                Some((source, _)) => self.cells[source].clone(),
+                None => self.constant(index).unwrap_or(&self.cells[index]).clone(),
+            }
+        })
+    }
+
+    /// Call `f` with the neighbors of the cell at a `(y, x)` index as [`SquareGrid::window_at`]
+    /// gives them, such as to inspect a neighborhood in a test without stepping.
+    pub fn neighbors_at<R>(
+        &self,
+        index: (usize, usize),
+        f: impl FnOnce(N::Neighbors<'_, S::Cell>) -> R,
+    ) -> R
+    where
+        S::Cell: Clone,
+    {
+        let window = self.window_at(index);
+        f(N::located_neighbors(window.view(), self.locate(index)))
+    }
+
+    /// Get a mutable view of the cells in the `(y, x)` ranges of a region, indexed from its corner.
+    ///
+    /// The view can be split further with the methods of [`ArrayViewMut2`] to write to
+    /// disjoint parts of the region at once.
+    pub fn window_mut(
+        &mut self,
+        region: (Range<usize>, Range<usize>),
+    ) -> ArrayViewMut2<'_, S::Cell> {
+        self.try_window_mut(region)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Get a mutable view of the cells in a region like [`SquareGrid::window_mut`], or an
+    /// error if the region isn't on the grid.
+    pub fn try_window_mut(
+        &mut self,
+        region: (Range<usize>, Range<usize>),
+    ) -> Result<ArrayViewMut2<'_, S::Cell>, GridError> {
+        let (y, x) = self.check_region(region)?;
+        Ok(self.cells_mut().slice_move(s![y, x]))
+    }
+
+    /// Get a region back if it is on the grid.
+    pub(crate) fn check_region(
+        &self,
+        (y, x): (Range<usize>, Range<usize>),
+    ) -> Result<(Range<usize>, Range<usize>), GridError> {
+        let (h, w) = self.cells().dim();
+        if y.start <= y.end && y.end <= h && x.start <= x.end && x.end <= w {
+            Ok((y, x))
+        } else {
+            Err(GridError::Region {
+                region: (y, x),
+                dims: (h, w),
+            })
+        }
+    }
+
+    /// Change the `(height, width)` of the grid, keeping the cells that still fit.
+    ///
+    /// The old cell at `(0, 0)` is moved to `offset` on the new grid and the rest
+    /// keep their position relative to it. New cells are clones of `fill`.
+    pub fn resize(&mut self, dims: (usize, usize), offset: (isize, isize), fill: S::Cell)
+    where
+        S::Cell: Clone,
+    {
+        self.try_resize(dims, offset, fill)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Change the `(height, width)` of the grid like [`SquareGrid::resize`], or get
+    /// [`GridError::Empty`] without changing anything if it would have no cells.
+    pub fn try_resize(
+        &mut self,
+        dims: (usize, usize),
+        offset: (isize, isize),
+        fill: S::Cell,
+    ) -> Result<(), GridError>
+    where
+        S::Cell: Clone,
+    {
+        self.try_resize_in(dims, offset, fill, &mut Vec::new())
+    }
+
+    /// Change the `(height, width)` of the grid like [`SquareGrid::resize`], building the new
+    /// cells in the allocation of `buffer` and leaving the allocation of the old cells in it.
+    ///
+    /// Passing the same buffer to every resize means that no memory is allocated once it is
+    /// large enough for the grid.
+    pub fn resize_in(
+        &mut self,
+        dims: (usize, usize),
+        offset: (isize, isize),
+        fill: S::Cell,
+        buffer: &mut Vec<S::Cell>,
+    ) where
+        S::Cell: Clone,
+    {
+        self.try_resize_in(dims, offset, fill, buffer)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Change the `(height, width)` of the grid like [`SquareGrid::resize_in`], or get
+    /// [`GridError::Empty`] without changing anything if it would have no cells.
+    pub fn try_resize_in(
+        &mut self,
+        dims: (usize, usize),
+        offset: (isize, isize),
+        fill: S::Cell,
+        buffer: &mut Vec<S::Cell>,
+    ) -> Result<(), GridError>
+    where
+        S::Cell: Clone,
+    {
+        if dims.0 == 0 || dims.1 == 0 {
+            return Err(GridError::Empty);
+        }
+        let r = N::RADIUS;
+        let padded = (dims.0 + 2 * r, dims.1 + 2 * r);
+        buffer.clear();
+        buffer.resize(padded.0 * padded.1, fill);
+        let mut cells = Array2::from_shape_vec(padded, mem::take(buffer)).unwrap();
+        let (h, w) = self.cells().dim();
+        for (y, x) in (0..h).cartesian_product(0..w) {
+            let (ny, nx) = (y as isize + offset.0, x as isize + offset.1);
+            if (0..dims.0 as isize).contains(&ny) && (0..dims.1 as isize).contains(&nx) {
+                mem::swap(
+                    &mut self.cells[(y + r, x + r)],
+                    &mut cells[(ny as usize + r, nx as usize + r)],
+                );
+            }
+        }
+        *buffer = mem::replace(&mut self.cells, cells).into_raw_vec();
+        if let Some(old) = &self.domain {
+            // New cells are in the domain and old cells keep whether they were.
+            let mut domain = Array2::from_elem(padded, false);
+            domain.slice_mut(&Self::inside()).fill(true);
+            for (y, x) in (0..h).cartesian_product(0..w) {
+                let (ny, nx) = (y as isize + offset.0, x as isize + offset.1);
+                if (0..dims.0 as isize).contains(&ny) && (0..dims.1 as isize).contains(&nx) {
+                    domain[(ny as usize + r, nx as usize + r)] = old[(y + r, x + r)];
+                }
+            }
+            self.domain = Some(domain);
+        }
+        buffer.clear();
+        self.modified();
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+        self.update_padding();
+        Ok(())
+    }
+
+    /// Shrink the grid to the cells in the `(y, x)` ranges of a region.
+    pub fn crop(&mut self, region: (Range<usize>, Range<usize>))
+    where
+        S::Cell: Clone,
+    {
+        self.try_crop(region)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Shrink the grid like [`SquareGrid::crop`], or get an error without changing anything if
+    /// the region is empty or isn't on the grid.
+    pub fn try_crop(&mut self, region: (Range<usize>, Range<usize>)) -> Result<(), GridError>
+    where
+        S::Cell: Clone,
+    {
+        let (y, x) = self.check_region(region)?;
+        if y.is_empty() || x.is_empty() {
+            return Err(GridError::Empty);
+        }
+        let fill = self[(y.start, x.start)].clone();
+        self.try_resize(
+            (y.len(), x.len()),
+            (-(y.start as isize), -(x.start as isize)),
+            fill,
+        )
+    }
+
+    /// Get the smallest rectangle containing every cell for which `f` returns `true`, or `None`
+    /// if there are none.
+    ///
+    /// Pass its [`Rect::region`] to [`SquareGrid::crop`] to crop the grid down to it.
+    pub fn bounding_box(&self, f: impl Fn(&S::Cell) -> bool + Send + Sync) -> Option<Rect>
+    where
+        S::Cell: Sync,
+    {
+        stats::bounding_box(self.cells(), f)
+    }
+
+    /// Copy a pattern, such as the cells of another grid or an RLE pattern, onto the grid
+    /// with its `(0, 0)` cell at `offset`.
+    ///
+    /// Parts of the pattern beyond an edge wrap around if the edge is connected and are
+    /// left out otherwise.
+    pub fn stamp(&mut self, pattern: ArrayView2<'_, S::Cell>, offset: (isize, isize))
+    where
+        S::Cell: Clone,
+    {
+        self.stamp_where(pattern, offset, |_| true);
+    }
+
+    /// Copy the cells of a pattern for which `opaque` returns `true` onto the grid with
+    /// its `(0, 0)` cell at `offset`, leaving the cells under the rest of the pattern alone.
+    ///
+    /// Parts of the pattern beyond an edge wrap around if the edge is connected and are
+    /// left out otherwise.
+    pub fn stamp_where(
+        &mut self,
+        pattern: ArrayView2<'_, S::Cell>,
+        offset: (isize, isize),
+        mut opaque: impl FnMut(&S::Cell) -> bool,
+    ) where
+        S::Cell: Clone,
+    {
+        for ((y, x), cell) in pattern.indexed_iter() {
+            if !opaque(cell) {
+                continue;
+            }
+            if let Some(index) = self.wrap((y as isize + offset.0, x as isize + offset.1)) {
+                self[index] = cell.clone();
+            }
+        }
+    }
+
+    /// Forget what was learned about the cells during the last step after they are modified
+    /// outside of a step.
+    pub(crate) fn modified(&mut self) {
+        self.changed = None;
+        self.partial = None;
+        if let Some(population) = &mut self.population {
+            population.invalidate();
+        }
+    }
+
+    /// Get the index on the grid that a position reaches by crossing connected edges,
+    /// or `None` if it is beyond an edge which isn't connected.
+    pub(crate) fn wrap(&self, (y, x): (isize, isize)) -> Option<(usize, usize)> {
+        let (h, w) = self.cells().dim();
+        let on_axis = |boundary: &Boundary<S::Cell>, position: isize, len: usize| {
+            if boundary.connects() {
+                boundary.map(position, len)
+            } else if (0..len as isize).contains(&position) {
+                Some(position as usize)
+            } else {
+                None
+            }
+        };
+        let (y, x) = self.shifted((y, x), (h, w));
+        let mut wy = on_axis(&self.boundary.0, y, h)?;
+        let mut wx = on_axis(&self.boundary.1, x, w)?;
+        if self.boundary.1.twists(x, w) {
+            wy = h - 1 - wy;
+        }
+        if self.boundary.0.twists(y, h) {
+            wx = w - 1 - wx;
+        }
+        Some((wy, wx))
+    }
+
+    /// Move a position which may be beyond the edges of a grid with the given dimensions by
+    /// the shifts of the seams it crosses, so only the wrapping of each axis is left.
+    pub(crate) fn shifted(&self, (y, x): (isize, isize), (h, w): (usize, usize)) -> (isize, isize) {
+        (
+            y + self.boundary.1.shift(x, w),
+            x + self.boundary.0.shift(y, h),
+        )
+    }
+
+    /// Copy the cells that a neighboring grid on a side needs for its halo, in row-major order.
+    ///
+    /// The strips of the north and south sides include the ghost cells of the west and east
+    /// halos, so exchanging the west and east halos first fills in the corners.
+    pub fn halo(&self, side: Side) -> Vec<S::Cell>
+    where
+        S::Cell: Clone,
+    {
+        self.cells
+            .slice(&self.strip(side, false))
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Set the ghost cells beyond a side from the [`SquareGrid::halo`] of the neighboring grid,
+    /// in row-major order.
+    ///
+    /// Ghost cells are only kept between steps on sides with a [`Boundary::Halo`].
+    pub fn set_halo(&mut self, side: Side, cells: &[S::Cell])
+    where
+        S::Cell: Clone,
+    {
+        self.modified();
+        let mut halo = self.cells.slice_mut(&self.strip(side, true));
+        assert_eq!(halo.len(), cells.len(), "halo is the wrong size");
+        halo.iter_mut()
+            .zip(cells)
+            .for_each(|(ghost, cell)| *ghost = cell.clone());
+    }
+
+    /// The slice of the padded cells in the strip along a side, either the cells inside the
+    /// side or the ghost cells beyond it.
+    fn strip(
+        &self,
+        side: Side,
+        ghost: bool,
+    ) -> ndarray::SliceInfo<[ndarray::SliceOrIndex; 2], ndarray::Ix2> {
+        let r = N::RADIUS as isize;
+        let (h, w) = (self.cells.nrows() as isize, self.cells.ncols() as isize);
+        let near = if ghost { 0..r } else { r..2 * r };
+        let far = |len: isize| {
+            if ghost {
+                len - r..len
+            } else {
+                len - 2 * r..len - r
+            }
+        };
+        match side {
+            Side::North => *s![near, ..],
+            Side::South => *s![far(h), ..],
+            Side::West => *s![r..h - r, near],
+            Side::East => *s![r..h - r, far(w)],
+        }
+    }
+
+    /// Set the boundaries of the `y` and `x` axes and the padding cells they give.
+    pub(crate) fn set_boundaries(&mut self, y: Boundary<S::Cell>, x: Boundary<S::Cell>)
+    where
+        S::Cell: Clone,
+        S::Diff: Clone,
+    {
+        self.copier.diff = Some(S::Diff::clone);
+        self.boundary = (y, x);
+        self.update_padding();
+    }
+
+    /// Set the padding cells from the cells they copy or the constant boundary cell.
+    pub(crate) fn update_padding(&mut self)
+    where
+        S::Cell: Clone,
+    {
+        self.copier.cell = Some(S::Cell::clone);
+        self.refresh_padding();
+    }
+
+    /// Set the padding cells like [`SquareGrid::update_padding`] if cells can be copied, and
+    /// otherwise leave the constant padding the grid was made with.
+    pub(crate) fn refresh_padding(&mut self) {
+        let copy = match self.copier.cell {
+            Some(copy) => copy,
+            None => return,
+        };
+        for index in padding(self.cells.dim(), N::RADIUS) {
+            let cell = match self.source(index) {
+                Some((source, _)) => copy(&self.cells[source]),
+                None => match self.constant(index) {
+                    Some(cell) => copy(cell),
+                    None => continue,
+                },
+            };
+            self.cells[index] = cell;
+        }
+    }
+
+    /// Get the index of the cell that a padding cell copies, if any, and whether
+    /// the `y` and `x` axes are mirrored by twists on the way there.
+    fn source(&self, (y, x): (usize, usize)) -> Option<((usize, usize), (bool, bool))> {
+        let r = N::RADIUS;
+        let (h, w) = (self.cells.nrows() - 2 * r, self.cells.ncols() - 2 * r);
+        let (y, x) = (y as isize - r as isize, x as isize - r as isize);
+        let (y, x) = self.shifted((y, x), (h, w));
+        let mut sy = self.boundary.0.map(y, h)?;
+        let mut sx = self.boundary.1.map(x, w)?;
+        let flip_y = self.boundary.1.twists(x, w);
+        let flip_x = self.boundary.0.twists(y, h);
+        if flip_y {
+            sy = h - 1 - sy;
+        }
+        if flip_x {
+            sx = w - 1 - sx;
+        }
+        Some(((sy + r, sx + r), (flip_y, flip_x)))
+    }
+
+    /// Get the constant cell of a padding cell which has no source, or `None` if it is a ghost cell.
+    fn constant(&self, (y, x): (usize, usize)) -> Option<&S::Cell> {
+        let r = N::RADIUS;
+        let (h, w) = (self.cells.nrows() - 2 * r, self.cells.ncols() - 2 * r);
+        let (y, x) = self.shifted((y as isize - r as isize, x as isize - r as isize), (h, w));
+        let outside_y = !(0..h as isize).contains(&y);
+        let outside_x = !(0..w as isize).contains(&x);
+        if (outside_y && matches!(self.boundary.0, Boundary::Halo))
+            || (outside_x && matches!(self.boundary.1, Boundary::Halo))
+        {
+            return None;
+        }
+        Some(match &self.boundary {
+            (Boundary::Constant(cell), _) if outside_y => cell,
+            (_, Boundary::Constant(cell)) => cell,
+            (Boundary::Constant(cell), _) => cell,
+            _ => unreachable!("only constant boundaries have no source"),
+        })
+    }
+
+    /// Get the location of the window around a cell.
+    pub(crate) fn locate(&self, index: (usize, usize)) -> Location {
+        let r = N::RADIUS;
+        let (index, bounds) = match &self.focus {
+            Some(focus) => {
+                let index = (focus.corner.0 + index.0, focus.corner.1 + index.1);
+                let bounds = window_bounds::<N, _>(&focus.boundary, focus.dims, index);
+                (index, bounds)
+            }
+            None => {
+                let dims = (self.cells.nrows() - 2 * r, self.cells.ncols() - 2 * r);
+                (index, window_bounds::<N, _>(&self.boundary, dims, index))
+            }
+        };
+        Location {
+            index,
+            bounds,
+            generation: self.generation,
+            seed: self.seed,
+        }
+    }
+
+    /// Check if flows can cross the edges between a padding cell and the grid.
+    ///
+    /// A shifted edge can move the other axis past an edge which isn't connected, so the
+    /// position is wrapped on the grid as a whole.
+    fn connected(&self, (y, x): (usize, usize)) -> bool {
+        let r = N::RADIUS as isize;
+        self.wrap((y as isize - r, x as isize - r)).is_some()
+    }
+
+    /// The slice of the padded cells which excludes the padding.
+    pub(crate) fn inside() -> ndarray::SliceInfo<[ndarray::SliceOrIndex; 2], ndarray::Ix2> {
+        let r = N::RADIUS as isize;
+        *s![r..-r, r..-r]
     }
 }
 
 #[cfg(feature = "use-rayon")]
-impl<S> SquareGrid<S>
+impl<S, N> SquareGrid<S, N>
 where
-    S: Sim<Neumann> + Sync,
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Sync,
+{
+    /// Iterate over the `(y, x)` indices and cells of the grid in parallel.
+    pub fn par_iter(
+        &self,
+    ) -> impl ndarray::parallel::prelude::ParallelIterator<Item = ((usize, usize), &S::Cell)> {
+        use ndarray::{parallel::prelude::*, Zip};
+        Zip::indexed(self.cells()).into_par_iter()
+    }
+
+    /// Iterate over the `(y, x)` indices and mutable cells of the grid in parallel.
+    pub fn par_iter_mut(
+        &mut self,
+    ) -> impl ndarray::parallel::prelude::ParallelIterator<Item = ((usize, usize), &mut S::Cell)>
+    {
+        use ndarray::{parallel::prelude::*, Zip};
+        Zip::indexed(self.cells_mut()).into_par_iter()
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: SquareNeighborhood,
     S::Cell: Send + Sync,
     S::Diff: Send + Sync,
-    S::Flow: Send,
+    N::Edges<S::Flow>: Send,
 {
-    pub fn step_parallel(&mut self) {
-        let diffs = self.compute_diffs();
-        let flows = self.perform_egress(diffs.view());
-        self.perform_ingress(flows);
+    /// Simulate one step, updating the cells in parallel when the `use-rayon` feature is
+    /// enabled and on the current thread otherwise.
+    ///
+    /// If a partial step is in progress, it is finished instead.
+    pub fn step(&mut self) {
+        if self.partial.is_some() {
+            self.step_rows(usize::MAX);
+            return;
+        }
+        let before = self.begin_step();
+        self.changed = None;
+        self.sweep();
+        self.generation += 1;
+        self.end_step(before);
     }
 
-    fn compute_diffs(&self) -> Array2<S::Diff> {
-        let mut diffs = Array2::from_shape_simple_fn(self.cells.dim(), || self.sim.diff_padding());
-        par_azip!((diff in diffs.slice_mut(s![1..-1, 1..-1]), cell in self.cells.windows((3, 3))) {
-            *diff = self.sim.compute(cell);
+    /// Update every cell once without counting a step or calling hooks.
+    pub(crate) fn sweep(&mut self) {
+        let clock = self.clock();
+        self.refresh_padding();
+        self.record(clock, |timings| &mut timings.padding);
+        let diffs = if self.update_order == UpdateOrder::Synchronous {
+            let clock = self.clock();
+            let mut diffs = self.take_diffs();
+            self.compute_diffs(&mut diffs);
+            self.record(clock, |timings| &mut timings.compute);
+            Some(diffs)
+        } else {
+            None
+        };
+        self.update(diffs);
+    }
+
+    /// Perform egress and ingress with diffs computed from the cells, or update the cells on
+    /// their own in the update order of the grid if there are no diffs.
+    pub(crate) fn update(&mut self, diffs: Option<Array2<S::Diff>>) {
+        let cells = self.cells.slice(&Self::inside());
+        let tally = self.population.as_mut().map(|p| p.tally(cells));
+        let clock = self.clock();
+        match diffs {
+            Some(diffs) => {
+                let flows = self.perform_egress(diffs.view(), tally.as_ref());
+                self.diffs = Some(diffs);
+                let clock = self.record(clock, |timings| &mut timings.egress);
+                self.perform_ingress(flows, tally.as_ref());
+                self.record(clock, |timings| &mut timings.ingress);
+            }
+            None => {
+                self.sweep_async(tally.as_ref());
+                self.record(clock, |timings| &mut timings.egress);
+            }
+        }
+        if let (Some(population), Some(tally)) = (&mut self.population, tally) {
+            population.apply(tally);
+        }
+    }
+
+    /// Simulate `n` steps, reusing the same buffers for each of them.
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Simulate one step and check if any cell changed.
+    ///
+    /// The cells are compared in parallel when the `use-rayon` feature is enabled.
+    pub fn step_changed(&mut self) -> bool
+    where
+        S::Cell: Clone + PartialEq,
+    {
+        let before = self.cells().to_owned();
+        self.step();
+        let changed = AtomicBool::new(false);
+        maybe_par_azip!((old in &before, new in self.cells()) {
+            if old != new {
+                changed.store(true, Ordering::Relaxed);
+            }
         });
-        diffs
+        changed.into_inner()
+    }
+
+    /// Step until a step leaves every cell unchanged, taking at most `max_generations` steps.
+    ///
+    /// Returns the generation of the first state which a step left unchanged, or `None` if the
+    /// cells were still changing after the limit.
+    pub fn run_until_stable(&mut self, max_generations: u64) -> Option<u64>
+    where
+        S::Cell: Clone + PartialEq,
+    {
+        for _ in 0..max_generations {
+            if !self.step_changed() {
+                return Some(self.generation - 1);
+            }
+        }
+        None
+    }
+
+    /// Exchange the halos of every side with a [`Boundary::Halo`], then step.
+    ///
+    /// `exchange` is given the strips of two opposite sides to send to the neighboring grids
+    /// on those sides and returns the strips received from them in the same order. The west
+    /// and east sides are exchanged before the north and south sides so the corners are filled.
+    ///
+    /// Ghost cells have the padding diff, so sims whose egress looks at the diffs of neighbors
+    /// can't see the diffs of cells on other grids.
+    pub fn step_with_halos(
+        &mut self,
+        mut exchange: impl FnMut([(Side, Vec<S::Cell>); 2]) -> [Vec<S::Cell>; 2],
+    ) where
+        S::Cell: Clone,
+    {
+        let axes = [
+            (
+                matches!(self.boundary.1, Boundary::Halo),
+                [Side::West, Side::East],
+            ),
+            (
+                matches!(self.boundary.0, Boundary::Halo),
+                [Side::North, Side::South],
+            ),
+        ];
+        for &(halo, sides) in &axes {
+            if halo {
+                let [a, b] = exchange(sides.map(|side| (side, self.halo(side))));
+                self.set_halo(sides[0], &a);
+                self.set_halo(sides[1], &b);
+            }
+        }
+        self.step();
+    }
+
+    /// Simulate one step, updating the cells in parallel.
+    #[cfg(feature = "use-rayon")]
+    pub fn step_parallel(&mut self) {
+        self.step();
+    }
+
+    /// Simulate one step, updating the cells in parallel on the threads of `pool`.
+    ///
+    /// This allows the number of threads used by the simulation to be limited.
+    #[cfg(feature = "use-rayon")]
+    pub fn step_in(&mut self, pool: &rayon::ThreadPool)
+    where
+        Self: Send,
+    {
+        pool.install(|| self.step());
+    }
+
+    fn window_shape() -> (usize, usize) {
+        (2 * N::RADIUS + 1, 2 * N::RADIUS + 1)
+    }
+
+    /// Take the diffs of the last step to be overwritten, or make new ones if there are none.
+    pub(crate) fn take_diffs(&mut self) -> Array2<S::Diff> {
+        let (sim, dim) = (&self.sim, self.cells.dim());
+        self.diffs
+            .take()
+            .filter(|diffs| diffs.dim() == self.cells.dim())
+            .unwrap_or_else(|| Array2::from_shape_simple_fn(dim, || sim.diff_padding()))
+    }
+
+    fn compute_diffs(&self, diffs: &mut Array2<S::Diff>) {
+        let compute = |index, window: ArrayView2<'_, S::Cell>| {
+            if !self.in_domain(index) {
+                return self.sim.diff_padding();
+            }
+            self.sim
+                .compute(N::located_neighbors(window, self.locate(index)))
+        };
+        match self.tile_size {
+            None => {
+                maybe_par_azip!((index index, diff in diffs.slice_mut(&Self::inside()), cell in self.cells.windows(Self::window_shape())) {
+                    *diff = compute(index, cell);
+                });
+            }
+            Some(tile_size) => {
+                let r = N::RADIUS;
+                let cells = self.cells.view();
+                for_each_tile(
+                    tiles(diffs.slice_mut(&Self::inside()), tile_size),
+                    |((oy, ox), mut diffs)| {
+                        let (h, w) = diffs.dim();
+                        let cells = cells.slice(s![oy..oy + h + 2 * r, ox..ox + w + 2 * r]);
+                        ndarray::azip!((index (y, x), diff in &mut diffs, cell in cells.windows(Self::window_shape())) {
+                            *diff = compute((oy + y, ox + x), cell);
+                        });
+                    },
+                );
+            }
+        }
+        self.pad_diffs(diffs);
+    }
+
+    /// Copy the diffs of the cells which padding cells copy into the padding.
+    ///
+    /// Grids which can't copy diffs have constant boundaries, so the padding keeps the padding
+    /// diff.
+    pub(crate) fn pad_diffs(&self, diffs: &mut Array2<S::Diff>) {
+        let copy = match self.copier.diff {
+            Some(copy) => copy,
+            None => return,
+        };
+        for index in padding(diffs.dim(), N::RADIUS) {
+            if let Some((source, _)) = self.source(index) {
+                diffs[index] = copy(&diffs[source]);
+            }
+        }
     }
 
     fn perform_egress(
         &mut self,
         diffs: ArrayView2<'_, S::Diff>,
-    ) -> Array2<ManuallyDrop<UnsafeCell<[S::Flow; 8]>>> {
+        tally: Option<&Tally<S::Cell>>,
+    ) -> FlowBuffer<N, S::Flow> {
         let mut flows = Array2::from_shape_simple_fn(self.cells.dim(), || {
-            ManuallyDrop::new(UnsafeCell::new([
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-                self.sim.flow_padding(),
-            ]))
-        });
-        let sim = &self.sim;
-        par_azip!((flow in flows.slice_mut(s![1..-1, 1..-1]), cell in self.cells.slice_mut(s![1..-1, 1..-1]), diffs in diffs.windows((3, 3))) {
-            *flow.get_mut() = sim.egress(cell, diffs);
+            ManuallyDrop::new(UnsafeCell::new(N::edges(|| self.sim.flow_padding())))
         });
-
-        unsafe fn exchange_chunk<T>(chunk: ArrayViewMut2<'_, ManuallyDrop<UnsafeCell<[T; 8]>>>) {
-            let top_left = &mut *chunk[(0, 0)].get();
-            let top_right = &mut *chunk[(0, 1)].get();
-            let bottom_left = &mut *chunk[(1, 0)].get();
-            let bottom_right = &mut *chunk[(1, 1)].get();
-            // Left to right
-            mem::swap(&mut bottom_left[0], &mut bottom_right[4]);
-            // Top to bottom
-            mem::swap(&mut top_right[6], &mut bottom_right[2]);
-            // Across
-            mem::swap(&mut top_left[7], &mut bottom_right[3]);
-            mem::swap(&mut top_right[5], &mut bottom_left[1]);
+        let r = N::RADIUS;
+        let dims = (self.cells.nrows() - 2 * r, self.cells.ncols() - 2 * r);
+        let (boundary, generation, seed) = (&self.boundary, self.generation, self.seed);
+        let (sim, domain) = (&self.sim, self.domain.as_ref());
+        let egress = |(y, x), cell: &mut S::Cell, window: ArrayView2<'_, S::Diff>| {
+            if domain.is_some_and(|domain| !domain[(y + r, x + r)]) {
+                return N::edges(|| sim.flow_padding());
+            }
+            let index = (y, x);
+            let location = Location {
+                index,
+                bounds: window_bounds::<N, _>(boundary, dims, index),
+                generation,
+                seed,
+            };
+            Tally::track(tally, cell, |cell| {
+                sim.egress(cell, N::located_neighbors(window, location))
+            })
+        };
+        match self.tile_size {
+            None => {
+                maybe_par_azip!((index index, flow in flows.slice_mut(&Self::inside()), cell in self.cells.slice_mut(&Self::inside()), diffs in diffs.windows(Self::window_shape())) {
+                    *flow.get_mut() = egress(index, cell, diffs);
+                });
+            }
+            Some(tile_size) => {
+                let flow_tiles = tiles(flows.slice_mut(&Self::inside()), tile_size);
+                let cell_tiles = tiles(self.cells.slice_mut(&Self::inside()), tile_size);
+                let tiles = flow_tiles.into_iter().zip(cell_tiles).collect();
+                for_each_tile(tiles, |(((oy, ox), mut flows), (_, mut cells))| {
+                    let (h, w) = flows.dim();
+                    let diffs = diffs.slice(s![oy..oy + h + 2 * r, ox..ox + w + 2 * r]);
+                    ndarray::azip!((index (y, x), flow in &mut flows, cell in &mut cells, diffs in diffs.windows(Self::window_shape())) {
+                        *flow.get_mut() = egress((oy + y, ox + x), cell, diffs);
+                    });
+                });
+            }
         }
 
         // The flows need to be moved around to where they are consumed.
-        // We do this by splitting the grid into 2x2 chunks.
-        // We exchange the diagnal movements in each chunk, and then we also
-        // exchange two specific sides (but the specific sides can be chosen arbitrarily).
-        // This performs four swaps per chunk, exchanging eight flows.
-        // At this point we have only exchanged 1/4th of the total flows, but by sequentially
-        // performing this same operation offset by (0, 0), (0, 1), (1, 0), and (1, 1)
-        // we can actually exchange all flows in four simple parallel operations.
-        for (y, x) in (0..2).cartesian_product(0..2) {
-            par_azip!((chunk in flows.slice_mut(s![y.., x..]).exact_chunks_mut((2, 2))) {
-                unsafe { exchange_chunk(chunk); }
-            });
+        let links: Vec<Link> = (0..N::EDGES)
+            .filter(|&edge| edge < N::opposite(edge))
+            .map(|edge| {
+                let (y, x) = N::delta(edge);
+                Link {
+                    edge,
+                    opposite: N::opposite(edge),
+                    delta: vec![y, x],
+                    parity: None,
+                }
+            })
+            .collect();
+        engine::exchange_flows(&mut flows, &links, N::edges_mut::<S::Flow>);
+
+        // Flows that left the grid across connected edges are sitting in the padding, so they
+        // are moved to the cell which the padding copies. If the edge is twisted, the cell
+        // receives the flow from the mirrored direction.
+        let mirror = |(flip_y, flip_x): (bool, bool), edge: usize| {
+            let (dy, dx) = N::delta(edge);
+            let delta = (if flip_y { -dy } else { dy }, if flip_x { -dx } else { dx });
+            N::edge(delta).unwrap()
+        };
+        let (h, w) = flows.dim();
+        let r = N::RADIUS as isize;
+        let inside = |y: isize, x: isize| {
+            (r..h as isize - r).contains(&y) && (r..w as isize - r).contains(&x)
+        };
+        for index in padding(flows.dim(), N::RADIUS) {
+            let (source, flips) = match self.source(index) {
+                Some(source) if self.connected(index) => source,
+                _ => continue,
+            };
+            for edge in 0..N::EDGES {
+                let (dy, dx) = N::delta(edge);
+                if inside(index.0 as isize + dy, index.1 as isize + dx) {
+                    unsafe {
+                        let from = N::edges_mut(&mut *flows[index].get());
+                        let to = N::edges_mut(&mut *flows[source].get());
+                        mem::swap(&mut from[edge], &mut to[mirror(flips, edge)]);
+                    }
+                }
+            }
         }
 
         flows
     }
 
-    fn perform_ingress(&mut self, mut flows: Array2<ManuallyDrop<UnsafeCell<[S::Flow; 8]>>>) {
-        let (h, w) = self.cells.dim();
+    fn perform_ingress(&mut self, flows: FlowBuffer<N, S::Flow>, tally: Option<&Tally<S::Cell>>) {
         let sim = &self.sim;
-        // At the end of this line, all of the manually drops MUST have been taken or dropped.
-        par_azip!((index (y, x), flow in &mut flows, cell in &mut self.cells) {
-            unsafe {
-                if (1..h-1).contains(&y) && (1..w-1).contains(&x) {
-                    // If its not part of the padding, we run the sim here.
-                    sim.ingress(cell, ManuallyDrop::take(flow).into_inner());
-                } else {
-                    // If this is part of the padding, we must manually drop.
-                    ManuallyDrop::drop(flow);
-                }
-            }
+        let domain = self.domain.as_ref();
+        let padding = [N::RADIUS; 2];
+        engine::take_flows(flows, &mut self.cells, &padding, domain, |cell, flows| {
+            Tally::track(tally, cell, |cell| sim.ingress(cell, flows))
         });
     }
 }
+
+impl<S, N> Index<(usize, usize)> for SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    type Output = S::Cell;
+
+    /// Get the cell at a `(y, x)` index, panicking if it is outside of the grid.
+    fn index(&self, index: (usize, usize)) -> &S::Cell {
+        self.get(index).expect("index is outside of the grid")
+    }
+}
+
+impl<S, N> IndexMut<(usize, usize)> for SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut S::Cell {
+        self.get_mut(index).expect("index is outside of the grid")
+    }
+}
+
+/// Split a view into tiles of at most `(th, tw)`, along with the index of their first cell.
+fn tiles<T>(
+    view: ArrayViewMut2<'_, T>,
+    (th, tw): (usize, usize),
+) -> Vec<((usize, usize), ArrayViewMut2<'_, T>)> {
+    let mut tiles = vec![];
+    let mut rest = view;
+    let mut y = 0;
+    while rest.nrows() > 0 {
+        let split = th.min(rest.nrows());
+        let (mut row, below) = rest.split_at(Axis(0), split);
+        rest = below;
+        let mut x = 0;
+        while row.ncols() > 0 {
+            let split = tw.min(row.ncols());
+            let (tile, right) = row.split_at(Axis(1), split);
+            row = right;
+            tiles.push(((y, x), tile));
+            x += tw;
+        }
+        y += th;
+    }
+    tiles
+}
+
+/// Process tiles in parallel when the `use-rayon` feature is enabled and on the current
+/// thread otherwise.
+#[cfg(feature = "use-rayon")]
+fn for_each_tile<T: Send>(tiles: Vec<T>, f: impl Fn(T) + Send + Sync) {
+    use ndarray::parallel::prelude::*;
+    tiles.into_par_iter().for_each(f);
+}
+
+/// Process tiles in parallel when the `use-rayon` feature is enabled and on the current
+/// thread otherwise.
+#[cfg(not(feature = "use-rayon"))]
+fn for_each_tile<T>(tiles: Vec<T>, f: impl Fn(T)) {
+    tiles.into_iter().for_each(f);
+}
+
+/// The indices of the padding cells of a padded grid, row by row.
+///
+/// Only the strips of padding are visited, so this takes time proportional to the perimeter.
+fn padding((h, w): (usize, usize), r: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..h).flat_map(move |y| {
+        let (left, right) = if (r..h - r).contains(&y) {
+            (0..r, w - r..w)
+        } else {
+            (0..w, w..w)
+        };
+        left.chain(right).map(move |x| (y, x))
+    })
+}
+
+/// Get the part of the window around a cell which is on a grid with the given dimensions.
+fn window_bounds<N, C>(
+    boundary: &(Boundary<C>, Boundary<C>),
+    (h, w): (usize, usize),
+    (y, x): (usize, usize),
+) -> WindowBounds
+where
+    N: SquareNeighborhood,
+{
+    let r = N::RADIUS;
+    let width = 2 * r + 1;
+    let axis = |boundary: &Boundary<C>, position: usize, len: usize| {
+        if boundary.connects() || matches!(boundary, Boundary::Halo) {
+            0..width
+        } else {
+            r.saturating_sub(position)..width.min(r + len - position)
+        }
+    };
+    WindowBounds {
+        y: axis(&boundary.0, y, h),
+        x: axis(&boundary.1, x, w),
+    }
+}
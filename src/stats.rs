@@ -0,0 +1,222 @@
+//! Statistics of cells, such as the cells of a grid from [`SquareGrid::cells`].
+//!
+//! The cells are reduced in parallel when the `use-rayon` feature is enabled and on the
+//! current thread otherwise.
+//!
+//! [`SquareGrid::cells`]: crate::SquareGrid::cells
+
+use ndarray::{ArrayView1, ArrayView2, Axis};
+use std::{collections::HashMap, hash::Hash, ops::Range};
+
+/// Fold every cell into the identity and combine the partial results.
+#[cfg(feature = "use-rayon")]
+fn reduce<C, A>(
+    cells: ArrayView2<'_, C>,
+    identity: impl Fn() -> A + Send + Sync,
+    fold: impl Fn(A, &C) -> A + Send + Sync,
+    combine: impl Fn(A, A) -> A + Send + Sync,
+) -> A
+where
+    C: Sync,
+    A: Send,
+{
+    use ndarray::parallel::prelude::*;
+    cells
+        .into_par_iter()
+        .fold(&identity, fold)
+        .reduce(&identity, combine)
+}
+
+/// Fold every cell into the identity and combine the partial results.
+#[cfg(not(feature = "use-rayon"))]
+fn reduce<C, A>(
+    cells: ArrayView2<'_, C>,
+    identity: impl Fn() -> A + Send + Sync,
+    fold: impl Fn(A, &C) -> A + Send + Sync,
+    _combine: impl Fn(A, A) -> A + Send + Sync,
+) -> A
+where
+    C: Sync,
+    A: Send,
+{
+    cells.iter().fold(identity(), fold)
+}
+
+/// Count the cells for which `f` returns `true`.
+pub fn population<C>(cells: ArrayView2<'_, C>, f: impl Fn(&C) -> bool + Send + Sync) -> usize
+where
+    C: Sync,
+{
+    reduce(
+        cells,
+        || 0,
+        |count, cell| count + f(cell) as usize,
+        |a, b| a + b,
+    )
+}
+
+/// Count the cells with each key returned by `f`.
+pub fn histogram<C, K>(
+    cells: ArrayView2<'_, C>,
+    f: impl Fn(&C) -> K + Send + Sync,
+) -> HashMap<K, usize>
+where
+    C: Sync,
+    K: Hash + Eq + Send,
+{
+    reduce(
+        cells,
+        HashMap::new,
+        |mut counts, cell| {
+            *counts.entry(f(cell)).or_insert(0) += 1;
+            counts
+        },
+        |mut a, b| {
+            for (key, count) in b {
+                *a.entry(key).or_insert(0) += count;
+            }
+            a
+        },
+    )
+}
+
+/// Get the smallest value returned by `f`, or `None` if there are no cells.
+///
+/// Values which can't be compared, like NaN, are skipped.
+pub fn min<C, T>(cells: ArrayView2<'_, C>, f: impl Fn(&C) -> T + Send + Sync) -> Option<T>
+where
+    C: Sync,
+    T: PartialOrd + Send,
+{
+    extreme(cells, f, |value, best| value < best)
+}
+
+/// Get the largest value returned by `f`, or `None` if there are no cells.
+///
+/// Values which can't be compared, like NaN, are skipped.
+pub fn max<C, T>(cells: ArrayView2<'_, C>, f: impl Fn(&C) -> T + Send + Sync) -> Option<T>
+where
+    C: Sync,
+    T: PartialOrd + Send,
+{
+    extreme(cells, f, |value, best| value > best)
+}
+
+/// Get the value returned by `f` which is `better` than every other.
+fn extreme<C, T>(
+    cells: ArrayView2<'_, C>,
+    f: impl Fn(&C) -> T + Send + Sync,
+    better: impl Fn(&T, &T) -> bool + Send + Sync,
+) -> Option<T>
+where
+    C: Sync,
+    T: PartialOrd + Send,
+{
+    let pick = |best: Option<T>, value: T| match best {
+        _ if value.partial_cmp(&value).is_none() => best,
+        Some(best) if !better(&value, &best) => Some(best),
+        _ => Some(value),
+    };
+    reduce(
+        cells,
+        || None,
+        |best, cell| pick(best, f(cell)),
+        |a, b| match b {
+            Some(b) => pick(a, b),
+            None => a,
+        },
+    )
+}
+
+/// Get the mean of the values returned by `f`, or `None` if there are no cells.
+pub fn mean<C>(cells: ArrayView2<'_, C>, f: impl Fn(&C) -> f64 + Send + Sync) -> Option<f64>
+where
+    C: Sync,
+{
+    let sum = reduce(cells, || 0.0, |sum, cell| sum + f(cell), |a, b| a + b);
+    match cells.len() {
+        0 => None,
+        len => Some(sum / len as f64),
+    }
+}
+
+/// A rectangle of cells given by its `(y, x)` ranges.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub y: Range<usize>,
+    pub x: Range<usize>,
+}
+
+impl Rect {
+    /// The `(height, width)` of the rectangle.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.y.len(), self.x.len())
+    }
+
+    /// Check if a `(y, x)` index is in the rectangle.
+    pub fn contains(&self, (y, x): (usize, usize)) -> bool {
+        self.y.contains(&y) && self.x.contains(&x)
+    }
+
+    /// The smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            y: self.y.start.min(other.y.start)..self.y.end.max(other.y.end),
+            x: self.x.start.min(other.x.start)..self.x.end.max(other.x.end),
+        }
+    }
+
+    /// The `(y, x)` ranges, such as for [`SquareGrid::crop`](crate::SquareGrid::crop).
+    pub fn region(&self) -> (Range<usize>, Range<usize>) {
+        (self.y.clone(), self.x.clone())
+    }
+}
+
+/// Get the smallest rectangle containing every cell for which `f` returns `true`, or `None`
+/// if there are none.
+///
+/// The rows are searched in parallel when the `use-rayon` feature is enabled.
+pub fn bounding_box<C>(
+    cells: ArrayView2<'_, C>,
+    f: impl Fn(&C) -> bool + Send + Sync,
+) -> Option<Rect>
+where
+    C: Sync,
+{
+    let row = |(y, row): (usize, ArrayView1<'_, C>)| {
+        let mut xs = row
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| f(cell))
+            .map(|(x, _)| x);
+        let first = xs.next()?;
+        let last = xs.last().unwrap_or(first);
+        Some(Rect {
+            y: y..y + 1,
+            x: first..last + 1,
+        })
+    };
+    let union = |a: Option<Rect>, b: Option<Rect>| match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(&b)),
+        (a, b) => a.or(b),
+    };
+    #[cfg(feature = "use-rayon")]
+    {
+        use ndarray::parallel::prelude::*;
+        cells
+            .axis_iter(Axis(0))
+            .into_par_iter()
+            .enumerate()
+            .map(row)
+            .reduce(|| None, union)
+    }
+    #[cfg(not(feature = "use-rayon"))]
+    {
+        cells
+            .axis_iter(Axis(0))
+            .enumerate()
+            .map(row)
+            .fold(None, union)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::ArrayView2;
+use std::{
+    io::{self, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Draws grids in a terminal, redrawing over the last frame each time.
+///
+/// Each cell is drawn as a character, optionally in a 24-bit ANSI color.
+#[derive(Clone, Debug, Default)]
+pub struct TermRenderer {
+    frame_time: Option<Duration>,
+    last_frame: Option<Instant>,
+    cleared: bool,
+}
+
+impl TermRenderer {
+    /// Make a renderer which draws frames as fast as they are given.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit how many frames are drawn each second by waiting before drawing a frame
+    /// too soon after the last one, or stop limiting them if `None`.
+    pub fn set_max_fps(&mut self, fps: Option<f64>) {
+        self.frame_time = fps.map(|fps| Duration::from_secs_f64(1.0 / fps));
+    }
+
+    /// Draw the generation and cells of a grid with the character of each cell.
+    pub fn render<S, N>(
+        &mut self,
+        writer: &mut impl Write,
+        grid: &SquareGrid<S, N>,
+        cell: impl Fn(&S::Cell) -> char,
+    ) -> io::Result<()>
+    where
+        S: Sim<N>,
+        N: SquareNeighborhood,
+        S::Cell: Send,
+    {
+        self.render_colored(writer, grid, |c| (cell(c), None))
+    }
+
+    /// Draw the generation and cells of a grid with the character and `[red, green, blue]`
+    /// color of each cell, if it has one.
+    pub fn render_colored<S, N>(
+        &mut self,
+        writer: &mut impl Write,
+        grid: &SquareGrid<S, N>,
+        cell: impl Fn(&S::Cell) -> (char, Option<[u8; 3]>),
+    ) -> io::Result<()>
+    where
+        S: Sim<N>,
+        N: SquareNeighborhood,
+        S::Cell: Send,
+    {
+        let mut frame = format!("generation {}\x1b[K\n", grid.generation());
+        write_frame(&mut frame, grid.cells(), cell);
+        self.wait();
+        if !self.cleared {
+            writer.write_all(b"\x1b[2J")?;
+            self.cleared = true;
+        }
+        writer.write_all(b"\x1b[H")?;
+        writer.write_all(frame.as_bytes())?;
+        writer.flush()
+    }
+
+    /// Wait until the next frame can be drawn.
+    fn wait(&mut self) {
+        if let (Some(frame_time), Some(last_frame)) = (self.frame_time, self.last_frame) {
+            if let Some(remaining) = frame_time.checked_sub(last_frame.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+        self.last_frame = Some(Instant::now());
+    }
+}
+
+/// Add the rows of cells to a frame, only changing the color when it differs from the last cell.
+fn write_frame<C>(
+    frame: &mut String,
+    cells: ArrayView2<'_, C>,
+    cell: impl Fn(&C) -> (char, Option<[u8; 3]>),
+) {
+    for row in cells.genrows() {
+        let mut color = None;
+        for c in row {
+            let (ch, next) = cell(c);
+            if next != color {
+                match next {
+                    Some([r, g, b]) => frame.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b)),
+                    None => frame.push_str("\x1b[39m"),
+                }
+                color = next;
+            }
+            frame.push(ch);
+        }
+        if color.is_some() {
+            frame.push_str("\x1b[39m");
+        }
+        frame.push_str("\x1b[K\n");
+    }
+}
@@ -0,0 +1,458 @@
+//! Helpers for unit testing rules on tiny grids drawn as ASCII art, and for checking
+//! properties of rules on many random grids with [`PropertyCheck`].
+//!
+//! Each line of the art is a row and each character is a cell, as given by [`AsciiCell`].
+//! Whitespace around the lines and blank lines are ignored, so the art can be indented along
+//! with the test. Cells beyond the art are the padding cell of the rule.
+//!
+//! ```
+//! use gridsim::{rules, testing};
+//!
+//! testing::assert_evolution_with(
+//!     rules::life(),
+//!     "
+//!     .....
+//!     ..#..
+//!     ..#..
+//!     ..#..
+//!     .....
+//!     ",
+//!     1,
+//!     "
+//!     .....
+//!     .....
+//!     .###.
+//!     .....
+//!     .....
+//!     ",
+//! );
+//! ```
+
+use crate::{
+    random::INITIAL,
+    rules::{BrainCell, WireworldCell},
+    Boundary, CellRng, Moore, Sim, SquareGrid, SquareNeighborhood, UpdateOrder,
+};
+use ndarray::Array2;
+use std::fmt::Debug;
+
+/// A cell which can be drawn as a single character.
+pub trait AsciiCell: Sized {
+    /// Make a cell from a character, if the character stands for a cell.
+    fn from_char(c: char) -> Option<Self>;
+
+    /// The character which stands for the cell.
+    fn to_char(&self) -> char;
+}
+
+/// Dead cells are `.` and live cells are `#`, though `O`, `o`, and `*` are also read as live.
+impl AsciiCell for bool {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(false),
+            '#' | 'O' | 'o' | '*' => Some(true),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+/// States are digits, or letters above `9`, with `.` for the dead state `0`.
+impl AsciiCell for u8 {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(0),
+            _ => c.to_digit(36).map(|state| state as u8),
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            0 => '.',
+            &state => std::char::from_digit(state as u32, 36).unwrap_or('?'),
+        }
+    }
+}
+
+/// Off cells are `.`, on cells are `#`, and dying cells are `+`.
+impl AsciiCell for BrainCell {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(BrainCell::Off),
+            '#' => Some(BrainCell::On),
+            '+' => Some(BrainCell::Dying),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            BrainCell::Off => '.',
+            BrainCell::On => '#',
+            BrainCell::Dying => '+',
+        }
+    }
+}
+
+/// Empty cells are `.`, heads are `H`, tails are `t`, and conductors are `#`.
+impl AsciiCell for WireworldCell {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(WireworldCell::Empty),
+            'H' => Some(WireworldCell::Head),
+            't' => Some(WireworldCell::Tail),
+            '#' => Some(WireworldCell::Conductor),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            WireworldCell::Empty => '.',
+            WireworldCell::Head => 'H',
+            WireworldCell::Tail => 't',
+            WireworldCell::Conductor => '#',
+        }
+    }
+}
+
+/// Make a grid of the cells drawn in ASCII art.
+///
+/// Panics if a character doesn't stand for a cell.
+pub fn grid_from_ascii<S>(sim: S, art: &str) -> SquareGrid<S>
+where
+    S: Sim<Moore>,
+    S::Cell: AsciiCell + Send,
+{
+    SquareGrid::from_chars(sim, &trim(art), |c| {
+        S::Cell::from_char(c).unwrap_or_else(|| panic!("{:?} doesn't stand for a cell", c))
+    })
+}
+
+/// Check that `rule` turns a cell with the given neighbors, in the edge order of [`Moore`],
+/// into `expected` in one step.
+///
+/// The rule is made with [`Default`]. The neighbors are surrounded by padding cells, which is
+/// only seen by rules that look at the diffs or flows of the neighbors.
+pub fn assert_rule<S>(center: S::Cell, neighbors: [S::Cell; 8], expected: S::Cell)
+where
+    S: Sim<Moore> + Default + Sync,
+    S::Cell: Clone + PartialEq + Debug + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+{
+    assert_rule_with(S::default(), center, neighbors, expected);
+}
+
+/// Check that `sim` turns a cell with the given neighbors, in the edge order of [`Moore`],
+/// into `expected` in one step.
+pub fn assert_rule_with<S>(sim: S, center: S::Cell, neighbors: [S::Cell; 8], expected: S::Cell)
+where
+    S: Sim<Moore> + Sync,
+    S::Cell: Clone + PartialEq + Debug + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+{
+    let mut cells = Array2::from_elem((3, 3), center.clone());
+    for (edge, neighbor) in neighbors.iter().enumerate() {
+        let (dy, dx) = Moore::DELTAS[edge];
+        cells[((1 + dy) as usize, (1 + dx) as usize)] = neighbor.clone();
+    }
+    let mut grid = SquareGrid::new(sim, cells);
+    grid.step();
+    let actual = &grid[(1, 1)];
+    assert!(
+        *actual == expected,
+        "rule turned {:?} with neighbors {:?} into {:?}, but {:?} was expected",
+        center,
+        neighbors,
+        actual,
+        expected,
+    );
+}
+
+/// Check that the rule turns the cells drawn in `before` into the cells drawn in `after` in
+/// `steps` steps.
+///
+/// The rule is made with [`Default`]. Both drawings must have the same dimensions.
+pub fn assert_evolution<S>(before: &str, steps: u64, after: &str)
+where
+    S: Sim<Moore> + Default + Sync,
+    S::Cell: AsciiCell + Clone + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+{
+    assert_evolution_with(S::default(), before, steps, after);
+}
+
+/// Check that `sim` turns the cells drawn in `before` into the cells drawn in `after` in
+/// `steps` steps.
+///
+/// When they differ, the panic shows both drawings side by side with the rows that differ
+/// marked.
+pub fn assert_evolution_with<S>(sim: S, before: &str, steps: u64, after: &str)
+where
+    S: Sim<Moore> + Sync,
+    S::Cell: AsciiCell + Clone + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+{
+    let mut grid = grid_from_ascii(sim, before);
+    let expected = trim(after);
+    let (h, w) = grid.cells().dim();
+    let lines: Vec<&str> = expected.lines().collect();
+    assert!(
+        lines.len() == h && lines.iter().all(|line| line.chars().count() == w),
+        "the drawings before and after must both be {}x{}",
+        h,
+        w,
+    );
+    grid.step_n(steps);
+    let actual = grid.to_chars(AsciiCell::to_char);
+    if actual != expected {
+        panic!(
+            "cells after {} steps don't match:\n{}",
+            steps,
+            side_by_side(&expected, &actual)
+        );
+    }
+}
+
+/// Check that [`SquareGrid::step`] gives the same cells as [`SquareGrid::step_reference`]
+/// for `steps` steps, starting from copies of `grid`.
+///
+/// The copies are stepped synchronously whatever the update order of `grid` is. When the cells
+/// differ, the panic shows the generation and the first cells which differ.
+pub fn assert_matches_reference<S, N>(grid: &SquareGrid<S, N>, steps: u64)
+where
+    S: Sim<N> + Clone + Sync,
+    N: SquareNeighborhood,
+    S::Cell: Clone + PartialEq + Debug + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    N::Edges<S::Flow>: Send,
+{
+    let mut actual = grid.clone();
+    actual.set_update_order(UpdateOrder::Synchronous);
+    let mut reference = grid.clone();
+    for _ in 0..steps {
+        actual.step();
+        reference.step_reference();
+        let diff = reference.diff(&actual);
+        if !diff.is_empty() {
+            panic!(
+                "{} cells differ from the reference at generation {}, such as (index, reference, actual): {:?}",
+                diff.len(),
+                actual.generation(),
+                &diff[..diff.len().min(4)],
+            );
+        }
+    }
+}
+
+/// Remove whitespace around each line and blank lines, ending every line with a newline.
+fn trim(art: &str) -> String {
+    art.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .flat_map(|line| line.chars().chain(Some('\n')))
+        .collect()
+}
+
+/// Show the expected and actual cells next to each other, marking the rows that differ.
+fn side_by_side(expected: &str, actual: &str) -> String {
+    let width = expected
+        .lines()
+        .map(str::len)
+        .chain(Some("expected".len()))
+        .max()
+        .unwrap_or(0);
+    let mut text = format!("{:<width$} | actual\n", "expected", width = width);
+    for (expected, actual) in expected.lines().zip(actual.lines()) {
+        let mark = if expected == actual { "" } else { "  <" };
+        text += &format!("{:<width$} | {}{}\n", expected, actual, mark, width = width);
+    }
+    text
+}
+
+/// A named symmetry of the square which transforms a grid in place.
+type Symmetry<S> = (&'static str, fn(&mut SquareGrid<S>));
+
+/// Checks properties of a rule over many random grids and generations.
+///
+/// Every case is a grid of random cells made from a generator seeded with the seed of the check
+/// plus the number of the case, so a failing case can be reproduced from the seed in the panic
+/// message. Grids wrap around their edges unless another boundary is given.
+///
+/// ```
+/// use gridsim::{rules, testing::PropertyCheck};
+///
+/// PropertyCheck::new(rules::life(), |rng| rng.gen_bool(0.3))
+///     .cases(4)
+///     .assert_symmetric();
+/// ```
+pub struct PropertyCheck<S, F>
+where
+    S: Sim<Moore>,
+{
+    sim: S,
+    cell: F,
+    dims: (usize, usize),
+    cases: u64,
+    generations: u64,
+    seed: u64,
+    boundary: Boundary<S::Cell>,
+}
+
+impl<S, F> PropertyCheck<S, F>
+where
+    S: Sim<Moore> + Clone + Sync,
+    S::Cell: Clone + PartialEq + Debug + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+    F: Fn(&mut CellRng) -> S::Cell,
+{
+    /// Check grids of cells made by `cell`, with 16 cases of 16x16 grids stepped 32 times.
+    pub fn new(sim: S, cell: F) -> Self {
+        Self {
+            sim,
+            cell,
+            dims: (16, 16),
+            cases: 16,
+            generations: 32,
+            seed: 0,
+            boundary: Boundary::Wrap,
+        }
+    }
+
+    /// Set the `(height, width)` of the grids.
+    pub fn dims(self, dims: (usize, usize)) -> Self {
+        Self { dims, ..self }
+    }
+
+    /// Set the number of random grids to check.
+    pub fn cases(self, cases: u64) -> Self {
+        Self { cases, ..self }
+    }
+
+    /// Set the number of steps to check each grid for.
+    pub fn generations(self, generations: u64) -> Self {
+        Self {
+            generations,
+            ..self
+        }
+    }
+
+    /// Set the seed of the first case.
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Set the boundary of every edge of the grids.
+    pub fn boundary(self, boundary: Boundary<S::Cell>) -> Self {
+        Self { boundary, ..self }
+    }
+
+    /// Check that the sum of `quantity` over every cell stays within `tolerance` of where it
+    /// started, such as for rules which only move mass between cells.
+    ///
+    /// Constant boundaries soak up anything flowing across them, so conserving rules should be
+    /// checked with a boundary that wraps.
+    pub fn assert_conserved(&self, quantity: impl Fn(&S::Cell) -> f64, tolerance: f64) {
+        let total = |grid: &SquareGrid<S>| grid.cells().iter().map(&quantity).sum::<f64>();
+        for seed in self.seeds() {
+            let mut grid = self.grid(seed);
+            let start = total(&grid);
+            for generation in 1..=self.generations {
+                grid.step();
+                let now = total(&grid);
+                assert!(
+                    (now - start).abs() <= tolerance,
+                    "quantity changed from {} to {} by generation {} of the case with seed {}",
+                    start,
+                    now,
+                    generation,
+                    seed,
+                );
+            }
+        }
+    }
+
+    /// Check that a grid of nothing but `cell` never changes, such as the dead state of a rule.
+    ///
+    /// The random cells aren't used by this check.
+    pub fn assert_quiescent(&self, cell: S::Cell) {
+        let cells = Array2::from_elem(self.dims, cell.clone());
+        let mut grid = SquareGrid::with_boundary(self.sim.clone(), cells, self.boundary.clone());
+        for generation in 1..=self.generations {
+            grid.step();
+            if let Some((index, changed)) = grid.iter_coords().find(|&(_, c)| *c != cell) {
+                panic!(
+                    "quiescent {:?} cell at {:?} changed to {:?} by generation {}",
+                    cell, index, changed, generation,
+                );
+            }
+        }
+    }
+
+    /// Check that the rule treats every direction the same, by checking that turning or
+    /// mirroring a grid and stepping it is the same as stepping it and then turning or mirroring
+    /// it, for every symmetry of the square.
+    pub fn assert_symmetric(&self) {
+        let symmetries: [Symmetry<S>; 7] = [
+            ("a quarter turn clockwise", SquareGrid::rotate_cw),
+            ("a half turn", |grid| {
+                grid.rotate_cw();
+                grid.rotate_cw();
+            }),
+            ("a quarter turn counter-clockwise", SquareGrid::rotate_ccw),
+            ("mirroring left to right", SquareGrid::mirror_horizontal),
+            ("mirroring top to bottom", SquareGrid::mirror_vertical),
+            ("transposing", SquareGrid::transpose),
+            ("transposing across the other diagonal", |grid| {
+                grid.transpose();
+                grid.rotate_cw();
+                grid.rotate_cw();
+            }),
+        ];
+        for seed in self.seeds() {
+            let grid = self.grid(seed);
+            for &(name, symmetry) in &symmetries {
+                let (mut stepped, mut turned) = (grid.clone(), grid.clone());
+                symmetry(&mut turned);
+                for generation in 1..=self.generations {
+                    stepped.step();
+                    turned.step();
+                    let mut expected = stepped.clone();
+                    symmetry(&mut expected);
+                    assert!(
+                        expected.cells() == turned.cells(),
+                        "rule isn't symmetric under {} by generation {} of the case with seed {}",
+                        name,
+                        generation,
+                        seed,
+                    );
+                }
+            }
+        }
+    }
+
+    fn seeds(&self) -> impl Iterator<Item = u64> {
+        let seed = self.seed;
+        (0..self.cases).map(move |case| seed.wrapping_add(case))
+    }
+
+    /// Make the random grid of a case.
+    fn grid(&self, seed: u64) -> SquareGrid<S> {
+        let cells = Array2::from_shape_fn(self.dims, |index| {
+            (self.cell)(&mut CellRng::new(seed, INITIAL, index))
+        });
+        SquareGrid::with_boundary(self.sim.clone(), cells, self.boundary.clone())
+    }
+}
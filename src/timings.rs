@@ -0,0 +1,96 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use std::time::{Duration, Instant};
+
+/// How long each stage of the last step of a grid took, kept by [`SquareGrid::time_steps`].
+///
+/// Update orders other than [`UpdateOrder::Synchronous`](crate::UpdateOrder::Synchronous)
+/// compute the diffs, perform egress, and perform ingress together, which is counted as egress.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StepTimings {
+    /// Updating the padding cells, which gathers the neighbors beyond the edges of the grid.
+    pub padding: Duration,
+    /// Computing the diffs of every cell.
+    pub compute: Duration,
+    /// Performing egress on every cell and moving the flows to where they are taken.
+    pub egress: Duration,
+    /// Performing ingress on every cell.
+    pub ingress: Duration,
+    /// The whole step, including hooks and updating the padding.
+    ///
+    /// For steps taken with [`SquareGrid::step_partial`], this includes the time between calls.
+    pub total: Duration,
+    /// The number of cells on the grid.
+    pub cells: usize,
+    started: Option<Instant>,
+}
+
+impl StepTimings {
+    /// The number of cells stepped per second over the whole step.
+    pub fn cells_per_second(&self) -> f64 {
+        self.cells as f64 / self.total.as_secs_f64()
+    }
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Time the stages of each step, such as to find which one dominates for a sim.
+    ///
+    /// The timings of a step can be read from a hook added with
+    /// [`SquareGrid::on_after_step`], or collected for every step with
+    /// [`SquareGrid::attach_profiler`].
+    pub fn time_steps(&mut self) {
+        self.timings = Some(StepTimings::default());
+    }
+
+    /// Stop timing steps.
+    pub fn untime_steps(&mut self) {
+        self.timings = None;
+    }
+
+    /// Get how long each stage of the last step took, or `None` if steps aren't timed.
+    pub fn step_timings(&self) -> Option<&StepTimings> {
+        self.timings.as_ref()
+    }
+
+    /// Start timing a step from scratch.
+    pub(crate) fn start_timings(&mut self) {
+        let cells = self.cells().len();
+        if let Some(timings) = &mut self.timings {
+            *timings = StepTimings {
+                cells,
+                started: Some(Instant::now()),
+                ..StepTimings::default()
+            };
+        }
+    }
+
+    /// Finish timing a step.
+    pub(crate) fn finish_timings(&mut self) {
+        if let Some(timings) = &mut self.timings {
+            if let Some(started) = timings.started.take() {
+                timings.total = started.elapsed();
+            }
+        }
+    }
+
+    /// Get the time a stage starts at if steps are timed.
+    pub(crate) fn clock(&self) -> Option<Instant> {
+        self.timings.as_ref().map(|_| Instant::now())
+    }
+
+    /// Add the time since `clock` to a stage, and get the time the next stage starts at.
+    pub(crate) fn record(
+        &mut self,
+        clock: Option<Instant>,
+        stage: impl FnOnce(&mut StepTimings) -> &mut Duration,
+    ) -> Option<Instant> {
+        let (timings, clock) = (self.timings.as_mut()?, clock?);
+        let now = Instant::now();
+        *stage(timings) += now - clock;
+        Some(now)
+    }
+}
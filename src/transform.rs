@@ -0,0 +1,194 @@
+use crate::{Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{Array2, Axis};
+use std::mem;
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send + Clone,
+{
+    /// Turn the grid a quarter turn clockwise, so the top row becomes the right column.
+    ///
+    /// The `(height, width)` and the boundaries of the axes are swapped.
+    pub fn rotate_cw(&mut self) {
+        self.transform(true, (false, true));
+    }
+
+    /// Turn the grid a quarter turn counter-clockwise, so the top row becomes the left column.
+    ///
+    /// The `(height, width)` and the boundaries of the axes are swapped.
+    pub fn rotate_ccw(&mut self) {
+        self.transform(true, (true, false));
+    }
+
+    /// Mirror the grid left to right.
+    pub fn mirror_horizontal(&mut self) {
+        self.transform(false, (false, true));
+    }
+
+    /// Mirror the grid top to bottom.
+    pub fn mirror_vertical(&mut self) {
+        self.transform(false, (true, false));
+    }
+
+    /// Swap the `y` and `x` axes, so the cell at `(y, x)` moves to `(x, y)`.
+    ///
+    /// The `(height, width)` and the boundaries of the axes are swapped.
+    pub fn transpose(&mut self) {
+        self.transform(true, (false, false));
+    }
+
+    /// Make a copy of the grid turned a quarter turn clockwise.
+    pub fn rotated_cw(&self) -> Self
+    where
+        Self: Clone,
+    {
+        self.transformed(Self::rotate_cw)
+    }
+
+    /// Make a copy of the grid turned a quarter turn counter-clockwise.
+    pub fn rotated_ccw(&self) -> Self
+    where
+        Self: Clone,
+    {
+        self.transformed(Self::rotate_ccw)
+    }
+
+    /// Make a copy of the grid mirrored left to right.
+    pub fn mirrored_horizontal(&self) -> Self
+    where
+        Self: Clone,
+    {
+        self.transformed(Self::mirror_horizontal)
+    }
+
+    /// Make a copy of the grid mirrored top to bottom.
+    pub fn mirrored_vertical(&self) -> Self
+    where
+        Self: Clone,
+    {
+        self.transformed(Self::mirror_vertical)
+    }
+
+    /// Make a copy of the grid with the `y` and `x` axes swapped.
+    pub fn transposed(&self) -> Self
+    where
+        Self: Clone,
+    {
+        self.transformed(Self::transpose)
+    }
+
+    /// Make a grid which repeats the cells `nx` times across and `ny` times down, such as to
+    /// build a large periodic world from a small motif.
+    ///
+    /// The new grid has the same sim, boundaries, generation, and seed, but not the hooks.
+    pub fn tile(&self, nx: usize, ny: usize) -> Self
+    where
+        S: Clone,
+    {
+        assert!(nx >= 1 && ny >= 1, "grid is empty, which isnt allowed");
+        let cells = self.cells();
+        let (h, w) = cells.dim();
+        self.with_cells(Array2::from_shape_fn((h * ny, w * nx), |(y, x)| {
+            cells[(y % h, x % w)].clone()
+        }))
+    }
+
+    /// Make a grid with the cells of `other` to the right of the cells of this grid.
+    ///
+    /// The grids must have the same height. The new grid has the sim, boundaries,
+    /// generation, and seed of this grid, but not the hooks.
+    pub fn hconcat(&self, other: &Self) -> Self
+    where
+        S: Clone,
+    {
+        assert_eq!(
+            self.cells().nrows(),
+            other.cells().nrows(),
+            "grids must have the same height"
+        );
+        let (h, w) = self.cells().dim();
+        self.with_cells(Array2::from_shape_fn(
+            (h, w + other.cells().ncols()),
+            |(y, x)| match x.checked_sub(w) {
+                None => self[(y, x)].clone(),
+                Some(x) => other[(y, x)].clone(),
+            },
+        ))
+    }
+
+    /// Make a grid with the cells of `other` below the cells of this grid.
+    ///
+    /// The grids must have the same width. The new grid has the sim, boundaries,
+    /// generation, and seed of this grid, but not the hooks.
+    pub fn vconcat(&self, other: &Self) -> Self
+    where
+        S: Clone,
+    {
+        assert_eq!(
+            self.cells().ncols(),
+            other.cells().ncols(),
+            "grids must have the same width"
+        );
+        let (h, w) = self.cells().dim();
+        self.with_cells(Array2::from_shape_fn(
+            (h + other.cells().nrows(), w),
+            |(y, x)| match y.checked_sub(h) {
+                None => self[(y, x)].clone(),
+                Some(y) => other[(y, x)].clone(),
+            },
+        ))
+    }
+
+    /// Make a grid like this one with different cells.
+    fn with_cells(&self, cells: Array2<S::Cell>) -> Self
+    where
+        S: Clone,
+    {
+        let (y, x) = self.boundaries();
+        let mut grid = Self::new(self.sim().clone(), cells);
+        grid.boundary = (y.clone(), x.clone());
+        grid.copier = self.copier;
+        grid.update_padding();
+        grid.generation = self.generation;
+        grid.set_seed(self.seed());
+        grid.set_tile_size(self.tile_size());
+        grid
+    }
+
+    fn transformed(&self, transform: impl FnOnce(&mut Self)) -> Self
+    where
+        Self: Clone,
+    {
+        let mut grid = self.clone();
+        transform(&mut grid);
+        grid
+    }
+
+    /// Transpose the cells if `transpose` and then flip the `(y, x)` axes which are set.
+    ///
+    /// The padding is the same width on every side, so it is moved along with the cells.
+    fn transform(&mut self, transpose: bool, (flip_y, flip_x): (bool, bool)) {
+        let mut cells = mem::replace(
+            &mut self.cells,
+            Array2::from_shape_vec((0, 0), vec![]).unwrap(),
+        );
+        if transpose {
+            cells = cells.reversed_axes();
+            mem::swap(&mut self.boundary.0, &mut self.boundary.1);
+        }
+        if flip_y {
+            cells.invert_axis(Axis(0));
+        }
+        if flip_x {
+            cells.invert_axis(Axis(1));
+        }
+        self.cells = cells.as_standard_layout().into_owned();
+        self.changed = None;
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+        self.update_padding();
+    }
+}
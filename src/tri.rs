@@ -0,0 +1,193 @@
+use crate::Neighborhood;
+use ndarray::{ArrayView2, IndexLonger};
+use std::marker::PhantomData;
+
+/// A neighborhood that can be simulated on a [`TriGrid`](crate::TriGrid).
+///
+/// The cell at `(y, x)` points up when `y + x` is even and down otherwise, so the neighbors
+/// of a cell depend on which way it points. On egress, each edge holds the flow sent towards
+/// that neighbor. On ingress, each edge holds the flow received from that neighbor.
+pub trait TriNeighborhood: Neighborhood {
+    /// The number of edges.
+    const EDGES: usize;
+
+    /// The `(y, x)` offset of an edge of a cell pointing up if `up` and down otherwise.
+    fn delta(edge: usize, up: bool) -> (isize, isize);
+
+    /// Make the neighbors from a 3x5 window centered on a cell pointing up if `up`.
+    fn neighbors<T>(window: ArrayView2<'_, T>, up: bool) -> Self::Neighbors<'_, T>;
+
+    /// Make edges by calling `f` once for each edge in order.
+    fn edges<T>(f: impl FnMut() -> T) -> Self::Edges<T>;
+
+    /// Get the edges as a slice indexed by edge.
+    fn edges_mut<T>(edges: &mut Self::Edges<T>) -> &mut [T];
+
+    /// The edge of the neighbor across `edge` which points back at a cell pointing up if `up`.
+    fn opposite(edge: usize, up: bool) -> usize {
+        let (dy, dx) = Self::delta(edge, up);
+        let neighbor_up = up ^ ((dy + dx) % 2 != 0);
+        (0..Self::EDGES)
+            .find(|&e| Self::delta(e, neighbor_up) == (-dy, -dx))
+            .expect("every neighbor must point back")
+    }
+}
+
+/// The three triangles sharing a side with a triangle.
+///
+/// Edge `0` is to the right, edge `1` is across the base, which is below a triangle pointing up
+/// and above one pointing down, and edge `2` is to the left.
+pub enum Tri {}
+
+impl Neighborhood for Tri {
+    type Neighbors<'a, T: 'a> = TriNeighbors<'a, T, Tri>;
+    type Edges<T> = [T; 3];
+}
+
+impl TriNeighborhood for Tri {
+    const EDGES: usize = 3;
+
+    fn delta(edge: usize, up: bool) -> (isize, isize) {
+        match edge {
+            0 => (0, 1),
+            1 => (if up { 1 } else { -1 }, 0),
+            2 => (0, -1),
+            _ => panic!("triangles only have three sides"),
+        }
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>, up: bool) -> TriNeighbors<'_, T, Tri> {
+        TriNeighbors::new(window, up)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 3] {
+        [(); 3].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 3]) -> &mut [T] {
+        edges
+    }
+
+    fn opposite(edge: usize, _: bool) -> usize {
+        2 - edge
+    }
+}
+
+/// The twelve triangles sharing a side or a corner with a triangle.
+///
+/// Edges are in row-major order of their offsets, which are every cell of the 3x5 window
+/// except the center and the two far corners on the side of the point.
+pub enum TriVertex {}
+
+impl Neighborhood for TriVertex {
+    type Neighbors<'a, T: 'a> = TriNeighbors<'a, T, TriVertex>;
+    type Edges<T> = [T; 12];
+}
+
+impl TriNeighborhood for TriVertex {
+    const EDGES: usize = 12;
+
+    fn delta(edge: usize, up: bool) -> (isize, isize) {
+        const UP: [(isize, isize); 12] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -2),
+            (0, -1),
+            (0, 1),
+            (0, 2),
+            (1, -2),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+        ];
+        const DOWN: [(isize, isize); 12] = [
+            (-1, -2),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (-1, 2),
+            (0, -2),
+            (0, -1),
+            (0, 1),
+            (0, 2),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        if up {
+            UP[edge]
+        } else {
+            DOWN[edge]
+        }
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>, up: bool) -> TriNeighbors<'_, T, TriVertex> {
+        TriNeighbors::new(window, up)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 12] {
+        [(); 12].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 12]) -> &mut [T] {
+        edges
+    }
+}
+
+/// The cells surrounding a triangle, along with the triangle itself.
+#[derive(Debug)]
+pub struct TriNeighbors<'a, T, N = Tri> {
+    window: ArrayView2<'a, T>,
+    up: bool,
+    _neighborhood: PhantomData<N>,
+}
+
+impl<'a, T, N> Clone for TriNeighbors<'a, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, N> Copy for TriNeighbors<'a, T, N> {}
+
+impl<'a, T, N> TriNeighbors<'a, T, N>
+where
+    N: TriNeighborhood,
+{
+    /// Wraps a 3x5 window centered on a triangle which points up if `up`.
+    pub fn new(window: ArrayView2<'a, T>, up: bool) -> Self {
+        assert_eq!(window.dim(), (3, 5), "tri neighbors need a 3x5 window");
+        Self {
+            window,
+            up,
+            _neighborhood: PhantomData,
+        }
+    }
+
+    /// Whether the triangle in the middle points up.
+    pub fn is_up(&self) -> bool {
+        self.up
+    }
+
+    /// The triangle in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        IndexLonger::index(&self.window, (1, 2))
+    }
+
+    /// The neighbor across an edge.
+    pub fn get(&self, edge: usize) -> &'a T {
+        let (dy, dx) = N::delta(edge, self.up);
+        IndexLonger::index(&self.window, ((1 + dy) as usize, (2 + dx) as usize))
+    }
+
+    /// Iterate over the neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a
+    where
+        N: 'a,
+    {
+        let this = *self;
+        (0..N::EDGES).map(move |edge| this.get(edge))
+    }
+}
@@ -0,0 +1,131 @@
+#![allow(clippy::reversed_empty_ranges)]
+
+use crate::{
+    engine::{self, Lattice, Link},
+    Sim, TriNeighborhood,
+};
+use ndarray::{s, Array2, ArrayView2, ArrayViewMut2, Ix2};
+use std::mem;
+
+/// Represents the state of a simulation on a grid of triangles.
+///
+/// Cells are indexed by `(y, x)`. The cell at `(y, x)` points up when `y + x` is even and down
+/// otherwise, so each row is a strip of alternating triangles.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Cell: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Cell: serde::Deserialize<'de>"
+    ))
+)]
+pub struct TriGrid<S, N>
+where
+    S: Sim<N>,
+    N: TriNeighborhood,
+{
+    sim: S,
+    cells: Array2<S::Cell>,
+    /// The diffs of the last step, which are kept so their allocation can be reused.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    diffs: Option<Array2<S::Diff>>,
+}
+
+impl<S, N> TriGrid<S, N>
+where
+    S: Sim<N>,
+    N: TriNeighborhood,
+    S::Cell: Send,
+{
+    /// Make a new grid with the given cells.
+    pub fn new(sim: S, mut original_cells: Array2<S::Cell>) -> Self {
+        let dims = original_cells.dim();
+        assert!(
+            dims.0 >= 1 && dims.1 >= 1,
+            "grid is empty, which isnt allowed"
+        );
+        let mut cells =
+            Array2::from_shape_simple_fn((dims.0 + 2, dims.1 + 4), || sim.cell_padding());
+        maybe_par_azip!((dest in &mut cells.slice_mut(s![1..-1, 2..-2]), cell in &mut original_cells) {
+            mem::swap(dest, cell);
+        });
+        Self {
+            sim,
+            cells,
+            diffs: None,
+        }
+    }
+
+    /// Check if the cell at a `(y, x)` index points up.
+    pub fn is_up((y, x): (usize, usize)) -> bool {
+        (y + x) % 2 == 0
+    }
+
+    /// Get view of cells on the grid, indexed by `(y, x)`.
+    pub fn cells(&self) -> ArrayView2<'_, S::Cell> {
+        self.cells.slice(s![1..-1, 2..-2])
+    }
+
+    /// Get mutable view of cells on the grid, indexed by `(y, x)`.
+    pub fn cells_mut(&mut self) -> ArrayViewMut2<'_, S::Cell> {
+        self.cells.slice_mut(s![1..-1, 2..-2])
+    }
+}
+
+impl<S, N> TriGrid<S, N>
+where
+    S: Sim<N> + Sync,
+    N: TriNeighborhood,
+    S::Cell: Send + Sync,
+    S::Diff: Send + Sync,
+    N::Edges<S::Flow>: Send,
+{
+    engine::step_methods!(N);
+}
+
+impl<N> Lattice<Ix2> for N
+where
+    N: TriNeighborhood,
+{
+    fn padding() -> Ix2 {
+        Ix2(1, 2)
+    }
+
+    fn window() -> Ix2 {
+        Ix2(3, 5)
+    }
+
+    fn neighbors<'a, T: 'a>(index: &[usize], window: ArrayView2<'a, T>) -> N::Neighbors<'a, T> {
+        let up = (index[0] + index[1]).is_multiple_of(2);
+        <N as TriNeighborhood>::neighbors(window, up)
+    }
+
+    fn edges<T>(f: impl FnMut() -> T) -> N::Edges<T> {
+        <N as TriNeighborhood>::edges(f)
+    }
+
+    fn edges_mut<T>(edges: &mut N::Edges<T>) -> &mut [T] {
+        <N as TriNeighborhood>::edges_mut(edges)
+    }
+
+    // The neighbors of a cell depend on which way it points, so each pair of neighbors is
+    // swapped from the cell which comes first, which points up if the sum of its padded
+    // indices is odd.
+    fn links() -> Vec<Link> {
+        (0..2)
+            .flat_map(|parity| {
+                let up = parity == 1;
+                (0..N::EDGES).filter_map(move |edge| {
+                    let (dy, dx) = N::delta(edge, up);
+                    ((dy, dx) > (0, 0)).then(|| Link {
+                        edge,
+                        opposite: N::opposite(edge, up),
+                        delta: vec![dy, dx],
+                        parity: Some(parity),
+                    })
+                })
+            })
+            .collect()
+    }
+}
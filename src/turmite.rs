@@ -0,0 +1,177 @@
+use crate::{resolve_first, Moore, Sim};
+use ndarray::ArrayView2;
+
+/// The way an ant is facing, ordered counter-clockwise starting from the right.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heading {
+    East,
+    North,
+    West,
+    South,
+}
+
+impl Heading {
+    const ALL: [Heading; 4] = [Heading::East, Heading::North, Heading::West, Heading::South];
+
+    /// The heading after making a turn.
+    pub fn turn(self, turn: Turn) -> Self {
+        let quarters = match turn {
+            Turn::None => 0,
+            Turn::Left => 1,
+            Turn::UTurn => 2,
+            Turn::Right => 3,
+        };
+        Self::ALL[(self as usize + quarters) % 4]
+    }
+
+    /// The edge of the [`Moore`] neighborhood the ant moves through.
+    pub fn edge(self) -> usize {
+        self as usize * 2
+    }
+}
+
+/// A turn relative to the current heading.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+/// What an ant does when it is in a given state standing on a given color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition {
+    /// The color written to the cell.
+    pub color: u8,
+    /// The turn made before moving forward.
+    pub turn: Turn,
+    /// The next state of the ant.
+    pub state: u8,
+}
+
+/// An ant that walks across the grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ant {
+    pub heading: Heading,
+    pub state: u8,
+}
+
+/// A cell of a [`Turmite`] simulation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurmiteCell {
+    pub color: u8,
+    pub ant: Option<Ant>,
+}
+
+/// A generalized Langton's ant where ants move between cells as flows.
+///
+/// Each step, every ant looks up the transition for its state and the color beneath it,
+/// paints the cell, turns, and moves forward one cell. Ants that walk off the grid are lost.
+/// If several ants arrive at the same cell, the one coming from the lowest edge is kept.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Turmite {
+    table: Vec<Vec<Transition>>,
+}
+
+impl Turmite {
+    /// Make a turmite from a table of transitions indexed by `[state][color]`.
+    pub fn new(table: Vec<Vec<Transition>>) -> Self {
+        assert!(!table.is_empty(), "turmite must have at least one state");
+        let colors = table[0].len();
+        for transitions in &table {
+            assert_eq!(
+                transitions.len(),
+                colors,
+                "every state must handle every color"
+            );
+            for transition in transitions {
+                assert!(
+                    (transition.color as usize) < colors
+                        && (transition.state as usize) < table.len(),
+                    "transition leads to an unknown color or state"
+                );
+            }
+        }
+        Self { table }
+    }
+
+    /// Make a single-state ant which cycles through one color per turn, such as `"RL"`
+    /// for Langton's ant or `"LLRR"`.
+    ///
+    /// The turns are `L` for left, `R` for right, `N` for none, and `U` for a U-turn.
+    pub fn from_turns(turns: &str) -> Self {
+        let turns: Vec<Turn> = turns
+            .chars()
+            .map(|c| match c {
+                'L' => Turn::Left,
+                'R' => Turn::Right,
+                'N' => Turn::None,
+                'U' => Turn::UTurn,
+                _ => panic!("invalid turn {:?}", c),
+            })
+            .collect();
+        let colors = turns.len();
+        Self::new(vec![turns
+            .into_iter()
+            .enumerate()
+            .map(|(color, turn)| Transition {
+                color: ((color + 1) % colors) as u8,
+                turn,
+                state: 0,
+            })
+            .collect()])
+    }
+
+    /// Langton's ant, which turns right on color `0` and left on color `1`.
+    pub fn langtons_ant() -> Self {
+        Self::from_turns("RL")
+    }
+
+    /// The transition for an ant in a state standing on a color.
+    pub fn transition(&self, state: u8, color: u8) -> Transition {
+        self.table[state as usize][color as usize]
+    }
+}
+
+impl Sim<Moore> for Turmite {
+    type Cell = TurmiteCell;
+    type Diff = ();
+    type Flow = Option<Ant>;
+
+    fn compute(&self, _: ArrayView2<'_, TurmiteCell>) {}
+
+    fn egress(&self, cell: &mut TurmiteCell, _: ArrayView2<'_, ()>) -> [Option<Ant>; 8] {
+        let mut flows = [None; 8];
+        if let Some(ant) = cell.ant.take() {
+            let transition = self.transition(ant.state, cell.color);
+            cell.color = transition.color;
+            let heading = ant.heading.turn(transition.turn);
+            flows[heading.edge()] = Some(Ant {
+                heading,
+                state: transition.state,
+            });
+        }
+        flows
+    }
+
+    fn ingress(&self, cell: &mut TurmiteCell, mut flows: [Option<Ant>; 8]) {
+        cell.ant = resolve_first(&mut flows).into_winner();
+    }
+
+    fn cell_padding(&self) -> TurmiteCell {
+        TurmiteCell::default()
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Option<Ant> {
+        None
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{population::Tally, CellRng, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{s, Array2};
+use std::{collections::BTreeMap, mem};
+
+/// The order in which the cells of a [`SquareGrid`] are updated in a step.
+///
+/// Every order other than [`UpdateOrder::Synchronous`] updates the cells on the current thread.
+/// When cells are updated on their own, the neighbors of a cell see the padding diff during
+/// its egress, and the neighbors it sends flows to are given them right away with the padding
+/// flow on their other edges, so each update of a cell can call ingress on its neighbors.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpdateOrder {
+    /// Every cell is updated at once from the cells as they were at the start of the step.
+    #[default]
+    Synchronous,
+    /// As many times as there are cells, a random cell is updated on its own, so some cells
+    /// are updated more than once in a step and some aren't updated at all.
+    ///
+    /// The cells are picked with a generator seeded from the seed and generation of the grid.
+    RandomSequential,
+    /// Every cell is updated on its own in row-major order.
+    Sweep,
+    /// The grid is split into blocks of `(height, width)` cells which are updated one at a time
+    /// in a random order, with the cells of each block updated at once.
+    Blocks((usize, usize)),
+}
+
+impl<S, N> SquareGrid<S, N>
+where
+    S: Sim<N>,
+    N: SquareNeighborhood,
+    S::Cell: Send,
+{
+    /// Get the order in which cells are updated in a step.
+    pub fn update_order(&self) -> UpdateOrder {
+        self.update_order
+    }
+
+    /// Set the order in which cells are updated in a step.
+    pub fn set_update_order(&mut self, update_order: UpdateOrder) {
+        if let UpdateOrder::Blocks((h, w)) = update_order {
+            assert!(h >= 1 && w >= 1, "blocks must have at least one cell");
+        }
+        self.update_order = update_order;
+    }
+
+    /// Update the cells in an order other than [`UpdateOrder::Synchronous`].
+    pub(crate) fn sweep_async(&mut self, tally: Option<&Tally<S::Cell>>) {
+        let (h, w) = self.cells().dim();
+        let mut rng = CellRng::new(self.seed(), self.generation, (h, w));
+        let mut diffs = Array2::from_shape_simple_fn(self.cells.dim(), || self.sim.diff_padding());
+        match self.update_order {
+            UpdateOrder::Synchronous => unreachable!("synchronous steps aren't asynchronous"),
+            UpdateOrder::RandomSequential => {
+                for _ in 0..h * w {
+                    let index = (
+                        rng.gen_range(h as u64) as usize,
+                        rng.gen_range(w as u64) as usize,
+                    );
+                    self.update_cells(&[index], &mut diffs, tally);
+                }
+            }
+            UpdateOrder::Sweep => {
+                for index in (0..h).flat_map(|y| (0..w).map(move |x| (y, x))) {
+                    self.update_cells(&[index], &mut diffs, tally);
+                }
+            }
+            UpdateOrder::Blocks((bh, bw)) => {
+                let mut blocks: Vec<(usize, usize)> = (0..h)
+                    .step_by(bh)
+                    .flat_map(|y| (0..w).step_by(bw).map(move |x| (y, x)))
+                    .collect();
+                for i in (1..blocks.len()).rev() {
+                    blocks.swap(i, rng.gen_range(i as u64 + 1) as usize);
+                }
+                for (by, bx) in blocks {
+                    let indices: Vec<_> = (by..(by + bh).min(h))
+                        .flat_map(|y| (bx..(bx + bw).min(w)).map(move |x| (y, x)))
+                        .collect();
+                    self.update_cells(&indices, &mut diffs, tally);
+                }
+            }
+        }
+    }
+
+    /// Update only the cells at the `(y, x)` indices at once, leaving `diffs` as padding.
+    fn update_cells(
+        &mut self,
+        indices: &[(usize, usize)],
+        diffs: &mut Array2<S::Diff>,
+        tally: Option<&Tally<S::Cell>>,
+    ) {
+        let r = N::RADIUS;
+        let d = 2 * r + 1;
+        let (h, w) = self.cells().dim();
+        let indices: Vec<(usize, usize)> = indices
+            .iter()
+            .copied()
+            .filter(|&index| self.in_domain(index))
+            .collect();
+        let indices = &indices[..];
+        for &(y, x) in indices {
+            let window = self.cells.slice(s![y..y + d, x..x + d]);
+            let location = self.locate((y, x));
+            diffs[(y + r, x + r)] = self.sim.compute(N::located_neighbors(window, location));
+        }
+
+        let sim = &self.sim;
+        let mut received = BTreeMap::new();
+        for &(y, x) in indices {
+            received
+                .entry((y, x))
+                .or_insert_with(|| N::edges(|| sim.flow_padding()));
+        }
+        for &(y, x) in indices {
+            let location = self.locate((y, x));
+            let window = diffs.slice(s![y..y + d, x..x + d]);
+            let mut flows = Tally::track(tally, &mut self.cells[(y + r, x + r)], |cell| {
+                sim.egress(cell, N::located_neighbors(window, location))
+            });
+            for (edge, flow) in N::edges_mut(&mut flows).iter_mut().enumerate() {
+                let (dy, dx) = N::delta(edge);
+                let to = (y as isize + dy, x as isize + dx);
+                let target = match self.wrap(to) {
+                    Some(target) if self.in_domain(target) => target,
+                    _ => continue,
+                };
+                // Crossing a twisted edge mirrors the direction the flow arrives from.
+                let to = self.shifted(to, (h, w));
+                let flip_y = self.boundary.1.twists(to.1, w);
+                let flip_x = self.boundary.0.twists(to.0, h);
+                let delta = (if flip_y { -dy } else { dy }, if flip_x { -dx } else { dx });
+                let arriving = N::edge(delta).unwrap();
+                let edges = received
+                    .entry(target)
+                    .or_insert_with(|| N::edges(|| sim.flow_padding()));
+                mem::swap(&mut N::edges_mut(edges)[N::opposite(arriving)], flow);
+            }
+        }
+        for &(y, x) in indices {
+            diffs[(y + r, x + r)] = sim.diff_padding();
+        }
+
+        let near_edge = |&(y, x): &(usize, usize)| y < r || x < r || y + r >= h || x + r >= w;
+        let touched_edge = received.keys().any(near_edge);
+        for ((y, x), flows) in received {
+            Tally::track(tally, &mut self.cells[(y + r, x + r)], |cell| {
+                sim.ingress(cell, flows)
+            });
+        }
+        if touched_edge {
+            self.refresh_padding();
+        }
+    }
+}
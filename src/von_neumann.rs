@@ -0,0 +1,103 @@
+use crate::{Neighborhood, SquareNeighborhood};
+use ndarray::{ArrayView2, IndexLonger};
+use std::ops::Index;
+
+/// The four cells sharing a side with a square.
+///
+/// Edges are ordered counter-clockwise starting from the right, so edge `0` is to the right,
+/// `1` is above, `2` is to the left, and `3` is below. On egress, each edge holds the flow sent
+/// towards that side. On ingress, each edge holds the flow received from that side.
+pub enum VonNeumann {}
+
+impl VonNeumann {
+    /// The `(y, x)` offset of each edge, which can be used to pick a neighbor to move to.
+    pub const DELTAS: [(isize, isize); 4] = [(0, 1), (-1, 0), (0, -1), (1, 0)];
+}
+
+impl Neighborhood for VonNeumann {
+    type Neighbors<'a, T: 'a> = VonNeumannNeighbors<'a, T>;
+    type Edges<T> = [T; 4];
+}
+
+impl SquareNeighborhood for VonNeumann {
+    const RADIUS: usize = 1;
+    const EDGES: usize = 4;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        Self::DELTAS[edge]
+    }
+
+    fn opposite(edge: usize) -> usize {
+        (edge + 2) % 4
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> VonNeumannNeighbors<'_, T> {
+        VonNeumannNeighbors::new(window)
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 4] {
+        [(); 4].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 4]) -> &mut [T] {
+        edges
+    }
+}
+
+/// The cells sharing a side with a square, along with the square itself.
+#[derive(Debug)]
+pub struct VonNeumannNeighbors<'a, T> {
+    window: ArrayView2<'a, T>,
+}
+
+impl<'a, T> Clone for VonNeumannNeighbors<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for VonNeumannNeighbors<'a, T> {}
+
+impl<'a, T> VonNeumannNeighbors<'a, T> {
+    /// Wraps a 3x3 window centered on the square.
+    ///
+    /// The corners of the window are not neighbors and are ignored.
+    pub fn new(window: ArrayView2<'a, T>) -> Self {
+        assert_eq!(
+            window.dim(),
+            (3, 3),
+            "von Neumann neighbors need a 3x3 window"
+        );
+        Self { window }
+    }
+
+    /// The square in the middle of the neighborhood.
+    pub fn center(&self) -> &'a T {
+        IndexLonger::index(&self.window, (1, 1))
+    }
+
+    /// The neighbor across an edge.
+    pub fn get(&self, edge: usize) -> &'a T {
+        let (y, x) = VonNeumann::DELTAS[edge];
+        IndexLonger::index(&self.window, ((1 + y) as usize, (1 + x) as usize))
+    }
+
+    /// Iterate over the four neighbors in edge order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let this = *self;
+        (0..4).map(move |edge| this.get(edge))
+    }
+
+    /// The 3x3 window indexed by `(y, x)`.
+    pub fn window(&self) -> ArrayView2<'a, T> {
+        self.window
+    }
+}
+
+impl<'a, T> Index<usize> for VonNeumannNeighbors<'a, T> {
+    type Output = T;
+
+    fn index(&self, edge: usize) -> &T {
+        self.get(edge)
+    }
+}
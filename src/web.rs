@@ -0,0 +1,108 @@
+//! A grid with an API made of types which `wasm-bindgen` can pass to JavaScript, for running
+//! simulations in the browser.
+//!
+//! Gridsim builds for `wasm32-unknown-unknown` with `default-features = false`, which steps
+//! grids on a single thread. With the `wasm` feature, [`WebGrid`] is exported to JavaScript
+//! with `wasm-bindgen`, and the frames are drawn by putting the `Uint8ClampedArray` from
+//! `pixels()` in an `ImageData`.
+
+use crate::{BitGrid, CellRng, LifeLikeRule, Render};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A grid of a life-like rule which keeps a framebuffer of its cells for drawing.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct WebGrid {
+    grid: BitGrid,
+    pixels: Vec<u8>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl WebGrid {
+    /// Make a grid of dead cells with a rulestring like `B3/S23`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(rule: &str, width: u32, height: u32) -> Result<WebGrid, String> {
+        let rule = LifeLikeRule::parse(rule).map_err(|e| e.to_string())?;
+        if width == 0 || height == 0 {
+            return Err("grid is empty, which isnt allowed".to_owned());
+        }
+        Ok(Self {
+            grid: BitGrid::new(rule, (height as usize, width as usize)),
+            pixels: vec![],
+        })
+    }
+
+    /// The width of the grid in cells.
+    pub fn width(&self) -> u32 {
+        self.grid.dim().1 as u32
+    }
+
+    /// The height of the grid in cells.
+    pub fn height(&self) -> u32 {
+        self.grid.dim().0 as u32
+    }
+
+    /// The number of steps the grid has taken.
+    pub fn generation(&self) -> u64 {
+        self.grid.generation()
+    }
+
+    /// Get the cell at a row and column, or `false` if it is outside of the grid.
+    pub fn get(&self, y: u32, x: u32) -> bool {
+        self.contains(y, x) && self.grid.get((y as usize, x as usize))
+    }
+
+    /// Set the cell at a row and column, doing nothing if it is outside of the grid.
+    pub fn set(&mut self, y: u32, x: u32, alive: bool) {
+        if self.contains(y, x) {
+            self.grid.set((y as usize, x as usize), alive);
+        }
+    }
+
+    /// Make each cell alive with a probability of `density`, using `seed` to pick them.
+    pub fn randomize(&mut self, seed: u64, density: f64) {
+        let (h, w) = self.grid.dim();
+        for y in 0..h {
+            for x in 0..w {
+                let alive = CellRng::new(seed, 0, (y, x)).gen_bool(density);
+                self.grid.set((y, x), alive);
+            }
+        }
+    }
+
+    /// Simulate one step.
+    pub fn step(&mut self) {
+        self.grid.step();
+    }
+
+    /// Simulate `n` steps.
+    pub fn step_n(&mut self, n: u32) {
+        self.grid.step_n(n as u64);
+    }
+
+    /// The number of bytes in the framebuffer, which is four for every cell.
+    pub fn pixels_len(&self) -> u32 {
+        4 * self.width() * self.height()
+    }
+
+    /// Draw the cells and copy their RGBA bytes into an array for an `ImageData`.
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(js_name = pixels)]
+    pub fn pixel_array(&mut self) -> js_sys::Uint8ClampedArray {
+        js_sys::Uint8ClampedArray::from(self.pixels())
+    }
+}
+
+impl WebGrid {
+    /// Draw the cells into the framebuffer and get its RGBA bytes.
+    pub fn pixels(&mut self) -> &[u8] {
+        self.pixels.resize(self.pixels_len() as usize, 0);
+        self.grid.render_into(&mut self.pixels);
+        &self.pixels
+    }
+
+    fn contains(&self, y: u32, x: u32) -> bool {
+        y < self.height() && x < self.width()
+    }
+}
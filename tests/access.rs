@@ -0,0 +1,45 @@
+use gridsim::{rules, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn access_indexing() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((3, 4), false));
+    grid[(1, 3)] = true;
+    *grid.get_mut((2, 0)).unwrap() = true;
+    assert!(grid[(1, 3)]);
+    assert_eq!(grid.get((2, 0)), Some(&true));
+    assert_eq!(grid.get((0, 0)), Some(&false));
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.get((0, 4)), None);
+    assert!(grid.get_mut((0, 4)).is_none());
+    assert_eq!(grid.cells().iter().filter(|&&c| c).count(), 2);
+}
+
+#[test]
+fn access_iter_coords() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((3, 4), false));
+    for ((y, x), cell) in grid.iter_mut_coords() {
+        *cell = y == x;
+    }
+    let coords: Vec<_> = grid
+        .iter_coords()
+        .filter(|&(_, &c)| c)
+        .map(|(coord, _)| coord)
+        .collect();
+    assert_eq!(coords, vec![(0, 0), (1, 1), (2, 2)]);
+    let rows: Vec<&[bool]> = grid.rows().collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1], &[false, true, false, false]);
+}
+
+#[test]
+#[cfg(feature = "use-rayon")]
+fn access_par_iter() {
+    use ndarray::parallel::prelude::*;
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((16, 9), false));
+    grid.par_iter_mut()
+        .for_each(|((y, x), cell)| *cell = (y + x) % 3 == 0);
+    let count = grid.par_iter().filter(|&(_, &c)| c).count();
+    assert_eq!(count, grid.iter_coords().filter(|&(_, &c)| c).count());
+    assert!(grid.par_iter().all(|((y, x), &c)| c == ((y + x) % 3 == 0)));
+}
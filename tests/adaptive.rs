@@ -0,0 +1,67 @@
+use gridsim::Moore;
+use gridsim::{rules, AdaptiveGrid, Hpp, Sim, SquareGrid};
+use ndarray::Array2;
+use std::fmt::Debug;
+
+/// Step an adaptive grid and a flat grid with the same cells and check they stay the same.
+fn check_matches_flat<S>(sim: S, cells: &[((usize, usize), S::Cell)], steps: usize)
+where
+    S: Sim<Moore> + Clone + Send + Sync,
+    S::Cell: Clone + PartialEq + Debug + Send + Sync,
+    S::Diff: Clone + Send + Sync,
+    S::Flow: Send,
+{
+    let mut adaptive = AdaptiveGrid::new(sim.clone(), 3, 4, sim.cell_padding());
+    for (index, cell) in cells {
+        adaptive.set(*index, cell.clone());
+    }
+    let mut flat = SquareGrid::new(sim, adaptive.to_array());
+    for _ in 0..steps {
+        adaptive.step();
+        flat.step();
+        assert_eq!(adaptive.to_array(), flat.cells());
+    }
+}
+
+#[test]
+fn adaptive_life_matches_flat() {
+    let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+    let cells: Vec<_> = glider
+        .iter()
+        .map(|&(y, x)| ((y + 3, x + 5), true))
+        .collect();
+    check_matches_flat(rules::life(), &cells, 60);
+
+    // The glider crashes into the corner and leaves a block behind.
+    let mut grid = AdaptiveGrid::new(rules::life(), 3, 4, false);
+    for &(index, cell) in &cells {
+        grid.set(index, cell);
+    }
+    assert_eq!(grid.fine_tiles(), 2);
+    grid.step();
+    // Tiles near the glider and near the edges of the grid are stepped, but not the middle.
+    assert!(grid.stepped_tiles() < 64);
+}
+
+#[test]
+fn adaptive_flows_match_flat() {
+    let cells = [((9, 9), 0b0101), ((20, 4), 0b1111), ((12, 30), 0b0010)];
+    check_matches_flat(Hpp::default(), &cells, 40);
+}
+
+#[test]
+fn adaptive_coarsens() {
+    let mut grid = AdaptiveGrid::new(rules::life(), 4, 8, false);
+    assert_eq!(grid.size(), 128);
+    assert_eq!(grid.fine_tiles(), 0);
+    // A lone cell dies and its tile is stored as one cell again.
+    grid.set((64, 64), true);
+    assert_eq!(grid.get((64, 64)), Some(&true));
+    assert_eq!(grid.fine_tiles(), 1);
+    grid.step();
+    assert_eq!(grid.fine_tiles(), 0);
+    assert_eq!(grid.get((64, 64)), Some(&false));
+    assert_eq!(grid.get((128, 0)), None);
+    assert_eq!(grid.generation(), 1);
+    assert_eq!(grid.to_array(), Array2::from_elem((128, 128), false));
+}
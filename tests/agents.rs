@@ -0,0 +1,83 @@
+use gridsim::AgentLayer;
+
+#[test]
+fn agents_spawn_and_index() {
+    let mut layer = AgentLayer::new((4, 5));
+    let a = layer.spawn((1, 1), "a").unwrap();
+    let b = layer.spawn((2, 3), "b").unwrap();
+    assert_eq!(layer.spawn((1, 1), "c"), None);
+    assert_eq!(layer.spawn((4, 0), "c"), None);
+    assert_eq!(layer.len(), 2);
+    assert_eq!(layer.at((2, 3)), Some(b));
+    assert_eq!(layer.position(a), Some((1, 1)));
+    assert_eq!(layer.remove(a), Some("a"));
+    assert_eq!(layer.at((1, 1)), None);
+    let c = layer.spawn((1, 1), "c").unwrap();
+    assert_ne!(c, a);
+    assert_eq!(
+        layer.iter().map(|agent| *agent.state).collect::<Vec<_>>(),
+        ["b", "c"]
+    );
+
+    assert_eq!(layer.offset((0, 0), (-1, 0)), None);
+    layer.set_wrap(true);
+    assert_eq!(layer.offset((0, 0), (-1, -2)), Some((3, 3)));
+}
+
+#[test]
+fn agents_move_and_resolve_conflicts() {
+    // Agents all walking east in a row, with one wall agent that never moves.
+    let mut layer = AgentLayer::new((3, 8));
+    let walkers: Vec<_> = (0..3).map(|x| layer.spawn((1, x), true).unwrap()).collect();
+    let wall = layer.spawn((1, 5), false).unwrap();
+    let east = |agent: gridsim::AgentRef<'_, bool>, layer: &AgentLayer<bool>| {
+        if *agent.state {
+            layer.offset(agent.position, (0, 1))
+        } else {
+            None
+        }
+    };
+    layer.step(east);
+    let row: Vec<_> = walkers
+        .iter()
+        .map(|&id| layer.position(id).unwrap())
+        .collect();
+    assert_eq!(row, [(1, 1), (1, 2), (1, 3)]);
+    layer.step(east);
+    layer.step(east);
+    let row: Vec<_> = walkers
+        .iter()
+        .map(|&id| layer.position(id).unwrap())
+        .collect();
+    assert_eq!(row, [(1, 2), (1, 3), (1, 4)]);
+    assert_eq!(layer.position(wall), Some((1, 5)));
+    assert_eq!(layer.generation(), 3);
+
+    // Two agents wanting the same cell, where the winner only depends on the seed.
+    let winners: Vec<_> = (0..16)
+        .map(|seed| {
+            let mut layer = AgentLayer::new((3, 3));
+            let left = layer.spawn((1, 0), ()).unwrap();
+            layer.spawn((1, 2), ()).unwrap();
+            layer.set_seed(seed);
+            layer.step(|_, _| Some((1, 1)));
+            assert_eq!(layer.len(), 2);
+            layer.at((1, 1)) == Some(left)
+        })
+        .collect();
+    assert!(winners.contains(&true) && winners.contains(&false));
+    let mut again = AgentLayer::new((3, 3));
+    let left = again.spawn((1, 0), ()).unwrap();
+    again.spawn((1, 2), ()).unwrap();
+    again.set_seed(5);
+    again.step(|_, _| Some((1, 1)));
+    assert_eq!(again.at((1, 1)) == Some(left), winners[5]);
+
+    // Two agents swapping places.
+    let mut layer = AgentLayer::new((1, 2));
+    let a = layer.spawn((0, 0), ()).unwrap();
+    let b = layer.spawn((0, 1), ()).unwrap();
+    layer.step(|agent, _| Some((0, 1 - agent.position.1)));
+    assert_eq!(layer.at((0, 0)), Some(b));
+    assert_eq!(layer.at((0, 1)), Some(a));
+}
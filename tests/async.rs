@@ -0,0 +1,69 @@
+use gridsim::{rules, SquareGrid};
+use ndarray::Array2;
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+    thread::{self, Thread},
+};
+
+/// Wakes the thread which is blocked on a future.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(value) => return value,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn async_steps() {
+    let grid = SquareGrid::new_random(rules::life(), (20, 20), 4, |rng| rng.gen_bool(0.35));
+    let mut expected = grid.clone();
+    let grid = grid.into_async();
+
+    block_on(async {
+        grid.step().await;
+        let mut generations = grid.generations();
+        let (generation, cells) = generations.next_generation().await;
+        expected.step_n(2);
+        assert_eq!(generation, 2);
+        assert_eq!(cells, expected.cells());
+
+        // Operations run in the order they started, even if their futures are dropped.
+        drop(grid.step_n(3));
+        let cells = grid.cells();
+        expected.step_n(3);
+        assert_eq!(cells.await, expected.cells());
+    });
+
+    let generation = block_on(grid.with(|grid| {
+        grid.fill(false);
+        grid.generation()
+    }));
+    assert_eq!(generation, 5);
+    assert_eq!(grid.lock().cells(), Array2::from_elem((20, 20), false));
+    let grid = grid.try_into_inner().unwrap();
+    assert_eq!(grid.generation(), 5);
+}
+
+#[test]
+#[should_panic(expected = "region is outside of the grid")]
+fn async_panics() {
+    let grid = SquareGrid::new(rules::life(), Array2::from_elem((4, 4), false)).into_async();
+    block_on(grid.with(|grid| grid.fill_region((0..9, 0..1), true)));
+}
@@ -0,0 +1,41 @@
+use gridsim::{
+    bench::{Backend, Bench},
+    rules, GenerationsRule,
+};
+
+#[test]
+fn bench_backends() {
+    let measurements = Bench::new(rules::life(), |rng| rng.gen_bool(0.3))
+        .sizes(vec![(16, 24), (8, 8)])
+        .tile_sizes(vec![(4, 4)])
+        .steps(3)
+        .run_with_bits();
+    let runs: Vec<_> = measurements.iter().map(|m| (m.backend, m.dims)).collect();
+    assert_eq!(
+        runs,
+        [
+            (Backend::Scalar, (16, 24)),
+            (Backend::Parallel, (16, 24)),
+            (Backend::Tiled((4, 4)), (16, 24)),
+            (Backend::Scalar, (8, 8)),
+            (Backend::Parallel, (8, 8)),
+            (Backend::Tiled((4, 4)), (8, 8)),
+            (Backend::Simd, (16, 24)),
+            (Backend::Simd, (8, 8)),
+            (Backend::Bits, (16, 24)),
+            (Backend::Bits, (8, 8)),
+        ]
+    );
+    for measurement in &measurements {
+        assert_eq!(measurement.steps, 3);
+        assert!(measurement.cells_per_second() > 0.0);
+    }
+    assert!(measurements[2].to_string().starts_with("16x24 tiled 4x4: "));
+
+    let brain = GenerationsRule::parse("/2/3").unwrap();
+    let brain = Bench::new(brain, |rng| rng.gen_bool(0.3) as u8)
+        .sizes(vec![(8, 8)])
+        .tile_sizes(vec![])
+        .run_with_simd();
+    assert_eq!(brain.len(), 3);
+}
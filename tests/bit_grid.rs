@@ -0,0 +1,146 @@
+use gridsim::{BitGrid, CellColor, CellRng, LifeLikeRule, Render, SquareGrid};
+use ndarray::Array2;
+
+fn soup(dim: (usize, usize), seed: u64) -> Array2<bool> {
+    Array2::from_shape_fn(dim, |index| CellRng::new(seed, 0, index).gen_bool(0.4))
+}
+
+fn check_rule(rule: &str, dim: (usize, usize)) {
+    let rule = LifeLikeRule::parse(rule).unwrap();
+    let cells = soup(dim, 3);
+    let mut grid = SquareGrid::new(rule, cells.clone());
+    let mut bits = BitGrid::from_cells(rule, cells.view());
+    assert_eq!(bits.cells(), grid.cells());
+    for _ in 0..20 {
+        grid.step();
+        bits.step();
+        assert_eq!(bits.cells(), grid.cells());
+    }
+    assert_eq!(bits.generation(), 20);
+    assert_eq!(
+        bits.population(),
+        grid.cells().iter().filter(|&&c| c).count() as u64
+    );
+}
+
+#[test]
+fn bit_grid_matches_square_grid() {
+    for &rule in &["B3/S23", "B36/S23", "B2/S", "B3678/S34678", "B0/S8"] {
+        check_rule(rule, (17, 64));
+        check_rule(rule, (23, 70));
+        check_rule(rule, (9, 200));
+        check_rule(rule, (1, 1));
+    }
+}
+
+#[test]
+fn bit_grid_get_set() {
+    let mut grid = BitGrid::new(LifeLikeRule::parse("B3/S23").unwrap(), (5, 130));
+    assert_eq!(grid.dim(), (5, 130));
+    for &x in &[63, 64, 65] {
+        grid.set((2, x), true);
+    }
+    assert!(grid.get((2, 64)));
+    assert!(!grid.get((2, 66)));
+    grid.step();
+    assert_eq!(grid.population(), 3);
+    assert!(grid.get((1, 64)) && grid.get((2, 64)) && grid.get((3, 64)));
+    grid.set((2, 64), false);
+    assert_eq!(grid.population(), 2);
+}
+
+#[test]
+fn count_rule_step_simd() {
+    for &rule in &["B3/S23", "B2/S", "B0/S8"] {
+        let rule = LifeLikeRule::parse(rule).unwrap();
+        let cells = soup((21, 67), 5);
+        let mut grid = SquareGrid::new(rule, cells.clone());
+        let mut simd = SquareGrid::with_boundary(rule, cells.clone(), gridsim::Boundary::Wrap);
+        let mut wrapped = SquareGrid::with_boundary(rule, cells, gridsim::Boundary::Wrap);
+        let mut bits = BitGrid::from_cells(rule, grid.cells());
+        for _ in 0..10 {
+            grid.step_simd();
+            bits.step();
+            simd.step_simd();
+            wrapped.step();
+            assert_eq!(grid.cells(), bits.cells());
+            assert_eq!(simd.cells(), wrapped.cells());
+        }
+        assert_eq!(grid.generation(), 10);
+    }
+
+    let rule = gridsim::GenerationsRule::parse("345/2/4").unwrap();
+    let cells = Array2::from_shape_fn((30, 30), |index| {
+        CellRng::new(9, 0, index).gen_range(4) as u8
+    });
+    let mut grid = SquareGrid::new(rule, cells.clone());
+    let mut simd = SquareGrid::new(rule, cells);
+    for _ in 0..10 {
+        grid.step();
+        simd.step_simd();
+        assert_eq!(grid.cells(), simd.cells());
+    }
+}
+
+#[test]
+fn count_rule_step_simd_domain() {
+    let rule = LifeLikeRule::parse("B3/S23").unwrap();
+    let cells = soup((24, 40), 7);
+    let domain = Array2::from_shape_fn((24, 40), |(y, x)| (y / 4 + x / 5) % 3 != 0);
+    let mut grid = SquareGrid::with_boundary(rule, cells.clone(), gridsim::Boundary::Wrap);
+    grid.set_domain(domain.view(), true);
+    let mut simd = grid.clone();
+    for _ in 0..10 {
+        grid.step();
+        simd.step_simd();
+        assert_eq!(simd.cells(), grid.cells());
+    }
+    // The walls stay alive.
+    assert!(simd
+        .cells()
+        .iter()
+        .zip(&domain)
+        .all(|(&cell, &inside)| inside || cell));
+}
+
+#[test]
+fn count_rule_step_active() {
+    let rule = LifeLikeRule::parse("B3/S23").unwrap();
+    let mut cells = Array2::from_elem((40, 50), false);
+    for &(y, x) in &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        cells[(y + 5, x + 5)] = true;
+        cells[(y + 30, x + 45)] = true;
+    }
+    for &boundary in &[gridsim::Boundary::Wrap, gridsim::Boundary::Constant(false)] {
+        let mut grid = SquareGrid::with_boundary(rule, cells.clone(), boundary);
+        let mut active = SquareGrid::with_boundary(rule, cells.clone(), boundary);
+        assert_eq!(active.changed_cells(), None);
+        for step in 0..120 {
+            if step == 60 {
+                grid.set_cell((20, 20), true);
+                active.set_cell((20, 20), true);
+                assert_eq!(active.changed_cells(), None);
+            }
+            grid.step();
+            active.step_active();
+            assert_eq!(grid.cells(), active.cells());
+            assert!(active.changed_cells().unwrap().len() <= 30);
+        }
+    }
+}
+
+#[test]
+fn render_framebuffer() {
+    let rule = LifeLikeRule::parse("B3/S23").unwrap();
+    let cells = soup((7, 70), 2);
+    let grid = SquareGrid::new(rule, cells.clone());
+    let bits = BitGrid::from_cells(rule, cells.view());
+    assert_eq!(grid.image_dim(), (7, 70));
+    let buffer = grid.render();
+    assert_eq!(buffer.len(), 4 * 7 * 70);
+    assert_eq!(buffer, bits.render());
+    for ((y, x), &alive) in cells.indexed_iter() {
+        let i = 4 * (y * 70 + x);
+        assert_eq!(buffer[i..i + 4], alive.color());
+    }
+}
@@ -0,0 +1,563 @@
+use gridsim::{
+    rules, Boundary, Moore, Neighborhood, Radius, Side, Sim, SquareGrid, SquareNeighborhood,
+};
+use ndarray::{arr2, Array2, ArrayView2};
+use std::collections::HashSet;
+
+/// Every cell becomes the sum of its neighborhood.
+#[derive(Debug)]
+pub struct Sum;
+
+impl Sim<Moore> for Sum {
+    type Cell = u32;
+    type Diff = u32;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, u32>) -> u32 {
+        cells.sum()
+    }
+
+    fn egress(&self, cell: &mut u32, diffs: ArrayView2<'_, u32>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut u32, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> u32 {
+        0
+    }
+
+    fn diff_padding(&self) -> u32 {
+        0
+    }
+
+    fn flow_padding(&self) {}
+}
+
+/// Every cell sends its position to all neighbors and counts how many positions it receives
+/// that match the wrapped position of the neighbor.
+#[derive(Debug)]
+pub struct WrapExchange {
+    dims: (usize, usize),
+}
+
+impl<N> Sim<N> for WrapExchange
+where
+    N: SquareNeighborhood,
+{
+    type Cell = ((usize, usize), usize);
+    type Diff = ();
+    type Flow = Option<(usize, usize)>;
+
+    fn compute(&self, _: N::Neighbors<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: N::Neighbors<'_, ()>) -> N::Edges<Self::Flow> {
+        N::edges(|| Some(cell.0))
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, mut flows: N::Edges<Self::Flow>) {
+        let (y, x) = cell.0;
+        let (h, w) = self.dims;
+        for (edge, flow) in N::edges_mut(&mut flows).iter().enumerate() {
+            let (dy, dx) = N::delta(edge);
+            let expected = (
+                (y as isize + dy).rem_euclid(h as isize) as usize,
+                (x as isize + dx).rem_euclid(w as isize) as usize,
+            );
+            assert_eq!(*flow, Some(expected));
+            cell.1 += 1;
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((0, 0), 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+#[test]
+fn boundary_map() {
+    let wrap = Boundary::<()>::Wrap;
+    assert_eq!(wrap.map(-1, 4), Some(3));
+    assert_eq!(wrap.map(5, 4), Some(1));
+    assert_eq!(Boundary::<()>::Clamp.map(-2, 4), Some(0));
+    assert_eq!(Boundary::<()>::Clamp.map(6, 4), Some(3));
+    assert_eq!(Boundary::Constant(()).map(-1, 4), None);
+    assert_eq!(Boundary::Constant(()).map(2, 4), Some(2));
+    assert_eq!(Boundary::<()>::Reflect.map(-1, 4), Some(1));
+    assert_eq!(Boundary::<()>::Reflect.map(4, 4), Some(2));
+    assert_eq!(Boundary::<()>::Reflect.map(-1, 1), Some(0));
+}
+
+#[test]
+fn boundary_cells() {
+    let cases = [
+        (Boundary::Constant(0), [3, 6, 5]),
+        (Boundary::Clamp, [12, 18, 24]),
+        (Boundary::Wrap, [18, 18, 18]),
+        (Boundary::Reflect, [15, 18, 21]),
+    ];
+    for (boundary, expected) in cases.iter() {
+        let mut grid = SquareGrid::with_boundary(Sum, arr2(&[[1, 2, 3]]), *boundary);
+        grid.step();
+        assert_eq!(grid.cells(), arr2(&[*expected]), "{:?}", boundary);
+    }
+}
+
+#[test]
+fn boundary_constant_cell() {
+    let mut grid = SquareGrid::with_boundary(Sum, arr2(&[[0]]), Boundary::Constant(1));
+    grid.step();
+    assert_eq!(grid.cells(), arr2(&[[8]]));
+}
+
+#[test]
+fn boundary_wrap_glider() {
+    let start = SquareGrid::from_rle(rules::life(), "x = 6, y = 6\nbo$2bo$3o!")
+        .unwrap()
+        .cells()
+        .to_owned();
+    let mut grid = SquareGrid::with_boundary(rules::life(), start.clone(), Boundary::Wrap);
+    for _ in 0..24 {
+        grid.step();
+    }
+    assert_eq!(grid.cells(), start);
+}
+
+fn wrap_exchange<N>(dims: (usize, usize))
+where
+    N: SquareNeighborhood,
+    WrapExchange: Sim<N, Cell = ((usize, usize), usize), Diff = (), Flow = Option<(usize, usize)>>,
+    N::Edges<Option<(usize, usize)>>: Send,
+{
+    let cells = Array2::from_shape_fn(dims, |index| (index, 0));
+    let mut grid: SquareGrid<_, N> =
+        SquareGrid::with_boundary(WrapExchange { dims }, cells, Boundary::Wrap);
+    grid.step();
+    assert!(grid.cells().iter().all(|&(_, count)| count == N::EDGES));
+}
+
+/// The eight cells a knight can jump to, which only gives its offsets and gets the rest of
+/// what a grid needs from them.
+enum Knight {}
+
+impl Neighborhood for Knight {
+    type Neighbors<'a, T: 'a> = ArrayView2<'a, T>;
+    type Edges<T> = [T; 8];
+}
+
+impl SquareNeighborhood for Knight {
+    const RADIUS: usize = 2;
+    const EDGES: usize = 8;
+
+    fn delta(edge: usize) -> (isize, isize) {
+        [
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+        ][edge]
+    }
+
+    fn neighbors<T>(window: ArrayView2<'_, T>) -> ArrayView2<'_, T> {
+        window
+    }
+
+    fn edges<T>(mut f: impl FnMut() -> T) -> [T; 8] {
+        [(); 8].map(|_| f())
+    }
+
+    fn edges_mut<T>(edges: &mut [T; 8]) -> &mut [T] {
+        edges
+    }
+}
+
+#[test]
+fn boundary_wrap_flows() {
+    wrap_exchange::<Moore>((5, 4));
+    wrap_exchange::<Moore>((1, 1));
+    wrap_exchange::<Radius<2>>((6, 5));
+    wrap_exchange::<Radius<3>>((2, 3));
+    wrap_exchange::<Knight>((6, 7));
+    wrap_exchange::<Knight>((2, 1));
+}
+
+/// Every cell sends its position to all neighbors and checks that it receives the position of
+/// the cell each neighbor copies across the boundaries.
+#[derive(Debug)]
+pub struct TopologyExchange {
+    dims: (usize, usize),
+    y: Boundary<()>,
+    x: Boundary<()>,
+}
+
+impl TopologyExchange {
+    fn expected(&self, (y, x): (isize, isize)) -> Option<(usize, usize)> {
+        let (h, w) = self.dims;
+        let outside_y = !(0..h as isize).contains(&y);
+        let outside_x = !(0..w as isize).contains(&x);
+        if outside_y && !self.y.connects() || outside_x && !self.x.connects() {
+            return None;
+        }
+        let (y, x) = (y + self.x.shift(x, w), x + self.y.shift(y, h));
+        let mut sy = self.y.map(y, h)?;
+        let mut sx = self.x.map(x, w)?;
+        if self.x.twists(x, w) {
+            sy = h - 1 - sy;
+        }
+        if self.y.twists(y, h) {
+            sx = w - 1 - sx;
+        }
+        Some((sy, sx))
+    }
+}
+
+impl Sim<Moore> for TopologyExchange {
+    type Cell = ((usize, usize), usize);
+    type Diff = ();
+    type Flow = Option<(usize, usize)>;
+
+    fn compute(&self, _: ArrayView2<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: ArrayView2<'_, ()>) -> [Self::Flow; 8] {
+        [Some(cell.0); 8]
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Self::Flow; 8]) {
+        let (y, x) = cell.0;
+        for (edge, flow) in flows.iter().enumerate() {
+            let (dy, dx) = Moore::delta(edge);
+            let expected = self.expected((y as isize + dy, x as isize + dx));
+            assert_eq!(*flow, expected, "{:?} edge {}", cell.0, edge);
+            cell.1 += flow.is_some() as usize;
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((0, 0), 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+fn topology_exchange(y: Boundary<()>, x: Boundary<()>) -> Array2<usize> {
+    let dims = (4, 5);
+    let cells = Array2::from_shape_fn(dims, |index| (index, 0));
+    let convert = |boundary: Boundary<()>| match boundary {
+        Boundary::Wrap => Boundary::Wrap,
+        Boundary::Twist => Boundary::Twist,
+        Boundary::Shift(shift) => Boundary::Shift(shift),
+        Boundary::Clamp => Boundary::Clamp,
+        Boundary::Reflect => Boundary::Reflect,
+        Boundary::Halo => Boundary::Halo,
+        Boundary::Constant(()) => Boundary::Constant(((0, 0), 0)),
+    };
+    let sim = TopologyExchange { dims, y, x };
+    let mut grid = SquareGrid::with_boundaries(sim, cells, convert(y), convert(x));
+    grid.step();
+    grid.cells().map(|&(_, count)| count)
+}
+
+#[test]
+fn boundary_topologies() {
+    let constant = Boundary::Constant(());
+    // Cylinder
+    let counts = topology_exchange(constant, Boundary::Wrap);
+    assert_eq!(counts[(0, 0)], 5);
+    assert_eq!(counts[(1, 0)], 8);
+    // Möbius strip
+    let counts = topology_exchange(constant, Boundary::Twist);
+    assert_eq!(counts[(0, 4)], 5);
+    assert_eq!(counts[(2, 4)], 8);
+    // Klein bottle
+    let counts = topology_exchange(Boundary::Wrap, Boundary::Twist);
+    assert!(counts.iter().all(|&count| count == 8));
+    // Clamped edges absorb flows even when the other axis wraps.
+    let counts = topology_exchange(Boundary::Clamp, Boundary::Wrap);
+    assert_eq!(counts[(0, 0)], 5);
+    // Twisted torus
+    let counts = topology_exchange(Boundary::Shift(2), Boundary::Wrap);
+    assert!(counts.iter().all(|&count| count == 8));
+    let counts = topology_exchange(Boundary::Wrap, Boundary::Shift(-1));
+    assert!(counts.iter().all(|&count| count == 8));
+}
+
+#[test]
+fn boundary_shifted_torus_cells() {
+    let cells = Array2::from_shape_fn((3, 4), |(y, x)| (y * 4 + x) as u32);
+    let mut grid = SquareGrid::with_boundaries(Sum, cells, Boundary::Shift(1), Boundary::Wrap);
+    grid.step();
+    // Crossing the top seam moves back one column, so the row above the top row is the bottom
+    // row moved right by one cell.
+    let sum = |cells: [u32; 9]| cells.iter().sum::<u32>();
+    assert_eq!(grid.cells()[(0, 0)], sum([10, 11, 8, 3, 0, 1, 7, 4, 5]));
+    // Crossing the bottom seam moves forward one column.
+    assert_eq!(grid.cells()[(2, 3)], sum([6, 7, 4, 10, 11, 8, 3, 0, 1]));
+}
+
+#[test]
+fn boundary_mobius_cells() {
+    let cells = Array2::from_shape_fn((3, 3), |(y, x)| (y * 3 + x) as u32);
+    let mut grid = SquareGrid::with_boundaries(Sum, cells, Boundary::Constant(0), Boundary::Twist);
+    grid.step();
+    // The left neighbors of the top left cell wrap around to the right edge upside down.
+    assert_eq!(grid.cells()[(0, 0)], 1 + 3 + 4 + 8 + 5);
+    assert_eq!(
+        grid.boundaries(),
+        (&Boundary::Constant(0), &Boundary::Twist)
+    );
+}
+
+#[test]
+fn boundary_halo_wraps_itself() {
+    let start = SquareGrid::from_rle(rules::life(), "x = 7, y = 6\nbo$2bo$3o!")
+        .unwrap()
+        .cells()
+        .to_owned();
+    let mut wrapped = SquareGrid::with_boundary(rules::life(), start.clone(), Boundary::Wrap);
+    let mut halo = SquareGrid::with_boundary(rules::life(), start, Boundary::Halo);
+    for _ in 0..30 {
+        wrapped.step();
+        // Each side receives the strip sent towards the opposite side.
+        halo.step_with_halos(|[(a, sent_a), (b, sent_b)]| {
+            assert_eq!(a.opposite(), b);
+            [sent_b, sent_a]
+        });
+        assert_eq!(halo.cells(), wrapped.cells());
+    }
+}
+
+#[test]
+fn boundary_halo_split_grid() {
+    let start = Array2::from_shape_fn((9, 12), |(y, x)| (y * 5 + x * 3) % 7 < 3);
+    let mut whole = SquareGrid::new(rules::life(), start.clone());
+    let halves = [
+        start.slice(ndarray::s![.., ..6]).to_owned(),
+        start.slice(ndarray::s![.., 6..]).to_owned(),
+    ];
+    let mut halves = halves.map(|cells| {
+        SquareGrid::with_boundaries(
+            rules::life(),
+            cells,
+            Boundary::Constant(false),
+            Boundary::Halo,
+        )
+    });
+    halves[0].set_halo(Side::West, &[false; 9]);
+    halves[1].set_halo(Side::East, &[false; 9]);
+    for _ in 0..10 {
+        let left = halves[0].halo(Side::East);
+        let right = halves[1].halo(Side::West);
+        halves[0].set_halo(Side::East, &right);
+        halves[1].set_halo(Side::West, &left);
+        for half in &mut halves {
+            half.step();
+        }
+        whole.step();
+        assert_eq!(halves[0].cells(), whole.cells().slice(ndarray::s![.., ..6]));
+        assert_eq!(halves[1].cells(), whole.cells().slice(ndarray::s![.., 6..]));
+    }
+
+    // New ghost cells abandon a partial step, which read the old ones.
+    halves[0].step_rows(4);
+    assert_eq!(halves[0].rows_computed(), Some(4));
+    halves[0].set_halo(Side::East, &halves[1].halo(Side::West));
+    assert_eq!(halves[0].rows_computed(), None);
+}
+
+/// Every cell sends a payload naming itself and the edge to each of its eight neighbors and
+/// keeps every payload it receives.
+#[derive(Debug)]
+struct Scatter;
+
+impl Sim<Moore> for Scatter {
+    type Cell = ((usize, usize), Vec<((usize, usize), usize)>);
+    type Diff = ();
+    type Flow = Option<((usize, usize), usize)>;
+
+    fn compute(&self, _: ArrayView2<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: ArrayView2<'_, ()>) -> [Self::Flow; 8] {
+        let mut edge = 0..;
+        [(); 8].map(|_| Some((cell.0, edge.next().unwrap())))
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Self::Flow; 8]) {
+        cell.1.extend(flows.iter().flatten());
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((0, 0), vec![])
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+/// Step a 4x5 grid of [`Scatter`] and check that every payload reached the cell at the offset
+/// of its edge at most once, returning the number of payloads which arrived.
+fn scatter(boundary: Boundary<<Scatter as Sim<Moore>>::Cell>) -> usize {
+    let dims = (4, 5);
+    let cells = Array2::from_shape_fn(dims, |index| (index, vec![]));
+    let mut grid = SquareGrid::with_boundary(Scatter, cells, boundary);
+    grid.step();
+
+    let mut seen = HashSet::new();
+    for ((y, x), (_, received)) in grid.iter_coords() {
+        for &((sy, sx), edge) in received {
+            assert!(
+                seen.insert(((sy, sx), edge)),
+                "{:?} edge {}",
+                (sy, sx),
+                edge
+            );
+            let (dy, dx) = Moore::delta(edge);
+            let target = (
+                (sy as isize + dy).rem_euclid(dims.0 as isize) as usize,
+                (sx as isize + dx).rem_euclid(dims.1 as isize) as usize,
+            );
+            assert_eq!(target, (y, x), "{:?} edge {}", (sy, sx), edge);
+        }
+    }
+    seen.len()
+}
+
+#[test]
+fn boundary_diagonal_payloads() {
+    // Every payload sent to a neighbor on the grid arrives, which is 16 for each horizontal
+    // edge, 15 for each vertical edge, and 12 for each diagonal edge.
+    assert_eq!(scatter(Boundary::Constant(((0, 0), vec![]))), 110);
+    assert_eq!(scatter(Boundary::Wrap), 4 * 5 * 8);
+}
+
+#[test]
+fn boundary_neighbors_at() {
+    let cells = Array2::from_shape_fn((3, 4), |(y, x)| (y * 4 + x) as u32);
+    let wrap = SquareGrid::with_boundary(Sum, cells.clone(), Boundary::Wrap);
+    let window = wrap.window_at((0, 0));
+    assert_eq!(window, arr2(&[[11, 8, 9], [3, 0, 1], [7, 4, 5]]));
+    assert_eq!(wrap.neighbors_at((0, 0), |window| Sum.compute(window)), 48);
+
+    let mixed = SquareGrid::with_boundaries(Sum, cells, Boundary::Constant(99), Boundary::Clamp);
+    assert_eq!(
+        mixed.window_at((2, 3)),
+        arr2(&[[6, 7, 7], [10, 11, 11], [99, 99, 99]])
+    );
+    // The boundaries are used even though no step has filled the padding from them yet.
+    assert_eq!(mixed.generation(), 0);
+
+    let window = gridsim::window_from::<Moore, _>(|(dy, dx)| (dy * 10 + dx) as i32);
+    assert_eq!(window, arr2(&[[-11, -10, -9], [-1, 0, 1], [9, 10, 11]]));
+}
+
+/// Every cell sends an eighth of its units to each neighbor.
+#[derive(Debug)]
+pub struct Spread;
+
+impl Sim<Moore> for Spread {
+    type Cell = u32;
+    type Diff = ();
+    type Flow = u32;
+
+    fn compute(&self, _: ArrayView2<'_, u32>) {}
+
+    fn egress(&self, cell: &mut u32, _: ArrayView2<'_, ()>) -> [u32; 8] {
+        let share = *cell / 8;
+        *cell -= share * 8;
+        [share; 8]
+    }
+
+    fn ingress(&self, cell: &mut u32, flows: [u32; 8]) {
+        *cell += flows.iter().sum::<u32>();
+    }
+
+    fn cell_padding(&self) -> u32 {
+        0
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> u32 {
+        0
+    }
+}
+
+#[test]
+fn boundary_shifted_torus_conserves() {
+    let cells = Array2::from_shape_fn((8, 10), |(y, x)| ((y * 7 + x * 3) % 5 * 8) as u32);
+    let total = cells.sum();
+    for &(y, x) in &[
+        (Boundary::Shift(3), Boundary::Wrap),
+        (Boundary::Wrap, Boundary::Shift(-4)),
+        (Boundary::Shift(13), Boundary::Clamp),
+    ] {
+        let mut grid = SquareGrid::with_boundaries(Spread, cells.clone(), y, x);
+        let mut reference = SquareGrid::with_boundaries(Spread, cells.clone(), y, x);
+        for _ in 0..10 {
+            grid.step();
+            reference.step_reference();
+            if x.connects() {
+                assert_eq!(grid.cells().sum(), total, "{:?}", (y, x));
+            }
+            assert_eq!(grid.cells(), reference.cells(), "{:?}", (y, x));
+        }
+    }
+}
+
+#[test]
+fn boundary_shifted_torus_step_active() {
+    let rule = rules::life();
+    let soup = Array2::from_shape_fn((64, 64), |(y, x)| (y * 31 + x * 17) % 7 < 2);
+    for &(y, x) in &[
+        (Boundary::Shift(3), Boundary::Wrap),
+        (Boundary::Wrap, Boundary::Shift(-5)),
+    ] {
+        let mut grid = SquareGrid::with_boundaries(rule, soup.clone(), y, x);
+        let mut active = SquareGrid::with_boundaries(rule, soup.clone(), y, x);
+        for _ in 0..20 {
+            grid.step();
+            active.step_active();
+            assert_eq!(grid.cells(), active.cells(), "{:?}", (y, x));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "only one axis can have a shifted or twisted boundary")]
+fn boundary_shift_on_both_axes() {
+    SquareGrid::with_boundary(Spread, Array2::zeros((4, 4)), Boundary::Shift(1));
+}
+
+#[test]
+#[should_panic(expected = "only one axis can have a shifted or twisted boundary")]
+fn boundary_shift_with_twist() {
+    SquareGrid::with_boundaries(
+        Spread,
+        Array2::zeros((4, 4)),
+        Boundary::Twist,
+        Boundary::Shift(1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "only one axis can have a shifted or twisted boundary")]
+fn boundary_twist_on_both_axes() {
+    SquareGrid::with_boundary(Spread, Array2::zeros((4, 4)), Boundary::Twist);
+}
@@ -0,0 +1,79 @@
+use gridsim::{Boundary, Bounded, BoundedNeighbors, Radius, Sim, SquareGrid, SquareNeighborhood};
+use ndarray::{arr2, Array2};
+
+/// Every cell becomes the number of its neighbors that are beyond the edges of the grid.
+#[derive(Debug)]
+pub struct OffEdge;
+
+impl<N> Sim<Bounded<N>> for OffEdge
+where
+    N: SquareNeighborhood,
+{
+    type Cell = usize;
+    type Diff = usize;
+    type Flow = ();
+
+    fn compute(&self, neighbors: BoundedNeighbors<'_, usize, N>) -> usize {
+        let off = neighbors.iter().filter(Option::is_none).count();
+        assert_eq!(off > 0, neighbors.at_edge());
+        assert_eq!(neighbors.get((0, 0)), Some(neighbors.center()));
+        off
+    }
+
+    fn egress(&self, cell: &mut usize, diffs: BoundedNeighbors<'_, usize, N>) -> N::Edges<()> {
+        *cell = *diffs.center();
+        N::edges(|| ())
+    }
+
+    fn ingress(&self, _: &mut usize, _: N::Edges<()>) {}
+
+    fn cell_padding(&self) -> usize {
+        0
+    }
+
+    fn diff_padding(&self) -> usize {
+        0
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn bounded_off_edge() {
+    let mut grid: SquareGrid<_, Bounded> = SquareGrid::new(OffEdge, Array2::zeros((3, 4)));
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        arr2(&[[5, 3, 3, 5], [3, 0, 0, 3], [5, 3, 3, 5]])
+    );
+}
+
+#[test]
+fn bounded_single_cell() {
+    let mut grid: SquareGrid<_, Bounded> = SquareGrid::new(OffEdge, Array2::zeros((1, 1)));
+    grid.step();
+    assert_eq!(grid.cells(), arr2(&[[8]]));
+}
+
+#[test]
+fn bounded_radius() {
+    let mut grid: SquareGrid<_, Bounded<Radius<2>>> =
+        SquareGrid::new(OffEdge, Array2::zeros((1, 5)));
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        arr2(&[[24 - 2, 24 - 3, 24 - 4, 24 - 3, 24 - 2]])
+    );
+}
+
+#[test]
+fn bounded_wrap() {
+    let mut grid: SquareGrid<_, Bounded> = SquareGrid::with_boundaries(
+        OffEdge,
+        Array2::zeros((3, 3)),
+        Boundary::Constant(0),
+        Boundary::Wrap,
+    );
+    grid.step();
+    assert_eq!(grid.cells(), arr2(&[[3, 3, 3], [0, 0, 0], [3, 3, 3]]));
+}
@@ -0,0 +1,107 @@
+use gridsim::{Boundary, BroadcastSim, Moore, Sim, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Cell {
+    beacon: bool,
+    /// The number of beacons heard before the last step.
+    heard: u32,
+}
+
+/// Beacons which are heard by every cell within a disk of a radius, without moving anything.
+struct Beacons {
+    radius: usize,
+}
+
+impl Sim<Moore> for Beacons {
+    type Cell = Cell;
+    type Diff = ();
+    type Flow = ();
+
+    fn compute(&self, _: ArrayView2<'_, Cell>) {}
+
+    fn egress(&self, _: &mut Cell, _: ArrayView2<'_, ()>) -> [(); 8] {
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Cell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Cell {
+        Cell::default()
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) {}
+}
+
+impl BroadcastSim<Moore> for Beacons {
+    type Message = u32;
+
+    fn radius(&self) -> usize {
+        self.radius
+    }
+
+    fn reaches(&self, (dy, dx): (isize, isize)) -> bool {
+        (dy * dy + dx * dx) as usize <= self.radius * self.radius
+    }
+
+    fn emit(&self, cell: &Cell) -> Option<u32> {
+        if cell.beacon {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    fn combine(&self, a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    fn receive(&self, cell: &mut Cell, message: Option<u32>) {
+        cell.heard = message.unwrap_or(0);
+    }
+}
+
+fn heard(grid: &SquareGrid<Beacons>) -> Array2<u32> {
+    grid.cells().map(|cell| cell.heard)
+}
+
+#[test]
+fn broadcast_within_radius() {
+    let cells = Array2::from_shape_fn((7, 9), |index| Cell {
+        beacon: index == (3, 2) || index == (3, 5),
+        heard: 0,
+    });
+    let mut grid = SquareGrid::new(Beacons { radius: 2 }, cells);
+    grid.step_broadcast();
+    assert_eq!(grid.generation(), 1);
+    assert_eq!(
+        heard(&grid),
+        ndarray::arr2(&[
+            [0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 1, 0, 0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 1, 0, 0],
+            [1, 1, 1, 2, 2, 1, 1, 1, 0],
+            [0, 1, 1, 1, 1, 1, 1, 0, 0],
+            [0, 0, 1, 0, 0, 1, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ])
+    );
+
+    grid.fill(Cell::default());
+    grid.broadcast();
+    assert_eq!(heard(&grid), Array2::zeros((7, 9)));
+}
+
+#[test]
+fn broadcast_reaches_each_cell_once() {
+    // The disk is wider than the grid, so it overlaps itself across the wrapped edges.
+    let cells = Array2::from_shape_fn((3, 4), |index| Cell {
+        beacon: index == (0, 0),
+        heard: 0,
+    });
+    let mut grid = SquareGrid::with_boundary(Beacons { radius: 3 }, cells, Boundary::Wrap);
+    grid.broadcast();
+    assert_eq!(heard(&grid), Array2::from_elem((3, 4), 1));
+}
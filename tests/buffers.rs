@@ -0,0 +1,35 @@
+use gridsim::{rules, GridBuffers, GridError, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn buffers_reused() {
+    let glider = |(y, x)| [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].contains(&(y, x));
+    let buffers = GridBuffers::with_capacity((6, 6), 1);
+    let (cells, diffs) = (buffers.cells.as_ptr(), buffers.diffs.as_ptr());
+    let mut grid = SquareGrid::with_buffers(rules::life(), (6, 6), buffers, glider).unwrap();
+    let mut expected = SquareGrid::new(rules::life(), Array2::from_shape_fn((6, 6), glider));
+    grid.step_n(4);
+    expected.step_n(4);
+    assert_eq!(grid.cells(), expected.cells());
+
+    // The same memory comes back out of the grid.
+    let buffers = grid.into_buffers();
+    assert_eq!(buffers.cells.as_ptr(), cells);
+    assert_eq!(buffers.diffs.as_ptr(), diffs);
+    assert!(buffers.cells.is_empty());
+    assert!(matches!(
+        SquareGrid::with_buffers(rules::life(), (0, 3), buffers, glider),
+        Err(GridError::Empty)
+    ));
+
+    // Resizing back and forth swaps between the same two allocations.
+    let mut buffer = Vec::with_capacity(64);
+    let spare = buffer.as_ptr();
+    expected.resize_in((4, 4), (0, 0), false, &mut buffer);
+    let old = buffer.as_ptr();
+    expected.resize_in((6, 6), (1, 1), false, &mut buffer);
+    assert_eq!(buffer.as_ptr(), spare);
+    expected.resize_in((5, 5), (0, 0), false, &mut buffer);
+    assert_eq!(buffer.as_ptr(), old);
+    assert_eq!(expected.cells().dim(), (5, 5));
+}
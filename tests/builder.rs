@@ -0,0 +1,50 @@
+use gridsim::{rules, Boundary, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn builder_options() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let steps = Arc::new(AtomicUsize::new(0));
+    let counter = steps.clone();
+    let mut grid = SquareGrid::builder(rules::life())
+        .size((5, 6))
+        .boundary(Boundary::Wrap)
+        .init(|(y, x)| y == 2 && (1..4).contains(&x))
+        .seed(7)
+        .tile_size((2, 2))
+        .history(8)
+        .on_after_step(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .build();
+    assert_eq!(grid.cells().dim(), (5, 6));
+    assert_eq!(grid.boundaries(), (&Boundary::Wrap, &Boundary::Wrap));
+    assert_eq!(grid.seed(), 7);
+    assert_eq!(grid.tile_size(), Some((2, 2)));
+
+    let mut expected =
+        SquareGrid::with_boundary(rules::life(), grid.cells().to_owned(), Boundary::Wrap);
+    grid.step();
+    expected.step();
+    assert_eq!(grid.cells(), expected.cells());
+    assert_eq!(grid.history_len(), 1);
+    assert_eq!(steps.load(Ordering::Relaxed), 1);
+
+    let random = SquareGrid::builder(rules::life())
+        .size((8, 8))
+        .random(3, |rng| rng.gen_bool(0.5))
+        .build();
+    let direct = SquareGrid::new_random(rules::life(), (8, 8), 3, |rng| rng.gen_bool(0.5));
+    assert_eq!(random.cells(), direct.cells());
+
+    let cells = Array2::from_elem((3, 4), true);
+    let grid = SquareGrid::builder(rules::life())
+        .cells(cells.clone())
+        .build();
+    assert_eq!(grid.cells(), cells);
+    assert_eq!(grid.history_len(), 0);
+}
@@ -0,0 +1,76 @@
+use gridsim::{rules, CancelToken, Cancelled, LifeLikeRule, Moore, Sim, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Life which cancels a token once it has computed a number of cells.
+struct CancellingGol {
+    life: LifeLikeRule,
+    token: CancelToken,
+    left: AtomicUsize,
+}
+
+impl Sim<Moore> for CancellingGol {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        if self.left.fetch_sub(1, Ordering::Relaxed) == 1 {
+            self.token.cancel();
+        }
+        self.life.compute(cells)
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        self.life.egress(cell, diffs)
+    }
+
+    fn ingress(&self, _: &mut bool, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn cancel_steps() {
+    let start = SquareGrid::new_random(rules::life(), (32, 8), 6, |rng| rng.gen_bool(0.4));
+    let token = CancelToken::new();
+    let sim = CancellingGol {
+        life: rules::life(),
+        token: token.clone(),
+        left: AtomicUsize::new(2 * 32 * 8 + 10),
+    };
+    let mut grid = SquareGrid::new(sim, start.cells().to_owned());
+    let mut expected = start.clone();
+    expected.step_n(2);
+
+    // The token is cancelled during the third step, which is abandoned.
+    assert_eq!(grid.step_n_cancellable(5, &token), Err(Cancelled));
+    assert_eq!(grid.generation(), 2);
+    assert_eq!(grid.cells(), expected.cells());
+    assert_eq!(grid.rows_computed(), None);
+    assert_eq!(grid.step_cancellable(&token), Err(Cancelled));
+
+    token.reset();
+    let mut blinker = SquareGrid::new(rules::life(), Array2::from_elem((5, 5), false));
+    blinker.fill_region((2..3, 1..4), true);
+    assert_eq!(blinker.run_until_stable_cancellable(10, &token), Ok(None));
+    blinker.fill_region((0..5, 0..5), false);
+    assert_eq!(
+        blinker.run_until_stable_cancellable(10, &token),
+        Ok(Some(10))
+    );
+    token.cancel();
+    assert_eq!(
+        blinker.run_until_stable_cancellable(10, &token),
+        Err(Cancelled)
+    );
+    assert_eq!(blinker.generation(), 11);
+}
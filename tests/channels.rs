@@ -0,0 +1,96 @@
+use gridsim::{Boundary, ChannelSim, Channeled, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+/// A creature which is carried to a neighbor on its own channel.
+#[derive(Clone, Debug, PartialEq)]
+struct Creature {
+    name: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Cell {
+    water: u32,
+    creature: Option<Creature>,
+}
+
+/// Water flows one unit to the right and creatures climb up, on separate channels.
+struct Migrate;
+
+impl ChannelSim for Migrate {
+    type Cell = Cell;
+    type Diff = ();
+    type Channels = (u32, Option<Creature>);
+
+    fn compute(&self, _: ArrayView2<'_, Cell>) {}
+
+    fn egress(&self, cell: &mut Cell, _: ArrayView2<'_, ()>) -> ([u32; 8], [Option<Creature>; 8]) {
+        let mut water = [0; 8];
+        if cell.water > 0 {
+            cell.water -= 1;
+            water[0] = 1;
+        }
+        let mut creatures: [Option<Creature>; 8] = Default::default();
+        creatures[2] = cell.creature.take();
+        (water, creatures)
+    }
+
+    fn ingress(&self, cell: &mut Cell, (water, creatures): ([u32; 8], [Option<Creature>; 8])) {
+        cell.water += water.iter().sum::<u32>();
+        for creature in IntoIterator::into_iter(creatures).flatten() {
+            assert!(cell.creature.is_none(), "two creatures met");
+            cell.creature = Some(creature);
+        }
+    }
+
+    fn cell_padding(&self) -> Cell {
+        Cell::default()
+    }
+
+    fn diff_padding(&self) {}
+
+    fn channel_padding(&self) -> (u32, Option<Creature>) {
+        (0, None)
+    }
+}
+
+#[test]
+fn channels_move_separately() {
+    let cells = Array2::from_shape_fn((4, 5), |(y, x)| Cell {
+        water: if x == 0 { y as u32 + 1 } else { 0 },
+        creature: if (y, x) == (0, 2) {
+            Some(Creature {
+                name: "frog".to_owned(),
+            })
+        } else {
+            None
+        },
+    });
+    let mut grid = SquareGrid::with_boundary(Channeled(Migrate), cells, Boundary::Wrap);
+
+    grid.step();
+    let water = grid.cells().map(|cell| cell.water);
+    assert_eq!(
+        water,
+        ndarray::arr2(&[
+            [0, 1, 0, 0, 0],
+            [1, 1, 0, 0, 0],
+            [2, 1, 0, 0, 0],
+            [3, 1, 0, 0, 0],
+        ])
+    );
+    // The creature climbed off the top row and wrapped around to the bottom.
+    assert_eq!(
+        grid[(3, 2)].creature.as_ref().map(|c| c.name.as_str()),
+        Some("frog")
+    );
+
+    grid.step_n(9);
+    assert_eq!(grid.cells().iter().map(|cell| cell.water).sum::<u32>(), 10);
+    let creatures: Vec<_> = grid
+        .cells()
+        .indexed_iter()
+        .filter(|(_, cell)| cell.creature.is_some())
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(creatures, vec![(2, 2)]);
+}
@@ -0,0 +1,135 @@
+use gridsim::{
+    rules::{ForestCell, ForestFire},
+    Compression, Random, SquareGrid,
+};
+use ndarray::Array2;
+use std::io::ErrorKind;
+
+fn forest(dim: (usize, usize)) -> SquareGrid<ForestFire, Random> {
+    let sim = ForestFire {
+        growth: 0.05,
+        lightning: 0.001,
+    };
+    SquareGrid::new(sim, Array2::from_elem(dim, ForestCell::Empty))
+}
+
+#[test]
+fn checkpoint_save_and_resume() {
+    let path = std::env::temp_dir().join(format!("gridsim-checkpoint-{}", std::process::id()));
+    let mut grid = forest((20, 30));
+    grid.set_seed(11);
+    grid.step_n(25);
+    grid.save_checkpoint(&path).unwrap();
+
+    let mut resumed = forest((3, 3));
+    resumed.load_checkpoint(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(resumed.generation(), 25);
+    assert_eq!(resumed.seed(), 11);
+    assert_eq!(resumed.cells(), grid.cells());
+
+    grid.step_n(10);
+    resumed.step_n(10);
+    assert_eq!(resumed.cells(), grid.cells());
+}
+
+#[test]
+fn checkpoint_rejects_invalid() {
+    let mut grid = SquareGrid::new(gridsim::rules::life(), Array2::from_elem((4, 4), true));
+    let mut bytes = vec![];
+    grid.write_checkpoint(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 8 + 4 + 1 + 4 * 8 + 16);
+
+    let mut wrong_magic = bytes.clone();
+    wrong_magic[0] = b'g';
+    let error = grid.read_checkpoint(&mut &wrong_magic[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let mut bad_cell = bytes.clone();
+    *bad_cell.last_mut().unwrap() = 7;
+    let error = grid.read_checkpoint(&mut &bad_cell[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let error = grid.read_checkpoint(&mut &bytes[..20]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert!(grid.cells().iter().all(|&c| c));
+}
+
+#[test]
+fn checkpoint_run_length() {
+    let mut cells = Array2::from_elem((100, 100), false);
+    cells[(50, 50)] = true;
+    cells[(99, 99)] = true;
+    let grid = SquareGrid::new(gridsim::rules::life(), cells);
+    let mut plain = vec![];
+    grid.write_checkpoint(&mut plain).unwrap();
+    let mut compressed = vec![];
+    grid.write_checkpoint_with(&mut compressed, Compression::RunLength)
+        .unwrap();
+    assert_eq!(
+        compressed.len(),
+        8 + 4 + 1 + 4 * 8 + (2 + 1) + (1 + 1) + (2 + 1) + (1 + 1)
+    );
+
+    let mut read = SquareGrid::new(gridsim::rules::life(), Array2::from_elem((1, 1), true));
+    read.read_checkpoint(&mut &compressed[..]).unwrap();
+    assert_eq!(read.cells(), grid.cells());
+
+    // Version 1 had no compression byte.
+    let mut version_1 = plain.clone();
+    version_1.remove(12);
+    version_1[8] = 1;
+    let mut read = SquareGrid::new(gridsim::rules::life(), Array2::from_elem((1, 1), true));
+    read.read_checkpoint(&mut &version_1[..]).unwrap();
+    assert_eq!(read.cells(), grid.cells());
+}
+
+#[test]
+fn checkpoint_rejects_huge_header() {
+    let grid = SquareGrid::new(gridsim::rules::life(), Array2::from_elem((2, 2), true));
+    let mut bytes = vec![];
+    grid.write_checkpoint_with(&mut bytes, Compression::RunLength)
+        .unwrap();
+    let header = |h: u64, w: u64, run: &[u8]| {
+        let mut bytes = bytes[..13].to_vec();
+        bytes.extend_from_slice(&h.to_le_bytes());
+        bytes.extend_from_slice(&w.to_le_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(run);
+        bytes.push(1);
+        bytes
+    };
+    let mut read = SquareGrid::new(gridsim::rules::life(), Array2::from_elem((1, 1), true));
+
+    let overflowing = header(u64::MAX, 2, &[4]);
+    let error = read.read_checkpoint(&mut &overflowing[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    // A single run of 2^62 cells.
+    let huge = header(
+        1 << 31,
+        1 << 31,
+        &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x40],
+    );
+    let error = read.read_checkpoint(&mut &huge[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    // Without a run, the missing cells end the checkpoint before much is allocated.
+    let unfinished = header(1 << 31, 1 << 31, &[2]);
+    let error = read.read_checkpoint(&mut &unfinished[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(read.cells(), Array2::from_elem((1, 1), true));
+}
+
+#[test]
+fn checkpoint_failed_save_is_removed() {
+    let path = std::env::temp_dir().join(format!("gridsim-failed-save-{}", std::process::id()));
+    std::fs::create_dir_all(&path).unwrap();
+    let grid = forest((4, 4));
+    assert!(grid.save_checkpoint(&path).is_err());
+    let mut temporary = path.clone().into_os_string();
+    temporary.push(".tmp");
+    let temporary_exists = std::path::Path::new(&temporary).exists();
+    std::fs::remove_dir(&path).unwrap();
+    assert!(!temporary_exists);
+}
@@ -0,0 +1,28 @@
+use gridsim::{rules, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn compare_diff() {
+    let before = SquareGrid::new(
+        rules::life(),
+        Array2::from_shape_fn((4, 5), |(y, x)| y == 1 && x < 3),
+    );
+    let mut after = before.clone();
+    after.step();
+    assert_eq!(before.diff_count(&after), 4);
+    let diff = before.diff(&after);
+    assert_eq!(
+        diff,
+        vec![
+            ((0, 1), false, true),
+            ((1, 0), true, false),
+            ((1, 2), true, false),
+            ((2, 1), false, true),
+        ]
+    );
+    assert!(after.diff(&after).is_empty());
+
+    let mut viewer = before.clone();
+    viewer.apply_diff(diff);
+    assert_eq!(viewer.cells(), after.cells());
+}
@@ -0,0 +1,68 @@
+use gridsim::{Moore, RowsError, Sim, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+/// Conway's Game of Life
+#[derive(Clone, Debug, Default)]
+pub struct Gol;
+
+impl Sim<Moore> for Gol {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        let n = cells.iter().filter(|&&c| c).count();
+        if cells[(1, 1)] {
+            (3..=4).contains(&n)
+        } else {
+            n == 3
+        }
+    }
+
+    fn egress(&self, cell: &mut Self::Cell, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Self::Cell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Self::Cell {
+        false
+    }
+
+    fn diff_padding(&self) -> Self::Diff {
+        false
+    }
+
+    fn flow_padding(&self) -> Self::Flow {}
+}
+
+#[test]
+fn convert_rows_and_arrays() {
+    let rows = vec![vec![false, true, false], vec![true, true, false]];
+    let grid = SquareGrid::from_rows(Gol, rows.clone()).unwrap();
+    assert_eq!(
+        grid.cells(),
+        ndarray::arr2(&[[false, true, false], [true, true, false]])
+    );
+    assert_eq!(grid.to_rows(), rows);
+
+    assert_eq!(
+        SquareGrid::from_rows(Gol, vec![vec![true, false], vec![true]]).err(),
+        Some(RowsError::Ragged {
+            row: 1,
+            len: 1,
+            expected: 2
+        })
+    );
+    assert_eq!(
+        SquareGrid::from_rows(Gol, vec![vec![]]).err(),
+        Some(RowsError::Empty)
+    );
+
+    let cells = Array2::from_shape_fn((4, 5), |(y, x)| (y + x) % 3 == 0);
+    let grid: SquareGrid<Gol> = cells.clone().into();
+    assert_eq!(grid.cells(), cells);
+    let back: Array2<bool> = grid.into();
+    assert_eq!(back, cells);
+}
@@ -0,0 +1,117 @@
+use gridsim::{CoupledGrids, Moore, Sim, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+/// Heat which spreads to the four sides and is added to by an agent.
+#[derive(Debug)]
+struct Heat;
+
+/// The heat of a cell and whether an agent was on it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct HeatCell {
+    heat: f64,
+    agent: bool,
+}
+
+impl Sim<Moore> for Heat {
+    type Cell = HeatCell;
+    type Diff = f64;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, HeatCell>) -> f64 {
+        let sides = [(0, 1), (1, 0), (1, 2), (2, 1)];
+        let around: f64 = sides.iter().map(|&index| cells[index].heat).sum();
+        let center = cells[(1, 1)];
+        (center.heat + around) / 5.0 + if center.agent { 1.0 } else { 0.0 }
+    }
+
+    fn egress(&self, cell: &mut HeatCell, diffs: ArrayView2<'_, f64>) -> [(); 8] {
+        cell.heat = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut HeatCell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> HeatCell {
+        HeatCell {
+            heat: 0.0,
+            agent: false,
+        }
+    }
+
+    fn diff_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn flow_padding(&self) {}
+}
+
+/// Agents which stay put and die once the heat under them passes a limit.
+#[derive(Debug)]
+struct Agents;
+
+/// Whether an agent is alive and the heat under it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct AgentCell {
+    alive: bool,
+    heat: f64,
+}
+
+impl Sim<Moore> for Agents {
+    type Cell = AgentCell;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, AgentCell>) -> bool {
+        let center = cells[(1, 1)];
+        center.alive && center.heat < 1.1
+    }
+
+    fn egress(&self, cell: &mut AgentCell, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        cell.alive = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut AgentCell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> AgentCell {
+        AgentCell {
+            alive: false,
+            heat: 0.0,
+        }
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn coupled_heat_and_agents() {
+    let heat = SquareGrid::new(Heat, Array2::from_elem((5, 5), Heat.cell_padding()));
+    let mut agents = Array2::from_elem((5, 5), Agents.cell_padding());
+    agents[(2, 2)].alive = true;
+    agents[(0, 4)].alive = true;
+    let agents = SquareGrid::new(Agents, agents);
+    let mut grids = CoupledGrids::new(
+        heat,
+        agents,
+        |cell: &mut HeatCell, other: &AgentCell| cell.agent = other.alive,
+        |cell: &mut AgentCell, other: &HeatCell| cell.heat = other.heat,
+    );
+
+    grids.step();
+    // The heat saw the agents before they stepped, and the agents saw no heat yet.
+    assert_eq!(grids.first().cells()[(2, 2)].heat, 1.0);
+    assert_eq!(grids.first().cells()[(1, 1)].heat, 0.0);
+    assert_eq!(grids.second().cells()[(2, 2)].heat, 0.0);
+    assert!(grids.second().cells()[(2, 2)].alive);
+
+    grids.step_n(3);
+    let (heat, agents) = grids.into_grids();
+    assert_eq!(heat.generation(), 4);
+    assert_eq!(agents.generation(), 4);
+    assert!(!agents.cells()[(2, 2)].alive);
+    assert!(heat.cells()[(2, 1)].heat > 0.0);
+}
@@ -0,0 +1,127 @@
+use gridsim::{CubeGrid, CubeNeighborhood, Moore3d, Moore3dNeighbors, Sim, VonNeumann3d};
+use ndarray::Array3;
+
+type Coord = (isize, isize, isize);
+
+/// Every cell sends its coordinate to all neighbors and counts how many correct coordinates it receives.
+#[derive(Debug)]
+pub struct Exchange;
+
+impl<N> Sim<N> for Exchange
+where
+    N: CubeNeighborhood,
+{
+    type Cell = (Coord, usize);
+    type Diff = ();
+    type Flow = Option<Coord>;
+
+    fn compute(&self, _: N::Neighbors<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: N::Neighbors<'_, ()>) -> N::Edges<Option<Coord>> {
+        N::edges(|| Some(cell.0))
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, mut flows: N::Edges<Option<Coord>>) {
+        let (z, y, x) = cell.0;
+        for (flow, &(dz, dy, dx)) in N::edges_mut(&mut flows).iter().zip(N::DELTAS) {
+            if let Some(coord) = *flow {
+                assert_eq!(coord, (z + dz, y + dy, x + dx));
+                cell.1 += 1;
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((-1, -1, -1), 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+fn in_bounds((z, y, x): Coord, dim: (usize, usize, usize)) -> bool {
+    (0..dim.0 as isize).contains(&z)
+        && (0..dim.1 as isize).contains(&y)
+        && (0..dim.2 as isize).contains(&x)
+}
+
+fn check_exchange<N>()
+where
+    N: CubeNeighborhood,
+    Exchange: Sim<N, Cell = (Coord, usize)>,
+    N::Edges<<Exchange as Sim<N>>::Flow>: Send,
+    <Exchange as Sim<N>>::Diff: Send + Sync,
+{
+    let dim = (4, 5, 6);
+    let mut grid: CubeGrid<Exchange, N> = CubeGrid::new(
+        Exchange,
+        Array3::from_shape_fn(dim, |(z, y, x)| ((z as isize, y as isize, x as isize), 0)),
+    );
+    grid.step();
+    for &((z, y, x), received) in grid.cells() {
+        let expected = N::DELTAS
+            .iter()
+            .filter(|&&(dz, dy, dx)| in_bounds((z + dz, y + dy, x + dx), dim))
+            .count();
+        assert_eq!(received, expected);
+    }
+}
+
+#[test]
+fn cube_von_neumann_exchange() {
+    check_exchange::<VonNeumann3d>();
+}
+
+#[test]
+fn cube_moore_exchange() {
+    check_exchange::<Moore3d>();
+}
+
+/// Cells come alive when any neighbor is alive.
+#[derive(Debug)]
+pub struct Grow;
+
+impl Sim<Moore3d> for Grow {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: Moore3dNeighbors<'_, bool>) -> bool {
+        *cells.center() || cells.iter().any(|&c| c)
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: Moore3dNeighbors<'_, bool>) -> [(); 26] {
+        *cell = *diffs.center();
+        [(); 26]
+    }
+
+    fn ingress(&self, _: &mut bool, _: [(); 26]) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn cube_moore_grows_to_cube() {
+    let mut grid = CubeGrid::new(
+        Grow,
+        Array3::from_shape_fn((5, 5, 5), |pos| pos == (2, 2, 2)),
+    );
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        Array3::from_shape_fn((5, 5, 5), |(z, y, x)| {
+            (1..=3).contains(&z) && (1..=3).contains(&y) && (1..=3).contains(&x)
+        })
+    );
+}
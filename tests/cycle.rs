@@ -0,0 +1,67 @@
+use gridsim::{rules, Boundary, Cycle, CycleDetector, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn cycle_run_until_stable() {
+    let mut block = SquareGrid::new(rules::life(), Array2::from_elem((4, 4), false));
+    block.fill_region((1..3, 1..3), true);
+    assert!(!block.step_changed());
+    assert_eq!(block.run_until_stable(10), Some(1));
+
+    let mut blinker = SquareGrid::new(rules::life(), Array2::from_elem((5, 5), false));
+    blinker.fill_region((2..3, 1..4), true);
+    assert_eq!(blinker.run_until_stable(100), None);
+    assert_eq!(blinker.generation(), 100);
+
+    let mut glider = SquareGrid::new(rules::life(), Array2::from_elem((8, 8), false));
+    for &index in &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        glider.set_cell(index, true);
+    }
+    let generation = glider.run_until_stable(100).unwrap();
+    assert_eq!(glider.generation(), generation + 1);
+    let stable = glider.cells().to_owned();
+    glider.step();
+    assert_eq!(glider.cells(), stable);
+}
+
+#[test]
+fn cycle_run_until_cycle() {
+    let mut blinker = SquareGrid::new(rules::life(), Array2::from_elem((5, 5), false));
+    blinker.fill_region((2..3, 1..4), true);
+    assert_eq!(
+        blinker.run_until_cycle(10),
+        Some(Cycle {
+            start: 0,
+            period: 2
+        })
+    );
+    assert_eq!(blinker.generation(), 2);
+
+    let mut glider = SquareGrid::with_boundary(
+        rules::life(),
+        Array2::from_elem((6, 6), false),
+        Boundary::Wrap,
+    );
+    for &index in &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        glider.set_cell(index, true);
+    }
+    assert_eq!(
+        glider.run_until_cycle(100),
+        Some(Cycle {
+            start: 0,
+            period: 24
+        })
+    );
+
+    let mut detector = CycleDetector::new();
+    assert_eq!(detector.observe(0, &"a"), None);
+    assert_eq!(detector.observe(1, &"b"), None);
+    assert_eq!(
+        detector.observe(5, &"a"),
+        Some(Cycle {
+            start: 0,
+            period: 5
+        })
+    );
+    assert_eq!(detector.len(), 2);
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "derive")]
+
+use gridsim::{Neighborhood, NeighborsExt, Sim, SoaArray, SoaCell, SquareGrid, SquareNeighborhood};
+use ndarray::Array2;
+
+/// The four orthogonal neighbors.
+#[derive(Neighborhood)]
+pub struct CrossNeighbors<'a, T> {
+    #[delta(0, 1)]
+    pub east: &'a T,
+    #[delta(-1, 0)]
+    pub north: &'a T,
+    #[delta(0, -1)]
+    pub west: &'a T,
+    #[delta(1, 0)]
+    pub south: &'a T,
+}
+
+/// Two cells away on either side horizontally.
+#[derive(Neighborhood)]
+pub struct Wide<'a, T> {
+    #[delta(0, -2)]
+    pub far_left: &'a T,
+    #[delta(0, 2)]
+    pub far_right: &'a T,
+}
+
+/// A cell with three fields of the same type.
+#[derive(Clone, Copy, Debug, PartialEq, SoaCell)]
+pub struct Fluid {
+    pub density: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+}
+
+type Coord = (isize, isize);
+
+/// Every cell sends its coordinate to all neighbors and counts how many correct coordinates it receives.
+#[derive(Debug)]
+pub struct Exchange;
+
+impl Sim<Cross> for Exchange {
+    type Cell = (Coord, usize);
+    type Diff = ();
+    type Flow = Option<Coord>;
+
+    fn compute(&self, _: CrossNeighbors<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: CrossNeighbors<'_, ()>) -> [Option<Coord>; 4] {
+        [Some(cell.0); 4]
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Option<Coord>; 4]) {
+        let (y, x) = cell.0;
+        for (direction, flow) in CrossDirection::ALL.iter().zip(&flows) {
+            if let Some(coord) = *flow {
+                let (dy, dx) = direction.delta();
+                assert_eq!(coord, (y + dy, x + dx));
+                cell.1 += 1;
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((-1, -1), 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+#[test]
+fn derive_directions() {
+    assert_eq!(Cross::RADIUS, 1);
+    assert_eq!(Cross::EDGES, 4);
+    assert_eq!(CrossDirection::East.opposite(), CrossDirection::West);
+    assert_eq!(CrossDirection::South.delta(), (1, 0));
+    assert_eq!(Cross::opposite(1), 3);
+    assert_eq!(WideNeighborhood::RADIUS, 2);
+    assert_eq!(WideDirection::FarLeft.opposite(), WideDirection::FarRight);
+
+    let window = Array2::from_shape_fn((3, 3), |(y, x)| y * 3 + x);
+    let neighbors = CrossNeighbors::new(window.view());
+    assert_eq!(neighbors[CrossDirection::North], 1);
+    assert_eq!(*neighbors.west, 3);
+    assert_eq!(neighbors.iter().copied().collect::<Vec<_>>(), [5, 1, 3, 7]);
+    let (direction, &cell) = neighbors.dir_iter().last().unwrap();
+    assert_eq!((direction, cell), (CrossDirection::South, 7));
+    assert_eq!(neighbors.count_neighbors(|&c| c % 2 == 1), 4);
+}
+
+#[test]
+fn derive_exchange() {
+    let mut grid: SquareGrid<_, Cross> = SquareGrid::new(
+        Exchange,
+        Array2::from_shape_fn((4, 5), |(y, x)| ((y as isize, x as isize), 0)),
+    );
+    grid.step_parallel();
+    assert_eq!(grid.cells()[(0, 0)].1, 2);
+    assert_eq!(grid.cells()[(0, 1)].1, 3);
+    assert_eq!(grid.cells()[(1, 1)].1, 4);
+}
+
+#[test]
+fn derive_soa_cell() {
+    assert_eq!(Fluid::FIELDS, 3);
+    let fluid = Fluid {
+        density: 1.0,
+        velocity_x: 2.0,
+        velocity_y: 3.0,
+    };
+    assert_eq!(fluid.field(2), 3.0);
+    assert_eq!(Fluid::from_fields(|field| fluid.field(field)), fluid);
+
+    let mut soa = SoaArray::from_elem((2, 2), fluid);
+    soa.field_mut(1).fill(5.0);
+    assert_eq!(soa.get((1, 0)).velocity_x, 5.0);
+    assert_eq!(soa.field(0).sum(), 4.0);
+}
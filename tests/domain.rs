@@ -0,0 +1,39 @@
+use gridsim::{rules, SquareGrid, UpdateOrder};
+use ndarray::{Array2, ArrayView2};
+
+#[test]
+fn domain_mask() {
+    let empty = Array2::from_elem((5, 5), false);
+    let domain = Array2::from_shape_fn((5, 5), |(_, x)| x != 0);
+    let expected = "#....\n##...\n##...\n##...\n#....\n";
+    for &order in &[UpdateOrder::Synchronous, UpdateOrder::Sweep] {
+        // The wall is alive but never updated, so it stays put while cells next to it are born.
+        let mut grid = SquareGrid::new(rules::life(), empty.clone());
+        grid.set_update_order(order);
+        grid.set_domain(domain.view(), true);
+        assert!(!grid.in_domain((3, 0)));
+        assert_eq!(grid.domain(), Some(domain.view()));
+        grid.step();
+        assert!(grid.cells().column(0).iter().all(|&c| c));
+        if order == UpdateOrder::Synchronous {
+            assert_eq!(grid_string(grid.cells()), expected);
+        }
+    }
+
+    let mut grid = SquareGrid::new(rules::life(), empty);
+    grid.set_domain(domain.view(), true);
+    grid.step_rows(2);
+    grid.step_rows(3);
+    assert_eq!(grid_string(grid.cells()), expected);
+
+    // Resizing keeps which old cells are in the domain.
+    grid.resize((5, 6), (0, 1), false);
+    assert!(grid.in_domain((0, 0)));
+    assert!(!grid.in_domain((0, 1)));
+    grid.clear_domain();
+    assert_eq!(grid.domain(), None);
+}
+
+fn grid_string(cells: ArrayView2<'_, bool>) -> String {
+    gridsim::write_chars(cells, |&c| if c { '#' } else { '.' })
+}
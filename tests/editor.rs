@@ -0,0 +1,46 @@
+use gridsim::{rules, GridError, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn editor_pause() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((8, 8), false));
+    grid.track_population();
+    assert_eq!(grid.population(), Some(&[64, 0][..]));
+    let edits = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let seen = edits.clone();
+    grid.on_edit(move |_, edited| seen.lock().unwrap().push(edited.to_vec()));
+
+    let mut editor = grid.pause();
+    let glider = ndarray::arr2(&[
+        [false, true, false],
+        [false, false, true],
+        [true, true, true],
+    ]);
+    editor.stamp(glider.view(), (0, 5)).unwrap();
+    editor.set((0, 6), true).unwrap();
+    editor.fill_region((5..6, 0..2), true).unwrap();
+    assert_eq!(
+        editor.set((8, 0), true),
+        Err(GridError::OutOfBounds {
+            index: (8, 0),
+            dims: (8, 8)
+        })
+    );
+    assert!(editor.stamp(glider.view(), (6, 6)).is_err());
+    assert!(editor.fill_region((0..9, 0..1), true).is_err());
+    assert_eq!(editor.edited().len(), 11);
+    editor.resume();
+
+    // The hooks see each edited cell once, and the grid knows about every edit.
+    assert_eq!(edits.lock().unwrap().len(), 1);
+    assert_eq!(edits.lock().unwrap()[0][..3], [(0, 5), (0, 6), (0, 7)]);
+    assert_eq!(grid.population(), Some(&[57, 7][..]));
+    let mut expected = SquareGrid::new(rules::life(), grid.cells().to_owned());
+    grid.step_n(3);
+    expected.step_n(3);
+    assert_eq!(grid.cells(), expected.cells());
+
+    // Pausing without editing doesn't call the hooks.
+    grid.pause().resume();
+    assert_eq!(edits.lock().unwrap().len(), 1);
+}
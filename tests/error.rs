@@ -0,0 +1,43 @@
+use gridsim::{rules, GridError, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn grid_errors() {
+    assert_eq!(
+        SquareGrid::try_new(rules::life(), Array2::from_elem((0, 3), false)).err(),
+        Some(GridError::Empty)
+    );
+    let mut grid = SquareGrid::try_new(rules::life(), Array2::from_elem((3, 4), false)).unwrap();
+    assert_eq!(
+        grid.try_set_cell((3, 0), true),
+        Err(GridError::OutOfBounds {
+            index: (3, 0),
+            dims: (3, 4)
+        })
+    );
+    assert_eq!(
+        grid.try_window((1..2, 2..5)).err(),
+        Some(GridError::Region {
+            region: (1..2, 2..5),
+            dims: (3, 4)
+        })
+    );
+    assert_eq!(
+        grid.try_resize((2, 0), (0, 0), false),
+        Err(GridError::Empty)
+    );
+    assert_eq!(grid.try_crop((1..1, 0..4)), Err(GridError::Empty));
+    assert_eq!(grid.cells().dim(), (3, 4));
+
+    grid.try_set_cell((2, 3), true).unwrap();
+    grid.try_window_mut((0..1, 0..2)).unwrap().fill(true);
+    grid.try_crop((0..3, 1..4)).unwrap();
+    assert_eq!(
+        grid.cells(),
+        ndarray::arr2(&[
+            [true, false, false],
+            [false, false, false],
+            [false, false, true]
+        ])
+    );
+}
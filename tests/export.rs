@@ -0,0 +1,65 @@
+#![cfg(feature = "export")]
+
+use gridsim::{pde::Heat, write_npy, write_vti, ExportFormat, Exporter, SquareGrid};
+use ndarray::{arr2, Array2};
+use std::{convert::TryInto, fs};
+
+#[test]
+fn export_npy() {
+    let mut bytes = vec![];
+    write_npy(&mut bytes, arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).view()).unwrap();
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+    assert!(header.starts_with("{'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }"));
+    assert!(header.ends_with(" \n"));
+    let values: Vec<f64> = bytes[10 + header_len..]
+        .chunks(8)
+        .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    assert_eq!(values, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn export_vti() {
+    let mut bytes = vec![];
+    let heat = arr2(&[[0.5, 1.0], [1.5, 2.0], [2.5, 3.0]]);
+    let zero = Array2::zeros((3, 2));
+    write_vti(&mut bytes, &[("heat", heat.view()), ("a<b", zero.view())]).unwrap();
+    let text = String::from_utf8(bytes).unwrap();
+    assert!(text.contains("<ImageData WholeExtent=\"0 2 0 3 0 0\""));
+    assert!(text.contains("Name=\"heat\""));
+    assert!(text.contains("Name=\"a&lt;b\""));
+    assert!(text.contains("\n          0.5 1\n          1.5 2\n          2.5 3\n"));
+    assert!(text.ends_with("</VTKFile>\n"));
+}
+
+#[test]
+fn export_every_generation() {
+    let directory = std::env::temp_dir().join(format!("gridsim-export-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let mut cells = Array2::zeros((4, 4));
+    cells[(1, 2)] = 16.0;
+    let mut grid = SquareGrid::new(Heat::new(0.2), cells);
+
+    let mut npy = Exporter::new(&directory, "heat", ExportFormat::Npy);
+    npy.add_field("t", |&t| t);
+    npy.add_field("hot", |&t| (t > 1.0) as u8 as f64);
+    let mut vti = Exporter::new(&directory, "heat", ExportFormat::Vti);
+    vti.add_field("t", |&t| t);
+
+    let paths = npy.export(&grid).unwrap();
+    assert_eq!(paths[1], directory.join("heat_hot_000000.npy"));
+    for _ in 0..2 {
+        vti.step(&mut grid).unwrap();
+    }
+    let paths = npy.export(&grid).unwrap();
+    assert_eq!(paths[0], directory.join("heat_t_000002.npy"));
+    let mut expected = vec![];
+    grid.write_npy(&mut expected, |&t| t).unwrap();
+    assert_eq!(fs::read(&paths[0]).unwrap(), expected);
+    assert!(directory.join("heat_000001.vti").exists());
+    assert!(directory.join("heat_000002.vti").exists());
+    fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,112 @@
+use gridsim::{
+    rules::{self, BrainCell, BriansBrain},
+    GenerationsRule, LargerThanLife, LtlShape, ParseRuleError, Radius, SquareGrid,
+};
+use ndarray::Array2;
+
+/// A small deterministic soup of states below `states`.
+fn soup(states: u8) -> Array2<u8> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    Array2::from_shape_simple_fn((24, 24), || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % states as u64) as u8
+    })
+}
+
+#[test]
+fn generations_parse() {
+    let rule = GenerationsRule::new(&[2], &[3, 4, 5], 4);
+    assert_eq!(GenerationsRule::parse("345/2/4"), Ok(rule));
+    assert_eq!(GenerationsRule::parse("B2/S345/C4"), Ok(rule));
+    assert_eq!(rule.to_string(), "B2/S345/C4");
+    assert_eq!(
+        GenerationsRule::parse("345/2"),
+        Err(ParseRuleError::InvalidFormat("345/2".to_owned()))
+    );
+}
+
+#[test]
+fn generations_matches_brians_brain() {
+    let cells = soup(3);
+    let brain =
+        cells.map(|&state| [BrainCell::Off, BrainCell::On, BrainCell::Dying][state as usize]);
+    let mut generations = SquareGrid::new(GenerationsRule::parse("/2/3").unwrap(), cells);
+    let mut brain = SquareGrid::new(BriansBrain, brain);
+    for _ in 0..10 {
+        generations.step();
+        brain.step();
+        assert_eq!(generations.cells(), brain.cells().map(|&cell| cell as u8));
+    }
+}
+
+#[test]
+fn larger_than_life_parse() {
+    let bosco = LargerThanLife::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+    assert_eq!(bosco.radius(), 5);
+    assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+    let hrot = LargerThanLife::parse("R2,C3,S2-3,5,B4,NN").unwrap();
+    assert_eq!(
+        hrot,
+        LargerThanLife::new(2, vec![4..=4], vec![2..=3, 5..=5])
+            .with_states(3)
+            .with_shape(LtlShape::VonNeumann)
+    );
+    assert!(LargerThanLife::parse("C0,S2..3,B3").is_err());
+    assert_eq!(
+        LargerThanLife::parse("R5,C1,M1,S34..58,B34..45,NM").unwrap(),
+        bosco
+    );
+    assert!(LargerThanLife::parse("R5,C0,M1,S58..34,B34..45,NM").is_err());
+    assert!(LargerThanLife::parse("R2,C0,S2,B5-4").is_err());
+    assert!(LargerThanLife::parse("R500,C0,S2,B3").is_ok());
+    assert!(LargerThanLife::parse("R501,C0,S2,B3").is_err());
+    assert!(LargerThanLife::parse("R99999999999999999999,C0,S2,B3").is_err());
+}
+
+#[test]
+#[should_panic(expected = "neighbor count 12 is more than 8")]
+fn generations_count_too_large() {
+    GenerationsRule::new(&[12], &[3, 4], 4);
+}
+
+#[test]
+#[should_panic(expected = "rule radius is larger than the neighborhood radius")]
+fn larger_than_life_radius_too_large() {
+    let rule = LargerThanLife::new(2, vec![3..=3], vec![2..=3]);
+    let _: SquareGrid<_, Radius<1>> = SquareGrid::new(rule, Array2::zeros((4, 4)));
+}
+
+#[test]
+fn larger_than_life_radius_one_is_life() {
+    let cells = soup(2);
+    let rule = LargerThanLife::parse("R1,C0,M0,S2..3,B3..3,NM").unwrap();
+    let mut life = SquareGrid::new(rules::life(), cells.map(|&c| c == 1));
+    let mut ltl: SquareGrid<_, Radius<1>> = SquareGrid::new(rule.clone(), cells.clone());
+    // A rule can run in a neighborhood larger than its own radius.
+    let mut wide: SquareGrid<_, Radius<3>> = SquareGrid::new(rule, cells);
+    for _ in 0..10 {
+        life.step();
+        ltl.step();
+        wide.step();
+        assert_eq!(ltl.cells(), life.cells().map(|&c| c as u8));
+        assert_eq!(wide.cells(), ltl.cells());
+    }
+}
+
+#[test]
+fn larger_than_life_von_neumann_counts_diamond() {
+    let rule = LargerThanLife::new(2, vec![1..=1], vec![]).with_shape(LtlShape::VonNeumann);
+    let mut cells = Array2::zeros((7, 7));
+    cells[(3, 3)] = 1;
+    let mut grid: SquareGrid<_, Radius<2>> = SquareGrid::new(rule, cells);
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((7, 7), |(y, x)| {
+            let distance = (y as isize - 3).abs() + (x as isize - 3).abs();
+            (1..=2).contains(&distance) as u8
+        })
+    );
+}
@@ -0,0 +1,27 @@
+use gridsim::{rules, GridSnapshot, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn frames_snapshots() {
+    let glider = |(y, x)| [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].contains(&(y, x));
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_shape_fn((6, 6), glider));
+    let mut expected = grid.clone();
+    let frames: Vec<GridSnapshot<bool>> = grid.frames().take(3).collect();
+    assert_eq!(
+        frames
+            .iter()
+            .map(|frame| frame.generation())
+            .collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+    for frame in &frames {
+        assert_eq!(frame.cells(), expected.cells());
+        expected.step();
+    }
+    // The grid is left at the last frame taken, and frames can skip steps.
+    assert_eq!(grid.generation(), 2);
+    let frame = grid.frames().every(4).nth(1).unwrap();
+    assert_eq!(frame.generation(), 6);
+    expected.step_n(3);
+    assert_eq!(frame.cells(), expected.cells());
+}
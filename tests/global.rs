@@ -0,0 +1,62 @@
+use gridsim::{GlobalSim, Moore, Sim, SquareGrid};
+use ndarray::{arr2, ArrayView2};
+
+/// Every cell becomes the total of all cells on the previous step.
+#[derive(Debug, Default)]
+pub struct Total {
+    total: u64,
+}
+
+impl Sim<Moore> for Total {
+    type Cell = u64;
+    type Diff = ();
+    type Flow = ();
+
+    fn compute(&self, _: ArrayView2<'_, u64>) {}
+
+    fn egress(&self, cell: &mut u64, _: ArrayView2<'_, ()>) -> [(); 8] {
+        *cell = self.total;
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut u64, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> u64 {
+        0
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) {}
+}
+
+impl GlobalSim<Moore> for Total {
+    type Global = u64;
+
+    fn identity(&self) -> u64 {
+        0
+    }
+
+    fn fold(&self, global: u64, &cell: &u64) -> u64 {
+        global + cell
+    }
+
+    fn combine(&self, a: u64, b: u64) -> u64 {
+        a + b
+    }
+
+    fn set_global(&mut self, global: u64) {
+        self.total = global;
+    }
+}
+
+#[test]
+fn global_total() {
+    let mut grid = SquareGrid::new(Total::default(), arr2(&[[1, 2, 3], [4, 5, 6]]));
+    assert_eq!(grid.global(), 21);
+    grid.step_global();
+    assert_eq!(grid.sim().total, 21);
+    assert_eq!(grid.cells(), arr2(&[[21; 3]; 2]));
+    grid.step_global();
+    assert_eq!(grid.cells(), arr2(&[[126; 3]; 2]));
+}
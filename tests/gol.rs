@@ -1,11 +1,13 @@
-use gridsim::{Neumann, Sim, SquareGrid};
+#![allow(clippy::manual_range_contains)]
+
+use gridsim::{Moore, Sim, SquareGrid};
 use ndarray::{Array2, ArrayView2};
 
 /// Conway's Game of Life
 #[derive(Debug)]
 pub struct Gol;
 
-impl Sim<Neumann> for Gol {
+impl Sim<Moore> for Gol {
     type Cell = bool;
     type Diff = bool;
     type Flow = ();
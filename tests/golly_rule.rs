@@ -0,0 +1,118 @@
+use gridsim::{rules, GollyRule, GollyRuleError, SquareGrid};
+use ndarray::arr2;
+
+const LIFE: &str = "@RULE LifeTable
+A comment about the rule.
+
+@TABLE
+n_states:2
+neighborhood:Moore
+symmetries:permute
+var a={0,1}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+0,1,1,1,0,0,0,0,0,1
+1,1,1,0,0,0,0,0,0,1
+1,1,1,1,0,0,0,0,0,1
+1,a,b,c,d,e,f,g,h,0 # everything else dies
+
+@COLORS
+1 255 255 255
+";
+
+#[test]
+fn golly_table_matches_life() {
+    let rule = GollyRule::parse(LIFE).unwrap();
+    assert_eq!(rule.name(), Some("LifeTable"));
+    assert_eq!(rule.states(), 2);
+
+    let mut life = SquareGrid::new_random(rules::life(), (16, 16), 3, |rng| rng.gen_bool(0.4));
+    let mut table = SquareGrid::new(rule, life.cells().map(|&c| c as u8));
+    for _ in 0..8 {
+        life.step();
+        table.step();
+        assert_eq!(table.cells(), life.cells().map(|&c| c as u8));
+    }
+}
+
+#[test]
+fn golly_table_binds_repeated_variables() {
+    let rule: GollyRule = "@TABLE
+n_states:3
+neighborhood:vonNeumann
+symmetries:rotate4
+var a={1,2}
+0a0a0a
+"
+    .parse()
+    .unwrap();
+    // Opposite neighbors with the same state are copied, in either direction.
+    assert_eq!(
+        rule.next(arr2(&[[0, 2, 0], [0, 0, 0], [0, 2, 0]]).view()),
+        2
+    );
+    assert_eq!(
+        rule.next(arr2(&[[0, 0, 0], [1, 0, 1], [0, 0, 0]]).view()),
+        1
+    );
+    assert_eq!(
+        rule.next(arr2(&[[0, 1, 0], [0, 0, 0], [0, 2, 0]]).view()),
+        0
+    );
+    // Cells which match no line keep their state.
+    assert_eq!(
+        rule.next(arr2(&[[0, 1, 0], [0, 2, 0], [0, 1, 0]]).view()),
+        2
+    );
+}
+
+#[test]
+fn golly_tree_reads_neighbors_in_order() {
+    // Every cell becomes its western neighbor, so patterns move east.
+    let rule = GollyRule::parse(
+        "@RULE East
+@TREE
+num_states=2
+num_neighbors=4
+num_nodes=8
+1 0 0
+1 1 1
+2 0 0
+2 1 1
+3 2 2
+3 3 3
+4 4 5
+5 6 6
+",
+    )
+    .unwrap();
+    let mut grid = SquareGrid::new(rule, arr2(&[[1, 0, 0], [0, 1, 0]]));
+    grid.step();
+    assert_eq!(grid.cells(), arr2(&[[0, 1, 0], [0, 0, 1]]));
+}
+
+#[test]
+fn golly_rule_errors() {
+    assert_eq!(
+        GollyRule::parse("@RULE Nothing\n@COLORS\n"),
+        Err(GollyRuleError::MissingRule)
+    );
+    assert_eq!(
+        GollyRule::parse("@TABLE\nn_states:2\nneighborhood:hexagonal\n"),
+        Err(GollyRuleError::Unsupported("hexagonal".to_owned()))
+    );
+    assert_eq!(
+        GollyRule::parse("@TABLE\nn_states:2\nneighborhood:vonNeumann\n0,1,0\n"),
+        Err(GollyRuleError::InvalidLine(4))
+    );
+    // The next state can't be a variable which isn't bound to a neighbor.
+    assert_eq!(
+        GollyRule::parse("@TABLE\nn_states:2\nvar a={0,1}\n0,0,0,0,0,0,0,0,0,a\n"),
+        Err(GollyRuleError::InvalidLine(4))
+    );
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "gpu")]
+
+use gridsim::{
+    gpu::{self, GpuError, GpuGrid},
+    rules, Boundary, LifeLikeRule, SquareGrid,
+};
+use ndarray::arr2;
+
+#[test]
+fn gpu_life_like_kernel() {
+    let rule = LifeLikeRule::parse("B36/S23").unwrap();
+    let kernel = gpu::life_like_kernel(&rule, false);
+    assert!(kernel.starts_with("// B36/S23\n"));
+    assert!(kernel.contains("const BIRTH: u32 = 72u;"));
+    assert!(kernel.contains("const SURVIVAL: u32 = 12u;"));
+    assert!(kernel.contains("@workgroup_size(16, 16)"));
+    assert!(kernel.contains("return 0u;"));
+    assert!(gpu::life_like_kernel(&rule, true).contains("% i32(params.width)"));
+
+    let kernel = gpu::custom_kernel("    return cell(y - 1, x);");
+    assert!(kernel.contains("fn step(y: i32, x: i32) -> u32 {\n    return cell(y - 1, x);\n}"));
+}
+
+#[test]
+fn gpu_buffers() {
+    let cells = arr2(&[[true, false, true], [false, false, true]]);
+    let buffer = gpu::to_buffer(cells.view(), |&c| c as u32);
+    assert_eq!(buffer, vec![1, 0, 1, 0, 0, 1]);
+    assert_eq!(gpu::from_buffer(&buffer, (2, 3), |s| s == 1), cells);
+}
+
+#[test]
+fn gpu_validate_kernels() {
+    let rule = LifeLikeRule::parse("B36/S23").unwrap();
+    gpu::validate_kernel(&gpu::life_like_kernel(&rule, false)).unwrap();
+    gpu::validate_kernel(&gpu::life_like_kernel(&rule, true)).unwrap();
+    gpu::validate_kernel(&gpu::custom_kernel("    return cell(y - 1, x);")).unwrap();
+    assert!(matches!(
+        gpu::validate_kernel(&gpu::custom_kernel("    return cell(y - 1);")),
+        Err(GpuError::Kernel(_))
+    ));
+}
+
+#[test]
+fn gpu_dispatch() {
+    let rule = rules::life();
+    let random = SquareGrid::new_random(rule, (40, 37), 5, |rng| rng.gen_bool(0.4));
+    let cells = random.cells().map(|&c| c as u32);
+    for &(wrap, boundary) in &[(false, Boundary::Constant(false)), (true, Boundary::Wrap)] {
+        let kernel = gpu::life_like_kernel(&rule, wrap);
+        let mut gpu = match GpuGrid::new(&kernel, cells.view()) {
+            Ok(gpu) => gpu,
+            // There is nothing to dispatch to.
+            Err(GpuError::NoAdapter) => return,
+            Err(error) => panic!("{}", error),
+        };
+        assert_eq!(gpu.cells(), cells);
+        let mut grid = SquareGrid::with_boundary(rule, random.cells().to_owned(), boundary);
+        gpu.step();
+        gpu.step_n(9);
+        grid.step_n(10);
+        assert_eq!(gpu.generation(), 10);
+        assert_eq!(gpu.cells(), grid.cells().map(|&c| c as u32));
+
+        gpu.set_cells(cells.view());
+        gpu.step_n(3);
+        grid.cells_mut().assign(&random.cells());
+        grid.step_n(3);
+        assert_eq!(gpu.cells(), grid.cells().map(|&c| c as u32));
+    }
+    let kernel = gpu::custom_kernel("    return 0u;");
+    assert_eq!(
+        GpuGrid::new(&kernel, ndarray::Array2::zeros((0, 3)).view()).unwrap_err(),
+        GpuError::Dimensions((0, 3))
+    );
+}
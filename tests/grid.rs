@@ -0,0 +1,130 @@
+use gridsim::{
+    rules, BitGrid, Boundary, Grid, InfiniteGrid, Moore, Sim, SquareGrid, VonNeumann,
+    VonNeumannNeighbors,
+};
+use ndarray::Array2;
+use std::{collections::BTreeSet, fmt::Debug, hash::Hash};
+
+/// Count the live cells with exactly `n` live neighbors, written once for every grid.
+fn with_live_neighbors<G>(grid: &G, n: usize) -> BTreeSet<G::Index>
+where
+    G: Grid<Cell = bool>,
+    G::Index: Ord + Hash + Debug,
+{
+    grid.indices()
+        .filter(|&index| grid.cell(index) == Some(true))
+        .filter(|&index| {
+            grid.neighbors(index)
+                .into_iter()
+                .filter(|&neighbor| grid.cell(neighbor) == Some(true))
+                .count()
+                == n
+        })
+        .collect()
+}
+
+fn step_all<G: Grid>(grid: &mut G, n: usize) {
+    for _ in 0..n {
+        grid.step();
+    }
+}
+
+#[test]
+fn generic_grids_agree() {
+    let mut cells = Array2::from_elem((8, 8), false);
+    for &index in &[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)] {
+        cells[index] = true;
+    }
+    let mut square = SquareGrid::new(rules::life(), cells.clone());
+    let mut bits = BitGrid::from_cells(rules::life(), cells.view());
+    let mut infinite = InfiniteGrid::from_cells(
+        rules::life(),
+        cells
+            .indexed_iter()
+            .filter(|(_, &c)| c)
+            .map(|((y, x), &c)| ((y as i64, x as i64), c)),
+    );
+    step_all(&mut square, 4);
+    step_all(&mut bits, 4);
+    step_all(&mut infinite, 4);
+
+    let expected = with_live_neighbors(&square, 2);
+    assert_eq!(expected.len(), 1);
+    assert_eq!(with_live_neighbors(&bits, 2), expected);
+    let positions: BTreeSet<_> = expected
+        .iter()
+        .map(|&(y, x)| (y as i64, x as i64))
+        .collect();
+    assert_eq!(with_live_neighbors(&infinite, 2), positions);
+    assert_eq!(Grid::cell(&square, (8, 0)), None);
+    assert_eq!(Grid::cell(&bits, (0, 8)), None);
+}
+
+#[test]
+fn square_neighbors_follow_boundaries() {
+    let cells = Array2::from_elem((4, 5), false);
+    let closed = SquareGrid::new(rules::life(), cells.clone());
+    assert_eq!(closed.neighbors((0, 0)), [(0, 1), (1, 0), (1, 1)]);
+    assert_eq!(closed.neighbors((1, 1)).len(), 8);
+    let torus = SquareGrid::with_boundary(rules::life(), cells, Boundary::Wrap);
+    let neighbors = torus.neighbors((0, 0));
+    assert_eq!(neighbors.len(), 8);
+    assert!(neighbors.contains(&(3, 4)));
+    assert_eq!(torus.indices().count(), 20);
+}
+
+/// A cell turns on when any of its four side neighbors is on.
+#[derive(Debug)]
+struct Spread;
+
+impl Sim<VonNeumann> for Spread {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: VonNeumannNeighbors<'_, bool>) -> bool {
+        *cells.center() || cells.iter().any(|&c| c)
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: VonNeumannNeighbors<'_, bool>) -> [(); 4] {
+        *cell = *diffs.center();
+        [(); 4]
+    }
+
+    fn ingress(&self, _: &mut bool, _: [(); 4]) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn von_neumann_and_moore() {
+    let mut cells = Array2::from_elem((5, 5), false);
+    cells[(2, 2)] = true;
+    let mut grid = SquareGrid::new(Spread, cells.clone());
+    grid.step();
+    let on: BTreeSet<_> = grid
+        .iter_coords()
+        .filter(|(_, &c)| c)
+        .map(|(i, _)| i)
+        .collect();
+    let expected: BTreeSet<_> = VonNeumann::DELTAS
+        .iter()
+        .map(|&(dy, dx)| ((2 + dy) as usize, (2 + dx) as usize))
+        .chain(Some((2, 2)))
+        .collect();
+    assert_eq!(on, expected);
+    assert_eq!(Grid::neighbors(&grid, (2, 2)).len(), 4);
+
+    let life = SquareGrid::<_, Moore>::new(rules::life(), cells);
+    assert_eq!(Grid::neighbors(&life, (2, 2)).len(), Moore::DELTAS.len());
+    #[allow(deprecated)]
+    let _: &SquareGrid<_, gridsim::Neumann> = &life;
+}
@@ -0,0 +1,158 @@
+use gridsim::{HashLifeGrid, InfiniteGrid, MacrocellError, Moore, Sim};
+use ndarray::{Array2, ArrayView2};
+
+fn life(cells: ArrayView2<'_, bool>) -> bool {
+    let n = cells.iter().filter(|&&c| c).count();
+    if cells[(1, 1)] {
+        (3..=4).contains(&n)
+    } else {
+        n == 3
+    }
+}
+
+/// Conway's Game of Life
+#[derive(Debug)]
+pub struct Gol;
+
+impl Sim<Moore> for Gol {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        life(cells)
+    }
+
+    fn egress(&self, cell: &mut Self::Cell, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Self::Cell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Self::Cell {
+        false
+    }
+
+    fn diff_padding(&self) -> Self::Diff {
+        false
+    }
+
+    fn flow_padding(&self) -> Self::Flow {}
+}
+
+/// A small deterministic soup of cells.
+fn soup() -> Array2<bool> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    Array2::from_shape_simple_fn((16, 16), || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state & 3 == 0
+    })
+}
+
+#[test]
+fn hash_life_matches_brute_force() {
+    let soup = soup();
+    let mut brute = InfiniteGrid::from_cells(
+        Gol,
+        soup.indexed_iter()
+            .map(|((y, x), &c)| ((y as i64, x as i64), c)),
+    );
+    let mut hash = HashLifeGrid::from_cells(life, soup.view());
+    let mut generation = 0;
+    for &steps in &[1, 2, 3, 8, 5, 16, 37] {
+        for _ in 0..steps {
+            brute.step();
+        }
+        hash.advance(steps);
+        generation += steps;
+        assert_eq!(hash.generation(), generation);
+        let mut expected: Vec<_> = brute.iter().map(|(position, _)| position).collect();
+        expected.sort_unstable();
+        let mut actual = hash.live_cells();
+        actual.sort_unstable();
+        assert_eq!(actual, expected, "generation {}", generation);
+    }
+}
+
+#[test]
+fn hash_life_glider_skips_ahead() {
+    let glider = Array2::from_shape_fn((3, 3), |p| {
+        [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].contains(&p)
+    });
+    let mut grid = HashLifeGrid::from_cells(life, glider.view());
+    grid.advance(1 << 20);
+    assert_eq!(grid.population(), 5);
+    let offset = 1 << 18;
+    assert_eq!(grid.cells((offset, offset), (3, 3)), glider);
+    assert!(!grid.get((0, 1)));
+}
+
+#[test]
+fn hash_life_collects_garbage() {
+    let soup = soup();
+    let mut unlimited = HashLifeGrid::from_cells(life, soup.view());
+    let mut limited = HashLifeGrid::from_cells(life, soup.view());
+    limited.set_node_limit(2000);
+    let mut most = 0;
+    for _ in 0..40 {
+        unlimited.advance(97);
+        limited.advance(97);
+        most = most.max(limited.node_count());
+        assert_eq!(limited.live_cells(), unlimited.live_cells());
+    }
+    assert!(most < unlimited.node_count());
+
+    // Only the nodes of the grid are left after collecting.
+    let population = unlimited.population();
+    unlimited.collect_garbage();
+    assert_eq!(unlimited.population(), population);
+    assert!(unlimited.node_count() < most);
+    unlimited.advance(1000);
+    limited.advance(1000);
+    assert_eq!(limited.live_cells(), unlimited.live_cells());
+}
+
+#[test]
+fn hash_life_macrocell() {
+    // A glider as Golly writes it, with its leaf in the south-east quadrant of the root.
+    let text = "[M2] (golly 2.0)\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n";
+    let grid = HashLifeGrid::from_macrocell(life, text).unwrap();
+    let mut cells = grid.live_cells();
+    cells.sort_unstable();
+    assert_eq!(cells, vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(
+        grid.to_macrocell(Some("B3/S23")),
+        "[M2] (gridsim)\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n"
+    );
+
+    // A cell far from the rest round-trips without expanding the empty space in between.
+    let mut grid = HashLifeGrid::from_cells(life, soup().view());
+    grid.advance(1 << 12);
+    grid.set((-1 << 30, 1 << 30), true);
+    let text = grid.to_macrocell(None);
+    assert!(text.lines().count() < 1000);
+    let read = HashLifeGrid::from_macrocell(life, &text).unwrap();
+    let (mut expected, mut actual) = (grid.live_cells(), read.live_cells());
+    expected.sort_unstable();
+    actual.sort_unstable();
+    assert_eq!(actual, expected);
+
+    let empty = HashLifeGrid::new(life).to_macrocell(None);
+    assert_eq!(
+        HashLifeGrid::from_macrocell(life, &empty)
+            .unwrap()
+            .population(),
+        0
+    );
+    assert_eq!(
+        HashLifeGrid::from_macrocell(life, "x = 3, y = 3\n").err(),
+        Some(MacrocellError::MissingHeader)
+    );
+    assert_eq!(
+        HashLifeGrid::from_macrocell(life, "[M2]\n.*$\n5 0 0 0 1\n").err(),
+        Some(MacrocellError::InvalidLine(3))
+    );
+}
@@ -0,0 +1,122 @@
+use gridsim::{Hex, HexDirection, HexGrid, HexNeighbors, Sim};
+use ndarray::Array2;
+
+/// Packard's snowflake: a cell freezes when exactly one neighbor is frozen.
+#[derive(Debug)]
+pub struct Snowflake;
+
+impl Sim<Hex> for Snowflake {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: HexNeighbors<'_, bool>) -> bool {
+        *cells.center() || cells.iter().filter(|&&c| c).count() == 1
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: HexNeighbors<'_, bool>) -> [(); 6] {
+        *cell = *diffs.center();
+        [(); 6]
+    }
+
+    fn ingress(&self, _: &mut bool, _: [(); 6]) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
+
+/// Particles that travel in a straight line.
+#[derive(Debug)]
+pub struct Particles;
+
+impl Sim<Hex> for Particles {
+    type Cell = Option<HexDirection>;
+    type Diff = ();
+    type Flow = Option<HexDirection>;
+
+    fn compute(&self, _: HexNeighbors<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: HexNeighbors<'_, ()>) -> [Option<HexDirection>; 6] {
+        let mut flows = [None; 6];
+        if let Some(direction) = cell.take() {
+            flows[direction as usize] = Some(direction);
+        }
+        flows
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Option<HexDirection>; 6]) {
+        for direction in HexDirection::ALL.iter().copied() {
+            if let Some(particle) = flows[direction as usize] {
+                assert_eq!(particle, direction.opposite());
+                *cell = Some(particle);
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        None
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+#[test]
+fn hex_snowflake_grows_to_neighbors() {
+    let mut grid = HexGrid::new(
+        Snowflake,
+        Array2::from_shape_fn((5, 5), |(r, q)| r == 2 && q == 2),
+    );
+    grid.step();
+    let expected = [(2, 2), (2, 3), (1, 3), (1, 2), (2, 1), (3, 1), (3, 2)];
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((5, 5), |rq| expected.contains(&rq))
+    );
+}
+
+#[test]
+fn hex_particles_move_along_deltas() {
+    for direction in HexDirection::ALL.iter().copied() {
+        let mut grid = HexGrid::new(
+            Particles,
+            Array2::from_shape_fn((5, 5), |(r, q)| {
+                if r == 2 && q == 2 {
+                    Some(direction)
+                } else {
+                    None
+                }
+            }),
+        );
+        grid.step();
+        let (dq, dr) = direction.delta();
+        let target = ((2 + dr) as usize, (2 + dq) as usize);
+        assert_eq!(
+            grid.cells(),
+            Array2::from_shape_fn((5, 5), |rq| if rq == target {
+                Some(direction)
+            } else {
+                None
+            })
+        );
+    }
+}
+
+#[test]
+fn hex_direction_cube_deltas_sum_to_zero() {
+    for direction in HexDirection::ALL.iter().copied() {
+        let (x, y, z) = direction.cube_delta();
+        assert_eq!(x + y + z, 0);
+        assert_eq!(direction.opposite().opposite(), direction);
+    }
+}
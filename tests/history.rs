@@ -0,0 +1,38 @@
+use gridsim::{rules, GridError, GridSnapshot, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn history_snapshot_and_rollback() {
+    let cells = Array2::from_shape_fn((9, 11), |(y, x)| (y * 3 + x * 7) % 5 < 2);
+    let mut grid = SquareGrid::new(rules::life(), cells);
+    let start = grid.snapshot();
+    grid.keep_last(3);
+    let mut states = vec![grid.snapshot()];
+    for _ in 0..5 {
+        grid.step();
+        states.push(grid.snapshot());
+    }
+    assert_eq!(grid.history_len(), 3);
+    assert_eq!(grid.rollback(1), 1);
+    assert_eq!(grid.snapshot(), states[4]);
+    assert_eq!(grid.rollback(10), 2);
+    assert_eq!(grid.snapshot(), states[2]);
+    assert_eq!(grid.rollback(1), 0);
+
+    grid.step();
+    assert_eq!(grid.snapshot(), states[3]);
+    grid.restore(&start).unwrap();
+    assert_eq!(grid.generation(), 0);
+    assert_eq!(grid.history_len(), 0);
+    assert_eq!(grid.snapshot(), states[0]);
+
+    let small = GridSnapshot::new(4, Array2::from_elem((2, 3), true)).unwrap();
+    grid.restore(&small).unwrap();
+    assert_eq!(grid.cells(), small.cells());
+    assert_eq!(grid.generation(), small.generation());
+
+    assert_eq!(
+        GridSnapshot::new(0, Array2::<bool>::from_elem((0, 3), true)),
+        Err(GridError::Empty)
+    );
+}
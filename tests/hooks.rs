@@ -0,0 +1,48 @@
+use gridsim::{rules, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn hooks_before_after_changed() {
+    use std::sync::{Arc, Mutex};
+
+    let mut blinker = SquareGrid::new(rules::life(), Array2::from_elem((5, 5), false));
+    blinker.fill_region((2..3, 1..4), true);
+    let log = Arc::new(Mutex::new(vec![]));
+    let before = log.clone();
+    blinker.on_before_step(move |grid| {
+        before
+            .lock()
+            .unwrap()
+            .push(format!("before {}", grid.generation()))
+    });
+    let after = log.clone();
+    blinker.on_after_step(move |grid| {
+        after
+            .lock()
+            .unwrap()
+            .push(format!("after {}", grid.generation()))
+    });
+    let changed = log.clone();
+    blinker.on_cell_changed(move |(y, x), &old, &new| {
+        changed
+            .lock()
+            .unwrap()
+            .push(format!("({}, {}) {} -> {}", y, x, old, new))
+    });
+    blinker.step();
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "before 0",
+            "(1, 2) false -> true",
+            "(2, 1) true -> false",
+            "(2, 3) true -> false",
+            "(3, 2) false -> true",
+            "after 1",
+        ]
+    );
+
+    blinker.clear_hooks();
+    blinker.step();
+    assert_eq!(log.lock().unwrap().len(), 6);
+}
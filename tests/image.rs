@@ -0,0 +1,89 @@
+#![cfg(feature = "image")]
+
+use gridsim::{rules, Animator, CellColor, SquareGrid};
+use ndarray::array;
+use std::time::Duration;
+
+#[test]
+fn image_png() {
+    let grid = SquareGrid::new(
+        rules::life(),
+        array![[true, false, true], [false, false, true]],
+    );
+    let image = grid.to_image();
+    assert_eq!(image.dim(), (2, 3));
+    assert_eq!(image[(1, 2)], true.color());
+
+    let mut png = vec![];
+    grid.write_png(&mut png).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+    // The checksum of an empty IEND chunk is always the same.
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+    // IDAT holds a zlib header and one stored block of the filtered rows.
+    let idat = 8 + 25;
+    assert_eq!(&png[idat + 4..idat + 8], b"IDAT");
+    let zlib = &png[idat + 8..];
+    assert_eq!(&zlib[..3], &[0x78, 0x01, 1]);
+    let len = u16::from_le_bytes([zlib[3], zlib[4]]) as usize;
+    assert_eq!(len, 2 * (1 + 3 * 4));
+    let rows = &zlib[7..7 + len];
+    assert_eq!(rows[0], 0);
+    assert_eq!(&rows[1..5], &[255; 4]);
+    assert_eq!(&rows[5..9], &[0, 0, 0, 255]);
+}
+
+/// Split a PNG into the kinds and data of its chunks.
+fn chunks(png: &[u8]) -> Vec<(String, &[u8])> {
+    let mut chunks = vec![];
+    let mut rest = &png[8..];
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let kind = String::from_utf8(rest[4..8].to_vec()).unwrap();
+        chunks.push((kind, &rest[8..8 + len]));
+        rest = &rest[12 + len..];
+    }
+    chunks
+}
+
+#[test]
+fn image_apng() {
+    let mut blinker = SquareGrid::new(rules::life(), ndarray::Array2::from_elem((5, 5), false));
+    blinker.fill_region((2..3, 1..4), true);
+    let mut animator = Animator::new(2, 3);
+    animator.set_delay(Duration::from_millis(250));
+    for _ in 0..5 {
+        animator.capture(&blinker);
+        blinker.step();
+    }
+    assert!(!animator.capture(&blinker));
+    blinker.step();
+    assert!(animator.capture_with(&blinker, |&c| if c { [255, 0, 0, 255] } else { [0; 4] }));
+    assert_eq!(animator.frames().len(), 4);
+    assert_eq!(animator.frames()[0].dim(), (15, 15));
+    assert_eq!(animator.frames()[0][(7, 3)], true.color());
+    assert_eq!(animator.frames()[1][(3, 7)], false.color());
+    assert_eq!(animator.frames()[3][(7, 3)], [255, 0, 0, 255]);
+
+    let mut png = vec![];
+    animator.write_apng(&mut png).unwrap();
+    let chunks = chunks(&png);
+    let kinds: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+    assert_eq!(
+        kinds,
+        ["IHDR", "acTL", "fcTL", "IDAT", "fcTL", "fdAT", "fcTL", "fdAT", "fcTL", "fdAT", "IEND"]
+    );
+    assert_eq!(chunks[1].1, &[0, 0, 0, 4, 0, 0, 0, 0]);
+    let sequence = |data: &[u8]| u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+    let sequences: Vec<u32> = chunks[2..10]
+        .iter()
+        .filter(|(kind, _)| kind != "IDAT")
+        .map(|(_, data)| sequence(data))
+        .collect();
+    assert_eq!(sequences, [0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(&chunks[2].1[20..24], &[0, 250, 3, 232]);
+
+    assert!(Animator::new(1, 1).write_apng(&mut vec![]).is_err());
+}
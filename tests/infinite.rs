@@ -0,0 +1,99 @@
+use gridsim::{InfiniteGrid, Moore, Sim};
+use ndarray::ArrayView2;
+
+/// Conway's Game of Life
+#[derive(Debug)]
+pub struct Gol;
+
+impl Sim<Moore> for Gol {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        let n = cells.iter().filter(|&&c| c).count();
+        if cells[(1, 1)] {
+            (3..=4).contains(&n)
+        } else {
+            n == 3
+        }
+    }
+
+    fn egress(&self, cell: &mut Self::Cell, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Self::Cell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Self::Cell {
+        false
+    }
+
+    fn diff_padding(&self) -> Self::Diff {
+        false
+    }
+
+    fn flow_padding(&self) -> Self::Flow {}
+}
+
+/// Particles that travel along an edge forever.
+#[derive(Debug)]
+pub struct Particles;
+
+impl Sim<Moore> for Particles {
+    type Cell = Option<usize>;
+    type Diff = ();
+    type Flow = Option<usize>;
+
+    fn compute(&self, _: ArrayView2<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: ArrayView2<'_, ()>) -> [Option<usize>; 8] {
+        let mut flows = [None; 8];
+        if let Some(edge) = cell.take() {
+            flows[edge] = Some(edge);
+        }
+        flows
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Option<usize>; 8]) {
+        *cell = flows.iter().find_map(|&flow| flow);
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        None
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+#[test]
+fn infinite_glider_travels() {
+    let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+    let mut grid = InfiniteGrid::from_cells(Gol, glider.iter().map(|&p| (p, true)));
+    for _ in 0..400 {
+        grid.step();
+    }
+    let mut cells: Vec<_> = grid.iter().map(|(position, _)| position).collect();
+    cells.sort_unstable();
+    let expected: Vec<_> = glider.iter().map(|&(y, x)| (y + 100, x + 100)).collect();
+    assert_eq!(cells, expected);
+}
+
+#[test]
+fn infinite_particles_leave_origin() {
+    let mut grid = InfiniteGrid::from_cells(Particles, vec![((0, 0), Some(0)), ((0, 0), Some(5))]);
+    assert_eq!(grid.len(), 1);
+    grid.set((-3, 7), Some(2));
+    for _ in 0..10 {
+        grid.step();
+    }
+    assert_eq!(grid.len(), 2);
+    assert_eq!(*grid.get((10, -10)), Some(5));
+    assert_eq!(*grid.get((-13, 7)), Some(2));
+    assert_eq!(*grid.get((0, 0)), None);
+}
@@ -0,0 +1,83 @@
+use gridsim::{Boundary, CollisionTable, Fhp, HexGrid, Hpp, SquareGrid, FHP_CHIRALITY};
+use ndarray::Array2;
+
+fn hex_velocities() -> Vec<(f64, f64)> {
+    (0..6)
+        .map(|i| {
+            let angle = i as f64 * std::f64::consts::PI / 3.0;
+            (-angle.sin(), angle.cos())
+        })
+        .collect()
+}
+
+#[test]
+fn collision_tables_conserve() {
+    let square = [(0.0, 1.0), (-1.0, 0.0), (0.0, -1.0), (1.0, 0.0)];
+    assert!(CollisionTable::hpp().is_conservative(&square));
+    assert!(CollisionTable::fhp(true).is_conservative(&hex_velocities()));
+    assert!(CollisionTable::fhp(false).is_conservative(&hex_velocities()));
+    assert_eq!(CollisionTable::fhp(true).directions(), 6);
+    assert_eq!(CollisionTable::fhp(true).collide(0b00_1001), 0b01_0010);
+    assert_eq!(CollisionTable::fhp(false).collide(0b00_1001), 0b10_0100);
+
+    let broken = CollisionTable::from_fn(4, |state| if state == 0b0001 { 0b0010 } else { state });
+    assert!(!broken.is_conservative(&square));
+}
+
+#[test]
+fn hpp_conserves_on_torus() {
+    let cells = Array2::from_shape_fn((16, 16), |(y, x)| ((y * 13 + x * 7) % 16) as u8);
+    let count =
+        |cells: ndarray::ArrayView2<'_, u8>| cells.iter().map(|c| c.count_ones()).sum::<u32>();
+    let momentum = |cells: ndarray::ArrayView2<'_, u8>| {
+        cells.iter().fold((0i32, 0i32), |(y, x), &c| {
+            let bit = |i: u8| (c >> i & 1) as i32;
+            (y + bit(3) - bit(1), x + bit(0) - bit(2))
+        })
+    };
+    let mut grid = SquareGrid::with_boundary(Hpp::default(), cells, Boundary::Wrap);
+    let (mass, p) = (count(grid.cells()), momentum(grid.cells()));
+    for _ in 0..50 {
+        grid.step();
+        assert_eq!(count(grid.cells()), mass);
+        assert_eq!(momentum(grid.cells()), p);
+    }
+}
+
+#[test]
+fn hpp_particles_move_and_collide() {
+    let mut cells = Array2::zeros((5, 5));
+    // One particle moving east and one moving west, meeting in the middle.
+    cells[(2, 1)] = 0b0001;
+    cells[(2, 3)] = 0b0100;
+    let mut grid = SquareGrid::new(Hpp::default(), cells);
+    grid.step();
+    assert_eq!(grid.cells()[(2, 2)], 0b0101);
+    grid.step();
+    assert_eq!(grid.cells()[(1, 2)], 0b0010);
+    assert_eq!(grid.cells()[(3, 2)], 0b1000);
+}
+
+#[test]
+fn fhp_conserves_away_from_edges() {
+    let mut cells = Array2::from_elem((21, 21), 0u8);
+    for y in 8..13 {
+        for x in 8..13 {
+            cells[(y, x)] = ((y * 11 + x * 5) % 64) as u8;
+        }
+    }
+    let mass = |grid: &HexGrid<Fhp>| {
+        grid.cells()
+            .iter()
+            .map(|c| (c & !FHP_CHIRALITY).count_ones())
+            .sum::<u32>()
+    };
+    let mut grid = HexGrid::new(Fhp::default(), cells);
+    let start = mass(&grid);
+    for _ in 0..5 {
+        grid.step();
+        assert_eq!(mass(&grid), start);
+    }
+    // Every cell started turning left, so after an odd number of steps they all turn right.
+    assert!(grid.cells().iter().all(|&c| c & FHP_CHIRALITY != 0));
+}
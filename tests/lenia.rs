@@ -0,0 +1,152 @@
+use gridsim::{Boundary, Kernel, Lenia, Moore, Radius, Sim, SmoothLife, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+/// A small deterministic soup of values between `0` and `1`.
+fn soup(dims: (usize, usize)) -> Array2<f64> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    Array2::from_shape_simple_fn(dims, || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % 1000) as f64 / 1000.0
+    })
+}
+
+/// Diffuses the cells using the laplacian.
+#[derive(Debug)]
+pub struct Diffusion {
+    laplacian: Kernel,
+    rate: f64,
+}
+
+impl Sim<Moore> for Diffusion {
+    type Cell = f64;
+    type Diff = f64;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, f64>) -> f64 {
+        let neighbors = self.laplacian.neighbors(cells);
+        neighbors.center() + self.rate * neighbors.weighted_sum()
+    }
+
+    fn egress(&self, cell: &mut f64, diffs: ArrayView2<'_, f64>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut f64, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn diff_padding(&self) -> f64 {
+        0.0
+    }
+
+    fn flow_padding(&self) {}
+}
+
+fn assert_close(a: &Array2<f64>, b: &Array2<f64>) {
+    assert_eq!(a.dim(), b.dim());
+    for (a, b) in a.iter().zip(b) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn kernel_shapes() {
+    let kernel = Kernel::lenia(6, &[1.0, 0.5]);
+    assert_eq!(kernel.radius(), 6);
+    assert!((kernel.weights().sum() - 1.0).abs() < 1e-12);
+    assert_eq!(kernel.weights()[(6, 6)], 0.0);
+
+    let disk = Kernel::disk(2.0);
+    assert_eq!(disk.radius(), 2);
+    assert_eq!(disk.weights()[(2, 2)], 1.0);
+    assert_eq!(disk.weights()[(0, 0)], 0.0);
+    let ring = Kernel::annulus(1.0, 3.0);
+    assert_eq!(ring.weights()[(3, 3)], 0.0);
+    assert_eq!(ring.weights()[(3, 5)], 1.0);
+}
+
+#[test]
+fn kernel_convolve_matches_apply() {
+    let cells = soup((13, 9));
+    let kernel = Kernel::radial(2, |d| 1.0 / (1.0 + d));
+    let convolved = kernel.convolve(cells.view());
+    let mut padded = Array2::zeros((17, 13));
+    padded.slice_mut(ndarray::s![2..15, 2..11]).assign(&cells);
+    let direct = Array2::from_shape_fn((13, 9), |(y, x)| {
+        kernel.apply(padded.slice(ndarray::s![y..y + 5, x..x + 5]))
+    });
+    assert_close(&convolved, &direct);
+}
+
+#[test]
+fn lenia_fft_matches_grid() {
+    let lenia = Lenia::new(Kernel::lenia(4, &[1.0]), 0.15, 0.015);
+    let mut cells = soup((20, 20));
+    let mut grid: SquareGrid<_, Radius<5>> = SquareGrid::new(lenia.clone(), cells.clone());
+    for _ in 0..3 {
+        grid.step();
+        lenia.step_fft(cells.view_mut());
+    }
+    assert_close(&grid.cells().to_owned(), &cells);
+    assert!(cells.iter().all(|&cell| (0.0..=1.0).contains(&cell)));
+}
+
+#[test]
+fn smooth_life_fft_matches_grid() {
+    let mut rule = SmoothLife::new(4.0);
+    rule.dt = Some(0.2);
+    let mut cells = soup((16, 24));
+    let mut grid: SquareGrid<_, Radius<4>> = SquareGrid::new(rule.clone(), cells.clone());
+    for _ in 0..2 {
+        grid.step();
+        rule.step_fft(cells.view_mut());
+    }
+    assert_close(&grid.cells().to_owned(), &cells);
+}
+
+#[test]
+fn smooth_life_empty_stays_empty() {
+    let rule = SmoothLife::new(3.0);
+    assert!(rule.transition(0.0, 0.0) < 1e-3);
+    let mut grid: SquareGrid<_, Radius<3>> = SquareGrid::new(rule, Array2::zeros((8, 8)));
+    grid.step();
+    assert!(grid.cells().iter().all(|&cell| cell < 1e-3));
+}
+
+#[test]
+fn kernel_neighbors() {
+    let kernel = Kernel::gaussian(1, 1.0);
+    let window = Array2::from_shape_fn((5, 5), |(y, x)| (y * 5 + x) as u8);
+    let neighbors = kernel.neighbors(window.view());
+    assert_eq!(*neighbors.center(), 12);
+    assert_eq!(neighbors.window().dim(), (3, 3));
+    assert_eq!(neighbors.iter().count(), 9);
+    // The window is symmetric around the center, so the blur doesn't change it.
+    assert!((neighbors.weighted_sum() - 12.0).abs() < 1e-9);
+    assert!(
+        (neighbors.weighted_sum_by(|&c| (c == 12) as u8 as f64) - kernel.weights()[(1, 1)]).abs()
+            < 1e-12
+    );
+}
+
+#[test]
+fn kernel_diffusion() {
+    let mut cells = Array2::zeros((8, 8));
+    cells[(3, 4)] = 1.0;
+    let sim = Diffusion {
+        laplacian: Kernel::laplacian(),
+        rate: 0.5,
+    };
+    let mut grid = SquareGrid::with_boundary(sim, cells, Boundary::Wrap);
+    for _ in 0..10 {
+        grid.step();
+    }
+    assert!((grid.cells().sum() - 1.0).abs() < 1e-9);
+    assert!(grid.cells()[(3, 4)] < 0.5);
+    assert!(grid.cells().iter().all(|&c| c > 0.0));
+}
@@ -0,0 +1,87 @@
+use gridsim::{parse_life, rules, write_life_105, LifeError, SquareGrid};
+use ndarray::Array2;
+
+const GLIDER_106: &str = "#Life 1.06
+0 -1
+1 0
+-1 1
+0 1
+1 1
+";
+
+const GLIDER_105: &str = "#Life 1.05
+#D A glider
+#N
+#P -1 -1
+.*
+..*
+***
+";
+
+fn glider() -> Vec<(i64, i64)> {
+    vec![(-1, 0), (0, 1), (1, -1), (1, 0), (1, 1)]
+}
+
+#[test]
+fn life_parse_both_versions() {
+    let pattern = parse_life(GLIDER_106).unwrap();
+    assert_eq!(pattern.rule, None);
+    assert_eq!(pattern.cells, glider());
+
+    let pattern = parse_life(GLIDER_105).unwrap();
+    assert_eq!(pattern.rule.as_deref(), Some("23/3"));
+    assert_eq!(pattern.cells, glider());
+}
+
+#[test]
+fn life_grid_round_trip() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((5, 6), false));
+    grid.place_life(GLIDER_106, (2, 3)).unwrap();
+    assert_eq!(
+        grid.to_chars(|&c| if c { '#' } else { '.' }),
+        "......\n...#..\n....#.\n..###.\n......\n"
+    );
+    assert_eq!(
+        grid.to_life_106((2, 3)),
+        "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n"
+    );
+    assert_eq!(
+        grid.to_life_105((2, 3)),
+        "#Life 1.05\n#P -1 -1\n.*\n..*\n***\n"
+    );
+
+    // Cells beyond the edges are left out.
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((2, 2), false));
+    grid.place_life(GLIDER_105, (0, 0)).unwrap();
+    assert_eq!(grid.to_chars(|&c| if c { '#' } else { '.' }), ".#\n##\n");
+}
+
+#[test]
+fn life_105_blocks() {
+    // Blank rows stay inside a block, and distant cells start another.
+    let cells = vec![(-3, 2), (0, 4), (5, 200)];
+    let text = write_life_105(cells.clone(), Some("23/36"));
+    assert_eq!(
+        text,
+        "#Life 1.05\n#R 23/36\n#P 2 -3\n*\n.\n.\n..*\n#P 162 5\n......................................*\n"
+    );
+    let pattern = parse_life(&text).unwrap();
+    assert_eq!(pattern.rule.as_deref(), Some("23/36"));
+    assert_eq!(pattern.cells, cells);
+}
+
+#[test]
+fn life_errors() {
+    assert_eq!(
+        parse_life("x = 1, y = 1\no!"),
+        Err(LifeError::MissingHeader)
+    );
+    assert_eq!(
+        parse_life("#Life 1.06\n0 0\n1\n"),
+        Err(LifeError::InvalidLine(3))
+    );
+    assert_eq!(
+        parse_life("#Life 1.05\n#P 0 0\n.o\n"),
+        Err(LifeError::InvalidLine(3))
+    );
+}
@@ -0,0 +1,49 @@
+use gridsim::{LifeLikeRule, ParseRuleError, SquareGrid};
+
+#[test]
+fn life_like_parse_notations() {
+    let conway = LifeLikeRule::new(&[3], &[2, 3]);
+    assert_eq!(LifeLikeRule::parse("B3/S23"), Ok(conway));
+    assert_eq!(LifeLikeRule::parse("b3/s23"), Ok(conway));
+    assert_eq!(LifeLikeRule::parse("S23/B3"), Ok(conway));
+    assert_eq!(LifeLikeRule::parse("23/3"), Ok(conway));
+    assert_eq!("B36/S23".parse(), Ok(LifeLikeRule::new(&[3, 6], &[2, 3])));
+    assert_eq!(
+        LifeLikeRule::parse("B2/S"),
+        Ok(LifeLikeRule::new(&[2], &[]))
+    );
+    assert_eq!(conway.to_string(), "B3/S23");
+}
+
+#[test]
+fn life_like_parse_errors() {
+    assert_eq!(
+        LifeLikeRule::parse("B3S23"),
+        Err(ParseRuleError::InvalidFormat("B3S23".to_owned()))
+    );
+    assert_eq!(
+        LifeLikeRule::parse("B3/X23"),
+        Err(ParseRuleError::InvalidFormat("B3/X23".to_owned()))
+    );
+    assert_eq!(
+        LifeLikeRule::parse("B39/S23"),
+        Err(ParseRuleError::InvalidCount('9'))
+    );
+}
+
+#[test]
+fn life_like_blinker() {
+    let rule = LifeLikeRule::parse("B3/S23").unwrap();
+    let mut grid = SquareGrid::from_chars(rule, ".....\n.....\n.###.\n.....\n.....", |c| c == '#');
+    grid.step();
+    assert_eq!(
+        grid.to_chars(|&c| if c { '#' } else { '.' }),
+        ".....\n..#..\n..#..\n..#..\n.....\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "neighbor count 9 is more than 8")]
+fn life_like_count_too_large() {
+    LifeLikeRule::new(&[3], &[2, 9]);
+}
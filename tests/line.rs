@@ -0,0 +1,84 @@
+use gridsim::{ElementaryRule, Line, LineGrid, Sim};
+use ndarray::{Array1, ArrayView1};
+
+fn line(s: &str) -> Array1<bool> {
+    s.chars().map(|c| c == '1').collect()
+}
+
+#[test]
+fn rule_30_from_single_cell() {
+    let mut grid = LineGrid::new(ElementaryRule::new(30), line("0001000"));
+    for expected in &["0011100", "0110010", "1101111"] {
+        grid.step();
+        assert_eq!(grid.cells(), line(expected));
+    }
+}
+
+#[test]
+#[cfg(feature = "use-rayon")]
+fn rule_30_parallel() {
+    let mut grid = LineGrid::new(ElementaryRule::new(30), line("0001000"));
+    let mut parallel = LineGrid::new(ElementaryRule::new(30), line("0001000"));
+    for _ in 0..3 {
+        grid.step();
+        parallel.step_parallel();
+        assert_eq!(parallel.cells(), grid.cells());
+    }
+}
+
+#[test]
+fn rule_110_lookup() {
+    let rule = ElementaryRule::new(110);
+    let next = [false, true, true, true, false, true, true, false];
+    for (n, &expected) in next.iter().enumerate() {
+        assert_eq!(rule.apply(n & 4 != 0, n & 2 != 0, n & 1 != 0), expected);
+    }
+}
+
+/// Particles that bounce back and forth, moving one cell per step.
+#[derive(Debug)]
+pub struct Bounce;
+
+impl Sim<Line> for Bounce {
+    /// The direction of the particle, if any, where `0` is right and `1` is left.
+    type Cell = Option<usize>;
+    type Diff = ();
+    type Flow = Option<usize>;
+
+    fn compute(&self, _: ArrayView1<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: ArrayView1<'_, ()>) -> [Option<usize>; 2] {
+        let mut flows = [None; 2];
+        if let Some(direction) = cell.take() {
+            flows[direction] = Some(direction);
+        }
+        flows
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Option<usize>; 2]) {
+        *cell = flows[0].or(flows[1]);
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        None
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+#[test]
+fn line_flows_reach_neighbors() {
+    let mut grid = LineGrid::new(
+        Bounce,
+        Array1::from(vec![None, Some(0), None, None, Some(1), None]),
+    );
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        Array1::from(vec![None, None, Some(0), Some(1), None, None])
+    );
+}
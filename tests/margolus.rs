@@ -0,0 +1,76 @@
+use gridsim::{MargolusGrid, MargolusRule};
+use ndarray::Array2;
+
+fn show(grid: &MargolusGrid<MargolusRule>) -> String {
+    grid.cells()
+        .genrows()
+        .into_iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| if c { 'o' } else { '.' })
+                .chain(Some('\n'))
+                .collect::<String>()
+        })
+        .collect()
+}
+
+#[test]
+fn margolus_rule_tables() {
+    assert!(MargolusRule::billiard_ball().is_reversible());
+    assert!(MargolusRule::critters().is_reversible());
+    assert!(MargolusRule::tron().is_reversible());
+    assert!(!MargolusRule::from_fn(|_| 0).is_reversible());
+
+    let critters = MargolusRule::critters();
+    let inverse = critters.inverse().unwrap();
+    for pattern in 0..16 {
+        assert_eq!(inverse.next(critters.next(pattern)), pattern);
+    }
+    assert_eq!(critters.next(0b0111), 0b0001);
+    assert_eq!(critters.next(0b0110), 0b0110);
+    assert_eq!(critters.next(0b0000), 0b1111);
+}
+
+#[test]
+fn margolus_billiard_ball_moves() {
+    let mut cells = Array2::from_elem((6, 6), false);
+    cells[(0, 0)] = true;
+    let mut grid = MargolusGrid::new(MargolusRule::billiard_ball(), cells);
+    for _ in 0..3 {
+        grid.step();
+    }
+    assert_eq!(grid.generation(), 3);
+    assert_eq!(
+        show(&grid),
+        "......\n......\n......\n...o..\n......\n......\n"
+    );
+}
+
+#[test]
+fn margolus_padding_absorbs() {
+    let mut cells = Array2::from_elem((4, 4), false);
+    cells[(2, 2)] = true;
+    let mut grid = MargolusGrid::new(MargolusRule::billiard_ball(), cells);
+    grid.step();
+    grid.step();
+    assert!(grid.cells().iter().all(|&c| !c));
+}
+
+#[test]
+#[cfg(feature = "use-rayon")]
+fn margolus_parallel_matches() {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let cells = Array2::from_shape_simple_fn((16, 12), || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state & 3 == 0
+    });
+    let mut sequential = MargolusGrid::new(MargolusRule::critters(), cells.clone());
+    let mut parallel = MargolusGrid::new(MargolusRule::critters(), cells);
+    for _ in 0..5 {
+        sequential.step();
+        parallel.step_parallel();
+    }
+    assert_eq!(sequential.cells(), parallel.cells());
+}
@@ -0,0 +1,96 @@
+use gridsim::{NdGrid, NdNeighbors, Sim, VonNeumannNd};
+
+type Index = [usize; 4];
+
+/// Every cell sends its index to all neighbors and counts how many correct indices it receives.
+#[derive(Debug)]
+pub struct Exchange;
+
+impl Sim<VonNeumannNd<4>> for Exchange {
+    type Cell = (Index, usize);
+    type Diff = ();
+    type Flow = Option<Index>;
+
+    fn compute(&self, _: NdNeighbors<'_, Self::Cell, 4>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: NdNeighbors<'_, (), 4>) -> Vec<Option<Index>> {
+        VonNeumannNd::<4>::edges(|| Some(cell.0))
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: Vec<Option<Index>>) {
+        for (edge, flow) in flows.into_iter().enumerate() {
+            if let Some(index) = flow {
+                let mut expected = cell.0;
+                for (i, delta) in expected.iter_mut().zip(VonNeumannNd::<4>::delta(edge)) {
+                    *i = (*i as isize + delta) as usize;
+                }
+                assert_eq!(index, expected);
+                cell.1 += 1;
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ([usize::MAX; 4], 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+#[test]
+fn exchange_4d() {
+    let mut grid = NdGrid::from_shape_fn(Exchange, [3, 4, 3, 5], |index| (index, 0));
+    assert_eq!(grid.shape(), [3, 4, 3, 5]);
+    grid.step();
+    assert_eq!(grid[[1, 1, 1, 1]].1, 8);
+    assert_eq!(grid[[0, 0, 0, 0]].1, 4);
+    assert_eq!(grid[[2, 1, 1, 4]].1, 6);
+    assert_eq!(grid.get([3, 0, 0, 0]), None);
+}
+
+/// A cell turns on when any neighbor is on.
+#[derive(Debug)]
+pub struct Spread;
+
+impl<const D: usize> Sim<VonNeumannNd<D>> for Spread {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: NdNeighbors<'_, bool, D>) -> bool {
+        *cells.center() || cells.iter().any(|&c| c)
+    }
+
+    fn egress(&self, cell: &mut bool, diffs: NdNeighbors<'_, bool, D>) -> Vec<()> {
+        *cell = *diffs.center();
+        VonNeumannNd::<D>::edges(|| ())
+    }
+
+    fn ingress(&self, _: &mut bool, _: Vec<()>) {}
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) -> bool {
+        false
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn spread_5d() {
+    let mut grid = NdGrid::from_shape_fn(Spread, [5; 5], |index| index == [2; 5]);
+    grid.step();
+    assert_eq!(grid.cells().iter().filter(|&&c| c).count(), 11);
+    grid.step();
+    // The cells within two steps of the center along the axes.
+    assert_eq!(grid.cells().iter().filter(|&&c| c).count(), 1 + 10 + 50);
+    grid[[0; 5]] = true;
+    assert!(grid[[0; 5]]);
+}
@@ -0,0 +1,81 @@
+use gridsim::{
+    Bounded, Dihedral, HexNeighbors, NeighborsExt, SquareNeighborhood, TriNeighbors, TriVertex,
+    VonNeumannNeighbors, WindowBounds,
+};
+use ndarray::{arr1, Array2, Axis};
+
+#[test]
+fn moore_and_line_skip_the_center() {
+    let window = Array2::from_shape_fn((3, 3), |(y, x)| y * 3 + x);
+    let window = window.view();
+    assert_eq!(window.count_neighbors(|&c| c >= 4), 4);
+    assert_eq!(window.fold_neighbors(0, |sum, &c| sum + c), 36 - 4);
+    assert_eq!(
+        window.map_neighbors(|&c| c).collect::<Vec<_>>(),
+        [5, 2, 1, 0, 3, 6, 7, 8]
+    );
+
+    let line = arr1(&[1, 2, 3]);
+    assert_eq!(
+        line.view().map_neighbors(|&c| c).collect::<Vec<_>>(),
+        [3, 1]
+    );
+}
+
+#[test]
+fn zip_with_weights() {
+    let window = Array2::from_shape_fn((3, 3), |(y, x)| (y * 3 + x) as f64);
+    let weights = [1.0, 0.5, 0.25, 0.0];
+    let weighted: f64 = VonNeumannNeighbors::new(window.view())
+        .zip_neighbors(&weights)
+        .map(|(&c, &w)| c * w)
+        .sum();
+    assert_eq!(weighted, 5.0 + 0.5 + 0.75);
+
+    let hex = HexNeighbors::new(window.view());
+    assert_eq!(hex.count_neighbors(|_| true), 6);
+    let strip = Array2::<u8>::zeros((3, 5));
+    let tri = TriNeighbors::<_, TriVertex>::new(strip.view(), true);
+    assert_eq!(tri.count_neighbors(|&c| c == 0), 12);
+}
+
+#[test]
+fn bounded_sees_the_edges() {
+    let window = Array2::from_elem((3, 3), true);
+    let neighbors = Bounded::<gridsim::Moore>::located_neighbors(
+        window.view(),
+        gridsim::Location {
+            index: (0, 0),
+            bounds: WindowBounds { y: 1..3, x: 1..3 },
+            generation: 0,
+            seed: 0,
+        },
+    );
+    assert_eq!(neighbors.count_neighbors(|c| c.is_none()), 5);
+    assert_eq!(neighbors.count_neighbors(|c| c == Some(&true)), 3);
+}
+
+#[test]
+fn rotations_and_reflections() {
+    let cells = Array2::from_shape_fn((3, 3), |(y, x)| y * 3 + x);
+    let window = cells.view();
+    assert_eq!(window.rotate_cw()[(1, 2)], 1);
+    assert_eq!(window.rotate_ccw()[(1, 0)], 1);
+    assert_eq!(window.rotate_cw().rotate_ccw(), window);
+    assert_eq!(window.mirror(Axis(0))[(0, 1)], 7);
+    assert_eq!(window.mirror(Axis(1))[(0, 0)], 2);
+
+    let symmetries = window.symmetries();
+    for (i, a) in symmetries.iter().enumerate() {
+        assert_eq!(a[(1, 1)], 4);
+        for b in &symmetries[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+
+    let sides = VonNeumannNeighbors::new(window).rotate_cw();
+    assert_eq!(
+        sides.map_neighbors(|&c| c).collect::<Vec<_>>(),
+        [1, 3, 7, 5]
+    );
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "net")]
+
+use gridsim::{net::HaloLink, rules, Boundary, Side, SquareGrid};
+use ndarray::{s, Array2};
+use std::{net::TcpListener, thread};
+
+#[test]
+fn net_split_grid() {
+    let start = Array2::from_shape_fn((10, 14), |(y, x)| (y * 5 + x * 3) % 7 < 3);
+    let steps = 12;
+    let mut whole = SquareGrid::new(rules::life(), start.clone());
+    for _ in 0..steps {
+        whole.step();
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let half = |cells: Array2<bool>, side: Side| {
+        let mut grid = SquareGrid::with_boundaries(
+            rules::life(),
+            cells,
+            Boundary::Constant(false),
+            Boundary::Halo,
+        );
+        grid.set_halo(side.opposite(), &[false; 10]);
+        grid
+    };
+    let mut left = half(start.slice(s![.., ..7]).to_owned(), Side::East);
+    let mut right = half(start.slice(s![.., 7..]).to_owned(), Side::West);
+    let right = thread::spawn(move || {
+        let mut link = HaloLink::new();
+        link.connect(Side::West, listener.accept().unwrap().0)
+            .unwrap();
+        for _ in 0..steps {
+            right.step_linked(&link).unwrap();
+        }
+        right.cells().to_owned()
+    });
+    let mut link = HaloLink::new();
+    link.connect(Side::East, std::net::TcpStream::connect(address).unwrap())
+        .unwrap();
+    assert!(link.is_connected(Side::East) && !link.is_connected(Side::West));
+    for _ in 0..steps {
+        left.step_linked(&link).unwrap();
+    }
+    let right = right.join().unwrap();
+    assert_eq!(left.cells(), whole.cells().slice(s![.., ..7]));
+    assert_eq!(right, whole.cells().slice(s![.., 7..]));
+}
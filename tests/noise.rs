@@ -0,0 +1,61 @@
+use gridsim::{
+    noise::{band, Noise, NoiseKind},
+    rules::{ForestCell, ForestFire},
+    Random, SquareGrid,
+};
+
+#[test]
+fn noise_smooth_and_bounded() {
+    for &kind in &[NoiseKind::Value, NoiseKind::Perlin, NoiseKind::Simplex] {
+        let mut noise = Noise::new(kind, 3);
+        noise.octaves = 3;
+        let values = noise.sample_grid((60, 60));
+        assert_eq!(values, noise.sample_grid((60, 60)));
+        assert!(
+            values.iter().all(|v| (-1.01..=1.01).contains(v)),
+            "{:?}",
+            kind
+        );
+        let mean = values.sum() / values.len() as f64;
+        assert!(mean.abs() < 0.3, "{:?} mean {}", kind, mean);
+        let spread = values.iter().cloned().fold(0.0f64, |m, v| m.max(v.abs()));
+        assert!(spread > 0.2, "{:?} is flat", kind);
+        for y in 0..60 {
+            for x in 1..60 {
+                assert!(
+                    (values[(y, x)] - values[(y, x - 1)]).abs() < 0.4,
+                    "{:?}",
+                    kind
+                );
+            }
+        }
+        assert_ne!(values, Noise::new(kind, 4).sample_grid((60, 60)));
+    }
+}
+
+#[test]
+fn noise_bands() {
+    assert_eq!(band(-0.5, &[-0.2, 0.4]), 0);
+    assert_eq!(band(0.0, &[-0.2, 0.4]), 1);
+    assert_eq!(band(0.4, &[-0.2, 0.4]), 2);
+
+    let noise = Noise::new(NoiseKind::Perlin, 1);
+    let sim = ForestFire {
+        growth: 0.01,
+        lightning: 0.0,
+    };
+    let states = [ForestCell::Empty, ForestCell::Tree];
+    let grid: SquareGrid<_, Random> =
+        SquareGrid::new_noise(sim, (40, 50), &noise, |v| states[band(v, &[0.0])]);
+    let trees = grid
+        .cells()
+        .iter()
+        .filter(|&&c| c == ForestCell::Tree)
+        .count();
+    assert!(trees > 0 && trees < 40 * 50);
+
+    let mut filled: SquareGrid<_, Random> =
+        SquareGrid::new(sim, ndarray::Array2::from_elem((40, 50), ForestCell::Fire));
+    filled.fill_noise(&noise, |v| states[band(v, &[0.0])]);
+    assert_eq!(filled.cells(), grid.cells());
+}
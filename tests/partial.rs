@@ -0,0 +1,55 @@
+use gridsim::{rules, Boundary, SquareGrid};
+use ndarray::ArrayView2;
+
+#[test]
+fn partial_steps() {
+    use std::time::Duration;
+
+    let cells = SquareGrid::new_random(rules::life(), (9, 11), 7, |rng| rng.gen_bool(0.4))
+        .cells()
+        .to_owned();
+    let start = SquareGrid::with_boundary(rules::life(), cells, Boundary::Wrap);
+    let mut expected = start.clone();
+    expected.step_n(2);
+
+    let mut grid = start.clone();
+    assert!(!grid.step_rows(4));
+    assert_eq!(grid.rows_computed(), Some(4));
+    assert_eq!(grid.cells(), start.cells());
+    assert_eq!(grid.generation(), 0);
+    assert!(!grid.step_rows(4));
+    assert!(grid.step_rows(4));
+    assert_eq!(grid.rows_computed(), None);
+    assert_eq!(grid.generation(), 1);
+    while !grid.step_partial(Duration::from_secs(1)) {}
+    assert_eq!(grid.cells(), expected.cells());
+
+    // Stepping finishes a partial step and modifying the cells abandons it.
+    grid.step_rows(3);
+    grid.step();
+    assert_eq!(grid.generation(), 3);
+    grid.step_rows(3);
+    grid[(0, 0)] = true;
+    assert_eq!(grid.rows_computed(), None);
+}
+
+#[test]
+fn partial_pipelined() {
+    let mut grid = SquareGrid::new_random(rules::life(), (16, 12), 2, |rng| rng.gen_bool(0.3));
+    let mut expected = grid.clone();
+    for _ in 0..3 {
+        let before = expected.cells().to_owned();
+        let seen = grid.step_pipelined(|cells| {
+            assert_eq!(cells, before);
+            grid_string(cells)
+        });
+        expected.step();
+        assert_eq!(seen, grid_string(before.view()));
+        assert_eq!(grid.cells(), expected.cells());
+    }
+    assert_eq!(grid.generation(), 3);
+}
+
+fn grid_string(cells: ArrayView2<'_, bool>) -> String {
+    gridsim::write_chars(cells, |&c| if c { '#' } else { '.' })
+}
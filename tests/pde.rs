@@ -0,0 +1,85 @@
+use gridsim::{
+    pde::{GrayScott, Heat, Stencil},
+    Boundary, SoaArray, SquareGrid,
+};
+use ndarray::Array2;
+
+#[test]
+fn pde_laplacian_of_quadratic() {
+    let window = Array2::from_shape_fn((3, 3), |(y, x)| {
+        let (y, x) = (y as f64 + 4.0, x as f64 - 2.0);
+        y * y + 3.0 * x * x + y * x
+    });
+    for &stencil in &[Stencil::Five, Stencil::Nine] {
+        let laplacian = stencil.laplacian(window.view(), |&v| v);
+        assert!(
+            (laplacian - 8.0).abs() < 1e-9,
+            "{:?} {}",
+            stencil,
+            laplacian
+        );
+    }
+}
+
+#[test]
+fn pde_heat_conserves_and_spreads() {
+    for &stencil in &[Stencil::Five, Stencil::Nine] {
+        let mut cells = Array2::zeros((9, 9));
+        cells[(4, 4)] = 81.0;
+        let mut heat = Heat::new(0.2);
+        heat.stencil = stencil;
+        let mut grid = SquareGrid::with_boundary(heat, cells, Boundary::Wrap);
+        grid.step_n(200);
+        let total: f64 = grid.cells().sum();
+        assert!((total - 81.0).abs() < 1e-9);
+        assert!(grid.cells().iter().all(|&t| (t - 1.0).abs() < 0.05));
+    }
+
+    let mut grid = SquareGrid::new(Heat::new(0.2), Array2::from_elem((4, 4), 1.0));
+    grid.step_n(100);
+    assert!(grid.cells().iter().all(|&t| t < 0.1));
+}
+
+#[test]
+fn pde_gray_scott() {
+    let mut cells = Array2::from_elem((32, 32), [1.0, 0.0]);
+    let mut grid = SquareGrid::with_boundary(GrayScott::mitosis(), cells.clone(), Boundary::Wrap);
+    grid.step_n(10);
+    assert!(grid.cells().iter().all(|&c| c == [1.0, 0.0]));
+
+    for y in 13..19 {
+        for x in 13..19 {
+            cells[(y, x)] = [0.5, 0.25];
+        }
+    }
+    let mut grid = SquareGrid::with_boundary(GrayScott::mitosis(), cells, Boundary::Wrap);
+    grid.step_n(500);
+    let v: f64 = grid.cells().iter().map(|c| c[1]).sum();
+    assert!(v > 1.0);
+    assert!(grid.cells()[(0, 0)][1] < 1e-6);
+    assert!(grid
+        .cells()
+        .iter()
+        .all(|c| c.iter().all(|x| (0.0..=1.0).contains(x))));
+}
+
+#[test]
+fn pde_soa_fields() {
+    let cells = Array2::from_shape_fn((3, 4), |(y, x)| [y as f64, x as f64]);
+    let mut grid = SquareGrid::new(GrayScott::mitosis(), cells.clone());
+    let mut soa = grid.to_soa();
+    assert_eq!(soa.dim(), (3, 4));
+    assert_eq!(soa.get((2, 1)), [2.0, 1.0]);
+    assert_eq!(soa.field(1), cells.map(|c| c[1]));
+    assert_eq!(soa.to_cells(), cells);
+
+    soa.field_mut(0).fill(0.5);
+    soa.set((0, 3), [7.0, 8.0]);
+    grid.load_soa(&soa);
+    assert_eq!(grid.cells()[(1, 2)], [0.5, 2.0]);
+    assert_eq!(grid.cells()[(0, 3)], [7.0, 8.0]);
+    assert_eq!(
+        SoaArray::from_elem((1, 2), 4.0).to_cells(),
+        Array2::from_elem((1, 2), 4.0)
+    );
+}
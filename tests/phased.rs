@@ -0,0 +1,98 @@
+use gridsim::{Boundary, Moore, PhasedGrid, Sim, VonNeumann, VonNeumannNeighbors};
+use ndarray::{Array2, ArrayView2};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Moves every cell one to the east.
+#[derive(Debug)]
+struct Move;
+
+impl Sim<VonNeumann> for Move {
+    type Cell = u8;
+    type Diff = ();
+    type Flow = u8;
+
+    fn compute(&self, _: VonNeumannNeighbors<'_, u8>) {}
+
+    fn egress(&self, cell: &mut u8, _: VonNeumannNeighbors<'_, ()>) -> [u8; 4] {
+        [std::mem::take(cell), 0, 0, 0]
+    }
+
+    fn ingress(&self, cell: &mut u8, flows: [u8; 4]) {
+        *cell = flows[2];
+    }
+
+    fn cell_padding(&self) -> u8 {
+        0
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> u8 {
+        0
+    }
+}
+
+/// Sets every cell to the number of its eight neighbors which are occupied.
+#[derive(Debug)]
+struct Crowd;
+
+impl Sim<Moore> for Crowd {
+    type Cell = u8;
+    type Diff = u8;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, u8>) -> u8 {
+        cells.iter().filter(|&&cell| cell != 0).count() as u8 - (cells[(1, 1)] != 0) as u8
+    }
+
+    fn egress(&self, cell: &mut u8, diffs: ArrayView2<'_, u8>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut u8, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> u8 {
+        0
+    }
+
+    fn diff_padding(&self) -> u8 {
+        0
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn phased_neighborhoods() {
+    let mut cells = Array2::zeros((5, 5));
+    cells[(2, 1)] = 7;
+    let mut grid = PhasedGrid::new(Move, Crowd, cells);
+    let steps = Arc::new(AtomicUsize::new(0));
+    let counter = steps.clone();
+    grid.first_mut().on_after_step(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+    grid.step();
+
+    // The cell moved before it was counted, so it is counted around its new place.
+    let mut expected = Array2::zeros((5, 5));
+    expected.slice_mut(ndarray::s![1..4, 1..4]).fill(1);
+    expected[(2, 2)] = 0;
+    assert_eq!(grid.cells(), expected);
+    assert_eq!(grid.generation(), 1);
+    assert_eq!(steps.load(Ordering::Relaxed), 1);
+
+    let mut cells = Array2::zeros((1, 3));
+    cells[(0, 2)] = 1;
+    let mut grid = PhasedGrid::with_boundary(Move, Crowd, cells, Boundary::Wrap);
+    grid.step();
+    // The cell wraps to the west edge, and the single row wraps onto itself above and below.
+    assert_eq!(grid.cells(), ndarray::arr2(&[[2, 3, 3]]));
+    grid.step();
+    assert_eq!(grid.cells(), Array2::from_elem((1, 3), 8));
+    assert_eq!(grid.into_grid().generation(), 2);
+}
@@ -0,0 +1,46 @@
+use gridsim::{rules, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn plaintext_glider() {
+    let mut grid =
+        SquareGrid::from_chars(rules::life(), ".#...\n..#..\n###..\n.....\n.....\n", |c| {
+            c == '#'
+        });
+    for _ in 0..4 {
+        grid.step();
+    }
+    assert_eq!(
+        grid.to_chars(|&c| if c { '#' } else { '.' }),
+        ".....\n..#..\n...#.\n.###.\n.....\n"
+    );
+}
+
+#[test]
+fn plaintext_pads_short_lines() {
+    let grid = SquareGrid::from_chars(rules::life(), "#\n.#\n", |c| c == '#');
+    assert_eq!(grid.to_chars(|&c| if c { '#' } else { '.' }), "#.\n.#\n");
+}
+
+#[test]
+fn plaintext_display_and_debug() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((3, 4), false));
+    grid.fill_region((1..2, 0..3), true);
+    let show = |&c: &bool| if c { '#' } else { '.' };
+    assert_eq!(grid.display_with(show).to_string(), grid.to_chars(show));
+    assert_eq!(
+        grid.display_with(show).truncate((2, 2)).to_string(),
+        ".....\n##...\n...\n"
+    );
+    assert_eq!(
+        grid.display_with(show).truncate((5, 5)).to_string(),
+        "....\n###.\n....\n"
+    );
+
+    let debug = format!("{:?}", grid);
+    assert!(debug.starts_with("SquareGrid { dims: (3, 4), generation: 0"));
+    assert!(debug.contains("[false, false, false, false]"));
+
+    let large = SquareGrid::new(rules::life(), Array2::from_elem((100, 100), false));
+    assert!(format!("{:?}", large).len() < 2000);
+}
@@ -0,0 +1,23 @@
+use gridsim::{rules, LifeLikeRule, SquareGrid};
+
+#[test]
+fn population_tracked() {
+    let mut grid = SquareGrid::new_random(rules::life(), (32, 32), 7, |rng| rng.gen_bool(0.4));
+    assert_eq!(grid.population(), None);
+    grid.track_population();
+    let alive = |grid: &SquareGrid<LifeLikeRule>| grid.cells().iter().filter(|&&c| c).count();
+    for _ in 0..8 {
+        let expected = alive(&grid);
+        assert_eq!(grid.population(), Some(&[32 * 32 - expected, expected][..]));
+        grid.step();
+    }
+    grid.fill(false);
+    grid.set_cell((3, 3), true);
+    assert_eq!(grid.population(), Some(&[32 * 32 - 1, 1][..]));
+    grid.set_tile_size(Some((5, 7)));
+    grid.stamp(ndarray::arr2(&[[true, true, true]]).view(), (10, 10));
+    grid.step();
+    assert_eq!(grid.clone().population(), Some(&[32 * 32 - 3, 3][..]));
+    grid.untrack_population();
+    assert_eq!(grid.population(), None);
+}
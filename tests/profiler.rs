@@ -0,0 +1,73 @@
+use gridsim::{rules, ProfileSummary, Profiler, SquareGrid};
+
+#[test]
+fn profiler_step_timings() {
+    use std::sync::{Arc, Mutex};
+
+    let mut grid = SquareGrid::new_random(rules::life(), (40, 30), 1, |rng| rng.gen_bool(0.3));
+    assert_eq!(grid.step_timings(), None);
+    grid.time_steps();
+    let seen = Arc::new(Mutex::new(vec![]));
+    let log = seen.clone();
+    grid.on_after_step(move |grid| log.lock().unwrap().push(grid.step_timings().copied()));
+    grid.step_n(2);
+
+    let timings = *grid.step_timings().unwrap();
+    assert_eq!(timings.cells, 1200);
+    assert!(timings.total >= timings.compute + timings.egress + timings.ingress);
+    assert!(timings.cells_per_second() > 0.0);
+    assert_eq!(seen.lock().unwrap()[1], Some(timings));
+
+    grid.untime_steps();
+    grid.step();
+    assert_eq!(grid.step_timings(), None);
+    assert_eq!(seen.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn profiler_summary() {
+    let mut grid = SquareGrid::new_random(rules::life(), (40, 30), 2, |rng| rng.gen_bool(0.3));
+    let profiler = Profiler::new();
+    grid.attach_profiler(&profiler);
+    grid.step_n(3);
+
+    let steps = profiler.steps();
+    assert_eq!(
+        steps
+            .iter()
+            .map(|&(generation, _)| generation)
+            .collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+    assert_eq!(steps[2].1, *grid.step_timings().unwrap());
+    let summary = profiler.summary();
+    assert_eq!(summary.steps, 3);
+    assert_eq!(summary.cells, 3600);
+    assert_eq!(
+        summary.total,
+        steps.iter().map(|(_, timings)| timings.total).sum()
+    );
+    assert!(summary.total >= summary.padding + summary.compute + summary.egress + summary.ingress);
+
+    let mut folded = vec![];
+    profiler.write_folded(&mut folded, "life").unwrap();
+    let folded = String::from_utf8(folded).unwrap();
+    let stacks: Vec<&str> = folded
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().0)
+        .collect();
+    assert_eq!(
+        stacks,
+        [
+            "life",
+            "life;padding",
+            "life;compute",
+            "life;egress",
+            "life;ingress"
+        ]
+    );
+
+    profiler.clear();
+    assert_eq!(profiler.summary(), ProfileSummary::default());
+    assert_eq!(profiler.summary().mean(), std::time::Duration::ZERO);
+}
@@ -0,0 +1,152 @@
+use gridsim::{
+    Extended, ExtendedDirection, ExtendedNeighbors, KnightMoves, Moore, OffsetNeighborhood,
+    OffsetNeighbors, Offsets, Radius, RadiusNeighbors, Sim, SquareGrid, SquareNeighborhood,
+};
+use ndarray::Array2;
+
+type Coord = (isize, isize);
+
+/// Every cell sends its coordinate to all neighbors and counts how many correct coordinates it receives.
+#[derive(Debug)]
+pub struct Exchange;
+
+impl<N> Sim<N> for Exchange
+where
+    N: SquareNeighborhood,
+{
+    type Cell = (Coord, usize);
+    type Diff = ();
+    type Flow = Option<Coord>;
+
+    fn compute(&self, _: N::Neighbors<'_, Self::Cell>) {}
+
+    fn egress(&self, cell: &mut Self::Cell, _: N::Neighbors<'_, ()>) -> N::Edges<Option<Coord>> {
+        N::edges(|| Some(cell.0))
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, mut flows: N::Edges<Option<Coord>>) {
+        let (y, x) = cell.0;
+        for (edge, flow) in N::edges_mut(&mut flows).iter().enumerate() {
+            if let Some(coord) = *flow {
+                let (dy, dx) = N::delta(edge);
+                assert_eq!(coord, (y + dy, x + dx));
+                cell.1 += 1;
+            }
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((-1, -1), 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Self::Flow {
+        None
+    }
+}
+
+fn check_exchange<N>()
+where
+    N: SquareNeighborhood,
+    Exchange: Sim<N, Cell = (Coord, usize), Diff = ()>,
+    N::Edges<<Exchange as Sim<N>>::Flow>: Send,
+{
+    let dim = (7, 9);
+    let mut grid: SquareGrid<Exchange, N> = SquareGrid::new(
+        Exchange,
+        Array2::from_shape_fn(dim, |(y, x)| ((y as isize, x as isize), 0)),
+    );
+    grid.step();
+    for &((y, x), received) in grid.cells() {
+        let expected = (0..N::EDGES)
+            .map(N::delta)
+            .filter(|&(dy, dx)| {
+                (0..dim.0 as isize).contains(&(y + dy)) && (0..dim.1 as isize).contains(&(x + dx))
+            })
+            .count();
+        assert_eq!(received, expected);
+    }
+}
+
+#[test]
+fn neumann_exchange() {
+    check_exchange::<Moore>();
+}
+
+#[test]
+fn radius_exchange() {
+    check_exchange::<Radius<1>>();
+    check_exchange::<Radius<2>>();
+    check_exchange::<Radius<3>>();
+}
+
+#[test]
+fn extended_exchange() {
+    check_exchange::<Extended>();
+}
+
+#[test]
+fn extended_directions() {
+    let mut deltas: Vec<_> = ExtendedDirection::ALL.iter().map(|d| d.delta()).collect();
+    for (edge, &direction) in ExtendedDirection::ALL.iter().enumerate() {
+        assert_eq!(direction as usize, edge);
+        assert_eq!(direction.opposite().opposite(), direction);
+        let (y, x) = direction.delta();
+        assert_eq!(direction.opposite().delta(), (-y, -x));
+        assert_eq!(direction.is_inner(), y.abs() <= 1 && x.abs() <= 1);
+    }
+    deltas.sort_unstable();
+    deltas.dedup();
+    assert_eq!(deltas.len(), 24);
+
+    let window = Array2::from_shape_fn((5, 5), |(y, x)| y * 5 + x);
+    let neighbors = ExtendedNeighbors::new(window.view());
+    assert_eq!(*neighbors.center(), 12);
+    assert_eq!(neighbors[ExtendedDirection::FarNorthWest], 0);
+    assert_eq!(neighbors[ExtendedDirection::EastSouthEast], 19);
+    assert_eq!(neighbors.inner().sum::<usize>(), 8 * 12);
+    assert_eq!(neighbors.outer().count(), 16);
+}
+
+#[test]
+fn radius_neighbors() {
+    let window = Array2::from_shape_fn((7, 7), |(y, x)| (y as isize - 3, x as isize - 3));
+    let neighbors = RadiusNeighbors::<_, 3>::new(window.view());
+    assert_eq!(*neighbors.center(), (0, 0));
+    assert_eq!(neighbors[(-3, 2)], (-3, 2));
+    assert_eq!(neighbors.iter().count(), Radius::<3>::EDGES);
+    assert!(neighbors.iter().all(|&delta| delta != (0, 0)));
+    for (edge, (delta, &cell)) in neighbors.iter_deltas().enumerate() {
+        assert_eq!(delta, cell);
+        assert_eq!(*neighbors.edge(edge), cell);
+    }
+}
+
+/// Cells three to the left and right, along with the cell below and above.
+enum Sparse {}
+
+impl Offsets for Sparse {
+    const OFFSETS: &'static [(isize, isize)] = &[(0, 3), (-1, 0), (0, -3), (1, 0)];
+}
+
+#[test]
+fn offset_exchange() {
+    check_exchange::<OffsetNeighborhood<KnightMoves>>();
+    check_exchange::<OffsetNeighborhood<Sparse>>();
+    assert_eq!(OffsetNeighborhood::<KnightMoves>::RADIUS, 2);
+    assert_eq!(OffsetNeighborhood::<Sparse>::RADIUS, 3);
+    assert_eq!(OffsetNeighborhood::<Sparse>::opposite(1), 3);
+}
+
+#[test]
+fn offset_neighbors() {
+    let window = Array2::from_shape_fn((5, 5), |(y, x)| (y as isize - 2, x as isize - 2));
+    let neighbors = OffsetNeighbors::<_, KnightMoves>::new(window.view());
+    assert_eq!(*neighbors.center(), (0, 0));
+    assert_eq!(neighbors[1], (-2, 1));
+    for (delta, &cell) in neighbors.iter_deltas() {
+        assert_eq!(delta, cell);
+    }
+    assert_eq!(neighbors.iter().count(), 8);
+}
@@ -0,0 +1,146 @@
+use gridsim::{
+    rules::{ForestCell, ForestFire},
+    CellRng, ReplayTrace, SquareGrid,
+};
+use ndarray::Array2;
+
+#[cfg(feature = "use-rayon")]
+fn forest(seed: u64, steps: usize) -> Array2<ForestCell> {
+    let sim = ForestFire {
+        growth: 0.05,
+        lightning: 0.001,
+    };
+    let mut grid = SquareGrid::new(sim, Array2::from_elem((32, 32), ForestCell::Empty));
+    grid.set_seed(seed);
+    for _ in 0..steps {
+        grid.step_parallel();
+    }
+    assert_eq!(grid.generation(), steps as u64);
+    grid.cells().to_owned()
+}
+
+#[test]
+fn random_cell_rng() {
+    let mut a = CellRng::new(1, 2, (3, 4));
+    let mut b = CellRng::new(1, 2, (3, 4));
+    let mut c = CellRng::new(1, 2, (4, 3));
+    assert_eq!(a.next_u64(), b.next_u64());
+    assert_ne!(a.next_u64(), c.next_u64());
+
+    let mut rng = CellRng::new(0, 0, (0, 0));
+    let heads = (0..10000).filter(|_| rng.gen_bool(0.25)).count();
+    assert!((2200..2800).contains(&heads));
+    assert!((0..1000).all(|_| rng.gen_range(6) < 6));
+    assert!((0..1000).all(|_| (0.0..1.0).contains(&rng.next_f64())));
+}
+
+#[test]
+#[cfg(feature = "use-rayon")]
+fn random_forest_reproducible() {
+    let a = forest(7, 50);
+    assert_eq!(a, forest(7, 50));
+    assert_ne!(a, forest(8, 50));
+    assert!(a.iter().any(|&c| c == ForestCell::Tree));
+}
+
+#[test]
+#[cfg(feature = "use-rayon")]
+fn random_forest_fire_spreads() {
+    let sim = ForestFire {
+        growth: 0.0,
+        lightning: 0.0,
+    };
+    let mut cells = Array2::from_elem((1, 5), ForestCell::Tree);
+    cells[(0, 0)] = ForestCell::Fire;
+    let mut grid = SquareGrid::new(sim, cells);
+    grid.step_parallel();
+    grid.step_parallel();
+    assert_eq!(
+        grid.cells().iter().copied().collect::<Vec<_>>(),
+        [
+            ForestCell::Empty,
+            ForestCell::Empty,
+            ForestCell::Fire,
+            ForestCell::Tree,
+            ForestCell::Tree
+        ]
+    );
+}
+
+#[test]
+fn random_forest_tiled() {
+    let new = || {
+        let sim = ForestFire {
+            growth: 0.05,
+            lightning: 0.001,
+        };
+        let cells = Array2::from_elem((30, 41), ForestCell::Empty);
+        SquareGrid::with_boundary(sim, cells, gridsim::Boundary::Wrap)
+    };
+    let mut grid = new();
+    let mut tiled = new();
+    tiled.set_tile_size(Some((8, 8)));
+    for _ in 0..50 {
+        grid.step();
+        tiled.step();
+    }
+    assert_eq!(grid.cells(), tiled.cells());
+}
+
+#[test]
+fn random_forest_replay() {
+    let new = |growth| {
+        let sim = ForestFire {
+            growth,
+            lightning: 0.001,
+        };
+        SquareGrid::new(sim, Array2::from_elem((40, 40), ForestCell::Empty))
+    };
+    let mut grid = new(0.05);
+    grid.set_seed(3);
+    let trace = ReplayTrace::record(&mut grid, 30);
+    assert_eq!((trace.seed, trace.start), (3, 0));
+    assert_eq!(trace.checksums.len(), 31);
+    assert_eq!(trace.checksums[30], grid.checksum());
+
+    #[cfg(feature = "use-rayon")]
+    {
+        let pool = gridsim::rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        assert_eq!(
+            trace.verify_with(&mut new(0.05), |grid| grid.step_in(&pool)),
+            Ok(())
+        );
+    }
+    let mut tiled = new(0.05);
+    tiled.set_tile_size(Some((7, 9)));
+    assert_eq!(trace.verify(&mut tiled), Ok(()));
+
+    let mismatch = trace.verify(&mut new(0.5)).unwrap_err();
+    assert!(mismatch.generation >= 1);
+    assert_eq!(
+        mismatch.expected,
+        trace.checksums[mismatch.generation as usize]
+    );
+}
+
+#[test]
+fn random_soup() {
+    let soup = |seed| {
+        SquareGrid::new_random(gridsim::rules::life(), (20, 30), seed, |rng| {
+            rng.gen_bool(0.3)
+        })
+    };
+    let grid = soup(5);
+    assert_eq!(grid.cells(), soup(5).cells());
+    assert_ne!(grid.cells(), soup(6).cells());
+    let alive = grid.cells().iter().filter(|&&c| c).count();
+    assert!((100..260).contains(&alive));
+
+    let mut region = SquareGrid::new(gridsim::rules::life(), Array2::from_elem((20, 30), false));
+    region.randomize_region((4..9, 10..25), 5, |rng| rng.gen_bool(0.3));
+    assert_eq!(region.window((4..9, 10..25)), grid.window((4..9, 10..25)));
+    assert!(region.window((0..4, 0..30)).iter().all(|&c| !c));
+}
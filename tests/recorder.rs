@@ -0,0 +1,49 @@
+use gridsim::{rules, stats, RecordFormat, Recorder, SquareGrid};
+use ndarray::Array2;
+use std::time::Duration;
+
+fn blinker() -> SquareGrid<gridsim::LifeLikeRule> {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((5, 5), false));
+    grid.fill_region((2..3, 1..4), true);
+    grid
+}
+
+#[test]
+fn recorder_csv() {
+    let mut recorder = Recorder::new(vec![], RecordFormat::Csv);
+    recorder.add_metric("population", |grid: &SquareGrid<_>| {
+        stats::population(grid.cells(), |&c| c) as f64
+    });
+    recorder.add_metric("top, row", |grid: &SquareGrid<_>| {
+        stats::population(grid.window((1..2, 0..5)), |&c| c) as f64
+    });
+    let mut grid = blinker();
+    recorder
+        .record(grid.generation(), Duration::from_millis(0), &grid)
+        .unwrap();
+    recorder.step(&mut grid).unwrap();
+    let text = String::from_utf8(recorder.into_inner()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "generation,step_time,population,\"top, row\"");
+    assert_eq!(lines[1], "0,0,3,0");
+    assert!(lines[2].starts_with("1,") && lines[2].ends_with(",3,1"));
+}
+
+#[test]
+fn recorder_json_lines() {
+    let mut recorder = Recorder::new(vec![], RecordFormat::JsonLines);
+    recorder.add_metric("\"alive\"", |grid: &SquareGrid<_>| {
+        stats::population(grid.cells(), |&c| c) as f64
+    });
+    recorder.add_metric("nan", |_: &SquareGrid<_>| f64::NAN);
+    let grid = blinker();
+    recorder
+        .record(7, Duration::from_millis(500), &grid)
+        .unwrap();
+    let text = String::from_utf8(recorder.into_inner()).unwrap();
+    assert_eq!(
+        text,
+        "{\"generation\":7,\"step_time\":0.5,\"\\\"alive\\\"\":3,\"nan\":null}\n"
+    );
+}
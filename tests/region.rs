@@ -0,0 +1,92 @@
+use gridsim::{rules, Boundary, SquareGrid};
+use ndarray::{Array2, ArrayView2};
+
+#[test]
+fn region_fill() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((5, 5), true));
+    grid.fill(false);
+    grid.fill_region((1..4, 2..3), true);
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((5, 5), |(y, x)| y == 2 && (1..=3).contains(&x))
+    );
+    grid.modify_region((0..5, 0..2), |c| *c = !*c);
+    grid.set_cell((4, 4), true);
+    assert_eq!(grid.cells().iter().filter(|&&c| c).count(), 12);
+    assert!(grid[(0, 0)] && !grid[(2, 1)] && grid[(4, 4)]);
+}
+
+#[test]
+fn region_stamp() {
+    let glider = SquareGrid::from_chars(rules::life(), ".#.\n..#\n###\n", |c| c == '#');
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((6, 6), false));
+    grid.stamp(glider.cells(), (4, -1));
+    assert_eq!(
+        grid.to_chars(|&c| if c { '#' } else { '.' }),
+        "......\n......\n......\n......\n#.....\n.#....\n"
+    );
+
+    let mut grid = SquareGrid::with_boundary(
+        rules::life(),
+        Array2::from_elem((6, 6), false),
+        gridsim::Boundary::Wrap,
+    );
+    grid.stamp(glider.cells(), (4, -1));
+    assert_eq!(
+        grid.to_chars(|&c| if c { '#' } else { '.' }),
+        "##...#\n......\n......\n......\n#.....\n.#....\n"
+    );
+
+    grid.fill(true);
+    grid.stamp_where(glider.cells(), (0, 0), |&c| !c);
+    assert_eq!(
+        grid.to_chars(|&c| if c { '#' } else { '.' }),
+        ".#.###\n..####\n######\n######\n######\n######\n"
+    );
+}
+
+#[test]
+fn region_window() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((6, 8), false));
+    {
+        let window = grid.window_mut((1..5, 2..6));
+        let (mut left, mut right) = window.split_at(ndarray::Axis(1), 2);
+        left[(0, 1)] = true;
+        right[(3, 0)] = true;
+    }
+    assert!(grid[(1, 3)] && grid[(4, 4)]);
+    let window = grid.window((1..5, 3..5));
+    assert_eq!(window.dim(), (4, 2));
+    assert_eq!(window.iter().filter(|&&c| c).count(), 2);
+    assert_eq!(
+        gridsim::write_chars(window, |&c| if c { '#' } else { '.' }),
+        "#.\n..\n..\n.#\n"
+    );
+}
+
+#[test]
+fn region_step() {
+    // Only the middle column of a blinker steps, so its ends stay alive.
+    let text = ".....\n.....\n.###.\n.....\n.....\n";
+    let mut grid = SquareGrid::from_chars(rules::life(), text, |c| c == '#');
+    grid.step_region((1..4, 2..3));
+    assert_eq!(grid.generation(), 1);
+    assert_eq!(
+        grid_string(grid.cells()),
+        ".....\n..#..\n.###.\n..#..\n.....\n"
+    );
+
+    // Cells at the border of the grid read across a wrapping boundary.
+    let text = ".....\n##..#\n.....\n";
+    let cells = SquareGrid::from_chars(rules::life(), text, |c| c == '#')
+        .cells()
+        .to_owned();
+    let mut grid = SquareGrid::with_boundary(rules::life(), cells, Boundary::Wrap);
+    grid.step_region((0..3, 0..1));
+    assert_eq!(grid_string(grid.cells()), "#....\n##..#\n#....\n");
+}
+
+fn grid_string(cells: ArrayView2<'_, bool>) -> String {
+    gridsim::write_chars(cells, |&c| if c { '#' } else { '.' })
+}
@@ -0,0 +1,39 @@
+use gridsim::{rules, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn resize_and_crop() {
+    let mut grid = SquareGrid::new(
+        rules::life(),
+        Array2::from_shape_fn((3, 3), |(y, x)| y == 1 && x < 3),
+    );
+    grid.resize((5, 6), (1, 2), false);
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((5, 6), |(y, x)| y == 2 && (2..=4).contains(&x))
+    );
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((5, 6), |(y, x)| x == 3 && (1..=3).contains(&y))
+    );
+    let rect = grid.bounding_box(|&c| c).unwrap();
+    assert_eq!((rect.y.clone(), rect.x.clone()), (1..4, 3..4));
+    grid.crop((1..4, 2..5));
+    assert_eq!(grid.cells(), Array2::from_shape_fn((3, 3), |(_, x)| x == 1));
+    grid.resize((2, 2), (-1, -1), true);
+    assert_eq!(grid.cells(), ndarray::arr2(&[[true, false], [true, false]]));
+}
+
+#[test]
+fn resize_then_step() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((3, 3), false));
+    grid.step();
+    grid.resize((5, 5), (0, 0), false);
+    grid.fill_region((2..3, 1..4), true);
+    grid.step();
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((5, 5), |(y, x)| x == 2 && (1..=3).contains(&y))
+    );
+}
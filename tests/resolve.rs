@@ -0,0 +1,64 @@
+use gridsim::{
+    resolve_first, resolve_max_by_key, resolve_priority, resolve_random, CellRng, Resolved,
+};
+
+#[test]
+fn resolve_first_edge() {
+    let mut flows = [None, Some('a'), None, Some('b'), Some('c')];
+    let resolved = resolve_first(&mut flows);
+    assert_eq!(resolved.winner, Some((1, 'a')));
+    assert_eq!(resolved.rejected, vec![(3, 'b'), (4, 'c')]);
+    assert!(resolved.is_conflict());
+    assert!(flows.iter().all(Option::is_none));
+
+    let mut flows: [Option<char>; 3] = [None; 3];
+    assert_eq!(
+        resolve_first(&mut flows),
+        Resolved {
+            winner: None,
+            rejected: vec![]
+        }
+    );
+}
+
+#[test]
+fn resolve_priority_order() {
+    let mut flows = [Some('a'), None, Some('c'), Some('d')];
+    let resolved = resolve_priority(&mut flows, &[1, 3, 0]);
+    assert_eq!(resolved.winner, Some((3, 'd')));
+    assert_eq!(resolved.rejected, vec![(0, 'a'), (2, 'c')]);
+
+    let mut flows = [None, None, Some('c')];
+    assert_eq!(
+        resolve_priority(&mut flows, &[0, 1]).into_winner(),
+        Some('c')
+    );
+}
+
+#[test]
+fn resolve_max_key() {
+    let mut flows = [Some(3), Some(7), Some(7), Some(1)];
+    let resolved = resolve_max_by_key(&mut flows, |_, &mass| mass);
+    assert_eq!(resolved.winner, Some((1, 7)));
+    assert_eq!(resolved.rejected.len(), 3);
+
+    let mut flows = [Some(3), Some(7)];
+    let resolved = resolve_max_by_key(&mut flows, |edge, _| std::cmp::Reverse(edge));
+    assert_eq!(resolved.winner, Some((0, 3)));
+}
+
+#[test]
+fn resolve_random_tiebreak() {
+    let mut wins = [0; 4];
+    for generation in 0..4000 {
+        let mut rng = CellRng::new(7, generation, (0, 0));
+        let mut flows = [Some(0), None, Some(2), Some(3)];
+        let (edge, flow) = resolve_random(&mut flows, &mut rng).winner.unwrap();
+        assert_eq!(edge, flow);
+        wins[edge] += 1;
+    }
+    assert_eq!(wins[1], 0);
+    for &edge in &[0, 2, 3] {
+        assert!((1100..1550).contains(&wins[edge]), "{:?}", wins);
+    }
+}
@@ -0,0 +1,129 @@
+use gridsim::{parse_rle, write_rle, Moore, RleError, RlePattern, Sim, SquareGrid};
+use ndarray::{arr2, Array2, ArrayView2};
+
+/// Conway's Game of Life
+#[derive(Debug)]
+pub struct Gol;
+
+impl Sim<Moore> for Gol {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        let n = cells.iter().filter(|&&c| c).count();
+        if cells[(1, 1)] {
+            (3..=4).contains(&n)
+        } else {
+            n == 3
+        }
+    }
+
+    fn egress(&self, cell: &mut Self::Cell, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Self::Cell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Self::Cell {
+        false
+    }
+
+    fn diff_padding(&self) -> Self::Diff {
+        false
+    }
+
+    fn flow_padding(&self) -> Self::Flow {}
+}
+
+const GLIDER: &str = "#N Glider
+#C A comment
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+#[test]
+fn rle_parse_glider() {
+    let pattern: RlePattern<bool> = parse_rle(GLIDER).unwrap();
+    assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+    assert_eq!(
+        pattern.cells,
+        Array2::from_shape_fn((3, 3), |p| [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+            .contains(&p))
+    );
+}
+
+#[test]
+fn rle_grid_round_trip() {
+    let grid = SquareGrid::from_rle(Gol, GLIDER).unwrap();
+    assert_eq!(grid.to_rle(), "x = 3, y = 3\nbo$2bo$3o!\n");
+}
+
+#[test]
+fn rle_blank_rows_and_wrapping() {
+    let mut cells = Array2::from_elem((5, 80), false);
+    cells[(0, 0)] = true;
+    cells[(3, 1)] = true;
+    for x in (0..80).step_by(2) {
+        cells[(4, x)] = true;
+    }
+    let text = write_rle(cells.view(), Some("B3/S23"));
+    assert!(text.lines().all(|line| line.len() <= 70));
+    assert!(text.contains("o3$bo$"));
+    let pattern: RlePattern<bool> = parse_rle(&text).unwrap();
+    assert_eq!(pattern.cells, cells);
+}
+
+#[test]
+fn rle_extended_states() {
+    let pattern: RlePattern<u8> = parse_rle("x = 4, y = 2\n.A2B$2pAyO!").unwrap();
+    assert_eq!(pattern.cells, arr2(&[[0, 1, 2, 2], [25, 25, 255, 0]]));
+    let text = write_rle(pattern.cells.view(), None);
+    assert_eq!(text, "x = 4, y = 2\n.A2B$2pAyO!\n");
+}
+
+#[test]
+fn rle_errors() {
+    assert_eq!(
+        parse_rle::<bool>("bo!"),
+        Err(RleError::InvalidHeader("bo!".to_owned()))
+    );
+    assert_eq!(
+        parse_rle::<bool>("#C only a comment"),
+        Err(RleError::MissingHeader)
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 2, y = 1\n3o!"),
+        Err(RleError::OutOfBounds)
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 2, y = 1\nB!"),
+        Err(RleError::UnsupportedState(2))
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 2, y = 1\nz!"),
+        Err(RleError::InvalidTag('z'))
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 4000000000, y = 4000000000\n!"),
+        Err(RleError::TooLarge)
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 2, y = 1\n99999999999999999999999o!"),
+        Err(RleError::OutOfBounds)
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 2, y = 2\n18446744073709551615$o!"),
+        Err(RleError::OutOfBounds)
+    );
+    assert_eq!(
+        parse_rle::<bool>("x = 2, y = 1\nb18446744073709551615bo!"),
+        Err(RleError::OutOfBounds)
+    );
+    assert_eq!(parse_rle::<bool>("x = 0, y = 0\n!").unwrap().cells.len(), 0);
+    assert_eq!(
+        SquareGrid::from_rle(Gol, "x = 0, y = 0\n!").err(),
+        Some(RleError::Empty)
+    );
+}
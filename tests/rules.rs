@@ -0,0 +1,248 @@
+use gridsim::{
+    heat_color,
+    rules::{self, BrainCell, BriansBrain, Wireworld, WireworldCell},
+    Boundary, BoxedRule, CellRng, Compose, SecondOrder, SecondOrderRule, SquareGrid,
+};
+use ndarray::{Array2, ArrayView2};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn show(grid: &SquareGrid<gridsim::LifeLikeRule>) -> String {
+    grid.to_chars(|&c| if c { 'o' } else { '.' })
+}
+
+#[test]
+fn rules_life_glider() {
+    let mut grid = SquareGrid::from_rle(rules::life(), "x = 5, y = 5\nbo$2bo$3o!").unwrap();
+    for _ in 0..4 {
+        grid.step();
+    }
+    assert_eq!(grid.to_rle(), "x = 5, y = 5\n$2bo$3bo$b3o!\n");
+}
+
+#[test]
+fn rules_seeds_pair() {
+    let mut grid =
+        SquareGrid::from_chars(rules::seeds(), "....\n....\n.oo.\n....\n....", |c| c == 'o');
+    grid.step();
+    assert_eq!(show(&grid), "....\n.oo.\n....\n.oo.\n....\n");
+}
+
+#[test]
+fn rules_high_life_births_on_six() {
+    let text = "ooo\n...\nooo";
+    let mut life = SquareGrid::from_chars(rules::life(), text, |c| c == 'o');
+    let mut high_life = SquareGrid::from_chars(rules::high_life(), text, |c| c == 'o');
+    life.step();
+    high_life.step();
+    assert_eq!(show(&life), ".o.\n...\n.o.\n");
+    assert_eq!(show(&high_life), ".o.\n.o.\n.o.\n");
+}
+
+#[test]
+fn rules_brians_brain() {
+    let brain = |c| match c {
+        'O' => BrainCell::On,
+        'D' => BrainCell::Dying,
+        _ => BrainCell::Off,
+    };
+    let show = |c: &BrainCell| match c {
+        BrainCell::On => 'O',
+        BrainCell::Dying => 'D',
+        BrainCell::Off => '.',
+    };
+    let mut grid = SquareGrid::from_chars(BriansBrain, "....\n....\n.OO.\n....\n....", brain);
+    grid.step();
+    assert_eq!(grid.to_chars(show), "....\n.OO.\n.DD.\n.OO.\n....\n");
+    grid.step();
+    assert_eq!(grid.to_chars(show), ".OO.\n.DD.\nO..O\n.DD.\n.OO.\n");
+}
+
+#[test]
+fn rules_wireworld_electron() {
+    let wire = |c| match c {
+        'H' => WireworldCell::Head,
+        't' => WireworldCell::Tail,
+        '#' => WireworldCell::Conductor,
+        _ => WireworldCell::Empty,
+    };
+    let show = |c: &WireworldCell| match c {
+        WireworldCell::Head => 'H',
+        WireworldCell::Tail => 't',
+        WireworldCell::Conductor => '#',
+        WireworldCell::Empty => '.',
+    };
+    let mut grid = SquareGrid::from_chars(Wireworld, ".....\ntH###\n.....", wire);
+    grid.step();
+    assert_eq!(grid.to_chars(show), ".....\n#tH##\n.....\n");
+    grid.step();
+    assert_eq!(grid.to_chars(show), ".....\n##tH#\n.....\n");
+}
+
+#[test]
+fn boxed_rules_switch_at_runtime() {
+    let soup = ndarray::Array2::from_shape_fn((16, 16), |(y, x)| (y * 7 + x * 3) % 5 < 2);
+    let menu: Vec<BoxedRule<bool>> = vec![rules::life().into(), rules::seeds().into()];
+    let mut grid = SquareGrid::new(menu[0].clone(), soup.clone());
+    let mut life = SquareGrid::new(rules::life(), soup);
+    for _ in 0..3 {
+        grid.step();
+        life.step();
+    }
+    assert_eq!(grid.cells(), life.cells());
+
+    *grid.sim_mut() = menu[1].clone();
+    let mut seeds = SquareGrid::new(rules::seeds(), life.cells().to_owned());
+    grid.step();
+    seeds.step();
+    assert_eq!(grid.cells(), seeds.cells());
+
+    // A rule written as a closure, where every cell copies the cell to its left.
+    *grid.sim_mut() = BoxedRule::new(false, |window| window[(1, 0)]);
+    let before = grid.cells().to_owned();
+    grid.step();
+    assert_eq!(
+        grid.cells().slice(ndarray::s![.., 1..]),
+        before.slice(ndarray::s![.., ..-1])
+    );
+}
+
+#[test]
+fn composed_rules() {
+    let soup = ndarray::Array2::from_shape_fn((12, 14), |(y, x)| (y * 5 + x * 11) % 7 < 3);
+
+    let mut layered = SquareGrid::new(rules::life().then(rules::seeds()), soup.clone());
+    let mut manual = SquareGrid::new(rules::life(), soup.clone());
+    for _ in 0..3 {
+        layered.step_layers();
+        manual.step();
+        *manual.sim_mut() = rules::seeds();
+        manual.step();
+        *manual.sim_mut() = rules::life();
+    }
+    assert_eq!(layered.cells(), manual.cells());
+    assert_eq!(layered.generation(), 3);
+
+    let mut masked = SquareGrid::new(
+        rules::life().mask(|(_, x)| x < 7, rules::seeds()),
+        soup.clone(),
+    );
+    masked.step_layers();
+    let mut life = SquareGrid::new(rules::life(), soup.clone());
+    let mut seeds = SquareGrid::new(rules::seeds(), soup);
+    life.step();
+    seeds.step();
+    assert_eq!(
+        masked.cells().slice(ndarray::s![.., ..7]),
+        life.cells().slice(ndarray::s![.., ..7])
+    );
+    assert_eq!(
+        masked.cells().slice(ndarray::s![.., 7..]),
+        seeds.cells().slice(ndarray::s![.., 7..])
+    );
+}
+
+#[test]
+fn rules_aging_blinker() {
+    let cells = Array2::from_shape_fn((5, 5), |(y, x)| y == 2 && (1..=3).contains(&x));
+    let mut grid = SquareGrid::aging(rules::life(), cells);
+    grid.step_n(3);
+    let (ages, changes) = (grid.ages(), grid.change_counts());
+    assert_eq!((ages[(2, 2)], changes[(2, 2)]), (3, 0));
+    assert_eq!((ages[(1, 2)], changes[(1, 2)]), (0, 3));
+    assert_eq!((ages[(0, 0)], changes[(0, 0)]), (3, 0));
+    assert_eq!(grid[(2, 1)].last_change(), Some(3));
+    assert_eq!(grid[(2, 2)].last_change(), None);
+    assert!(grid[(1, 2)].cell && !grid[(2, 1)].cell);
+
+    let heatmap = grid.render_heatmap(|cell| cell.changes() as f64, 3.0);
+    assert_eq!(&heatmap[4 * 7..4 * 8], &[255, 255, 255, 255]);
+    assert_eq!(&heatmap[..4], &[0, 0, 0, 255]);
+    assert_eq!(heat_color(0.5), [255, 128, 0, 255]);
+}
+
+/// Fredkin's parity rule made reversible by exclusive or with the previous generation.
+struct Parity;
+
+impl SecondOrderRule for Parity {
+    type Cell = bool;
+
+    fn next(&self, &previous: &bool, current: ArrayView2<'_, bool>) -> bool {
+        let n = current.iter().filter(|&&c| c).count() - current[(1, 1)] as usize;
+        (n % 2 == 1) ^ previous
+    }
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn rules_second_order_reverses() {
+    let random =
+        |seed| Array2::from_shape_fn((12, 16), |index| CellRng::new(seed, 0, index).gen_bool(0.3));
+    let (previous, current) = (random(1), random(2));
+    let mut grid = SquareGrid::second_order(Parity, previous.clone(), current.clone());
+    grid.step_n(10);
+    assert_ne!(grid.current(), current);
+    grid.reverse();
+    grid.step_n(10);
+    grid.reverse();
+    assert_eq!(grid.current(), current);
+    assert_eq!(grid.cells().map(|cell| cell.previous), previous);
+    assert_eq!(grid.generation(), 20);
+
+    let mut wrapped =
+        SquareGrid::with_boundary(SecondOrder(Parity), grid.cells().to_owned(), Boundary::Wrap);
+    wrapped.step_n(5);
+    wrapped.reverse();
+    wrapped.step_n(5);
+    wrapped.reverse();
+    assert_eq!(wrapped.current(), current);
+}
+
+static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+/// A cell which counts how many times cells of its type are cloned.
+#[derive(Debug, PartialEq)]
+struct Counted(u64);
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        CLONES.fetch_add(1, Ordering::Relaxed);
+        Counted(self.0)
+    }
+}
+
+/// Adds up the window and subtracts the previous state.
+struct Sum;
+
+impl SecondOrderRule for Sum {
+    type Cell = Counted;
+
+    fn next(&self, previous: &Counted, current: ArrayView2<'_, Counted>) -> Counted {
+        Counted(
+            current
+                .iter()
+                .map(|c| c.0)
+                .sum::<u64>()
+                .wrapping_sub(previous.0),
+        )
+    }
+
+    fn cell_padding(&self) -> Counted {
+        Counted(0)
+    }
+}
+
+#[test]
+fn rules_second_order_borrows_windows() {
+    let cells =
+        |offset| Array2::from_shape_fn((8, 8), |(y, x)| Counted((y * 8 + x + offset) as u64));
+    let mut grid = SquareGrid::second_order(Sum, cells(0), cells(1));
+    grid.step();
+    let clones = CLONES.swap(0, Ordering::Relaxed);
+    // Cloning every window would take nine clones for each of the 64 cells.
+    assert!(clones < 9 * 64, "{} clones", clones);
+    let center = (0..3).flat_map(|y| (0..3).map(move |x| (y * 8 + x + 1) as u64));
+    assert_eq!(grid.current()[(1, 1)], Counted(center.sum::<u64>() - 9));
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "serde")]
+
+use gridsim::{
+    ElementaryRule, GridError, GridSnapshot, HexDirection, LineGrid, Moore, Sim, SquareGrid,
+};
+use ndarray::{Array1, Array2, ArrayView2};
+use serde::{Deserialize, Serialize};
+
+/// Conway's Game of Life
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Gol;
+
+impl Sim<Moore> for Gol {
+    type Cell = bool;
+    type Diff = bool;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, bool>) -> bool {
+        let n = cells.iter().filter(|&&c| c).count();
+        if cells[(1, 1)] {
+            (3..=4).contains(&n)
+        } else {
+            n == 3
+        }
+    }
+
+    fn egress(&self, cell: &mut Self::Cell, diffs: ArrayView2<'_, bool>) -> [(); 8] {
+        *cell = diffs[(1, 1)];
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Self::Cell, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Self::Cell {
+        false
+    }
+
+    fn diff_padding(&self) -> Self::Diff {
+        false
+    }
+
+    fn flow_padding(&self) -> Self::Flow {}
+}
+
+#[test]
+fn serde_square_grid_round_trip() {
+    let mut grid = SquareGrid::new(
+        Gol,
+        Array2::from_shape_fn((5, 6), |(y, x)| y == 2 && (1..=3).contains(&x)),
+    );
+    let json = serde_json::to_string(&grid).unwrap();
+    let mut restored: SquareGrid<Gol> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.cells(), grid.cells());
+    grid.step_parallel();
+    restored.step_parallel();
+    assert_eq!(restored.cells(), grid.cells());
+}
+
+#[test]
+fn serde_snapshot_restore() {
+    let mut grid = SquareGrid::new(Gol, Array2::from_elem((3, 4), true));
+    let json = serde_json::to_string(&grid.snapshot()).unwrap();
+    let snapshot: GridSnapshot<bool> = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot, grid.snapshot());
+
+    let empty = r#"{"generation":2,"cells":{"v":1,"dim":[0,3],"data":[]}}"#;
+    let empty: GridSnapshot<bool> = serde_json::from_str(empty).unwrap();
+    assert_eq!(grid.restore(&empty), Err(GridError::Empty));
+    assert_eq!(grid.snapshot(), snapshot);
+}
+
+#[test]
+fn serde_line_grid_round_trip() {
+    let grid = LineGrid::new(
+        ElementaryRule::new(110),
+        Array1::from(vec![false, true, true, false]),
+    );
+    let json = serde_json::to_string(&grid).unwrap();
+    let restored: LineGrid<ElementaryRule> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.cells(), grid.cells());
+}
+
+#[test]
+fn serde_directions() {
+    let json = serde_json::to_string(&HexDirection::ALL).unwrap();
+    let restored: [HexDirection; 6] = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, HexDirection::ALL);
+}
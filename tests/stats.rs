@@ -0,0 +1,50 @@
+use gridsim::stats;
+use ndarray::{array, Array2};
+
+#[test]
+fn stats_reductions() {
+    let cells = array![[0u8, 1, 2], [1, 1, 0], [2, 1, 1]];
+    assert_eq!(stats::population(cells.view(), |&c| c == 1), 5);
+    let histogram = stats::histogram(cells.view(), |&c| c);
+    assert_eq!(histogram.len(), 3);
+    assert_eq!((histogram[&0], histogram[&1], histogram[&2]), (2, 5, 2));
+    assert_eq!(stats::min(cells.view(), |&c| c), Some(0));
+    assert_eq!(stats::max(cells.view(), |&c| c), Some(2));
+    assert_eq!(stats::mean(cells.view(), |&c| c as f64), Some(1.0));
+
+    let empty = Array2::<u8>::zeros((0, 3));
+    assert_eq!(stats::population(empty.view(), |_| true), 0);
+    assert_eq!(stats::max(empty.view(), |&c| c), None);
+    assert_eq!(stats::mean(empty.view(), |&c| c as f64), None);
+}
+
+#[test]
+fn stats_skip_nan() {
+    let cells = array![[f64::NAN, 1.0], [-2.0, 3.5]];
+    assert_eq!(stats::min(cells.view(), |&c| c), Some(-2.0));
+    assert_eq!(stats::max(cells.view(), |&c| c), Some(3.5));
+    let nan = array![[f64::NAN]];
+    assert_eq!(stats::min(nan.view(), |&c| c), None);
+}
+
+#[test]
+fn stats_bounding_box() {
+    let cells = array![
+        [0u8, 0, 0, 0, 0],
+        [0, 0, 1, 0, 0],
+        [0, 1, 0, 0, 0],
+        [0, 0, 0, 1, 0],
+    ];
+    let rect = stats::bounding_box(cells.view(), |&c| c == 1).unwrap();
+    assert_eq!(rect, stats::Rect { y: 1..4, x: 1..4 });
+    assert_eq!(rect.dims(), (3, 3));
+    assert!(rect.contains((2, 2)));
+    assert!(!rect.contains((0, 2)));
+    assert_eq!(stats::bounding_box(cells.view(), |&c| c == 2), None);
+    assert_eq!(
+        stats::bounding_box(cells.view(), |&c| c == 0)
+            .unwrap()
+            .dims(),
+        (4, 5)
+    );
+}
@@ -0,0 +1,102 @@
+use gridsim::{rules, Moore, Sim, SquareGrid, UpdateOrder};
+use ndarray::{Array2, ArrayView2};
+
+#[test]
+fn step_sequential() {
+    let mut grid = SquareGrid::new(
+        rules::life(),
+        Array2::from_shape_fn((5, 5), |(y, x)| y == 2 && (1..=3).contains(&x)),
+    );
+    grid.step();
+    assert_eq!(grid.generation(), 1);
+    assert_eq!(
+        grid.cells(),
+        Array2::from_shape_fn((5, 5), |(y, x)| x == 2 && (1..=3).contains(&y))
+    );
+}
+
+#[test]
+#[cfg(feature = "use-rayon")]
+fn step_in_pool() {
+    let pool = gridsim::rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let mut grid =
+        SquareGrid::from_chars(rules::life(), ".#...\n..#..\n###..\n.....\n.....\n", |c| {
+            c == '#'
+        });
+    let mut expected = SquareGrid::new(rules::life(), grid.cells().to_owned());
+    for _ in 0..4 {
+        grid.step_in(&pool);
+        expected.step();
+    }
+    assert_eq!(grid.cells(), expected.cells());
+    assert_eq!(grid.generation(), 4);
+}
+
+#[test]
+fn step_n() {
+    let cells = Array2::from_shape_fn((9, 11), |(y, x)| (y * 3 + x * 7) % 5 < 2);
+    let mut grid = SquareGrid::new(rules::life(), cells.clone());
+    let mut stepped = SquareGrid::new(rules::life(), cells);
+    grid.step_n(7);
+    for _ in 0..7 {
+        stepped.step();
+    }
+    assert_eq!(grid.generation(), 7);
+    assert_eq!(grid.cells(), stepped.cells());
+}
+
+/// A cell of [`Boxed`] which can't be cloned.
+#[derive(Debug, PartialEq)]
+struct Alive(Box<bool>);
+
+/// Conway's Game of Life with cells and diffs which can't be cloned.
+struct Boxed;
+
+impl Sim<Moore> for Boxed {
+    type Cell = Alive;
+    type Diff = Alive;
+    type Flow = ();
+
+    fn compute(&self, cells: ArrayView2<'_, Alive>) -> Alive {
+        let n = cells.iter().filter(|c| *c.0).count();
+        Alive(Box::new(if *cells[(1, 1)].0 {
+            (3..=4).contains(&n)
+        } else {
+            n == 3
+        }))
+    }
+
+    fn egress(&self, cell: &mut Alive, diffs: ArrayView2<'_, Alive>) -> [(); 8] {
+        *cell.0 = *diffs[(1, 1)].0;
+        [(); 8]
+    }
+
+    fn ingress(&self, _: &mut Alive, _: [(); 8]) {}
+
+    fn cell_padding(&self) -> Alive {
+        Alive(Box::new(false))
+    }
+
+    fn diff_padding(&self) -> Alive {
+        Alive(Box::new(false))
+    }
+
+    fn flow_padding(&self) {}
+}
+
+#[test]
+fn step_cells_not_clone() {
+    let mut grid = SquareGrid::new_random(rules::life(), (20, 30), 4, |rng| rng.gen_bool(0.4));
+    let mut boxed = SquareGrid::new(Boxed, grid.cells().map(|&c| Alive(Box::new(c))));
+    grid.step_n(3);
+    boxed.step_n(2);
+    while !boxed.step_rows(7) {}
+    boxed.set_update_order(UpdateOrder::Sweep);
+    boxed.step();
+    grid.set_update_order(UpdateOrder::Sweep);
+    grid.step();
+    assert_eq!(boxed.cells().map(|c| *c.0), grid.cells());
+}
@@ -0,0 +1,32 @@
+use gridsim::{rules, SquareGrid, TermRenderer};
+use ndarray::Array2;
+
+#[test]
+fn term_renderer() {
+    let mut grid = SquareGrid::new(rules::life(), Array2::from_elem((2, 3), false));
+    grid.set_cell((0, 1), true);
+    let mut renderer = TermRenderer::new();
+    let mut out = vec![];
+    renderer
+        .render(&mut out, &grid, |&c| if c { '#' } else { '.' })
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\x1b[2J\x1b[Hgeneration 0\x1b[K\n.#.\x1b[K\n...\x1b[K\n"
+    );
+
+    let mut out = vec![];
+    renderer
+        .render_colored(&mut out, &grid, |&c| {
+            if c {
+                ('#', Some([1, 2, 3]))
+            } else {
+                (' ', None)
+            }
+        })
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\x1b[Hgeneration 0\x1b[K\n \x1b[38;2;1;2;3m#\x1b[39m \x1b[K\n   \x1b[K\n"
+    );
+}
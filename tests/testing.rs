@@ -0,0 +1,167 @@
+use gridsim::{
+    rules::{self, BrainCell, BriansBrain, Wireworld, WireworldCell},
+    testing::{self, AsciiCell, PropertyCheck},
+    Boundary, Hpp, Moore, Sim, SquareGrid, UpdateOrder,
+};
+use ndarray::{Array2, ArrayView2};
+
+#[test]
+fn testing_assert_rule() {
+    let (off, on) = (BrainCell::Off, BrainCell::On);
+    testing::assert_rule::<BriansBrain>(off, [on, off, on, off, off, off, off, off], on);
+    testing::assert_rule::<BriansBrain>(off, [on, on, on, off, off, off, off, off], off);
+    testing::assert_rule::<BriansBrain>(on, [off; 8], BrainCell::Dying);
+    let mut birth = [false; 8];
+    birth[..3].copy_from_slice(&[true; 3]);
+    testing::assert_rule_with(rules::life(), false, birth, true);
+}
+
+#[test]
+fn testing_assert_evolution() {
+    testing::assert_evolution::<Wireworld>(
+        "
+        tH###
+        ",
+        2,
+        "
+        ##tH#
+        ",
+    );
+    testing::assert_evolution_with(
+        rules::life(),
+        "
+        .#...
+        ..#..
+        ###..
+        .....
+        .....
+        ",
+        4,
+        "
+        .....
+        ..#..
+        ...#.
+        .###.
+        .....
+        ",
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected | actual\n.#.      | .#.\n.#.      | .#.\n...      | .#.  <\n")]
+fn testing_evolution_mismatch() {
+    testing::assert_evolution_with(rules::life(), ".#.\n.#.\n.#.\n", 2, ".#.\n.#.\n...\n");
+}
+
+#[test]
+fn testing_ascii_cells() {
+    let grid = testing::grid_from_ascii(Wireworld, "  .Ht#\n\n  ##..\n");
+    assert_eq!(grid.cells().dim(), (2, 4));
+    assert_eq!(grid[(0, 1)], WireworldCell::Head);
+    assert_eq!(grid.to_chars(AsciiCell::to_char), ".Ht#\n##..\n");
+    assert_eq!(u8::from_char('a'), Some(10));
+    assert_eq!(12u8.to_char(), 'c');
+    assert_eq!(bool::from_char('O'), Some(true));
+    assert_eq!(bool::from_char('x'), None);
+}
+
+/// Moves every cell one to the east, which conserves cells but isn't symmetric.
+#[derive(Clone, Debug, Default)]
+struct Drift;
+
+impl Sim<Moore> for Drift {
+    type Cell = bool;
+    type Diff = ();
+    type Flow = bool;
+
+    fn compute(&self, _: ArrayView2<'_, bool>) {}
+
+    fn egress(&self, cell: &mut bool, _: ArrayView2<'_, ()>) -> [bool; 8] {
+        let mut flows = [false; 8];
+        flows[0] = std::mem::take(cell);
+        flows
+    }
+
+    fn ingress(&self, cell: &mut bool, flows: [bool; 8]) {
+        *cell = flows[4];
+    }
+
+    fn cell_padding(&self) -> bool {
+        false
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn testing_properties() {
+    let life = PropertyCheck::new(rules::life(), |rng| rng.gen_bool(0.3))
+        .dims((12, 10))
+        .cases(4)
+        .generations(8);
+    life.assert_symmetric();
+    life.assert_quiescent(false);
+    PropertyCheck::new(Drift, |rng| rng.gen_bool(0.5))
+        .seed(3)
+        .assert_conserved(|&c| c as u8 as f64, 0.0);
+}
+
+#[test]
+#[should_panic(expected = "isn't symmetric under a quarter turn clockwise by generation 1")]
+fn testing_asymmetric() {
+    PropertyCheck::new(Drift, |rng| rng.gen_bool(0.5)).assert_symmetric();
+}
+
+#[test]
+#[should_panic(expected = "by generation 1 of the case with seed 9")]
+fn testing_not_conserved() {
+    PropertyCheck::new(Drift, |rng| rng.gen_bool(0.5))
+        .seed(9)
+        .boundary(Boundary::Constant(false))
+        .assert_conserved(|&c| c as u8 as f64, 0.0);
+}
+
+#[test]
+#[should_panic(expected = "quiescent true cell")]
+fn testing_not_quiescent() {
+    PropertyCheck::new(rules::life(), |_| false).assert_quiescent(true);
+}
+
+#[test]
+fn testing_assert_matches_reference() {
+    let boundaries = [
+        Boundary::Wrap,
+        Boundary::Twist,
+        Boundary::Shift(3),
+        Boundary::Clamp,
+        Boundary::Reflect,
+        Boundary::Constant(0b0101),
+        Boundary::Halo,
+    ];
+    let cells = Array2::from_shape_fn((9, 12), |(y, x)| ((y * 13 + x * 7) % 16) as u8);
+    for y in &boundaries {
+        for x in &boundaries {
+            let seam = |b: &Boundary<u8>| matches!(b, Boundary::Shift(_) | Boundary::Twist);
+            if seam(y) && seam(x) {
+                continue;
+            }
+            let grid = SquareGrid::with_boundaries(Hpp::default(), cells.clone(), *y, *x);
+            testing::assert_matches_reference(&grid, 12);
+        }
+    }
+
+    // Tiles, other update orders, and domains don't change the synchronous step.
+    let mut grid = SquareGrid::new_random(rules::life(), (20, 17), 3, |rng| rng.gen_bool(0.4));
+    grid.set_tile_size(Some((6, 5)));
+    grid.set_update_order(UpdateOrder::Sweep);
+    let domain = Array2::from_shape_fn((20, 17), |(y, x)| (y + x) % 7 != 0);
+    grid.set_domain(domain.view(), false);
+    testing::assert_matches_reference(&grid, 10);
+    let mut reference = grid.clone();
+    reference.step_reference_n(4);
+    assert_eq!(reference.generation(), 4);
+}
@@ -0,0 +1,63 @@
+use gridsim::{rules, Boundary, SquareGrid};
+use ndarray::Array2;
+
+#[test]
+fn transform_rotate_and_mirror() {
+    let glider = Array2::from_shape_fn((5, 7), |index| {
+        [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].contains(&index)
+    });
+    let mut grid = SquareGrid::with_boundaries(
+        rules::life(),
+        glider.clone(),
+        Boundary::Wrap,
+        Boundary::Clamp,
+    );
+
+    let rotated = grid.rotated_cw();
+    assert_eq!(rotated.cells().dim(), (7, 5));
+    assert!(rotated[(1, 4)] && rotated[(0, 2)]);
+    assert_eq!(rotated.boundaries(), (&Boundary::Clamp, &Boundary::Wrap));
+    assert_eq!(rotated.rotated_ccw().cells(), glider);
+    assert!(grid.mirrored_horizontal()[(0, 5)]);
+    assert!(grid.mirrored_vertical()[(4, 1)]);
+    assert_eq!(grid.transposed().cells(), glider.t());
+
+    // Life doesn't care which way the grid is turned.
+    let mut turned = grid.rotated_cw();
+    grid.step();
+    turned.step();
+    turned.rotate_ccw();
+    assert_eq!(turned.cells(), grid.cells());
+    for _ in 0..4 {
+        turned.rotate_cw();
+    }
+    assert_eq!(turned.cells(), grid.cells());
+}
+
+#[test]
+fn transform_tile_and_concat() {
+    let blinker = Array2::from_shape_fn((3, 3), |(y, _)| y == 1);
+    let grid = SquareGrid::with_boundary(rules::life(), blinker.clone(), Boundary::Wrap);
+    let mut tiled = grid.tile(3, 2);
+    assert_eq!(tiled.cells().dim(), (6, 9));
+    assert_eq!(tiled.boundaries(), (&Boundary::Wrap, &Boundary::Wrap));
+    assert_eq!(
+        tiled.cells(),
+        Array2::from_shape_fn((6, 9), |(y, x)| blinker[(y % 3, x % 3)])
+    );
+    // Wrapping a periodic world makes every tile step the same as the motif.
+    let mut motif = grid.clone();
+    motif.step();
+    tiled.step();
+    assert_eq!(tiled.cells(), motif.tile(3, 2).cells());
+
+    let empty = SquareGrid::new(rules::life(), Array2::from_elem((3, 2), false));
+    let wide = grid.hconcat(&empty);
+    assert_eq!(wide.cells().dim(), (3, 5));
+    assert!(wide[(1, 2)] && !wide[(1, 3)]);
+    let tall = grid.vconcat(&grid);
+    assert_eq!(
+        tall.cells(),
+        Array2::from_shape_fn((6, 3), |(y, _)| y % 3 == 1)
+    );
+}
@@ -0,0 +1,153 @@
+use gridsim::{Sim, Tri, TriGrid, TriNeighborhood, TriNeighbors, TriVertex};
+use ndarray::Array2;
+
+/// Counts the live neighbors of each cell, where only cells with a count of `0` are alive.
+#[derive(Debug)]
+struct Count;
+
+macro_rules! count {
+    ($n:ident, $edges:literal) => {
+        impl Sim<$n> for Count {
+            type Cell = u8;
+            type Diff = u8;
+            type Flow = ();
+
+            fn compute(&self, cells: TriNeighbors<'_, u8, $n>) -> u8 {
+                cells.iter().filter(|&&c| c == 0).count() as u8
+            }
+
+            fn egress(&self, cell: &mut u8, diffs: TriNeighbors<'_, u8, $n>) -> [(); $edges] {
+                *cell = *diffs.center();
+                [(); $edges]
+            }
+
+            fn ingress(&self, _: &mut u8, _: [(); $edges]) {}
+
+            fn cell_padding(&self) -> u8 {
+                u8::MAX
+            }
+
+            fn diff_padding(&self) -> u8 {
+                u8::MAX
+            }
+
+            fn flow_padding(&self) {}
+        }
+    };
+}
+
+count!(Tri, 3);
+count!(TriVertex, 12);
+
+fn count_around<N>(center: (usize, usize)) -> Array2<u8>
+where
+    N: TriNeighborhood,
+    Count: Sim<N, Cell = u8>,
+    <Count as Sim<N>>::Diff: Send + Sync,
+    N::Edges<<Count as Sim<N>>::Flow>: Send,
+{
+    let mut cells = Array2::from_elem((5, 7), u8::MAX);
+    cells[center] = 0;
+    let mut grid = TriGrid::new(Count, cells);
+    grid.step();
+    grid.cells().to_owned()
+}
+
+#[test]
+fn edge_neighbors() {
+    for &(center, up) in &[((2, 3), false), ((2, 4), true)] {
+        assert_eq!(TriGrid::<Count, Tri>::is_up(center), up);
+        let cells = count_around::<Tri>(center);
+        assert_eq!(cells.iter().filter(|&&c| c == 1).count(), 3);
+        let base = if up { 3 } else { 1 };
+        for &index in &[(2, center.1 - 1), (2, center.1 + 1), (base, center.1)] {
+            assert_eq!(cells[index], 1);
+        }
+    }
+}
+
+#[test]
+fn vertex_neighbors() {
+    for &center in &[(2, 3), (2, 4)] {
+        let cells = count_around::<TriVertex>(center);
+        assert_eq!(cells.iter().filter(|&&c| c == 1).count(), 12);
+        assert_eq!(cells[center], 0);
+    }
+}
+
+#[test]
+fn opposites() {
+    fn check<N: TriNeighborhood>() {
+        for &up in &[true, false] {
+            for edge in 0..N::EDGES {
+                let (dy, dx) = N::delta(edge, up);
+                let neighbor_up = up ^ ((dy + dx) % 2 != 0);
+                let back = N::opposite(edge, up);
+                assert_eq!(N::delta(back, neighbor_up), (-dy, -dx));
+                assert_eq!(N::opposite(back, neighbor_up), edge);
+            }
+        }
+    }
+    check::<Tri>();
+    check::<TriVertex>();
+}
+
+/// Every cell sends its index to each neighbor, which checks where it came from.
+#[derive(Debug)]
+struct Senders {
+    dims: (usize, usize),
+}
+
+type Index = (usize, usize);
+
+impl Sim<TriVertex> for Senders {
+    type Cell = (Index, usize);
+    type Diff = ();
+    type Flow = Option<Index>;
+
+    fn compute(&self, _: TriNeighbors<'_, Self::Cell, TriVertex>) {}
+
+    fn egress(
+        &self,
+        cell: &mut Self::Cell,
+        _: TriNeighbors<'_, (), TriVertex>,
+    ) -> [Option<Index>; 12] {
+        [Some(cell.0); 12]
+    }
+
+    fn ingress(&self, cell: &mut Self::Cell, flows: [Option<Index>; 12]) {
+        let ((y, x), up) = (cell.0, (cell.0 .0 + cell.0 .1) % 2 == 0);
+        for (edge, flow) in flows.iter().enumerate() {
+            let (dy, dx) = TriVertex::delta(edge, up);
+            let (ny, nx) = (y as isize + dy, x as isize + dx);
+            let expected = if (0..self.dims.0 as isize).contains(&ny)
+                && (0..self.dims.1 as isize).contains(&nx)
+            {
+                Some((ny as usize, nx as usize))
+            } else {
+                None
+            };
+            assert_eq!(*flow, expected);
+            cell.1 += flow.is_some() as usize;
+        }
+    }
+
+    fn cell_padding(&self) -> Self::Cell {
+        ((usize::MAX, usize::MAX), 0)
+    }
+
+    fn diff_padding(&self) {}
+
+    fn flow_padding(&self) -> Option<Index> {
+        None
+    }
+}
+
+#[test]
+fn flows_reach_neighbors() {
+    let dims = (4, 6);
+    let mut grid = TriGrid::new(Senders { dims }, Array2::from_shape_fn(dims, |i| (i, 0)));
+    grid.step();
+    assert_eq!(grid.cells()[(1, 2)].1, 12);
+    assert_eq!(grid.cells()[(0, 0)].1, 5);
+}
@@ -0,0 +1,90 @@
+use gridsim::{Ant, Heading, SquareGrid, Turmite, TurmiteCell};
+use ndarray::Array2;
+
+fn ant_grid(turmite: Turmite, size: usize) -> SquareGrid<Turmite> {
+    let mut cells = Array2::from_elem((size, size), TurmiteCell::default());
+    cells[(size / 2, size / 2)].ant = Some(Ant {
+        heading: Heading::North,
+        state: 0,
+    });
+    SquareGrid::new(turmite, cells)
+}
+
+fn ants(grid: &SquareGrid<Turmite>) -> Vec<((usize, usize), Ant)> {
+    grid.cells()
+        .indexed_iter()
+        .filter_map(|(position, cell)| cell.ant.map(|ant| (position, ant)))
+        .collect()
+}
+
+#[test]
+fn langtons_ant_first_square() {
+    let mut grid = ant_grid(Turmite::langtons_ant(), 20);
+    grid.step();
+    assert_eq!(
+        ants(&grid),
+        vec![(
+            (10, 11),
+            Ant {
+                heading: Heading::East,
+                state: 0
+            }
+        )]
+    );
+    for _ in 0..3 {
+        grid.step();
+    }
+    assert_eq!(
+        ants(&grid),
+        vec![(
+            (10, 10),
+            Ant {
+                heading: Heading::North,
+                state: 0
+            }
+        )]
+    );
+    let black: Vec<_> = grid
+        .cells()
+        .indexed_iter()
+        .filter(|(_, cell)| cell.color == 1)
+        .map(|(position, _)| position)
+        .collect();
+    assert_eq!(black, vec![(10, 10), (10, 11), (11, 10), (11, 11)]);
+}
+
+#[test]
+fn turmite_walks_off_grid() {
+    let mut grid = ant_grid(Turmite::from_turns("NN"), 6);
+    for _ in 0..3 {
+        grid.step();
+    }
+    assert_eq!(ants(&grid).len(), 1);
+    grid.step();
+    assert!(ants(&grid).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn turmite_rejects_unknown_colors() {
+    use gridsim::{Transition, Turn};
+    Turmite::new(vec![vec![Transition {
+        color: 1,
+        turn: Turn::Left,
+        state: 0,
+    }]]);
+}
+
+#[test]
+fn langtons_ant_tiled() {
+    let mut grid = ant_grid(Turmite::langtons_ant(), 20);
+    let mut tiled = ant_grid(Turmite::langtons_ant(), 20);
+    tiled.set_tile_size(Some((3, 7)));
+    assert_eq!(tiled.tile_size(), Some((3, 7)));
+    for _ in 0..200 {
+        grid.step();
+        tiled.step();
+        assert_eq!(ants(&grid), ants(&tiled));
+    }
+    assert_eq!(grid.cells(), tiled.cells());
+}
@@ -0,0 +1,48 @@
+use gridsim::{rules, SquareGrid, UpdateOrder};
+
+#[test]
+fn update_orders() {
+    let start = SquareGrid::new_random(rules::life(), (9, 11), 5, |rng| rng.gen_bool(0.4));
+
+    // Updating each cell in place in row-major order.
+    let mut expected = start.cells().to_owned();
+    for y in 0..9 {
+        for x in 0..11 {
+            let n = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (y as isize + dy, x as isize + dx)))
+                .filter(|&(ny, nx)| (ny, nx) != (y as isize, x as isize))
+                .filter(|&(ny, nx)| ny >= 0 && nx >= 0 && ny < 9 && nx < 11)
+                .filter(|&(ny, nx)| expected[(ny as usize, nx as usize)])
+                .count();
+            expected[(y, x)] = n == 3 || (n == 2 && expected[(y, x)]);
+        }
+    }
+    let mut sweep = start.clone();
+    sweep.set_update_order(UpdateOrder::Sweep);
+    sweep.step();
+    assert_eq!(sweep.cells(), expected);
+
+    // A single block is the same as a synchronous step.
+    let (mut sync, mut block) = (start.clone(), start.clone());
+    block.set_update_order(UpdateOrder::Blocks((9, 11)));
+    sync.step_n(3);
+    block.step_n(3);
+    assert_eq!(sync.cells(), block.cells());
+
+    let mut blocks = start.clone();
+    blocks.set_update_order(UpdateOrder::Blocks((2, 3)));
+    blocks.step();
+    assert_ne!(blocks.cells(), sync.cells());
+
+    let mut random = start.clone();
+    random.set_update_order(UpdateOrder::RandomSequential);
+    random.set_seed(3);
+    random.track_population();
+    let mut again = random.clone();
+    random.step_n(4);
+    again.step_n(4);
+    assert_eq!(random.cells(), again.cells());
+    let alive = random.cells().iter().filter(|&&c| c).count();
+    assert_eq!(random.population(), Some(&[99 - alive, alive][..]));
+    assert_eq!(random.update_order(), UpdateOrder::RandomSequential);
+}
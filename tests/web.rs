@@ -0,0 +1,33 @@
+use gridsim::web::WebGrid;
+
+#[test]
+fn web_grid() {
+    assert!(WebGrid::new("B3/S23x", 4, 4).is_err());
+    assert!(WebGrid::new("B3/S23", 0, 4).is_err());
+
+    let mut grid = WebGrid::new("B3/S23", 5, 4).unwrap();
+    assert_eq!((grid.width(), grid.height()), (5, 4));
+    for x in 1..4 {
+        grid.set(1, x, true);
+    }
+    grid.set(9, 9, true);
+    assert!(!grid.get(9, 9));
+    grid.step();
+    assert_eq!(grid.generation(), 1);
+    assert!(grid.get(0, 2) && grid.get(1, 2) && grid.get(2, 2) && !grid.get(1, 1));
+    grid.step_n(3);
+    assert_eq!(grid.generation(), 4);
+
+    assert_eq!(grid.pixels_len(), 4 * 5 * 4);
+    let pixels = grid.pixels();
+    assert_eq!(pixels.len(), 4 * 5 * 4);
+    assert_eq!(pixels[4 * (5 + 1)..][..4], [255; 4]);
+    assert_eq!(pixels[..4], [0, 0, 0, 255]);
+
+    grid.randomize(1, 0.5);
+    let alive = (0..4)
+        .flat_map(|y| (0..5).map(move |x| (y, x)))
+        .filter(|&(y, x)| grid.get(y, x))
+        .count();
+    assert!(alive > 0 && alive < 20);
+}